@@ -1,4 +1,15 @@
-use axum::{extract::Query, Json};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::Query,
+    middleware::{self, Next},
+    response::Response,
+    Json,
+};
 use okapi_operation::{axum_integration::*, *};
 use serde::Deserialize;
 
@@ -43,12 +54,69 @@ async fn echo_put(body: Json<Request>) -> Json<String> {
     Json(body.0.data)
 }
 
+/// Request counts by `operationId`.
+#[derive(Clone, Default)]
+struct RequestCounts(Arc<Mutex<BTreeMap<String, u64>>>);
+
+impl RequestCounts {
+    /// Register counters of all operations in advance, so they are exported before first
+    /// request.
+    fn register(&self, inventory: &[OperationDescriptor]) {
+        let mut counts = self.0.lock().unwrap();
+        for operation in inventory {
+            if let Some(operation_id) = &operation.operation_id {
+                let _ = counts.entry(operation_id.clone()).or_default();
+            }
+        }
+    }
+
+    /// Render counters in Prometheus text format.
+    fn render(&self) -> String {
+        let mut output = String::from("# TYPE http_requests_total counter\n");
+        for (operation_id, count) in self.0.lock().unwrap().iter() {
+            let _ = writeln!(
+                output,
+                "http_requests_total{{operation_id=\"{operation_id}\"}} {count}"
+            );
+        }
+        output
+    }
+}
+
+/// Middleware, counting requests by `operationId` of matched route.
+async fn count_requests(
+    counts: RequestCounts,
+    request: axum::extract::Request,
+    next: Next,
+) -> Response {
+    if let Some(operation_id) = OperationIds::for_request(&request) {
+        *counts
+            .0
+            .lock()
+            .unwrap()
+            .entry(operation_id.to_owned())
+            .or_default() += 1;
+    }
+    next.run(request).await
+}
+
 #[tokio::main]
 async fn main() {
-    let app = Router::new()
-        .route(
-            "/echo",
-            get(oh!(echo_get)).post(oh!(echo_post)).put(oh!(echo_put)),
+    let counts = RequestCounts::default();
+    let router = Router::new().route(
+        "/echo",
+        get(oh!(echo_get)).post(oh!(echo_post)).put(oh!(echo_put)),
+    );
+    counts.register(&router.operation_inventory().expect("no problem"));
+
+    let metrics = counts.clone();
+    let app = router
+        .layer(middleware::from_fn(move |request, next| {
+            count_requests(counts.clone(), request, next)
+        }))
+        .route_undocumented(
+            "/metrics",
+            axum::routing::get(move || async move { metrics.render() }),
         )
         .finish_openapi("/openapi", "Demo", "1.0.0")
         .expect("no problem");
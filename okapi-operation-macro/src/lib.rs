@@ -1,6 +1,8 @@
 #![allow(clippy::manual_unwrap_or_default)]
 
-use syn::{parse_macro_input, ItemFn};
+use syn::{ItemFn, TraitItemFn};
+
+use crate::operation::Handler;
 
 mod error;
 mod operation;
@@ -13,7 +15,15 @@ pub fn openapi(
     attr: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    match operation::openapi(attr, parse_macro_input!(input as ItemFn)) {
+    // Trait methods have no visibility and may have no body
+    let input = match syn::parse::<ItemFn>(input.clone()) {
+        Ok(x) => Handler::Fn(x),
+        Err(err) => match syn::parse::<TraitItemFn>(input) {
+            Ok(x) => Handler::TraitFn(x),
+            Err(_) => return err.into_compile_error().into(),
+        },
+    };
+    match operation::openapi(attr, input) {
         Ok(x) => x.into(),
         Err(err) => err.write().into(),
     }
@@ -0,0 +1,92 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use crate::{
+    operation::route::{HttpMethod, Route, RoutePath},
+    utils::string_value,
+};
+
+pub(super) static COLLECT_ATTRIBUTE_NAME: &str = "collect";
+
+/// Registration of operation in global inventory.
+///
+/// Either `collect(path = "...", method = "...")` or `collect` (in this case route is taken
+/// from `path` and `method` attributes of operation).
+#[derive(Debug, Default, FromMeta, PartialEq)]
+#[darling(from_word = || Ok(Self::default()))]
+pub(super) struct Collect {
+    #[darling(default, with = string_value)]
+    path: Option<RoutePath>,
+    #[darling(default, with = string_value)]
+    method: Option<HttpMethod>,
+}
+
+impl Collect {
+    /// Resolve route of collected operation, falling back to route of operation.
+    pub(super) fn route(&self, operation_route: Option<&Route>) -> Result<Route, String> {
+        let path = self
+            .path
+            .clone()
+            .or_else(|| operation_route.map(|x| x.path.clone()));
+        let method = self
+            .method
+            .clone()
+            .or_else(|| operation_route.map(|x| x.method.clone()));
+        Route::new(path, method)?.ok_or_else(|| {
+            format!(
+                "`{COLLECT_ATTRIBUTE_NAME}` requires path and method, specify them with \
+                `{COLLECT_ATTRIBUTE_NAME}(path = \"...\", method = \"...\")` or with \
+                `path` and `method` attributes"
+            )
+        })
+    }
+}
+
+/// Generate registration of operation generator.
+pub(super) fn submit(
+    route: &Route,
+    crate_name: &TokenStream,
+    generator_name: &Ident,
+) -> TokenStream {
+    let path = &route.path;
+    let method = &route.method;
+    quote! {
+        #crate_name::_macro_prelude::inventory::submit! {
+            #crate_name::CollectedOperation::new(#path, #method, #generator_name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Meta};
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let input: Meta = parse_quote! { collect(path = "/users/{id}", method = "get") };
+        let collect = Collect::from_meta(&input).expect("Successfully parsed");
+        let route = collect.route(None).expect("Route is specified");
+        assert_eq!(
+            route,
+            Route {
+                path: RoutePath::from_string("/users/{id}").expect("Valid path"),
+                method: HttpMethod::from_string("GET").expect("Valid method"),
+            }
+        );
+
+        let input: Meta = parse_quote! { collect };
+        let collect = Collect::from_meta(&input).expect("Successfully parsed");
+        assert!(collect.route(None).is_err());
+        assert_eq!(collect.route(Some(&route)), Ok(route));
+
+        let input: Meta = parse_quote! { collect(path = "users", method = "get") };
+        assert!(Collect::from_meta(&input).is_err());
+
+        let input: Meta = parse_quote! { collect(path = "/", method = "not a method") };
+        assert!(Collect::from_meta(&input).is_err());
+    }
+}
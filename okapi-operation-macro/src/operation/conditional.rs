@@ -0,0 +1,49 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+/// Conditional request validators (`conditional(etag)`).
+#[derive(Debug, FromMeta, PartialEq)]
+#[darling(and_then = "Self::validate")]
+pub(super) struct Conditional {
+    #[darling(default)]
+    etag: bool,
+}
+
+impl Conditional {
+    fn validate(self) -> darling::Result<Self> {
+        if !self.etag {
+            return Err(darling::Error::custom(
+                "`conditional` should specify validator (`etag`)",
+            ));
+        }
+        Ok(self)
+    }
+}
+
+impl ToTokens for Conditional {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if self.etag {
+            tokens.extend(quote! { document_conditional_etag(&mut operation); });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Meta};
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let input: Meta = parse_quote! { conditional(etag) };
+        assert_eq!(
+            Conditional::from_meta(&input).expect("Successfully parsed"),
+            Conditional { etag: true }
+        );
+
+        let input: Meta = parse_quote! { conditional() };
+        assert!(Conditional::from_meta(&input).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::Lit;
+
+use crate::utils::{quote_option, string_value};
+
+/// Numeric attribute value, written either as number or as string literal (`minimum = 1`,
+/// `minimum = 0.5` or `minimum = "1"`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Number(f64);
+
+impl FromMeta for Number {
+    fn from_value(value: &Lit) -> Result<Self, darling::Error> {
+        match value {
+            Lit::Int(x) => x.base10_parse().map(Self).map_err(darling::Error::from),
+            Lit::Float(x) => x.base10_parse().map(Self).map_err(darling::Error::from),
+            Lit::Str(x) => Self::from_string(&x.value()),
+            _ => Err(darling::Error::unexpected_lit_type(value)),
+        }
+        .map_err(|err| err.with_span(value))
+    }
+
+    fn from_string(value: &str) -> Result<Self, darling::Error> {
+        value
+            .parse()
+            .map(Self)
+            .map_err(|_| darling::Error::custom(format!("`{value}` is not a number")))
+    }
+}
+
+impl ToTokens for Number {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = self.0;
+        tokens.extend(quote! { #value });
+    }
+}
+
+/// Constraints, merged into generated schema of parameter (`default`, `minimum`, `maximum`
+/// and `pattern` attributes).
+#[derive(Debug, Default, FromMeta)]
+pub(super) struct SchemaConstraints {
+    #[darling(default, with = string_value)]
+    default: Option<String>,
+    #[darling(default)]
+    minimum: Option<Number>,
+    #[darling(default)]
+    maximum: Option<Number>,
+    #[darling(default, with = string_value)]
+    pattern: Option<String>,
+}
+
+impl SchemaConstraints {
+    fn is_empty(&self) -> bool {
+        self.default.is_none()
+            && self.minimum.is_none()
+            && self.maximum.is_none()
+            && self.pattern.is_none()
+    }
+
+    /// Wrap expression, producing schema, with application of constraints.
+    pub(super) fn apply_to(&self, schema: impl ToTokens) -> TokenStream {
+        if self.is_empty() {
+            return schema.into_token_stream();
+        }
+        let default = quote_option(&self.default);
+        let minimum = quote_option(&self.minimum);
+        let maximum = quote_option(&self.maximum);
+        let pattern = quote_option(&self.pattern);
+        quote! {
+            apply_schema_constraints(
+                #schema,
+                SchemaConstraints {
+                    default: #default,
+                    minimum: #minimum,
+                    maximum: #maximum,
+                    pattern: #pattern,
+                },
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Meta};
+
+    use super::*;
+
+    #[test]
+    fn parse_number() {
+        for (input, expected) in [
+            (parse_quote!(minimum = 1), 1.0),
+            (parse_quote!(minimum = -2), -2.0),
+            (parse_quote!(minimum = 0.5), 0.5),
+            (parse_quote!(minimum = "10"), 10.0),
+        ] {
+            let input: Meta = input;
+            assert_eq!(Number::from_meta(&input).unwrap(), Number(expected));
+        }
+        let input: Meta = parse_quote!(minimum = "abc");
+        assert!(Number::from_meta(&input).is_err());
+    }
+}
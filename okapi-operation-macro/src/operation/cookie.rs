@@ -3,15 +3,19 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::Path;
 
-use crate::{operation::parameters::ParameterStyle, utils::quote_option};
+use crate::{
+    operation::parameters::ParameterStyle,
+    utils::{quote_option, string_value},
+};
 
 pub(super) static COOKIE_ATTRIBUTE_NAME: &str = "cookie";
 
 /// Cookie parameter.
 #[derive(Debug, FromMeta)]
 pub(super) struct Cookie {
+    #[darling(with = string_value)]
     name: String,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
     description: Option<String>,
     #[darling(default)]
     required: bool,
@@ -21,6 +25,7 @@ pub(super) struct Cookie {
     explode: Option<bool>,
     #[darling(default)]
     allow_empty_value: bool,
+    #[darling(with = string_value)]
     schema: Path,
     // TODO: support content as well
 }
@@ -0,0 +1,59 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::Path;
+
+use crate::utils::string_value;
+
+/// Discriminator of enum schema (`discriminator(schema = "...", property_name = "...")`).
+#[derive(Debug, FromMeta, PartialEq)]
+pub(super) struct Discriminator {
+    #[darling(with = string_value)]
+    schema: Path,
+    #[darling(default, with = string_value)]
+    property_name: Option<String>,
+}
+
+impl ToTokens for Discriminator {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let schema = &self.schema;
+        tokens.extend(match &self.property_name {
+            Some(property_name) => quote! {
+                components.set_discriminator_property::<#schema>(#property_name);
+            },
+            None => quote! { components.set_discriminator::<#schema>(); },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Meta};
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let input: Meta = parse_quote! { discriminator(schema = "crate::Payment") };
+        assert_eq!(
+            Discriminator::from_meta(&input).expect("Successfully parsed"),
+            Discriminator {
+                schema: parse_quote! { crate::Payment },
+                property_name: None,
+            }
+        );
+
+        let input: Meta =
+            parse_quote! { discriminator(schema = "Payment", property_name = "kind") };
+        assert_eq!(
+            Discriminator::from_meta(&input).expect("Successfully parsed"),
+            Discriminator {
+                schema: parse_quote! { Payment },
+                property_name: Some("kind".into()),
+            }
+        );
+
+        let input: Meta = parse_quote! { discriminator(property_name = "kind") };
+        assert!(Discriminator::from_meta(&input).is_err());
+    }
+}
@@ -2,12 +2,13 @@ use darling::FromMeta;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
-use crate::utils::quote_option;
+use crate::utils::{quote_option, string_value};
 
 #[derive(Debug, FromMeta, PartialEq)]
 pub(super) struct ExternalDocs {
+    #[darling(with = string_value)]
     url: String,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
     description: Option<String>,
 }
 
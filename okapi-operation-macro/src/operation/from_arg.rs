@@ -0,0 +1,148 @@
+use syn::{FnArg, GenericArgument, Pat, Path, PathArguments, Signature, Type, TypePath};
+
+use super::schema_json::{EnumValues, ParameterSchema, SchemaJson};
+use crate::error::Error;
+
+/// Extractors, which wrap type of parameter (i.e. `Query<u32>`).
+static PARAMETER_EXTRACTORS: &[&str] = &["Query", "Path"];
+
+/// Resolve schema of parameter from one of `schema`, `schema_json`, `enum_values` or `from_arg`
+/// attributes. If parameter name is missing, name of argument is used.
+pub(super) fn resolve_parameter_schema(
+    sig: &Signature,
+    name: &mut Option<String>,
+    schema: Option<&Path>,
+    schema_json: Option<&SchemaJson>,
+    enum_values: Option<&EnumValues>,
+    from_arg: Option<&str>,
+) -> Result<ParameterSchema, Error> {
+    if name.is_none() && from_arg.is_none() {
+        return Err(Error::custom("Parameter should have `name` attribute"));
+    }
+    let sources = [
+        schema.is_some(),
+        schema_json.is_some(),
+        enum_values.is_some(),
+        from_arg.is_some(),
+    ];
+    match sources.into_iter().filter(|x| *x).count() {
+        0 => {
+            return Err(Error::custom(
+                "Parameter should have one of `schema`, `schema_json`, `enum_values` or `from_arg` attributes",
+            ))
+        }
+        1 => {}
+        _ => {
+            return Err(Error::custom(
+                "Parameter can have only one of `schema`, `schema_json`, `enum_values` or `from_arg` attributes",
+            ))
+        }
+    }
+    if let Some(schema) = schema {
+        return Ok(ParameterSchema::Type(Type::Path(TypePath {
+            qself: None,
+            path: schema.clone(),
+        })));
+    }
+    if let Some(schema_json) = schema_json {
+        return Ok(ParameterSchema::Json(schema_json.clone()));
+    }
+    if let Some(enum_values) = enum_values {
+        return Ok(ParameterSchema::Json(enum_values.to_schema_json()));
+    }
+    let from_arg = from_arg.expect("checked above");
+    let _ = name.get_or_insert_with(|| from_arg.to_owned());
+    Ok(ParameterSchema::Type(schema_type_from_arg(sig, from_arg)?))
+}
+
+/// Find schema type of parameter, bound to function argument `name`.
+///
+/// Supported arguments:
+///   - `name: T` (`T`, or inner type if `T` is `Query<_>` or `Path<_>`);
+///   - `Wrapper(name): Wrapper<T>` (`T`);
+///   - `Wrapper((a, name)): Wrapper<(A, T)>` (`T`).
+pub(super) fn schema_type_from_arg(sig: &Signature, name: &str) -> Result<Type, Error> {
+    for pt in sig.inputs.iter().filter_map(|x| match x {
+        FnArg::Receiver(_) => None,
+        FnArg::Typed(y) => Some(y),
+    }) {
+        if let Some(ty) = find_in_pattern(&pt.pat, &pt.ty, name) {
+            return Ok(ty);
+        }
+    }
+    Err(Error::syn_spanned(
+        &sig.inputs,
+        format!("Argument `{name}` not found in function signature"),
+    ))
+}
+
+fn find_in_pattern(pat: &Pat, ty: &Type, name: &str) -> Option<Type> {
+    match pat {
+        Pat::Ident(x) if x.ident == name => Some(match single_generic_argument(ty) {
+            Some((wrapper, inner)) if PARAMETER_EXTRACTORS.contains(&wrapper.as_str()) => {
+                inner.clone()
+            }
+            _ => ty.clone(),
+        }),
+        Pat::TupleStruct(x) if x.elems.len() == 1 => {
+            let (_, inner) = single_generic_argument(ty)?;
+            find_in_pattern(&x.elems[0], inner, name)
+        }
+        Pat::Tuple(x) => {
+            let Type::Tuple(tuple) = ty else {
+                return None;
+            };
+            x.elems
+                .iter()
+                .zip(tuple.elems.iter())
+                .find_map(|(pat, ty)| find_in_pattern(pat, ty, name))
+        }
+        Pat::Paren(x) => find_in_pattern(&x.pat, ty, name),
+        _ => None,
+    }
+}
+
+/// Name of type and its single generic type argument (i.e. `Query` and `u32` for `Query<u32>`).
+pub(super) fn single_generic_argument(ty: &Type) -> Option<(String, &Type)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(ref args) = segment.arguments else {
+        return None;
+    };
+    let mut types = args.args.iter().filter_map(|x| match x {
+        GenericArgument::Type(y) => Some(y),
+        _ => None,
+    });
+    match (types.next(), types.next()) {
+        (Some(inner), None) => Some((segment.ident.to_string(), inner)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn find_argument_type() {
+        let sig: Signature = parse_quote! {
+            fn handle(
+                page: Query<u32>,
+                Path((id, name)): Path<(u64, String)>,
+                limit: Option<u32>,
+                Json(body): Json<Body>,
+            )
+        };
+        let find = |name| schema_type_from_arg(&sig, name).ok();
+        assert_eq!(find("page"), Some(parse_quote!(u32)));
+        assert_eq!(find("id"), Some(parse_quote!(u64)));
+        assert_eq!(find("name"), Some(parse_quote!(String)));
+        assert_eq!(find("limit"), Some(parse_quote!(Option<u32>)));
+        assert_eq!(find("body"), Some(parse_quote!(Body)));
+        assert_eq!(find("missing"), None);
+    }
+}
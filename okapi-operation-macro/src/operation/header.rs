@@ -3,15 +3,23 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::Path;
 
-use crate::{operation::parameters::ParameterStyle, utils::quote_option};
+use crate::{
+    operation::{
+        parameters::ParameterStyle,
+        schema_json::{EnumValues, SchemaJson},
+    },
+    utils::{quote_option, string_value},
+};
 
 pub(super) static HEADER_ATTRIBUTE_NAME: &str = "header";
 
 /// Header common description (in both `parameters` and `responses` sections).
 #[derive(Debug, FromMeta)]
+#[darling(and_then = "Self::validate")]
 pub(super) struct Header {
-    pub name: String,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
+    name: Option<String>,
+    #[darling(default, with = string_value)]
     description: Option<String>,
     #[darling(default)]
     required: bool,
@@ -19,31 +27,92 @@ pub(super) struct Header {
     deprecated: bool,
     #[darling(default)]
     style: Option<ParameterStyle>,
-    schema: Path,
+    #[darling(default, with = string_value)]
+    schema: Option<Path>,
+    #[darling(default, with = string_value)]
+    typed: Option<Path>,
+    #[darling(default, with = string_value)]
+    schema_json: Option<SchemaJson>,
+    #[darling(default, with = string_value)]
+    enum_values: Option<EnumValues>,
     // TODO: support content as well
 }
 
 impl Header {
+    fn validate(self) -> Result<Self, darling::Error> {
+        let schemas = [
+            self.schema.is_some(),
+            self.typed.is_some(),
+            self.schema_json.is_some(),
+            self.enum_values.is_some(),
+        ];
+        match schemas.into_iter().filter(|x| *x).count() {
+            0 => {
+                return Err(darling::Error::custom(
+                    "Header should have one of `schema`, `typed`, `schema_json` or `enum_values` attributes",
+                ))
+            }
+            1 => {}
+            _ => {
+                return Err(darling::Error::custom(
+                    "Header can have only one of `schema`, `typed`, `schema_json` or `enum_values` attributes",
+                ))
+            }
+        }
+        if self.name.is_none() && self.typed.is_none() {
+            return Err(darling::Error::missing_field("name"));
+        }
+        Ok(self)
+    }
+
+    /// Expression, producing name of header.
+    pub(super) fn name(&self) -> TokenStream {
+        match (&self.name, &self.typed) {
+            (Some(name), _) => quote! { #name.to_owned() },
+            (None, Some(ty)) => quote! { <#ty as ToHeaderSchema>::header_name() },
+            (None, None) => unreachable!("validated on parsing"),
+        }
+    }
+
+    fn description(&self) -> TokenStream {
+        match (&self.description, &self.typed) {
+            (Some(description), _) => quote! { Some(#description.into()) },
+            (None, Some(ty)) => quote! { <#ty as ToHeaderSchema>::description() },
+            (None, None) => quote! { None },
+        }
+    }
+
     fn schema(&self) -> TokenStream {
         let style = quote_option(&self.style);
-        let ty = &self.schema;
+        let schema = match (
+            &self.schema,
+            &self.typed,
+            &self.schema_json,
+            &self.enum_values,
+        ) {
+            (Some(ty), ..) => quote! { components.schema_for::<#ty>() },
+            (None, Some(ty), ..) => quote! { <#ty as ToHeaderSchema>::generate(components)? },
+            (None, None, Some(json), _) => quote! { #json },
+            (None, None, None, Some(values)) => values.to_schema_json().into_token_stream(),
+            (None, None, None, None) => unreachable!("validated on parsing"),
+        };
         quote! {
             okapi::openapi3::ParameterValue::Schema {
                 style: #style,
                 explode: None,
                 allow_reserved: false,
-                schema: components.schema_for::<#ty>(),
+                schema: #schema,
                 example: Default::default(),
                 examples: Default::default(),
             }
         }
     }
 
-    pub(super) fn for_parameter(&self) -> ParameterHeader {
+    pub(super) fn for_parameter(&self) -> ParameterHeader<'_> {
         ParameterHeader(self)
     }
 
-    pub(super) fn for_response(&self) -> ResponseHeader {
+    pub(super) fn for_response(&self) -> ResponseHeader<'_> {
         ResponseHeader(self)
     }
 }
@@ -53,14 +122,14 @@ pub(super) struct ParameterHeader<'a>(pub(super) &'a Header);
 
 impl<'a> ToTokens for ParameterHeader<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let name = &self.0.name;
-        let description = quote_option(&self.0.description);
+        let name = self.0.name();
+        let description = self.0.description();
         let required = &self.0.required;
         let deprecated = &self.0.deprecated;
         let schema = self.0.schema();
         let new_tokens = quote! {
             okapi::openapi3::Parameter {
-                name: #name.into(),
+                name: #name,
                 location: "header".into(),
                 description: #description,
                 required: #required,
@@ -79,7 +148,7 @@ pub(super) struct ResponseHeader<'a>(pub(super) &'a Header);
 
 impl<'a> ToTokens for ResponseHeader<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let description = quote_option(&self.0.description);
+        let description = self.0.description();
         let required = &self.0.required;
         let deprecated = &self.0.deprecated;
         let schema = self.0.schema();
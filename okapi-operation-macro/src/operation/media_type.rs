@@ -0,0 +1,93 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+/// Media type (MIME type, like `application/json`), validated during parsing.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct MediaType(String);
+
+impl MediaType {
+    /// Parse media type, returning message with description of problem if it is invalid.
+    pub(super) fn parse(value: &str) -> Result<Self, String> {
+        let mut parts = value.split(';');
+        let essence = parts.next().unwrap_or_default().trim();
+        let (type_, subtype) = essence
+            .split_once('/')
+            .ok_or_else(|| format!("Invalid media type `{value}`, expected `type/subtype`"))?;
+        if !is_token(type_) || !is_token(subtype) || (type_ == "*" && subtype != "*") {
+            return Err(format!(
+                "Invalid media type `{value}`, type and subtype should be non-empty tokens"
+            ));
+        }
+        for parameter in parts {
+            let valid = parameter
+                .trim()
+                .split_once('=')
+                .is_some_and(|(name, value)| {
+                    is_token(name) && (is_token(value) || is_quoted_string(value))
+                });
+            if !valid {
+                return Err(format!(
+                    "Invalid parameter `{}` of media type `{value}`, expected `name=value`",
+                    parameter.trim()
+                ));
+            }
+        }
+        Ok(Self(value.into()))
+    }
+}
+
+impl FromMeta for MediaType {
+    fn from_string(value: &str) -> Result<Self, darling::Error> {
+        Self::parse(value).map_err(darling::Error::custom)
+    }
+}
+
+impl ToTokens for MediaType {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = &self.0;
+        tokens.extend(quote! { #value });
+    }
+}
+
+/// Check whether value is token (RFC 9110, section 5.6.2).
+pub(super) fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&c))
+}
+
+fn is_quoted_string(value: &str) -> bool {
+    value.len() >= 2 && value.starts_with('"') && value.ends_with('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        for value in [
+            "application/json",
+            "application/problem+json",
+            "text/plain; charset=utf-8",
+            "multipart/form-data; boundary=\"abc def\"",
+            "image/*",
+            "*/*",
+        ] {
+            assert_eq!(MediaType::parse(value), Ok(MediaType(value.into())));
+        }
+        for value in [
+            "json",
+            "application/",
+            "/json",
+            "application/json/x",
+            "*/json",
+            "text/plain; charset",
+            "text plain/x",
+        ] {
+            assert!(MediaType::parse(value).is_err(), "{value} is invalid");
+        }
+    }
+}
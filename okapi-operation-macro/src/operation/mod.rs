@@ -3,26 +3,46 @@ use std::cell::RefCell;
 use darling::{ast::NestedMeta, FromMeta};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, ToTokens};
-use syn::{Ident, ItemFn, Visibility};
+use syn::{Ident, ItemFn, Signature, TraitItemFn, Type, Visibility};
 
-use self::{external_docs::ExternalDocs, request_body::RequestBody, response::Responses};
+use self::{
+    collect::{Collect, COLLECT_ATTRIBUTE_NAME},
+    conditional::Conditional,
+    discriminator::Discriminator,
+    external_docs::ExternalDocs,
+    request_body::{BodyDetection, RequestBody},
+    response::Responses,
+    route::{HttpMethod, Route, RoutePath},
+    typed_header::TypedHeaderArgument,
+};
 use crate::{
     error::Error,
     operation::{parameters::Parameters, security::Security},
-    utils::quote_option,
+    utils::{nested, quote_option, string_value},
     OPENAPI_FUNCTION_NAME_SUFFIX,
 };
 
+mod collect;
+mod conditional;
+mod constraints;
 mod cookie;
+mod discriminator;
 mod external_docs;
+mod from_arg;
 mod header;
+mod media_type;
+mod pagination;
 mod parameters;
 mod path;
 mod query;
 mod reference;
 mod request_body;
 mod response;
+mod route;
+mod schema_json;
 mod security;
+mod typed_header;
+mod xml;
 
 // TODO:
 //  - support examples ??
@@ -39,30 +59,55 @@ thread_local! {
 
 #[derive(Debug, FromMeta)]
 struct OperationAttrs {
-    #[darling(default)]
+    #[darling(default, with = string_value)]
     summary: Option<String>,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
     description: Option<String>,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
     operation_id: Option<String>,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
     tags: Option<String>,
     #[darling(default)]
     deprecated: bool,
     #[darling(default)]
+    idempotent: bool,
+    #[darling(default, with = nested)]
+    conditional: Option<Conditional>,
+    #[darling(multiple)]
+    discriminator: Vec<Discriminator>,
+    #[darling(default, with = nested)]
     external_docs: Option<ExternalDocs>,
-    #[darling(default)]
+    #[darling(default, with = nested)]
     parameters: Parameters,
-    #[darling(default)]
+    #[darling(default, with = nested)]
     responses: Responses,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
+    response_type: Option<Type>,
+    #[darling(default, with = nested)]
     security: Option<Security>,
+    #[darling(default, with = string_value)]
+    path_override: Option<String>,
+    #[darling(default, with = string_value)]
+    path: Option<RoutePath>,
+    #[darling(default, with = string_value)]
+    method: Option<HttpMethod>,
+    #[darling(default)]
+    collect: Option<Collect>,
+    #[darling(default)]
+    body_detection: BodyDetection,
+    #[darling(default)]
+    warn_unknown_extractors: bool,
 
-    #[darling(default = "OperationAttrs::default_crate_name", rename = "crate")]
+    #[darling(
+        default = "OperationAttrs::default_crate_name",
+        rename = "crate",
+        with = string_value
+    )]
     crate_name: String,
     #[darling(
         default = "OperationAttrs::default_attribute_name",
-        rename = "rename_attribute"
+        rename = "rename_attribute",
+        with = string_value
     )]
     attribute_name: String,
 }
@@ -112,21 +157,90 @@ impl OperationAttrs {
     }
 }
 
+/// Function, annotated with macro.
+pub(crate) enum Handler {
+    /// Free function or method in `impl` block.
+    Fn(ItemFn),
+    /// Method in trait definition (with or without default implementation).
+    TraitFn(TraitItemFn),
+}
+
+impl Handler {
+    fn sig(&self) -> &Signature {
+        match self {
+            Self::Fn(x) => &x.sig,
+            Self::TraitFn(x) => &x.sig,
+        }
+    }
+
+    fn sig_mut(&mut self) -> &mut Signature {
+        match self {
+            Self::Fn(x) => &mut x.sig,
+            Self::TraitFn(x) => &mut x.sig,
+        }
+    }
+}
+
+impl ToTokens for Handler {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Fn(x) => x.to_tokens(tokens),
+            Self::TraitFn(x) => x.to_tokens(tokens),
+        }
+    }
+}
+
 pub(crate) fn openapi(
     attrs: proc_macro::TokenStream,
-    mut input: ItemFn,
+    mut input: Handler,
 ) -> Result<TokenStream, Error> {
     let attrs = NestedMeta::parse_meta_list(attrs.into())?;
     let mut operation_attrs = OperationAttrs::from_list(&attrs)?;
 
     set_current_attribute_name(operation_attrs.attribute_name.clone());
 
+    let route = Route::new(operation_attrs.path.take(), operation_attrs.method.take())
+        .map_err(Error::custom)?;
+    let collected_route = match operation_attrs.collect {
+        Some(ref collect) => {
+            validate_collect(&input)?;
+            Some(collect.route(route.as_ref()).map_err(Error::custom)?)
+        }
+        None => None,
+    };
+
+    operation_attrs.parameters.resolve_schemas(input.sig())?;
+    let is_trait_method = matches!(input, Handler::TraitFn(_));
+    if let Some(ty) = operation_attrs.response_type.take() {
+        operation_attrs.responses.set_return_type(ty)?;
+    }
+    let ignore_return_type = operation_attrs.responses.ignore_return_type;
     operation_attrs
         .responses
-        .add_return_type(&input, operation_attrs.responses.ignore_return_type);
-    let request_body = RequestBody::from_item_fn(&mut input)?;
-    let openapi_generator_fn =
-        build_openapi_generator_fn(&input.sig.ident, &input.vis, operation_attrs, request_body)?;
+        .add_return_type(input.sig(), is_trait_method, ignore_return_type)?;
+    let unknown_extractors = if operation_attrs.warn_unknown_extractors {
+        RequestBody::unknown_extractors(input.sig(), &operation_attrs.body_detection)
+    } else {
+        vec![]
+    };
+    let typed_headers = TypedHeaderArgument::from_signature(input.sig());
+    let request_body =
+        RequestBody::from_signature(input.sig_mut(), &operation_attrs.body_detection)?;
+    let vis = match input {
+        Handler::Fn(ref x) => x.vis.clone(),
+        Handler::TraitFn(_) => Visibility::Inherited,
+    };
+    let openapi_generator_fn = build_openapi_generator_fn(
+        &input.sig().ident,
+        &vis,
+        is_trait_method,
+        operation_attrs,
+        request_body,
+        &unknown_extractors,
+        &typed_headers,
+        route,
+        collected_route,
+    )?;
     let output = quote! {
         #input
 
@@ -135,11 +249,48 @@ pub(crate) fn openapi(
     Ok(output)
 }
 
+/// Check that operation could be registered in global inventory.
+fn validate_collect(input: &Handler) -> Result<(), Error> {
+    if cfg!(not(feature = "auto-collect")) {
+        return Err(Error::custom(format!(
+            "`{COLLECT_ATTRIBUTE_NAME}` requires `auto-collect` feature of okapi-operation"
+        )));
+    }
+    let Handler::Fn(item_fn) = input else {
+        return Err(Error::syn_spanned(
+            input.sig(),
+            format!("`{COLLECT_ATTRIBUTE_NAME}` cannot be used on trait methods"),
+        ));
+    };
+    if item_fn.sig.receiver().is_some()
+        || item_fn
+            .sig
+            .generics
+            .params
+            .iter()
+            .any(|x| !matches!(x, syn::GenericParam::Lifetime(_)))
+    {
+        return Err(Error::syn_spanned(
+            &item_fn.sig,
+            format!(
+                "`{COLLECT_ATTRIBUTE_NAME}` can be used only on non-generic functions without receiver"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn build_openapi_generator_fn(
     handler_name: &Ident,
     vis: &Visibility,
+    is_trait_method: bool,
     attrs: OperationAttrs,
     request_body: Option<RequestBody>,
+    unknown_extractors: &[String],
+    typed_headers: &[TypedHeaderArgument],
+    route: Option<Route>,
+    collected_route: Option<Route>,
 ) -> Result<TokenStream, Error> {
     let name = format_ident!("{}{}", handler_name, OPENAPI_FUNCTION_NAME_SUFFIX);
 
@@ -155,13 +306,39 @@ fn build_openapi_generator_fn(
     });
     let parameters = &attrs.parameters;
     let responses = &attrs.responses;
+    let handler_name_str = handler_name.to_string();
+    let handler_name_str = handler_name_str.trim_start_matches("r#");
+    let path_override = attrs
+        .path_override
+        .as_ref()
+        .map(|x| quote! { .path_override(#x) });
+    let idempotent = attrs
+        .idempotent
+        .then(|| quote! { document_idempotency_key(&mut operation); });
+    let conditional = &attrs.conditional;
+    let discriminators = &attrs.discriminator;
+    let submit = collected_route.map(|x| collect::submit(&x, &crate_name, &name));
+    // Keep trait dyn compatible
+    let where_clause = is_trait_method.then(|| quote! { where Self: Sized });
     Ok(quote! {
         #[allow(non_snake_case, unused)]
         #vis fn #name(
             components: &mut #crate_name::Components
-        ) -> std::result::Result<#crate_name::okapi::openapi3::Operation, anyhow::Error> {
+        ) -> std::result::Result<#crate_name::okapi::openapi3::Operation, #crate_name::Error>
+        #where_clause
+        {
             use #crate_name::_macro_prelude::*;
 
+            components.set_operation_context(
+                OperationContext::new(#handler_name_str)
+                    #path_override
+                    #route
+                    #(.unknown_extractor(#unknown_extractors))*
+            );
+            if components.is_route_probe() {
+                return Ok(Default::default());
+            }
+            #(#discriminators)*
             let mut operation = okapi::openapi3::Operation {
                 #attrs
                 #request_body
@@ -169,8 +346,13 @@ fn build_openapi_generator_fn(
                 #parameters
                 ..Default::default()
             };
+            #idempotent
+            #conditional
+            #(#typed_headers)*
             Ok(operation)
         }
+
+        #submit
     })
 }
 
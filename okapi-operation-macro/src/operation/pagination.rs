@@ -0,0 +1,135 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+use crate::utils::string_value;
+
+pub(super) static PAGINATION_ATTRIBUTE_NAME: &str = "pagination";
+
+/// Kind of pagination.
+#[derive(Debug, Default, FromMeta, PartialEq)]
+#[darling(rename_all = "snake_case")]
+enum PaginationStyle {
+    #[default]
+    Page,
+    Cursor,
+}
+
+/// Query parameters of paginated request.
+#[derive(Debug, FromMeta, PartialEq)]
+#[darling(and_then = "Self::validate")]
+pub(super) struct Pagination {
+    #[darling(default)]
+    style: PaginationStyle,
+    #[darling(default, with = string_value)]
+    page: Option<String>,
+    #[darling(default, with = string_value)]
+    cursor: Option<String>,
+    #[darling(default, with = string_value)]
+    page_size: Option<String>,
+    #[darling(default)]
+    max_page_size: Option<u64>,
+}
+
+impl Pagination {
+    fn validate(self) -> darling::Result<Self> {
+        match self.style {
+            PaginationStyle::Page if self.cursor.is_some() => Err(darling::Error::custom(
+                "`cursor` can be used only with `style = \"cursor\"`",
+            )),
+            PaginationStyle::Cursor if self.page.is_some() => Err(darling::Error::custom(
+                "`page` can be used only with `style = \"page\"`",
+            )),
+            _ => Ok(self),
+        }
+    }
+}
+
+impl ToTokens for Pagination {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let (position, position_description, position_schema, page_size) = match self.style {
+            PaginationStyle::Page => (
+                self.page.as_deref().unwrap_or("page"),
+                "Number of page (starting from 1)",
+                quote! {{
+                    let mut schema = components.schema_for::<u64>();
+                    schema.number().minimum = Some(1.0);
+                    schema
+                }},
+                self.page_size.as_deref().unwrap_or("per_page"),
+            ),
+            PaginationStyle::Cursor => (
+                self.cursor.as_deref().unwrap_or("cursor"),
+                "Cursor of page",
+                quote! { components.schema_for::<String>() },
+                self.page_size.as_deref().unwrap_or("limit"),
+            ),
+        };
+        let maximum = self
+            .max_page_size
+            .map(|x| quote! { schema.number().maximum = Some(#x as f64); });
+        let parameter = |name: &str, description: &str, schema: TokenStream| {
+            quote! {
+                okapi::openapi3::Parameter {
+                    name: #name.into(),
+                    location: "query".into(),
+                    description: Some(#description.into()),
+                    required: false,
+                    deprecated: false,
+                    allow_empty_value: false,
+                    value: okapi::openapi3::ParameterValue::Schema {
+                        style: None,
+                        explode: None,
+                        allow_reserved: false,
+                        schema: #schema,
+                        example: Default::default(),
+                        examples: Default::default(),
+                    },
+                    extensions: Default::default(),
+                }
+            }
+        };
+        let position = parameter(position, position_description, position_schema);
+        let page_size = parameter(
+            page_size,
+            "Maximum number of items in page",
+            quote! {{
+                let mut schema = components.schema_for::<u64>();
+                schema.number().minimum = Some(1.0);
+                #maximum
+                schema
+            }},
+        );
+        tokens.extend(quote! {
+            v.push(okapi::openapi3::RefOr::Object(#position));
+            v.push(okapi::openapi3::RefOr::Object(#page_size));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Meta};
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let input: Meta =
+            parse_quote! { pagination(style = "cursor", page_size = "size", max_page_size = 50) };
+
+        assert_eq!(
+            Pagination::from_meta(&input).expect("Successfully parsed"),
+            Pagination {
+                style: PaginationStyle::Cursor,
+                page: None,
+                cursor: None,
+                page_size: Some("size".into()),
+                max_page_size: Some(50),
+            }
+        );
+
+        let input: Meta = parse_quote! { pagination(cursor = "after") };
+        assert!(Pagination::from_meta(&input).is_err());
+    }
+}
@@ -1,17 +1,19 @@
 use darling::FromMeta;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::{punctuated::Punctuated, Meta, Token};
+use syn::{punctuated::Punctuated, Meta, Signature, Token};
 
 use super::cookie::{Cookie, COOKIE_ATTRIBUTE_NAME};
 use crate::{
+    error::Error,
     operation::{
         header::{Header, HEADER_ATTRIBUTE_NAME},
+        pagination::{Pagination, PAGINATION_ATTRIBUTE_NAME},
         path::{Path, PATH_ATTRIBUTE_NAME},
         query::{Query, QUERY_ATTRIBUTE_NAME},
         reference::{Reference, REFERENCE_ATTRIBUTE_NAME},
     },
-    utils::meta_to_meta_list,
+    utils::{meta_to_meta_list, nested, string_value},
 };
 
 // TODO: support cookie parameters
@@ -52,6 +54,7 @@ pub(super) struct Parameters {
     query_parameters: Vec<Query>,
     cookie_parameters: Vec<Cookie>,
     ref_parameters: Vec<Reference>,
+    pagination: Option<Pagination>,
 }
 
 impl FromMeta for Parameters {
@@ -64,31 +67,58 @@ impl FromMeta for Parameters {
                 .get_ident()
                 .ok_or_else(|| darling::Error::custom("Should have Ident").with_span(&meta))?;
             if meta_ident == HEADER_ATTRIBUTE_NAME {
-                this.header_parameters.push(Header::from_meta(&meta)?);
+                this.header_parameters.push(nested::<Header>(&meta)?);
             } else if meta_ident == PATH_ATTRIBUTE_NAME {
-                this.path_parameters.push(Path::from_meta(&meta)?);
+                this.path_parameters.push(nested::<Path>(&meta)?);
             } else if meta_ident == QUERY_ATTRIBUTE_NAME {
-                this.query_parameters.push(Query::from_meta(&meta)?);
+                this.query_parameters.push(nested::<Query>(&meta)?);
             } else if meta_ident == COOKIE_ATTRIBUTE_NAME {
-                this.cookie_parameters.push(Cookie::from_meta(&meta)?);
+                this.cookie_parameters.push(nested::<Cookie>(&meta)?);
             } else if meta_ident == REFERENCE_ATTRIBUTE_NAME {
-                this.ref_parameters.push(Reference::from_meta(&meta)?);
+                this.ref_parameters.push(string_value::<Reference>(&meta)?);
+            } else if meta_ident == PAGINATION_ATTRIBUTE_NAME {
+                if this.pagination.is_some() {
+                    return Err(darling::Error::custom("Only single pagination allowed")
+                        .with_span(meta_ident));
+                }
+                this.pagination = Some(nested::<Pagination>(&meta)?);
             } else {
-                return Err(
-                    darling::Error::custom("Unsupported type of parameter").with_span(meta_ident)
-                );
+                return Err(darling::Error::custom(format!(
+                    "Unsupported type of parameter `{meta_ident}`, expected one of `{}`, `{}`, `{}`, `{}`, `{}` or `{}`",
+                    HEADER_ATTRIBUTE_NAME,
+                    PATH_ATTRIBUTE_NAME,
+                    QUERY_ATTRIBUTE_NAME,
+                    COOKIE_ATTRIBUTE_NAME,
+                    REFERENCE_ATTRIBUTE_NAME,
+                    PAGINATION_ATTRIBUTE_NAME,
+                ))
+                .with_span(meta_ident));
             }
         }
         Ok(this)
     }
 }
 
+impl Parameters {
+    /// Resolve schemas of parameters, which reference function arguments.
+    pub(super) fn resolve_schemas(&mut self, sig: &Signature) -> Result<(), Error> {
+        for parameter in &mut self.path_parameters {
+            parameter.resolve_schema(sig)?;
+        }
+        for parameter in &mut self.query_parameters {
+            parameter.resolve_schema(sig)?;
+        }
+        Ok(())
+    }
+}
+
 impl ToTokens for Parameters {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let header_parameters = self.header_parameters.iter().map(|x| x.for_parameter());
         let path_parameters = &self.path_parameters;
         let query_parameters = &self.query_parameters;
         let ref_parameters = &self.ref_parameters;
+        let pagination = &self.pagination;
         tokens.extend(quote! {
             parameters: {
                 let mut v = Vec::new();
@@ -96,6 +126,7 @@ impl ToTokens for Parameters {
                 #(v.push(okapi::openapi3::RefOr::Object(#path_parameters));)*
                 #(v.push(okapi::openapi3::RefOr::Object(#query_parameters));)*
                 #(v.push(#ref_parameters);)*
+                #pagination
                 v
             },
         });
@@ -1,32 +1,73 @@
 use darling::FromMeta;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
+use syn::Signature;
 
-use crate::{operation::parameters::ParameterStyle, utils::quote_option};
+use crate::{
+    error::Error,
+    operation::{
+        constraints::SchemaConstraints,
+        from_arg::resolve_parameter_schema,
+        parameters::ParameterStyle,
+        schema_json::{EnumValues, ParameterSchema, SchemaJson},
+    },
+    utils::{quote_option, string_value},
+};
 
 pub(super) static PATH_ATTRIBUTE_NAME: &str = "path";
 
 /// Path parameter.
 #[derive(Debug, FromMeta)]
 pub(super) struct Path {
-    name: String,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
+    name: Option<String>,
+    #[darling(default, with = string_value)]
     description: Option<String>,
     #[darling(default)]
     deprecated: bool,
     #[darling(default)]
     style: Option<ParameterStyle>,
-    schema: syn::Path,
+    #[darling(default, with = string_value)]
+    schema: Option<syn::Path>,
+    #[darling(default, with = string_value)]
+    schema_json: Option<SchemaJson>,
+    #[darling(default, with = string_value)]
+    enum_values: Option<EnumValues>,
+    #[darling(default, with = string_value)]
+    from_arg: Option<String>,
+    #[darling(flatten)]
+    constraints: SchemaConstraints,
+    /// Schema, resolved from `schema`, `schema_json`, `enum_values` or `from_arg`.
+    #[darling(skip)]
+    resolved_schema: Option<ParameterSchema>,
     // TODO: support content as well
 }
 
+impl Path {
+    pub(super) fn resolve_schema(&mut self, sig: &Signature) -> Result<(), Error> {
+        self.resolved_schema = Some(resolve_parameter_schema(
+            sig,
+            &mut self.name,
+            self.schema.as_ref(),
+            self.schema_json.as_ref(),
+            self.enum_values.as_ref(),
+            self.from_arg.as_deref(),
+        )?);
+        Ok(())
+    }
+}
+
 impl ToTokens for Path {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let name = &self.name;
+        let name = self.name.as_deref().unwrap_or_default();
         let description = quote_option(&self.description);
         let deprecated = &self.deprecated;
         let style = quote_option(&self.style);
-        let ty = &self.schema;
+        let schema = self.constraints.apply_to(
+            self.resolved_schema
+                .as_ref()
+                .expect("Schema should be resolved"),
+        );
         tokens.extend(quote! {
             okapi::openapi3::Parameter {
                 name: #name.into(),
@@ -40,7 +81,7 @@ impl ToTokens for Path {
                         style: #style,
                         explode: None,
                         allow_reserved: false,
-                        schema: components.schema_for::<#ty>(),
+                        schema: #schema,
                         example: Default::default(),
                         examples: Default::default(),
                     }
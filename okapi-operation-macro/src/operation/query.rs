@@ -1,17 +1,27 @@
 use darling::FromMeta;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::Path;
+use syn::{Path, Signature};
 
-use crate::{operation::parameters::ParameterStyle, utils::quote_option};
+use crate::{
+    error::Error,
+    operation::{
+        constraints::SchemaConstraints,
+        from_arg::resolve_parameter_schema,
+        parameters::ParameterStyle,
+        schema_json::{EnumValues, ParameterSchema, SchemaJson},
+    },
+    utils::{quote_option, string_value},
+};
 
 pub(super) static QUERY_ATTRIBUTE_NAME: &str = "query";
 
 /// Query parameter.
 #[derive(Debug, FromMeta)]
 pub(super) struct Query {
-    name: String,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
+    name: Option<String>,
+    #[darling(default, with = string_value)]
     description: Option<String>,
     #[darling(default)]
     required: bool,
@@ -25,13 +35,39 @@ pub(super) struct Query {
     allow_empty_value: bool,
     #[darling(default)]
     allow_reserved: bool,
-    schema: Path,
+    #[darling(default, with = string_value)]
+    schema: Option<Path>,
+    #[darling(default, with = string_value)]
+    schema_json: Option<SchemaJson>,
+    #[darling(default, with = string_value)]
+    enum_values: Option<EnumValues>,
+    #[darling(default, with = string_value)]
+    from_arg: Option<String>,
+    #[darling(flatten)]
+    constraints: SchemaConstraints,
+    /// Schema, resolved from `schema`, `schema_json`, `enum_values` or `from_arg`.
+    #[darling(skip)]
+    resolved_schema: Option<ParameterSchema>,
     // TODO: support content as well
 }
 
+impl Query {
+    pub(super) fn resolve_schema(&mut self, sig: &Signature) -> Result<(), Error> {
+        self.resolved_schema = Some(resolve_parameter_schema(
+            sig,
+            &mut self.name,
+            self.schema.as_ref(),
+            self.schema_json.as_ref(),
+            self.enum_values.as_ref(),
+            self.from_arg.as_deref(),
+        )?);
+        Ok(())
+    }
+}
+
 impl ToTokens for Query {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let name = &self.name;
+        let name = self.name.as_deref().unwrap_or_default();
         let description = quote_option(&self.description);
         let required = &self.required;
         let deprecated = &self.deprecated;
@@ -39,7 +75,11 @@ impl ToTokens for Query {
         let explode = quote_option(&self.explode);
         let allow_empty_values = &self.allow_empty_value;
         let allow_reserved = &self.allow_reserved;
-        let ty = &self.schema;
+        let schema = self.constraints.apply_to(
+            self.resolved_schema
+                .as_ref()
+                .expect("Schema should be resolved"),
+        );
         tokens.extend(quote! {
             okapi::openapi3::Parameter {
                 name: #name.into(),
@@ -53,7 +93,7 @@ impl ToTokens for Query {
                         style: #style,
                         explode: #explode,
                         allow_reserved: #allow_reserved,
-                        schema: components.schema_for::<#ty>(),
+                        schema: #schema,
                         example: Default::default(),
                         examples: Default::default(),
                     }
@@ -18,7 +18,7 @@ impl ToTokens for Reference {
         let reference = self.0.as_str();
         tokens.extend(quote! {
             okapi::openapi3::RefOr::Ref(okapi::openapi3::Ref {
-                reference: #reference.into()
+                reference: component_reference(components, #reference)
             })
         });
     }
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use syn::{PatType, Type};
 
-use super::{RequestBody, RequestBodyAttrs};
+use super::{unwrap_option, RequestBody, RequestBodyAttrs};
 use crate::error::Error;
 
 lazy_static::lazy_static! {
@@ -17,23 +17,80 @@ lazy_static::lazy_static! {
 
         // 3rd party types
         "Bytes",
+        "MsgPack",
+        "MsgPackRaw",
     ].into_iter().collect();
+
+    // Extractors, which are never body. Parameters extractors are documented
+    // with `parameters` attribute.
+    static ref KNOWN_NON_BODY_TYPES: HashSet<&'static str> = [
+        // axum types
+        "State",
+        "Extension",
+        "ConnectInfo",
+        "HeaderMap",
+        "Method",
+        "Uri",
+        "OriginalUri",
+        "MatchedPath",
+        "Extensions",
+        "Version",
+        "Path",
+        "Query",
+        "RawQuery",
+        "RawPathParams",
+
+        // 3rd party types
+        "TypedHeader",
+        "CookieJar",
+    ].into_iter().collect();
+}
+
+/// Kind of axum handler argument, recognized by type name.
+enum AxumArgument {
+    Body,
+    NonBody,
+}
+
+fn classify_axum(ty: &Type, extra_types: &[&str]) -> Option<AxumArgument> {
+    let Type::Path(ref path) = *ty else {
+        return None;
+    };
+    for pat_seg in path.path.segments.iter().rev() {
+        let ident = pat_seg.ident.to_string();
+        if KNOWN_BODY_TYPES.contains(ident.as_str()) || extra_types.contains(&ident.as_str()) {
+            return Some(AxumArgument::Body);
+        }
+        if KNOWN_NON_BODY_TYPES.contains(ident.as_str()) {
+            return Some(AxumArgument::NonBody);
+        }
+    }
+    None
 }
 
 impl RequestBody {
-    pub(super) fn try_find_axum(pt: &PatType) -> Result<Option<Self>, Error> {
-        let Type::Path(ref path) = *pt.ty else {
-            return Ok(None);
+    /// Detect body from argument type. Body is required, unless wrapped in `Option`
+    /// (i.e. `Option<Json<T>>`).
+    pub(super) fn try_find_axum(pt: &PatType, extra_types: &[&str]) -> Result<Option<Self>, Error> {
+        let (argument_type, required) = match unwrap_option(&pt.ty) {
+            Some(inner) => (inner, false),
+            None => (&*pt.ty, true),
         };
-        for pat_seg in path.path.segments.iter().rev() {
-            if KNOWN_BODY_TYPES.contains(pat_seg.ident.to_string().as_str()) {
-                return Ok(Some(Self {
-                    argument_type: *pt.ty.clone(),
-                    attrs: RequestBodyAttrs::default(),
-                }));
-            }
+        match classify_axum(argument_type, extra_types) {
+            Some(AxumArgument::Body) => Ok(Some(Self {
+                argument_type: argument_type.clone(),
+                attrs: RequestBodyAttrs {
+                    required,
+                    ..Default::default()
+                },
+            })),
+            Some(AxumArgument::NonBody) | None => Ok(None),
         }
+    }
 
-        Ok(None)
+    /// Whether argument type is recognized neither as body, nor as known extractor.
+    pub(super) fn is_unknown_axum(pt: &PatType, extra_types: &[&str]) -> bool {
+        let ty = unwrap_option(&pt.ty).unwrap_or(&pt.ty);
+        classify_axum(ty, extra_types).is_none()
     }
 }
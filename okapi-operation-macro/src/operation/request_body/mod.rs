@@ -1,11 +1,15 @@
 use darling::FromMeta;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::{FnArg, ItemFn, PatType, Path, Type};
+use syn::{
+    ext::IdentExt, parse::ParseStream, punctuated::Punctuated, Attribute, FnArg, Ident, LitStr,
+    Meta, PatType, Path, Signature, Token, Type,
+};
 
 use crate::{
     error::Error,
-    utils::{attribute_to_args, quote_option},
+    operation::{from_arg::single_generic_argument, media_type::MediaType, xml::Xml},
+    utils::{attribute_to_args, nested, quote_option, string_value},
 };
 
 #[cfg(feature = "axum")]
@@ -14,15 +18,97 @@ mod axum;
 static REQUEST_BODY_ATTRIBUTE_NAME_DEPRECATED: &str = "request_body";
 static REQUEST_BODY_ATTRIBUTE_NAME: &str = "body";
 
+/// Request body content definition (either `content = "..."` or
+/// `content(type = "...", mime = "...")`).
+#[derive(Debug)]
+struct Content {
+    ty: Path,
+    mime: Option<MediaType>,
+}
+
+impl FromMeta for Content {
+    fn from_meta(meta: &Meta) -> Result<Self, darling::Error> {
+        // NOTE: `type` is keyword, so it can't be parsed as `Meta`
+        if let Meta::List(list) = meta {
+            let mut ty = None;
+            let mut mime = None;
+            let pairs = list.parse_args_with(|input: ParseStream| {
+                Punctuated::<_, Token![,]>::parse_terminated_with(input, |input| {
+                    let name = input.call(Ident::parse_any)?;
+                    let _: Token![=] = input.parse()?;
+                    Ok((name, input.parse::<LitStr>()?))
+                })
+            })?;
+            for (name, value) in pairs {
+                if name == "type" {
+                    ty = Some(value.parse()?);
+                } else if name == "mime" {
+                    mime = Some(
+                        MediaType::parse(&value.value())
+                            .map_err(|err| darling::Error::custom(err).with_span(&value))?,
+                    );
+                } else {
+                    return Err(darling::Error::unknown_field(&name.to_string()).with_span(&name));
+                }
+            }
+            let ty = ty.ok_or_else(|| darling::Error::missing_field("type").with_span(list))?;
+            return Ok(Self { ty, mime });
+        }
+        Ok(Self {
+            ty: string_value(meta)?,
+            mime: None,
+        })
+    }
+}
+
+impl ToTokens for Content {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ty = &self.ty;
+        tokens.extend(if let Some(ref mime) = self.mime {
+            quote! { override_content_type(<#ty as ToMediaTypes>::generate(components)?, #mime)? }
+        } else {
+            quote! { <#ty as ToMediaTypes>::generate(components)? }
+        });
+    }
+}
+
+/// Detection of request body from types of arguments: `body_detection(off)` disables it,
+/// `body_detection(extra = "MyJson, ApiJson")` adds names of types, detected as body.
+#[derive(Debug, Default, FromMeta)]
+pub(super) struct BodyDetection {
+    #[darling(default)]
+    off: bool,
+    #[darling(default, with = string_value)]
+    extra: Option<String>,
+}
+
+impl BodyDetection {
+    /// Names of additional types, detected as body.
+    #[allow(unused)]
+    fn extra_types(&self) -> Vec<&str> {
+        self.extra
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .collect()
+    }
+}
+
 /// Request body definition for inline attribute.
 #[derive(Debug, FromMeta, Default)]
 struct RequestBodyAttrs {
-    #[darling(default)]
+    #[darling(default, with = string_value)]
     description: Option<String>,
     #[darling(default)]
     required: bool,
-    #[darling(default)]
-    content: Option<Path>,
+    #[darling(multiple)]
+    content: Vec<Content>,
+    #[darling(default, with = string_value)]
+    content_type: Option<MediaType>,
+    #[darling(default, with = nested)]
+    xml: Option<Xml>,
 }
 
 #[derive(Debug)]
@@ -33,8 +119,11 @@ pub(super) struct RequestBody {
 
 impl RequestBody {
     /// Create body definition from function signature.
-    pub(super) fn from_item_fn(item_fn: &mut ItemFn) -> Result<Option<Self>, Error> {
-        for pt in item_fn.sig.inputs.iter_mut().filter_map(|x| match x {
+    pub(super) fn from_signature(
+        sig: &mut Signature,
+        detection: &BodyDetection,
+    ) -> Result<Option<Self>, Error> {
+        for pt in sig.inputs.iter_mut().filter_map(|x| match x {
             FnArg::Receiver(_) => None,
             FnArg::Typed(y) => Some(y),
         }) {
@@ -42,7 +131,10 @@ impl RequestBody {
                 return Ok(Some(x));
             }
 
-            if let Some(x) = Self::try_find_framework_specific(pt)? {
+            if detection.off {
+                continue;
+            }
+            if let Some(x) = Self::try_find_framework_specific(pt, detection)? {
                 return Ok(Some(x));
             }
         }
@@ -50,6 +142,27 @@ impl RequestBody {
         Ok(None)
     }
 
+    /// Types of arguments, which are neither body, nor known extractors (should be called
+    /// before [`RequestBody::from_signature`], which removes `#[body]` attributes).
+    #[allow(unused)]
+    pub(super) fn unknown_extractors(sig: &Signature, detection: &BodyDetection) -> Vec<String> {
+        #[cfg(feature = "axum")]
+        return sig
+            .inputs
+            .iter()
+            .filter_map(|x| match x {
+                FnArg::Receiver(_) => None,
+                FnArg::Typed(y) => Some(y),
+            })
+            .filter(|pt| !pt.attrs.iter().any(is_body_attribute))
+            .filter(|pt| Self::is_unknown_axum(pt, &detection.extra_types()))
+            .map(|pt| pt.ty.to_token_stream().to_string().replace(' ', ""))
+            .collect();
+
+        #[cfg(not(feature = "axum"))]
+        Vec::new()
+    }
+
     // NOTE: also removes all related attributes
     fn try_find_in_arg_attrs(pt: &mut PatType) -> Result<Option<Self>, Error> {
         let mut non_matched_attrs = vec![];
@@ -57,9 +170,7 @@ impl RequestBody {
 
         // Check attributes, removing matching
         for attr in pt.attrs.drain(..) {
-            if attr.path().get_ident().map_or(false, |x| {
-                x == REQUEST_BODY_ATTRIBUTE_NAME || x == REQUEST_BODY_ATTRIBUTE_NAME_DEPRECATED
-            }) {
+            if is_body_attribute(&attr) {
                 matched_attrs.push(attr);
             } else {
                 non_matched_attrs.push(attr);
@@ -77,18 +188,38 @@ impl RequestBody {
             return Ok(None);
         };
         let parsed_attrs = RequestBodyAttrs::from_list(&attribute_to_args(&attr)?)?;
+        if parsed_attrs.content.len() > 1 && parsed_attrs.content_type.is_some() {
+            return Err(Error::syn_spanned(
+                attr,
+                "`content_type` cannot be used with multiple `content` definitions, \
+                use `content(type = \"...\", mime = \"...\")` instead",
+            ));
+        }
 
+        // Optional body is never required (same as detected body)
+        let argument_type = match unwrap_option(&pt.ty) {
+            Some(_) if parsed_attrs.required => {
+                return Err(Error::syn_spanned(
+                    attr,
+                    "Body of `Option<T>` argument is optional, `required = true` cannot be used",
+                ));
+            }
+            Some(inner) => inner,
+            None => &pt.ty,
+        };
         Ok(Some(Self {
             attrs: parsed_attrs,
-            argument_type: *pt.ty.clone(),
+            argument_type: argument_type.clone(),
         }))
     }
 
-    // TODO: allow disable this behaviour
     #[allow(unused)]
-    fn try_find_framework_specific(pt: &PatType) -> Result<Option<Self>, Error> {
+    fn try_find_framework_specific(
+        pt: &PatType,
+        detection: &BodyDetection,
+    ) -> Result<Option<Self>, Error> {
         #[cfg(feature = "axum")]
-        if let Some(x) = Self::try_find_axum(pt)? {
+        if let Some(x) = Self::try_find_axum(pt, &detection.extra_types())? {
             return Ok(Some(x));
         }
 
@@ -96,27 +227,79 @@ impl RequestBody {
     }
 }
 
+/// Inner type of `Option<T>`.
+fn unwrap_option(ty: &Type) -> Option<&Type> {
+    match single_generic_argument(ty) {
+        Some((wrapper, inner)) if wrapper == "Option" => Some(inner),
+        _ => None,
+    }
+}
+
+fn is_body_attribute(attr: &Attribute) -> bool {
+    attr.path().get_ident().is_some_and(|x| {
+        x == REQUEST_BODY_ATTRIBUTE_NAME || x == REQUEST_BODY_ATTRIBUTE_NAME_DEPRECATED
+    })
+}
+
 impl ToTokens for RequestBody {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let description = quote_option(&self.attrs.description);
         let required = self.attrs.required;
-        let content_generator = if let Some(ref x) = self.attrs.content {
-            quote! {
-                <#x as ToMediaTypes>::generate
+        let content = match self.attrs.content.as_slice() {
+            [] => {
+                let ty = &self.argument_type;
+                quote! { <#ty as ToMediaTypes>::generate(components)? }
             }
+            [content] => quote! { #content },
+            [content, rest @ ..] => quote! {{
+                let mut content = #content;
+                #(merge_media_types(&mut content, #rest)?;)*
+                content
+            }},
+        };
+        let content = if let Some(ref content_type) = self.attrs.content_type {
+            quote! { override_content_type(#content, #content_type)? }
         } else {
-            let ty = &self.argument_type;
-            quote! {
-                <#ty as ToMediaTypes>::generate
-            }
+            content
+        };
+        let content = if let Some(ref xml) = self.attrs.xml {
+            xml.wrap_content(content)
+        } else {
+            content
         };
         tokens.extend(quote! {
             okapi::openapi3::RequestBody {
                 description: #description,
                 required: #required,
-                content: #content_generator(components)?,
+                content: #content,
                 ..Default::default()
             }
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn explicit_optional_body() {
+        let find = |arg: FnArg| {
+            let FnArg::Typed(mut pt) = arg else {
+                panic!("Argument should be typed");
+            };
+            RequestBody::try_find_in_arg_attrs(&mut pt).map(|x| x.map(|y| y.attrs.required))
+        };
+        assert!(matches!(
+            find(parse_quote!(#[body] body: Option<Json<Request>>)),
+            Ok(Some(false))
+        ));
+        assert!(matches!(
+            find(parse_quote!(#[body(required = true)] body: Json<Request>)),
+            Ok(Some(true))
+        ));
+        assert!(find(parse_quote!(#[body(required = true)] body: Option<Json<Request>>)).is_err());
+    }
+}
@@ -3,23 +3,73 @@ use std::ops::Deref;
 use darling::FromMeta;
 use quote::{quote, ToTokens};
 use syn::{
-    punctuated::Punctuated, token::Paren, ItemFn, Meta, Path, ReturnType, Token, Type, TypeTuple,
+    punctuated::Punctuated, token::Paren, FnArg, GenericArgument, GenericParam, LitStr, Meta, Path,
+    PathArguments, ReturnType, Signature, Token, Type, TypeTuple,
 };
 
 use crate::{
+    error::Error,
     operation::{
         header::{Header, HEADER_ATTRIBUTE_NAME},
+        media_type::MediaType,
         reference::{Reference, REFERENCE_ATTRIBUTE_NAME},
+        schema_json::SchemaJson,
+        xml::Xml,
     },
-    utils::meta_to_meta_list,
+    utils::{meta_to_meta_list, nested, string_value},
 };
 
-// TODO: throw error if responses from different sources overlap OR merge them via oneOf
-
 static RESPONSE_ATTRIBUTE_NAME: &str = "response";
+static DEFAULT_RESPONSE_ATTRIBUTE_NAME: &str = "default_response";
+static NEGOTIATED_ATTRIBUTE_NAME: &str = "negotiated";
+static DEFAULT_STATUS: &str = "default";
+static NEGOTIATED_DEFAULT_STATUS: &str = "200";
 static IGNORE_RETURN_TYPE_ATTRIBUTE_NAME: &str = "ignore_return_type";
+static RETURN_TYPE_ATTRIBUTE_NAME: &str = "return_type";
+static RESPONSE_TYPE_ATTRIBUTE_NAME: &str = "response_type";
+static MERGE_STRATEGY_ATTRIBUTE_NAME: &str = "merge_strategy";
 static FROM_TYPE_ATTRIBUTE_NAME: &str = "from_type";
 
+/// How to resolve responses with same status from different sources.
+#[derive(Debug, Default, FromMeta)]
+#[darling(rename_all = "snake_case")]
+enum MergeStrategy {
+    #[default]
+    Merge,
+    Error,
+    Replace,
+}
+
+impl ToTokens for MergeStrategy {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(match self {
+            Self::Merge => quote! { MergeStrategy::Merge },
+            Self::Error => quote! { MergeStrategy::Error },
+            Self::Replace => quote! { MergeStrategy::Replace },
+        });
+    }
+}
+
+/// Status of response: HTTP status code (`200`), range of codes (`2XX`) or `default`.
+#[derive(Debug, PartialEq, Eq)]
+struct Status(String);
+
+impl FromMeta for Status {
+    fn from_string(value: &str) -> Result<Self, darling::Error> {
+        match value.as_bytes() {
+            _ if value == DEFAULT_STATUS => Ok(Self(value.into())),
+            [b'1'..=b'5', b'0'..=b'9', b'0'..=b'9'] => Ok(Self(value.into())),
+            [class @ b'1'..=b'5', b'x' | b'X', b'x' | b'X'] => {
+                Ok(Self(format!("{}XX", *class as char)))
+            }
+            _ => Err(darling::Error::custom(format!(
+                "Invalid status `{value}`, expected HTTP status code (like `200`), range of codes \
+                 (like `2XX`) or `default`"
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 struct Headers {
     headers: Vec<Header>,
@@ -36,12 +86,14 @@ impl FromMeta for Headers {
                 .get_ident()
                 .ok_or_else(|| darling::Error::custom("Should have Ident").with_span(&meta))?;
             if meta_ident == HEADER_ATTRIBUTE_NAME {
-                this.headers.push(Header::from_meta(&meta)?);
+                this.headers.push(nested::<Header>(&meta)?);
             } else if meta_ident == REFERENCE_ATTRIBUTE_NAME {
-                this.refs.push(Reference::from_meta(&meta)?);
+                this.refs.push(string_value::<Reference>(&meta)?);
             } else {
                 return Err(darling::Error::custom(
-                    "Response's header definition should have 'header' or 'reference' Ident",
+                    format!(
+                    "Unsupported response header definition `{meta_ident}`, expected `{HEADER_ATTRIBUTE_NAME}` or `{REFERENCE_ATTRIBUTE_NAME}`"
+                ),
                 )
                 .with_span(meta_ident));
             }
@@ -57,9 +109,9 @@ impl ToTokens for Headers {
             .iter()
             .map(|x| {
                 let value = x.for_response();
-                let name = &x.name;
+                let name = x.name();
                 quote! {
-                    let _ = map.insert(#name.into(), okapi::openapi3::RefOr::Object(#value));
+                    let _ = map.insert(#name, okapi::openapi3::RefOr::Object(#value));
                 }
             })
             .chain(self.refs.iter().map(|x| {
@@ -77,23 +129,78 @@ impl ToTokens for Headers {
 }
 
 #[derive(Debug, FromMeta)]
+#[darling(and_then = "Self::validate")]
 struct Response {
-    status: String,
+    #[darling(default, with = string_value)]
+    status: Option<Status>,
+    #[darling(with = string_value)]
     description: String,
-    content: Path,
-    #[darling(default)]
+    #[darling(default, with = string_value)]
+    content: Option<Path>,
+    #[darling(default, with = string_value)]
+    schema_json: Option<SchemaJson>,
+    #[darling(default, with = string_value)]
+    content_type: Option<MediaType>,
+    #[darling(default, with = nested)]
+    xml: Option<Xml>,
+    #[darling(default, with = nested)]
     headers: Headers,
 }
 
+impl Response {
+    fn validate(self) -> Result<Self, darling::Error> {
+        match (&self.content, &self.schema_json) {
+            (Some(_), Some(_)) => Err(darling::Error::custom(
+                "Response cannot have both `content` and `schema_json` attributes",
+            )),
+            (None, None) => Err(darling::Error::custom(
+                "Response should have either `content` or `schema_json` attribute",
+            )),
+            _ => Ok(self),
+        }
+    }
+}
+
 impl ToTokens for Response {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let description = &self.description;
-        let ty = &self.content;
         let headers = &self.headers;
+        let content = match (&self.content, &self.schema_json) {
+            (Some(ty), _) => {
+                if let Some(ref content_type) = self.content_type {
+                    quote! {
+                        override_content_type(<#ty as ToMediaTypes>::generate(components)?, #content_type)?
+                    }
+                } else {
+                    quote! { <#ty as ToMediaTypes>::generate(components)? }
+                }
+            }
+            (None, Some(json)) => {
+                // JSON schema is usually used for JSON content
+                let content_type = self
+                    .content_type
+                    .as_ref()
+                    .map_or_else(|| quote! { "application/json" }, |x| quote! { #x });
+                quote! {
+                    okapi::map! {
+                        #content_type.into() => okapi::openapi3::MediaType {
+                            schema: Some(#json),
+                            ..Default::default()
+                        }
+                    }
+                }
+            }
+            (None, None) => unreachable!("validated on parsing"),
+        };
+        let content = if let Some(ref xml) = self.xml {
+            xml.wrap_content(content)
+        } else {
+            content
+        };
         let new_tokens = quote! {
             okapi::openapi3::RefOr::Object(okapi::openapi3::Response {
                 description: #description.into(),
-                content: <#ty as ToMediaTypes>::generate(components)?,
+                content: #content,
                 headers: #headers,
                 ..Default::default()
             })
@@ -102,9 +209,64 @@ impl ToTokens for Response {
     }
 }
 
+/// Types of negotiated response content (`content("Json<T>", "Csv<T>")`).
+#[derive(Debug)]
+struct NegotiatedContent(Vec<Path>);
+
+impl FromMeta for NegotiatedContent {
+    fn from_meta(meta: &Meta) -> Result<Self, darling::Error> {
+        let list = meta_to_meta_list(meta)?;
+        let types = list
+            .parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?
+            .iter()
+            .map(|x| x.parse::<Path>())
+            .collect::<Result<Vec<_>, _>>()?;
+        if types.len() < 2 {
+            return Err(darling::Error::custom(
+                "Negotiated response should have at least 2 content types",
+            )
+            .with_span(list));
+        }
+        Ok(Self(types))
+    }
+}
+
+/// Response, which content depends on `Accept` header of request.
+#[derive(Debug, FromMeta)]
+struct NegotiatedResponse {
+    #[darling(default, with = string_value)]
+    status: Option<Status>,
+    #[darling(default, with = string_value)]
+    description: Option<String>,
+    content: NegotiatedContent,
+    #[darling(default, with = nested)]
+    headers: Headers,
+}
+
+impl ToTokens for NegotiatedResponse {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let description = self.description.as_deref().unwrap_or_default();
+        let headers = &self.headers;
+        let (first, rest) = self.content.0.split_first().expect("validated on parsing");
+        tokens.extend(quote! {
+            okapi::openapi3::RefOr::Object(okapi::openapi3::Response {
+                description: #description.into(),
+                content: {
+                    let mut content = <#first as ToMediaTypes>::generate(components)?;
+                    #(merge_media_types(&mut content, <#rest as ToMediaTypes>::generate(components)?)?;)*
+                    content
+                },
+                headers: #headers,
+                ..Default::default()
+            })
+        });
+    }
+}
+
 #[derive(Debug, FromMeta)]
 struct RefResponse {
-    status: String,
+    #[darling(with = string_value)]
+    status: Status,
     reference: Reference,
 }
 
@@ -122,26 +284,133 @@ fn unit_type() -> Type {
     })
 }
 
+/// Check whether type contains `impl Trait` anywhere inside.
+fn contains_impl_trait(ty: &Type) -> bool {
+    match ty {
+        Type::ImplTrait(_) => true,
+        Type::Group(x) => contains_impl_trait(&x.elem),
+        Type::Paren(x) => contains_impl_trait(&x.elem),
+        Type::Reference(x) => contains_impl_trait(&x.elem),
+        Type::Tuple(x) => x.elems.iter().any(contains_impl_trait),
+        Type::Path(x) => x.path.segments.iter().any(|seg| match seg.arguments {
+            PathArguments::AngleBracketed(ref args) => args.args.iter().any(|arg| match arg {
+                GenericArgument::Type(ty) => contains_impl_trait(ty),
+                _ => false,
+            }),
+            _ => false,
+        }),
+        _ => false,
+    }
+}
+
+/// Call of handler, which output type is used for inferring responses.
+///
+/// Used when handler returns opaque type, which cannot be named.
+#[derive(Debug)]
+struct OpaqueReturn {
+    call: proc_macro2::TokenStream,
+    is_async: bool,
+}
+
+impl OpaqueReturn {
+    fn from_signature(sig: &Signature, is_trait_method: bool) -> Result<Self, Error> {
+        if sig
+            .generics
+            .params
+            .iter()
+            .any(|x| !matches!(x, GenericParam::Lifetime(_)))
+            || sig
+                .inputs
+                .iter()
+                .any(|x| matches!(x, FnArg::Typed(pt) if contains_impl_trait(&pt.ty)))
+        {
+            return Err(Error::syn_spanned(
+                &sig.output,
+                "Responses cannot be inferred from opaque return type of generic function, \
+                specify concrete type with `response_type = \"...\"` \
+                or use `responses(ignore_return_type = true)`",
+            ));
+        }
+        let name = &sig.ident;
+        let args = sig.inputs.iter().map(|_| quote! { unreachable!() });
+        let call = if is_trait_method || sig.receiver().is_some() {
+            quote! { Self::#name(#(#args),*) }
+        } else {
+            quote! { #name(#(#args),*) }
+        };
+        Ok(Self {
+            call,
+            is_async: sig.asyncness.is_some(),
+        })
+    }
+}
+
+impl ToTokens for OpaqueReturn {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let call = &self.call;
+        let generate_fn = if self.is_async {
+            quote! { generate_responses_for_future_output }
+        } else {
+            quote! { generate_responses_for_output }
+        };
+        // Handler is never called, it is only used to infer its output type
+        tokens.extend(quote! {{
+            #[allow(unreachable_code, clippy::diverging_sub_expression)]
+            let output = if true { None } else { Some(#call) };
+            #generate_fn(output, components)?
+        }});
+    }
+}
+
 #[derive(Debug)]
 pub(super) struct Responses {
     responses: Vec<Response>,
+    negotiated: Vec<NegotiatedResponse>,
     refs: Vec<RefResponse>,
     from_type: Vec<Path>,
+    return_type: Option<Type>,
     ret_type: Type,
+    opaque_return: Option<OpaqueReturn>,
+    merge_strategy: MergeStrategy,
     pub ignore_return_type: bool,
 }
 
 impl Responses {
-    pub(crate) fn add_return_type(&mut self, item_fn: &ItemFn, ignore_return_type: bool) {
+    /// Set type, used for generating responses instead of return type (`response_type`
+    /// attribute).
+    pub(crate) fn set_return_type(&mut self, ty: Type) -> Result<(), Error> {
+        if self.return_type.is_some() {
+            return Err(Error::syn_spanned(
+                ty,
+                format!(
+                    "`{RESPONSE_TYPE_ATTRIBUTE_NAME}` conflicts with `responses({RETURN_TYPE_ATTRIBUTE_NAME} = \"...\")`"
+                ),
+            ));
+        }
+        self.return_type = Some(ty);
+        Ok(())
+    }
+
+    pub(crate) fn add_return_type(
+        &mut self,
+        sig: &Signature,
+        is_trait_method: bool,
+        ignore_return_type: bool,
+    ) -> Result<(), Error> {
         self.ignore_return_type = ignore_return_type;
-        self.ret_type = if let ReturnType::Type(_, ref ty) = item_fn.sig.output {
+        if let Some(ref ty) = self.return_type {
+            self.ret_type = ty.clone();
+            return Ok(());
+        }
+        self.ret_type = if let ReturnType::Type(_, ref ty) = sig.output {
             ty.deref().clone()
         } else {
-            Type::Tuple(TypeTuple {
-                paren_token: Paren::default(),
-                elems: Punctuated::new(),
-            })
+            unit_type()
         };
+        if !self.ignore_return_type && contains_impl_trait(&self.ret_type) {
+            self.opaque_return = Some(OpaqueReturn::from_signature(sig, is_trait_method)?);
+        }
+        Ok(())
     }
 }
 
@@ -149,9 +418,13 @@ impl Default for Responses {
     fn default() -> Self {
         Self {
             responses: Default::default(),
+            negotiated: Default::default(),
             refs: Default::default(),
             from_type: Default::default(),
+            return_type: None,
             ret_type: unit_type(),
+            opaque_return: None,
+            merge_strategy: Default::default(),
             ignore_return_type: Default::default(),
         }
     }
@@ -167,18 +440,47 @@ impl FromMeta for Responses {
                 .get_ident()
                 .ok_or_else(|| darling::Error::custom("Should have Ident").with_span(&meta))?;
             if meta_ident == RESPONSE_ATTRIBUTE_NAME {
-                let parsed = Response::from_meta(&meta)?;
+                let parsed = nested::<Response>(&meta)?;
+                if parsed.status.is_none() {
+                    return Err(darling::Error::custom(format!(
+                        "Missing field `status`, use `{DEFAULT_RESPONSE_ATTRIBUTE_NAME}(...)` for default response"
+                    ))
+                    .with_span(&meta));
+                }
                 this.responses.push(parsed);
+            } else if meta_ident == DEFAULT_RESPONSE_ATTRIBUTE_NAME {
+                let mut parsed = nested::<Response>(&meta)?;
+                if let Some(ref status) = parsed.status {
+                    return Err(darling::Error::custom(format!(
+                        "Default response shouldn't have status, use `{RESPONSE_ATTRIBUTE_NAME}(status = \"{}\", ...)` instead",
+                        status.0
+                    ))
+                    .with_span(&meta));
+                }
+                parsed.status = Some(Status(DEFAULT_STATUS.into()));
+                this.responses.push(parsed);
+            } else if meta_ident == NEGOTIATED_ATTRIBUTE_NAME {
+                let mut parsed = nested::<NegotiatedResponse>(&meta)?;
+                let _ = parsed
+                    .status
+                    .get_or_insert_with(|| Status(NEGOTIATED_DEFAULT_STATUS.into()));
+                this.negotiated.push(parsed);
             } else if meta_ident == REFERENCE_ATTRIBUTE_NAME {
-                let parsed = RefResponse::from_meta(&meta)?;
+                let parsed = nested::<RefResponse>(&meta)?;
                 this.refs.push(parsed);
             } else if meta_ident == IGNORE_RETURN_TYPE_ATTRIBUTE_NAME {
                 this.ignore_return_type = bool::from_meta(&meta)?;
+            } else if meta_ident == RETURN_TYPE_ATTRIBUTE_NAME {
+                this.return_type = Some(string_value::<Type>(&meta)?);
+            } else if meta_ident == MERGE_STRATEGY_ATTRIBUTE_NAME {
+                this.merge_strategy = string_value::<MergeStrategy>(&meta)?;
             } else if meta_ident == FROM_TYPE_ATTRIBUTE_NAME {
-                this.from_type.push(Path::from_meta(&meta)?);
+                this.from_type.push(string_value::<Path>(&meta)?);
             } else {
                 return Err(darling::Error::custom(
-                    "Response definition should have 'response', 'reference', 'from_type' or 'ignore_return_type' Ident",
+                    format!(
+                        "Unsupported response definition `{meta_ident}`, expected one of `{RESPONSE_ATTRIBUTE_NAME}`, `{DEFAULT_RESPONSE_ATTRIBUTE_NAME}`, `{NEGOTIATED_ATTRIBUTE_NAME}`, `{REFERENCE_ATTRIBUTE_NAME}`, `{FROM_TYPE_ATTRIBUTE_NAME}`, `{RETURN_TYPE_ATTRIBUTE_NAME}`, `{MERGE_STRATEGY_ATTRIBUTE_NAME}` or `{IGNORE_RETURN_TYPE_ATTRIBUTE_NAME}`"
+                    ),
                 )
                 .with_span(meta_ident));
             }
@@ -191,28 +493,48 @@ impl ToTokens for Responses {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let base_responses = if self.ignore_return_type {
             quote! { okapi::openapi3::Responses::default() }
+        } else if let Some(ref opaque_return) = self.opaque_return {
+            quote! { #opaque_return }
         } else {
             let ret_type = &self.ret_type;
             quote! { <#ret_type as ToResponses>::generate(components)? }
         };
+        let merge_strategy = &self.merge_strategy;
         let attrs = self
             .responses
             .iter()
-            .map(|x| (&x.status, quote! {#x}))
+            .filter_map(|x| Some((x.status.as_ref()?, quote! {#x})))
+            .chain(
+                self.negotiated
+                    .iter()
+                    .filter_map(|x| Some((x.status.as_ref()?, quote! {#x}))),
+            )
             .chain(self.refs.iter().map(|x| (&x.status, quote! {#x})))
-            .map(|(status, response)| {
-                if status == "default" {
-                    quote! { responses.default.replace(#response) }
+            .map(|(Status(status), response)| {
+                let other = if status == DEFAULT_STATUS {
+                    quote! {
+                        okapi::openapi3::Responses {
+                            default: Some(#response),
+                            ..Default::default()
+                        }
+                    }
                 } else {
-                    quote! { responses.responses.insert(#status.into(), #response) }
-                }
+                    quote! {
+                        okapi::openapi3::Responses {
+                            responses: okapi::map! { #status.into() => #response },
+                            ..Default::default()
+                        }
+                    }
+                };
+                quote! { merge_responses(&mut responses, #other, #merge_strategy)? }
             });
         let from_type = self.from_type.iter().map(|ty| {
             quote! {
-                okapi::merge::merge_responses(
+                merge_responses(
                     &mut responses,
-                    &<#ty as ToResponses>::generate(components)?
-                ).map_err(|err| anyhow::anyhow!("Failed to merge responses: {}", err))?
+                    <#ty as ToResponses>::generate(components)?,
+                    #merge_strategy,
+                )?
             }
         });
         tokens.extend(quote! {
@@ -225,3 +547,105 @@ impl ToTokens for Responses {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Meta};
+
+    use super::*;
+
+    #[test]
+    fn parse_status() {
+        for (value, expected) in [
+            ("200", "200"),
+            ("2XX", "2XX"),
+            ("4xx", "4XX"),
+            ("default", "default"),
+        ] {
+            assert_eq!(
+                Status::from_string(value).expect("Valid status"),
+                Status(expected.into())
+            );
+        }
+        for value in ["20", "600", "2X0", "XXX", "Default", ""] {
+            assert!(Status::from_string(value).is_err(), "{value} is invalid");
+        }
+    }
+
+    #[test]
+    fn parse_default_response() {
+        let input: Meta = parse_quote! {
+            responses(default_response(description = "Error", content = "String"))
+        };
+        let responses = Responses::from_meta(&input).expect("Successfully parsed");
+        assert_eq!(
+            responses.responses[0].status,
+            Some(Status("default".into()))
+        );
+
+        let input: Meta = parse_quote! {
+            responses(default_response(status = "200", description = "Error", content = "String"))
+        };
+        assert!(Responses::from_meta(&input).is_err());
+
+        let input: Meta = parse_quote! {
+            responses(response(description = "Error", content = "String"))
+        };
+        assert!(Responses::from_meta(&input).is_err());
+    }
+
+    #[test]
+    fn actionable_errors() {
+        let input: Meta = parse_quote! {
+            responses(response(status))
+        };
+        let errors: Vec<_> = Responses::from_meta(&input)
+            .expect_err("Invalid response")
+            .into_iter()
+            .collect();
+        assert!(errors.iter().all(|x| x.explicit_span().is_some()));
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert!(messages[0].starts_with("`status` requires value: `status = \"...\"`"));
+        assert!(messages[1].starts_with("Missing field `description`"));
+        assert_eq!(messages.len(), 2);
+
+        let input: Meta = parse_quote! {
+            responses(response(status = "200", description = "Ok"))
+        };
+        let err = Responses::from_meta(&input).expect_err("Missing content");
+        assert!(err
+            .to_string()
+            .starts_with("Response should have either `content` or `schema_json` attribute"));
+
+        let input: Meta = parse_quote! {
+            responses(response(status = 200, description = "Ok", content = "String"))
+        };
+        let err = Responses::from_meta(&input).expect_err("Invalid status");
+        assert!(err.explicit_span().is_some());
+        assert!(err
+            .to_string()
+            .starts_with("`status` should be string literal"));
+
+        let input: Meta = parse_quote! {
+            responses(response(description = "Ok", content = "String"))
+        };
+        let err = Responses::from_meta(&input).expect_err("Missing status");
+        assert!(err.to_string().contains("default_response(...)"));
+    }
+
+    #[test]
+    fn parse_negotiated() {
+        let input: Meta = parse_quote! {
+            responses(negotiated(description = "Report", content("Json<Report>", "Csv<Report>")))
+        };
+        let responses = Responses::from_meta(&input).expect("Successfully parsed");
+        assert_eq!(responses.negotiated[0].status, Some(Status("200".into())));
+        assert_eq!(responses.negotiated[0].content.0.len(), 2);
+
+        let input: Meta = parse_quote! {
+            responses(negotiated(content("Json<Report>")))
+        };
+        let err = Responses::from_meta(&input).expect_err("Single content type");
+        assert!(err.explicit_span().is_some());
+    }
+}
@@ -0,0 +1,110 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+use crate::operation::media_type::is_token;
+
+pub(super) static PATH_ATTRIBUTE_NAME: &str = "path";
+pub(super) static METHOD_ATTRIBUTE_NAME: &str = "method";
+
+/// HTTP method (standard or custom), normalized to uppercase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct HttpMethod(String);
+
+impl FromMeta for HttpMethod {
+    fn from_string(value: &str) -> Result<Self, darling::Error> {
+        if !is_token(value) {
+            return Err(darling::Error::custom(format!(
+                "Invalid HTTP method `{value}`, expected method name like `get` or `post`"
+            )));
+        }
+        Ok(Self(value.to_ascii_uppercase()))
+    }
+}
+
+impl ToTokens for HttpMethod {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = &self.0;
+        tokens.extend(quote! { #value });
+    }
+}
+
+/// Path template in OpenAPI format (like `/users/{id}`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct RoutePath(String);
+
+impl FromMeta for RoutePath {
+    fn from_string(value: &str) -> Result<Self, darling::Error> {
+        if !value.starts_with('/') {
+            return Err(darling::Error::custom(format!(
+                "Path `{value}` should start with `/`"
+            )));
+        }
+        Ok(Self(value.into()))
+    }
+}
+
+impl ToTokens for RoutePath {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = &self.0;
+        tokens.extend(quote! { #value });
+    }
+}
+
+/// Path and method of operation, declared in macro attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Route {
+    pub(super) path: RoutePath,
+    pub(super) method: HttpMethod,
+}
+
+impl Route {
+    /// Combine separately specified path and method.
+    pub(super) fn new(
+        path: Option<RoutePath>,
+        method: Option<HttpMethod>,
+    ) -> Result<Option<Self>, String> {
+        match (path, method) {
+            (Some(path), Some(method)) => Ok(Some(Self { path, method })),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(format!(
+                "`{PATH_ATTRIBUTE_NAME}` requires `{METHOD_ATTRIBUTE_NAME}` to be specified"
+            )),
+            (None, Some(_)) => Err(format!(
+                "`{METHOD_ATTRIBUTE_NAME}` requires `{PATH_ATTRIBUTE_NAME}` to be specified"
+            )),
+        }
+    }
+}
+
+impl ToTokens for Route {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let path = &self.path;
+        let method = &self.method;
+        tokens.extend(quote! {
+            .declared_route(#path, #method)?
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            HttpMethod::from_string("get").expect("Valid method"),
+            HttpMethod("GET".into())
+        );
+        assert!(HttpMethod::from_string("PROPFIND").is_ok());
+        assert!(HttpMethod::from_string("not a method").is_err());
+
+        assert!(RoutePath::from_string("/users/{id}").is_ok());
+        assert!(RoutePath::from_string("users").is_err());
+
+        assert!(Route::new(Some(RoutePath("/".into())), None).is_err());
+        assert!(Route::new(None, Some(HttpMethod("GET".into()))).is_err());
+        assert_eq!(Route::new(None, None), Ok(None));
+    }
+}
@@ -0,0 +1,90 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::Type;
+
+/// Raw JSON schema (like `schema_json = r#"{"type": "string", "format": "uuid"}"#`), which
+/// is used when no Rust type exists. Validated during parsing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct SchemaJson(String);
+
+impl FromMeta for SchemaJson {
+    fn from_string(value: &str) -> Result<Self, darling::Error> {
+        match serde_json::from_str::<serde_json::Value>(value) {
+            Ok(serde_json::Value::Object(_)) => Ok(Self(value.into())),
+            Ok(_) => Err(darling::Error::custom("Schema should be JSON object")),
+            Err(err) => Err(darling::Error::custom(format!(
+                "Invalid JSON schema: {err}"
+            ))),
+        }
+    }
+}
+
+impl ToTokens for SchemaJson {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = &self.0;
+        tokens.extend(quote! { schema_from_json(components, #value)? });
+    }
+}
+
+/// Comma-separated list of allowed string values (like `enum_values = "asc,desc"`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct EnumValues(Vec<String>);
+
+impl EnumValues {
+    /// String schema, restricted to listed values.
+    pub(super) fn to_schema_json(&self) -> SchemaJson {
+        SchemaJson(serde_json::json!({ "type": "string", "enum": self.0 }).to_string())
+    }
+}
+
+impl FromMeta for EnumValues {
+    fn from_string(value: &str) -> Result<Self, darling::Error> {
+        let values: Vec<String> = value.split(',').map(|x| x.trim().to_owned()).collect();
+        if values.iter().any(String::is_empty) {
+            return Err(darling::Error::custom(
+                "Enum values should be non-empty comma-separated list: `enum_values = \"a,b,c\"`",
+            ));
+        }
+        Ok(Self(values))
+    }
+}
+
+/// Schema of parameter, either generated from type or provided as JSON.
+#[derive(Debug)]
+pub(super) enum ParameterSchema {
+    Type(Type),
+    Json(SchemaJson),
+}
+
+impl ToTokens for ParameterSchema {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Self::Type(ty) => quote! { components.schema_for::<#ty>() },
+            Self::Json(json) => quote! { #json },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert!(SchemaJson::from_string(r#"{"type": "string", "format": "uuid"}"#).is_ok());
+        assert!(SchemaJson::from_string(r#""string""#).is_err());
+        assert!(SchemaJson::from_string(r#"{"type": "#).is_err());
+    }
+
+    #[test]
+    fn enum_values() {
+        let values = EnumValues::from_string("asc, desc").expect("Valid enum values");
+        assert_eq!(
+            values.to_schema_json(),
+            SchemaJson(r#"{"enum":["asc","desc"],"type":"string"}"#.into())
+        );
+        assert!(EnumValues::from_string("").is_err());
+        assert!(EnumValues::from_string("a,,b").is_err());
+    }
+}
@@ -3,7 +3,7 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{punctuated::Punctuated, Expr, Meta, Token};
 
-use crate::utils::{meta_to_meta_list, meta_to_meta_name_value};
+use crate::utils::{meta_to_meta_list, meta_to_meta_name_value, nested};
 
 static SECURITY_SCHEME_ATTRIBUTE_NAME: &str = "security_scheme";
 static SECURITY_SCHEME_NAME_ATTRIBUTE_NAME: &str = "name";
@@ -33,11 +33,13 @@ impl FromMeta for Security {
 
             match meta_ident {
                 _ if meta_ident == SECURITY_SCHEME_ATTRIBUTE_NAME => {
-                    this.schemes.push(SecurityScheme::from_meta(&meta)?)
+                    this.schemes.push(nested::<SecurityScheme>(&meta)?)
                 }
                 _ => {
-                    return Err(darling::Error::custom("Unsupported type of parameter")
-                        .with_span(meta_ident))
+                    return Err(darling::Error::custom(format!(
+                        "Unsupported security definition `{meta_ident}`, expected `{SECURITY_SCHEME_ATTRIBUTE_NAME}`"
+                    ))
+                    .with_span(meta_ident))
                 }
             }
         }
@@ -79,7 +81,7 @@ impl FromMeta for SecurityScheme {
                         return Err(darling::Error::custom(
                             "Security scheme name should be string literal",
                         )
-                        .with_span(meta_ident));
+                        .with_span(&meta.value));
                     };
                     this.name = String::from_value(&lit.lit)?;
                 }
@@ -88,21 +90,23 @@ impl FromMeta for SecurityScheme {
                         return Err(darling::Error::custom(
                             "Security scheme scope should be string literal",
                         )
-                        .with_span(meta_ident));
+                        .with_span(&meta.value));
                     };
                     let val = String::from_value(&lit.lit)?;
                     this.scopes = val.split(',').map(|v| v.to_owned()).collect();
                 }
                 _ => {
-                    return Err(darling::Error::custom("Unsupported type of parameter")
-                        .with_span(meta_ident))
+                    return Err(darling::Error::custom(format!(
+                        "Unsupported security scheme field `{meta_ident}`, expected `{SECURITY_SCHEME_NAME_ATTRIBUTE_NAME}` or `{SECURITY_SCHEME_SCOPES_ATTRIBUTE_NAME}`"
+                    ))
+                    .with_span(meta_ident))
                 }
             }
         }
 
         if this.name.is_empty() {
             return Err(darling::Error::custom(format!(
-                "Required attribute '{}' is missing",
+                "Required attribute `{}` is missing",
                 SECURITY_SCHEME_NAME_ATTRIBUTE_NAME
             ))
             .with_span(meta));
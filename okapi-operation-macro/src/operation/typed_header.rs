@@ -0,0 +1,89 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{FnArg, Signature, Type};
+
+use super::from_arg::single_generic_argument;
+
+static TYPED_HEADER_TYPE_NAME: &str = "TypedHeader";
+
+/// Header, extracted with `TypedHeader<H>` (required) or `Option<TypedHeader<H>>` (optional)
+/// handler argument.
+#[derive(Debug)]
+pub(super) struct TypedHeaderArgument {
+    header_type: Type,
+    required: bool,
+}
+
+impl TypedHeaderArgument {
+    /// Find typed headers in function signature (only with `axum` feature).
+    pub(super) fn from_signature(sig: &Signature) -> Vec<Self> {
+        if cfg!(not(feature = "axum")) {
+            return Vec::new();
+        }
+        sig.inputs
+            .iter()
+            .filter_map(|x| match x {
+                FnArg::Receiver(_) => None,
+                FnArg::Typed(y) => Some(y),
+            })
+            .filter_map(|pt| Self::from_type(&pt.ty))
+            .collect()
+    }
+
+    fn from_type(ty: &Type) -> Option<Self> {
+        let (wrapper, inner) = single_generic_argument(ty)?;
+        if wrapper == TYPED_HEADER_TYPE_NAME {
+            return Some(Self {
+                header_type: inner.clone(),
+                required: true,
+            });
+        }
+        if wrapper == "Option" {
+            let (wrapper, inner) = single_generic_argument(inner)?;
+            if wrapper == TYPED_HEADER_TYPE_NAME {
+                return Some(Self {
+                    header_type: inner.clone(),
+                    required: false,
+                });
+            }
+        }
+        None
+    }
+}
+
+impl ToTokens for TypedHeaderArgument {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let header_type = &self.header_type;
+        let required = self.required;
+        tokens.extend(quote! {
+            (&TypedHeaderProbe::<#header_type>::new()).add_typed_header(
+                components,
+                &mut operation,
+                #required,
+            )?;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn detect() {
+        let detect =
+            |ty: Type| TypedHeaderArgument::from_type(&ty).map(|x| (x.header_type, x.required));
+        assert_eq!(
+            detect(parse_quote!(TypedHeader<Authorization<Bearer>>)),
+            Some((parse_quote!(Authorization<Bearer>), true))
+        );
+        assert_eq!(
+            detect(parse_quote!(Option<axum_extra::TypedHeader<UserAgent>>)),
+            Some((parse_quote!(UserAgent), false))
+        );
+        assert_eq!(detect(parse_quote!(Option<UserAgent>)), None);
+        assert_eq!(detect(parse_quote!(Json<UserAgent>)), None);
+    }
+}
@@ -0,0 +1,73 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+use crate::utils::{quote_option, string_value};
+
+/// XML object of content schema.
+#[derive(Debug, FromMeta, PartialEq)]
+pub(super) struct Xml {
+    #[darling(default, with = string_value)]
+    name: Option<String>,
+    #[darling(default, with = string_value)]
+    namespace: Option<String>,
+    #[darling(default, with = string_value)]
+    prefix: Option<String>,
+    #[darling(default)]
+    attribute: bool,
+    #[darling(default)]
+    wrapped: bool,
+}
+
+impl Xml {
+    /// Wrap code, generating content, with setting XML object.
+    pub(super) fn wrap_content(&self, content: TokenStream) -> TokenStream {
+        quote! {{
+            let mut content = #content;
+            set_xml_object(&mut content, &#self)?;
+            content
+        }}
+    }
+}
+
+impl ToTokens for Xml {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let name = quote_option(&self.name);
+        let namespace = quote_option(&self.namespace);
+        let prefix = quote_option(&self.prefix);
+        let attribute = self.attribute;
+        let wrapped = self.wrapped;
+        tokens.extend(quote! {
+            XmlObject {
+                name: #name,
+                namespace: #namespace,
+                prefix: #prefix,
+                attribute: #attribute,
+                wrapped: #wrapped,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Meta};
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let input: Meta = parse_quote! { xml(name = "user", prefix = "u", wrapped) };
+
+        assert_eq!(
+            Xml::from_meta(&input).expect("Successfully parsed"),
+            Xml {
+                name: Some("user".into()),
+                namespace: None,
+                prefix: Some("u".into()),
+                attribute: false,
+                wrapped: true,
+            }
+        );
+    }
+}
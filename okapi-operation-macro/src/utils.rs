@@ -1,7 +1,9 @@
-use darling::ast::NestedMeta;
+use darling::{ast::NestedMeta, FromMeta};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::{punctuated::Punctuated, Attribute, Meta, MetaList, MetaNameValue, Token};
+use syn::{
+    punctuated::Punctuated, Attribute, Expr, ExprLit, Lit, Meta, MetaList, MetaNameValue, Token,
+};
 
 use crate::error::Error;
 
@@ -28,7 +30,7 @@ pub(super) fn take_attributes(attrs: &mut Vec<Attribute>, attr_name: &str) -> Ve
     let mut non_matched_attrs = vec![];
     let mut result = vec![];
     for attr in attrs.drain(..) {
-        if attr.path().get_ident().map_or(false, |x| x == attr_name) {
+        if attr.path().get_ident().is_some_and(|x| x == attr_name) {
             result.push(attr);
         } else {
             non_matched_attrs.push(attr);
@@ -38,15 +40,55 @@ pub(super) fn take_attributes(attrs: &mut Vec<Attribute>, attr_name: &str) -> Ve
     result
 }
 
+/// Name of attribute as written by user (for error messages).
+pub(super) fn meta_name(meta: &Meta) -> String {
+    meta.path()
+        .get_ident()
+        .map(|x| x.to_string())
+        .unwrap_or_else(|| "<unknown>".into())
+}
+
+/// Attach span of `node` to every error, which doesn't have one yet.
+///
+/// Errors, produced by derived `FromMeta` implementations for missing fields or by
+/// validation, have no span and otherwise are reported at macro call site.
+pub(super) fn with_default_span<T: syn::spanned::Spanned>(
+    err: darling::Error,
+    node: &T,
+) -> darling::Error {
+    darling::Error::multiple(err.into_iter().map(|x| x.with_span(node)).collect())
+}
+
+/// Parse nested attribute, written as `name(...)`.
+pub(super) fn nested<T: FromMeta>(meta: &Meta) -> Result<T, darling::Error> {
+    let list = meta_to_meta_list(meta)?;
+    T::from_meta(meta).map_err(|err| with_default_span(err, list))
+}
+
+/// Parse attribute, written as `name = "..."`.
+///
+/// Used with `#[darling(with = ...)]` on string-valued fields instead of darling's
+/// generic messages like ``Unexpected meta-item format `word` ``.
+pub(super) fn string_value<T: FromMeta>(meta: &Meta) -> Result<T, darling::Error> {
+    let name_value = meta_to_meta_name_value(meta)?;
+    match &name_value.value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(_), ..
+        }) => T::from_meta(meta).map_err(|err| with_default_span(err, &name_value.value)),
+        value => Err(darling::Error::custom(format!(
+            "`{}` should be string literal: `{0} = \"...\"`",
+            meta_name(meta)
+        ))
+        .with_span(value)),
+    }
+}
+
 pub(super) fn meta_to_meta_list(meta: &Meta) -> Result<&MetaList, darling::Error> {
     match meta {
         Meta::List(list) => Ok(list),
         rest => Err(darling::Error::custom(format!(
-            "'{}' should be Meta::List",
-            rest.path()
-                .get_ident()
-                .map(|x| x.to_string())
-                .unwrap_or_else(|| "<unknown>".into())
+            "`{}` should be specified as list: `{0}(...)`",
+            meta_name(rest)
         ))
         .with_span(rest)),
     }
@@ -55,13 +97,15 @@ pub(super) fn meta_to_meta_list(meta: &Meta) -> Result<&MetaList, darling::Error
 pub(super) fn meta_to_meta_name_value(meta: &Meta) -> Result<&MetaNameValue, darling::Error> {
     match meta {
         Meta::NameValue(name_value) => Ok(name_value),
-        rest => Err(darling::Error::custom(format!(
-            "'{}' should be Meta::NameValue",
-            rest.path()
-                .get_ident()
-                .map(|x| x.to_string())
-                .unwrap_or_else(|| "<unknown>".into())
+        Meta::Path(path) => Err(darling::Error::custom(format!(
+            "`{}` requires value: `{0} = \"...\"`",
+            meta_name(meta)
         ))
-        .with_span(rest)),
+        .with_span(path)),
+        Meta::List(list) => Err(darling::Error::custom(format!(
+            "`{}` should be specified as `{0} = \"...\"`, not as list",
+            meta_name(meta)
+        ))
+        .with_span(list)),
     }
 }
@@ -0,0 +1,78 @@
+//! Benchmark of [`Components::schema_for`] for types, repeated across many operations.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use okapi_operation::{
+    schemars::{gen::SchemaSettings, JsonSchema},
+    ComponentsBuilder,
+};
+
+#[derive(JsonSchema)]
+#[schemars(crate = "okapi_operation::schemars")]
+#[allow(dead_code)]
+struct Address {
+    country: String,
+    city: String,
+    street: String,
+    building: u32,
+}
+
+#[derive(JsonSchema)]
+#[schemars(crate = "okapi_operation::schemars")]
+#[allow(dead_code)]
+struct User {
+    id: u64,
+    name: String,
+    email: Option<String>,
+    addresses: Vec<Address>,
+    tags: Vec<String>,
+}
+
+/// Number of operations, requesting the same types.
+const OPERATIONS: usize = 500;
+
+fn schema_for(c: &mut Criterion) {
+    let mut group = c.benchmark_group("schema_for");
+    for inline_subschemas in [false, true] {
+        group.bench_with_input(
+            BenchmarkId::new("cached", inline_subschemas),
+            &inline_subschemas,
+            |b, &inline_subschemas| {
+                b.iter(|| {
+                    let mut components = ComponentsBuilder::default()
+                        .inline_subschemas(inline_subschemas)
+                        .build();
+                    for _ in 0..OPERATIONS {
+                        let _ = components.schema_for::<User>();
+                        let _ = components.schema_for::<Vec<User>>();
+                    }
+                })
+            },
+        );
+        // Same work without memoization (generating schema and applying visitors every time)
+        group.bench_with_input(
+            BenchmarkId::new("uncached", inline_subschemas),
+            &inline_subschemas,
+            |b, &inline_subschemas| {
+                b.iter(|| {
+                    let mut settings = SchemaSettings::openapi3();
+                    settings.inline_subschemas = inline_subschemas;
+                    let mut generator = settings.into_generator();
+                    for _ in 0..OPERATIONS {
+                        for mut object in [
+                            generator.subschema_for::<User>().into_object(),
+                            generator.subschema_for::<Vec<User>>().into_object(),
+                        ] {
+                            for visitor in generator.visitors_mut() {
+                                visitor.visit_schema_object(&mut object);
+                            }
+                        }
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, schema_for);
+criterion_main!(benches);
@@ -0,0 +1,116 @@
+use okapi::{
+    map,
+    openapi3::{MediaType, RefOr, Response, Responses, SchemaObject},
+    Map,
+};
+
+use crate::{Components, Error};
+
+/// Response (or request body) with single media type.
+///
+/// [`ToMediaTypes`](crate::ToMediaTypes) and [`ToResponses`](crate::ToResponses)
+/// implementations for types, implementing this trait, are generated with
+/// [`impl_api_component`](crate::impl_api_component) macro.
+///
+/// # Example
+///
+/// ```rust
+/// # use okapi_operation::*;
+/// struct Created(String);
+///
+/// impl ApiComponent for Created {
+///     const MEDIA_TYPE: &'static str = "text/plain";
+///     const STATUS: u16 = 201;
+///     const DESCRIPTION: &'static str = "Resource created";
+///
+///     fn schema(components: &mut Components) -> Option<okapi::openapi3::SchemaObject> {
+///         Some(components.schema_for::<String>())
+///     }
+/// }
+///
+/// impl_api_component!(Created);
+///
+/// #[openapi]
+/// async fn create() -> Created {
+/// # todo!()
+/// }
+/// ```
+pub trait ApiComponent {
+    /// Media type of content.
+    const MEDIA_TYPE: &'static str;
+
+    /// Status code of response.
+    const STATUS: u16 = 200;
+
+    /// Description of response.
+    const DESCRIPTION: &'static str = "";
+
+    /// Schema of content (not documented by default).
+    fn schema(_components: &mut Components) -> Option<SchemaObject> {
+        None
+    }
+
+    /// Media types of content (used by `ToMediaTypes` implementation).
+    fn media_types(components: &mut Components) -> Result<Map<String, MediaType>, Error> {
+        Ok(map! {
+            Self::MEDIA_TYPE.into() => MediaType {
+                schema: Self::schema(components),
+                ..Default::default()
+            }
+        })
+    }
+
+    /// Responses (used by `ToResponses` implementation).
+    fn responses(components: &mut Components) -> Result<Responses, Error> {
+        Ok(Responses {
+            responses: map! {
+                Self::STATUS.to_string() => RefOr::Object(Response {
+                    description: Self::DESCRIPTION.into(),
+                    content: Self::media_types(components)?,
+                    ..Default::default()
+                })
+            },
+            ..Default::default()
+        })
+    }
+}
+
+/// Generate [`ToMediaTypes`](crate::ToMediaTypes) and [`ToResponses`](crate::ToResponses)
+/// implementations for type, implementing [`ApiComponent`].
+///
+/// # Example
+///
+/// ```rust,compile
+/// # use okapi_operation::*;
+/// struct Accepted;
+///
+/// impl ApiComponent for Accepted {
+///     const MEDIA_TYPE: &'static str = "text/plain";
+///     const STATUS: u16 = 202;
+/// }
+///
+/// impl_api_component!(Accepted);
+/// ```
+#[macro_export]
+macro_rules! impl_api_component {
+    ($ty:ty) => {
+        impl $crate::ToMediaTypes for $ty {
+            fn generate(
+                components: &mut $crate::Components,
+            ) -> Result<
+                $crate::okapi::Map<String, $crate::okapi::openapi3::MediaType>,
+                $crate::Error,
+            > {
+                <$ty as $crate::ApiComponent>::media_types(components)
+            }
+        }
+
+        impl $crate::ToResponses for $ty {
+            fn generate(
+                components: &mut $crate::Components,
+            ) -> Result<$crate::okapi::openapi3::Responses, $crate::Error> {
+                <$ty as $crate::ApiComponent>::responses(components)
+            }
+        }
+    };
+}
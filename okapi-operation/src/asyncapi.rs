@@ -0,0 +1,263 @@
+//! Conversion of webhooks and callbacks into [AsyncAPI](https://www.asyncapi.com/docs/reference/specification/v2.6.0)
+//! document.
+//!
+//! Services, which both serve HTTP API and send events, could describe events as webhooks
+//! (`x-webhooks` extension of specification) or callbacks of operations and publish them
+//! as AsyncAPI document as well.
+
+use okapi::{
+    openapi3::{Components, OpenApi, Operation, PathItem, RequestBody},
+    Map,
+};
+use serde_json::{json, Value};
+
+use crate::walk::{path_item_operations, resolve_ref};
+
+/// Version of generated AsyncAPI documents.
+pub const ASYNCAPI_VERSION: &str = "2.6.0";
+
+/// Extension of specification with webhooks (name -> path item), as used by OpenAPI 3.0
+/// tools.
+pub const WEBHOOKS_EXTENSION: &str = "x-webhooks";
+
+/// Generate AsyncAPI 2.x document from webhooks and callbacks of specification.
+///
+/// Every webhook (from `x-webhooks` extension) and callback of operation becomes channel
+/// with `subscribe` operation (i.e. application sends messages, which consumers receive).
+/// Channels are named by webhook or callback name; runtime expression of callback URL
+/// is put into channel description. Messages are generated from request body of first
+/// operation of path item (one message per content type). Schemas are copied from
+/// specification components, so references to them stay valid.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use okapi_operation::*;
+/// let spec = OpenApiBuilder::new("Demo", "1.0.0").build()?;
+/// let document = asyncapi::from_openapi(&spec)?;
+/// std::fs::write("asyncapi.json", serde_json::to_string_pretty(&document)?)?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn from_openapi(spec: &OpenApi) -> Result<Value, serde_json::Error> {
+    let components = spec.components.clone().unwrap_or_default();
+    let mut channels = serde_json::Map::new();
+
+    if let Some(webhooks) = spec.extensions.get(WEBHOOKS_EXTENSION) {
+        let webhooks: Map<String, PathItem> = serde_json::from_value(webhooks.clone())?;
+        for (name, item) in &webhooks {
+            if let Some(channel) = channel(&components, item, None)? {
+                let _ = channels.entry(name.clone()).or_insert(channel);
+            }
+        }
+    }
+    for item in spec.paths.values() {
+        for (_, operation) in path_item_operations(item) {
+            for (name, callback) in &operation.callbacks {
+                let Some(callback) = resolve_ref(&components, callback) else {
+                    continue;
+                };
+                let single = callback.callbacks.len() == 1;
+                for (expression, item) in &callback.callbacks {
+                    let Some(channel) = channel(&components, item, Some(expression))? else {
+                        continue;
+                    };
+                    let name = if single {
+                        name.clone()
+                    } else {
+                        format!("{name} {expression}")
+                    };
+                    let _ = channels.entry(name).or_insert(channel);
+                }
+            }
+        }
+    }
+
+    let mut info = json!({
+        "title": spec.info.title,
+        "version": spec.info.version,
+    });
+    if let Some(ref description) = spec.info.description {
+        info["description"] = json!(description);
+    }
+    let mut document = json!({
+        "asyncapi": ASYNCAPI_VERSION,
+        "info": info,
+        "channels": channels,
+    });
+    if !components.schemas.is_empty() {
+        document["components"] = json!({
+            "schemas": serde_json::to_value(&components.schemas)?,
+        });
+    }
+    Ok(document)
+}
+
+/// Channel with `subscribe` operation for first operation of path item.
+fn channel(
+    components: &Components,
+    item: &PathItem,
+    expression: Option<&str>,
+) -> Result<Option<Value>, serde_json::Error> {
+    let Some((_, operation)) = path_item_operations(item).next() else {
+        return Ok(None);
+    };
+    let mut channel = json!({ "subscribe": subscribe(components, operation)? });
+    let description = match (expression, item.description.as_ref()) {
+        (Some(expression), Some(description)) => Some(format!(
+            "{}\n\nCallback URL: `{expression}`",
+            description.trim()
+        )),
+        (Some(expression), None) => Some(format!("Callback URL: `{expression}`")),
+        (None, description) => description.cloned(),
+    };
+    if let Some(description) = description {
+        channel["description"] = json!(description);
+    }
+    Ok(Some(channel))
+}
+
+fn subscribe(components: &Components, operation: &Operation) -> Result<Value, serde_json::Error> {
+    let mut subscribe = json!({});
+    if let Some(ref operation_id) = operation.operation_id {
+        subscribe["operationId"] = json!(operation_id);
+    }
+    if let Some(ref summary) = operation.summary {
+        subscribe["summary"] = json!(summary);
+    }
+    if let Some(ref description) = operation.description {
+        subscribe["description"] = json!(description);
+    }
+    if !operation.tags.is_empty() {
+        subscribe["tags"] = operation
+            .tags
+            .iter()
+            .map(|x| json!({ "name": x }))
+            .collect();
+    }
+    let body = operation
+        .request_body
+        .as_ref()
+        .and_then(|x| resolve_ref(components, x));
+    let mut messages = match body {
+        Some(body) => messages(body)?,
+        None => Vec::new(),
+    };
+    match messages.len() {
+        0 => {}
+        1 => subscribe["message"] = messages.remove(0),
+        _ => subscribe["message"] = json!({ "oneOf": messages }),
+    }
+    Ok(subscribe)
+}
+
+fn messages(body: &RequestBody) -> Result<Vec<Value>, serde_json::Error> {
+    body.content
+        .iter()
+        .map(|(content_type, media_type)| {
+            let mut message = json!({ "contentType": content_type });
+            if let Some(ref schema) = media_type.schema {
+                message["payload"] = serde_json::to_value(schema)?;
+            }
+            if let Some(ref description) = body.description {
+                message["description"] = json!(description);
+            }
+            Ok(message)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+    use okapi::openapi3::{Callback, MediaType, RefOr};
+
+    use super::*;
+    use crate::{JsonSchema, OpenApiBuilder};
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi::schemars")]
+    #[allow(dead_code)]
+    struct Event {
+        id: u64,
+    }
+
+    fn event_item(components: &mut crate::Components, operation_id: &str) -> PathItem {
+        PathItem {
+            post: Some(Operation {
+                operation_id: Some(operation_id.into()),
+                request_body: Some(RefOr::Object(RequestBody {
+                    content: okapi::map! {
+                        "application/json".into() => MediaType {
+                            schema: Some(components.schema_for::<Event>()),
+                            ..Default::default()
+                        },
+                    },
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn callbacks_and_webhooks() {
+        let mut builder = OpenApiBuilder::new("Events", "1.0.0");
+        let _ = builder.operation("/subscriptions", Method::POST, |components| {
+            Ok(Operation {
+                callbacks: okapi::map! {
+                    "onEvent".into() => RefOr::Object(Callback {
+                        callbacks: okapi::map! {
+                            "{$request.body#/url}".into() => event_item(components, "sendEvent"),
+                        },
+                        extensions: Default::default(),
+                    }),
+                },
+                ..Default::default()
+            })
+        });
+        let mut spec = builder.build().expect("Failed to build spec");
+        let _ = spec.extensions.insert(
+            WEBHOOKS_EXTENSION.into(),
+            json!({
+                "userCreated": {
+                    "post": {
+                        "summary": "User created",
+                        "requestBody": {
+                            "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Event"}}}
+                        },
+                        "responses": {}
+                    }
+                }
+            }),
+        );
+
+        let document = from_openapi(&spec).unwrap();
+        assert_eq!(document["asyncapi"], "2.6.0");
+        assert_eq!(
+            document["channels"],
+            json!({
+                "userCreated": {
+                    "subscribe": {
+                        "summary": "User created",
+                        "message": {
+                            "contentType": "application/json",
+                            "payload": {"$ref": "#/components/schemas/Event"}
+                        }
+                    }
+                },
+                "onEvent": {
+                    "description": "Callback URL: `{$request.body#/url}`",
+                    "subscribe": {
+                        "operationId": "sendEvent",
+                        "message": {
+                            "contentType": "application/json",
+                            "payload": {"$ref": "#/components/schemas/Event"}
+                        }
+                    }
+                }
+            })
+        );
+        assert!(document["components"]["schemas"].get("Event").is_some());
+    }
+}
@@ -0,0 +1,279 @@
+//! Filtering specification for specific audience (i.e. caller of specification endpoint).
+
+use std::collections::{BTreeSet, HashSet};
+
+use okapi::openapi3::{OpenApi, Operation, SecurityRequirement};
+
+use crate::{
+    builder::retain_operations,
+    walk::{all_path_item_operations, reachable_schemas},
+    Error,
+};
+
+/// Audience of specification: restricted tags, which are visible to it, and security
+/// scopes, granted to it.
+///
+/// # Example
+///
+/// ```rust
+/// # use okapi_operation::*;
+/// # let spec = OpenApiBuilder::new("Demo", "1.0.0").build().unwrap();
+/// let restricted_tags = ["internal".to_string()];
+/// let public = Audience::new().filter_spec(&spec, &restricted_tags)?;
+/// let staff = Audience::new()
+///     .tag("internal")
+///     .scope("users:write")
+///     .filter_spec(&spec, &restricted_tags)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Audience {
+    tags: BTreeSet<String>,
+    scopes: BTreeSet<String>,
+}
+
+impl Audience {
+    /// Create audience without access to restricted tags and without scopes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow restricted tag.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let _ = self.tags.insert(tag.into());
+        self
+    }
+
+    /// Allow restricted tags.
+    pub fn tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Grant security scope.
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        let _ = self.scopes.insert(scope.into());
+        self
+    }
+
+    /// Grant security scopes.
+    pub fn scopes<I>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.scopes.extend(scopes.into_iter().map(Into::into));
+        self
+    }
+
+    /// Whether operation is visible to audience.
+    ///
+    /// Operation is hidden if it have restricted tag, which is not allowed for audience,
+    /// or if audience lacks scopes for every security requirement of operation (or of
+    /// specification, if operation doesn't override it).
+    pub fn is_visible(
+        &self,
+        operation: &Operation,
+        restricted_tags: &[String],
+        default_security: &[SecurityRequirement],
+    ) -> bool {
+        let tags_allowed = operation
+            .tags
+            .iter()
+            .all(|tag| !restricted_tags.contains(tag) || self.tags.contains(tag));
+        let security = operation.security.as_deref().unwrap_or(default_security);
+        let scopes_granted = security.is_empty()
+            || security.iter().any(|requirement| {
+                requirement
+                    .values()
+                    .flatten()
+                    .all(|scope| self.scopes.contains(scope))
+            });
+        tags_allowed && scopes_granted
+    }
+
+    /// Copy of specification without operations, which are not visible to audience (see
+    /// [`Audience::is_visible`]), including operations of custom methods.
+    ///
+    /// Schemas and tags, used only by removed operations, are removed too. Returns an error if
+    /// specification contains invalid operations of custom methods.
+    pub fn filter_spec(
+        &self,
+        spec: &OpenApi,
+        restricted_tags: &[String],
+    ) -> Result<OpenApi, Error> {
+        let mut spec = spec.clone();
+        let schemas_before = reachable_schemas(&mut spec);
+        let tags_before = operation_tags(&spec);
+
+        let default_security = std::mem::take(&mut spec.security);
+        retain_operations(&mut spec, |operation| {
+            self.is_visible(operation, restricted_tags, &default_security)
+        })?;
+        spec.security = default_security;
+
+        // Schemas and tags, which are not used by any operation before filtering (like
+        // registered explicitly), are kept
+        let schemas_after = reachable_schemas(&mut spec);
+        if let Some(components) = spec.components.as_mut() {
+            components
+                .schemas
+                .retain(|name, _| !schemas_before.contains(name) || schemas_after.contains(name));
+        }
+        let tags_after = operation_tags(&spec);
+        spec.tags
+            .retain(|tag| !tags_before.contains(&tag.name) || tags_after.contains(&tag.name));
+        Ok(spec)
+    }
+
+    /// Part of audience, which affects visibility of operations with specified restricted
+    /// tags and security scopes (so audiences with same visible operations are equal).
+    #[cfg(feature = "axum")]
+    pub(crate) fn restrict(&self, restricted_tags: &[String], scopes: &BTreeSet<String>) -> Self {
+        Self {
+            tags: self
+                .tags
+                .iter()
+                .filter(|x| restricted_tags.contains(x))
+                .cloned()
+                .collect(),
+            scopes: self.scopes.intersection(scopes).cloned().collect(),
+        }
+    }
+}
+
+/// Security scopes, required by operations of specification.
+#[cfg(feature = "axum")]
+pub(crate) fn required_scopes(spec: &OpenApi) -> BTreeSet<String> {
+    let mut scopes: BTreeSet<String> = spec
+        .security
+        .iter()
+        .flat_map(|x| x.values().flatten())
+        .cloned()
+        .collect();
+    for item in spec.paths.values() {
+        for (_, operation) in all_path_item_operations(item) {
+            scopes.extend(
+                operation
+                    .security
+                    .iter()
+                    .flatten()
+                    .flat_map(|x| x.values().flatten())
+                    .cloned(),
+            );
+        }
+    }
+    scopes
+}
+
+/// Tags of operations of specification.
+fn operation_tags(spec: &OpenApi) -> HashSet<String> {
+    spec.paths
+        .values()
+        .flat_map(all_path_item_operations)
+        .flat_map(|(_, operation)| operation.into_owned().tags)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+    use okapi::openapi3::Operation;
+
+    use super::*;
+    use crate::OpenApiBuilder;
+
+    fn operation(tag: &str, scopes: &[&str]) -> Operation {
+        let mut security = SecurityRequirement::new();
+        let _ = security.insert(
+            "oauth".into(),
+            scopes.iter().map(ToString::to_string).collect(),
+        );
+        Operation {
+            tags: vec![tag.into()],
+            security: Some(vec![security]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn filter_spec() {
+        let spec = OpenApiBuilder::new("title", "version")
+            .operation("/users", Method::GET, |_| Ok(operation("users", &[])))
+            .operation("/users", Method::POST, |_| {
+                Ok(operation("users", &["users:write"]))
+            })
+            .operation("/admin", Method::GET, |_| Ok(operation("internal", &[])))
+            .build()
+            .expect("Failed to build spec");
+        let restricted_tags = ["internal".to_string()];
+
+        let public = Audience::new()
+            .filter_spec(&spec, &restricted_tags)
+            .unwrap();
+        assert_eq!(public.paths.keys().collect::<Vec<_>>(), ["/users"]);
+        assert!(public.paths["/users"].post.is_none());
+
+        let staff = Audience::new()
+            .tag("internal")
+            .scope("users:write")
+            .filter_spec(&spec, &restricted_tags)
+            .unwrap();
+        assert_eq!(staff, spec);
+    }
+
+    #[test]
+    fn filter_spec_unused_components() {
+        let mut builder = OpenApiBuilder::new("title", "version");
+        let _ = builder
+            .tag(okapi::openapi3::Tag {
+                name: "internal".into(),
+                ..Default::default()
+            })
+            .operation("/users", Method::GET, |_| Ok(operation("users", &[])))
+            .operation("/search", Method::from_bytes(b"QUERY").unwrap(), |_| {
+                let mut operation = operation("internal", &[]);
+                operation.request_body = Some(
+                    serde_json::from_value(serde_json::json!({
+                        "content": {
+                            "application/json": {
+                                "schema": {"$ref": "#/components/schemas/Internal"}
+                            }
+                        }
+                    }))
+                    .unwrap(),
+                );
+                Ok(operation)
+            });
+        let mut spec = builder.build().expect("Failed to build spec");
+        let _ = spec
+            .components
+            .get_or_insert_with(Default::default)
+            .schemas
+            .insert("Internal".into(), Default::default());
+        let _ = spec
+            .components
+            .get_or_insert_with(Default::default)
+            .schemas
+            .insert("Standalone".into(), Default::default());
+        let restricted_tags = ["internal".to_string()];
+
+        let public = Audience::new()
+            .filter_spec(&spec, &restricted_tags)
+            .unwrap();
+        assert!(public.paths.get("/search").is_none());
+        assert!(public.tags.is_empty());
+        let schemas = &public.components.as_ref().unwrap().schemas;
+        assert_eq!(schemas.keys().collect::<Vec<_>>(), ["Standalone"]);
+
+        let staff = Audience::new()
+            .tag("internal")
+            .filter_spec(&spec, &restricted_tags)
+            .unwrap();
+        assert_eq!(staff, spec);
+    }
+}
@@ -0,0 +1,54 @@
+use http::Method;
+
+use crate::OperationGenerator;
+
+/// Operation, registered in global inventory with `#[openapi(collect(...))]`.
+///
+/// Registered operations are added to specification with
+/// [`OpenApiBuilder::from_inventory`](crate::OpenApiBuilder::from_inventory) or
+/// [`OpenApiBuilder::try_inventory_operations`](crate::OpenApiBuilder::try_inventory_operations).
+#[derive(Clone, Copy, Debug)]
+pub struct CollectedOperation {
+    path: &'static str,
+    method: &'static str,
+    generator: OperationGenerator,
+}
+
+impl CollectedOperation {
+    #[doc(hidden)]
+    pub const fn new(
+        path: &'static str,
+        method: &'static str,
+        generator: OperationGenerator,
+    ) -> Self {
+        Self {
+            path,
+            method,
+            generator,
+        }
+    }
+
+    /// Declared path.
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+
+    /// Declared method.
+    pub fn method(&self) -> Method {
+        Method::from_bytes(self.method.as_bytes()).expect("Method is validated by macro")
+    }
+
+    /// Operation generator.
+    pub fn generator(&self) -> OperationGenerator {
+        self.generator
+    }
+}
+
+inventory::collect!(CollectedOperation);
+
+/// Iterate over all operations, registered with `#[openapi(collect(...))]`.
+///
+/// Order of operations is unspecified.
+pub fn collected_operations() -> impl Iterator<Item = &'static CollectedOperation> {
+    inventory::iter::<CollectedOperation>.into_iter()
+}
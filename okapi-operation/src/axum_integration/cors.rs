@@ -0,0 +1,105 @@
+use okapi::{
+    openapi3::{Operation, ParameterValue, RefOr, Response, Responses},
+    schemars::schema::{InstanceType, SchemaObject},
+    Map,
+};
+
+use crate::{header::header, Components, Error};
+
+/// Marker wrapper of CORS layer (like `tower_http::cors::CorsLayer`), which makes
+/// [`Router::cors_layer`](super::Router::cors_layer) document preflight requests.
+///
+/// # Example
+///
+/// ```rust
+/// # use okapi_operation::{*, axum_integration::*};
+/// # #[derive(Clone)]
+/// # struct CorsLayer;
+/// # impl<S> tower::Layer<S> for CorsLayer {
+/// #     type Service = S;
+/// #     fn layer(&self, inner: S) -> S { inner }
+/// # }
+/// #[openapi]
+/// async fn handler() {}
+///
+/// let app = Router::<()>::new()
+///     .route("/", get(openapi_handler!(handler)))
+///     .cors_layer(DocumentedCors::new(CorsLayer).allow_headers(["content-type"]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DocumentedCors<L> {
+    pub(super) layer: L,
+    pub(super) allow_headers: Vec<String>,
+}
+
+impl<L> DocumentedCors<L> {
+    /// Wrap CORS layer.
+    pub fn new(layer: L) -> Self {
+        Self {
+            layer,
+            allow_headers: Vec::new(),
+        }
+    }
+
+    /// Set request headers, allowed by layer (documented in `Access-Control-Allow-Headers`
+    /// response header).
+    pub fn allow_headers<I, S>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allow_headers = headers.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Generator of preflight operation (actual operation is set by customizer).
+pub(super) fn preflight_generator(_: &mut Components) -> Result<Operation, Error> {
+    Ok(Operation::default())
+}
+
+/// Operation of preflight request for path with specified methods.
+pub(super) fn preflight_operation(methods: &str, allow_headers: &str) -> Operation {
+    let header = |description: &str, value: &str| {
+        let schema = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            ..Default::default()
+        };
+        let mut header = header(schema, Some(description), true);
+        if let ParameterValue::Schema { example, .. } = &mut header.value {
+            *example = (!value.is_empty()).then(|| value.into());
+        }
+        RefOr::Object(header)
+    };
+
+    let mut headers = Map::new();
+    let _ = headers.insert(
+        "Access-Control-Allow-Origin".into(),
+        header("Origin, allowed to make requests", ""),
+    );
+    let _ = headers.insert(
+        "Access-Control-Allow-Methods".into(),
+        header("Methods, allowed for path", methods),
+    );
+    if !allow_headers.is_empty() {
+        let _ = headers.insert(
+            "Access-Control-Allow-Headers".into(),
+            header("Request headers, allowed for path", allow_headers),
+        );
+    }
+    let mut responses = Responses::default();
+    let _ = responses.responses.insert(
+        "200".into(),
+        RefOr::Object(Response {
+            description: "CORS preflight response".into(),
+            headers,
+            ..Default::default()
+        }),
+    );
+    Operation {
+        summary: Some("CORS preflight".into()),
+        tags: vec!["cors".into()],
+        responses,
+        ..Default::default()
+    }
+}
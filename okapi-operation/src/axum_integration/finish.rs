@@ -0,0 +1,360 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+};
+use http::{header, request::Parts, HeaderValue, StatusCode};
+use okapi::openapi3::{OpenApi, Operation, SecurityRequirement};
+
+use super::DEFAULT_OPENAPI_PATH;
+use crate::{audience::required_scopes, Audience, Error};
+
+/// Options of [`Router::finish_openapi_with`](super::Router::finish_openapi_with).
+///
+/// # Example
+///
+/// ```rust
+/// # use okapi_operation::axum_integration::*;
+/// let app = Router::<()>::new()
+///     .finish_openapi_with(
+///         FinishOptions::new()
+///             .path("/openapi")
+///             .title("Demo")
+///             .version("1.0.0")
+///             .hide_self(true)
+///             .ui(Ui::SwaggerUi),
+///     )
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct FinishOptions {
+    pub(super) path: String,
+    pub(super) title: Option<String>,
+    pub(super) version: Option<String>,
+    pub(super) operation: FinishOpenapiOptions,
+    pub(super) ui: Ui,
+    pub(super) ui_path: String,
+    pub(super) filter: Option<SpecFilter>,
+}
+
+impl Default for FinishOptions {
+    fn default() -> Self {
+        Self {
+            path: DEFAULT_OPENAPI_PATH.into(),
+            title: None,
+            version: None,
+            operation: FinishOpenapiOptions::default(),
+            ui: Ui::None,
+            ui_path: DEFAULT_UI_PATH.into(),
+            filter: None,
+        }
+    }
+}
+
+impl FinishOptions {
+    /// Create default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set path, at which specification is served ([`DEFAULT_OPENAPI_PATH`] by default).
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set specification title, overriding one from builder template.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set specification version, overriding one from builder template.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Don't document operation, which serves specification (see
+    /// [`FinishOpenapiOptions::hidden`]).
+    pub fn hide_self(mut self, hide: bool) -> Self {
+        self.operation = self.operation.hidden(hide);
+        self
+    }
+
+    /// Set options of operation, which serves specification.
+    pub fn operation(mut self, options: FinishOpenapiOptions) -> Self {
+        self.operation = options;
+        self
+    }
+
+    /// Serve documentation UI for specification ([`Ui::None`] by default).
+    pub fn ui(mut self, ui: Ui) -> Self {
+        self.ui = ui;
+        self
+    }
+
+    /// Set path, at which documentation UI is served ([`DEFAULT_UI_PATH`] by default).
+    pub fn ui_path(mut self, path: impl Into<String>) -> Self {
+        self.ui_path = path.into();
+        self
+    }
+
+    /// Filter served specification for each caller (see [`SpecFilter`]).
+    pub fn filter(mut self, filter: SpecFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+}
+
+/// Filter of specification, served by
+/// [`Router::finish_openapi_with`](super::Router::finish_openapi_with), which removes
+/// operations, not visible to caller (see [`Audience::is_visible`]).
+///
+/// [`Audience`] of caller is extracted from request. Filtered specification is generated
+/// once per audience and cached (audiences are cached by restricted tags and scopes, used in
+/// specification, so number of cached specifications doesn't depend on callers).
+///
+/// # Example
+///
+/// ```rust
+/// # use okapi_operation::{axum_integration::*, Audience};
+/// let filter = SpecFilter::new(|parts| {
+///     let is_staff = parts.headers.get("x-role").is_some_and(|x| x == "staff");
+///     if is_staff {
+///         Audience::new().tag("internal")
+///     } else {
+///         Audience::new()
+///     }
+/// })
+/// .restricted_tag("internal");
+/// let app = Router::<()>::new()
+///     .finish_openapi_with(FinishOptions::new().filter(filter))
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct SpecFilter {
+    audience: Arc<dyn Fn(&Parts) -> Audience + Send + Sync>,
+    restricted_tags: Vec<String>,
+}
+
+impl fmt::Debug for SpecFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpecFilter")
+            .field("restricted_tags", &self.restricted_tags)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SpecFilter {
+    /// Create filter, which extracts audience from request with `audience` function.
+    pub fn new<F>(audience: F) -> Self
+    where
+        F: Fn(&Parts) -> Audience + Send + Sync + 'static,
+    {
+        Self {
+            audience: Arc::new(audience),
+            restricted_tags: Vec::new(),
+        }
+    }
+
+    /// Add tag, operations with which are visible only to audiences with this tag.
+    pub fn restricted_tag(mut self, tag: impl Into<String>) -> Self {
+        self.restricted_tags.push(tag.into());
+        self
+    }
+}
+
+/// State of filtered specification endpoint.
+#[derive(Clone)]
+pub(super) struct FilteredSpec {
+    spec: Arc<OpenApi>,
+    filter: SpecFilter,
+    /// Security scopes, required by operations of specification.
+    scopes: Arc<BTreeSet<String>>,
+    cache: Arc<Mutex<HashMap<Audience, Arc<OpenApi>>>>,
+}
+
+impl FilteredSpec {
+    pub(super) fn new(spec: OpenApi, filter: SpecFilter) -> Self {
+        Self {
+            scopes: Arc::new(required_scopes(&spec)),
+            spec: Arc::new(spec),
+            filter,
+            cache: Arc::default(),
+        }
+    }
+
+    fn for_request(&self, parts: &Parts) -> Result<Arc<OpenApi>, Error> {
+        let audience =
+            (self.filter.audience)(parts).restrict(&self.filter.restricted_tags, &self.scopes);
+        let mut cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(spec) = cache.get(&audience) {
+            return Ok(spec.clone());
+        }
+        let spec = Arc::new(audience.filter_spec(&self.spec, &self.filter.restricted_tags)?);
+        let _ = cache.insert(audience, spec.clone());
+        Ok(spec)
+    }
+}
+
+/// Serves specification, filtered for caller.
+pub(super) async fn serve_filtered_spec(
+    State(state): State<FilteredSpec>,
+    parts: Parts,
+) -> Response {
+    match state.for_request(&parts) {
+        Ok(spec) => super::spec_response(&spec, &parts.headers),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("text/plain; charset=utf-8"),
+            )],
+            err.to_string(),
+        )
+            .into_response(),
+    }
+}
+
+/// Default path of documentation UI.
+pub const DEFAULT_UI_PATH: &str = "/docs";
+
+/// Documentation UI, served by [`Router::finish_openapi_with`](super::Router::finish_openapi_with).
+///
+/// UI assets are loaded from CDN by browser.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Ui {
+    /// Don't serve UI.
+    #[default]
+    None,
+    /// [Swagger UI](https://swagger.io/tools/swagger-ui/).
+    SwaggerUi,
+    /// [Redoc](https://github.com/Redocly/redoc).
+    Redoc,
+}
+
+impl Ui {
+    /// HTML page of UI, which loads specification from `spec_url`.
+    pub(super) fn html(self, spec_url: &str) -> Option<String> {
+        let html = match self {
+            Self::None => return None,
+            Self::SwaggerUi => format!(
+                r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>API documentation</title>
+<link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+<div id="swagger-ui"></div>
+<script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+<script>SwaggerUIBundle({{ url: "{spec_url}", dom_id: "#swagger-ui" }});</script>
+</body>
+</html>
+"##
+            ),
+            Self::Redoc => format!(
+                r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>API documentation</title>
+</head>
+<body>
+<redoc spec-url="{spec_url}"></redoc>
+<script src="https://cdn.redoc.ly/redoc/latest/bundles/redoc.standalone.js"></script>
+</body>
+</html>
+"##
+            ),
+        };
+        Some(html)
+    }
+}
+
+/// Options of operation, which serves OpenAPI specification, mounted by
+/// [`Router::finish_openapi_with_options`](super::Router::finish_openapi_with_options).
+///
+/// # Example
+///
+/// ```rust
+/// # use okapi_operation::axum_integration::*;
+/// let options = FinishOpenapiOptions::new()
+///     .tag("meta")
+///     .summary("Get API specification")
+///     .security("api_key", []);
+/// let app = Router::<()>::new()
+///     .finish_openapi_with_options("/openapi", "Demo", "1.0.0", options)
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FinishOpenapiOptions {
+    tag: Option<String>,
+    summary: Option<String>,
+    security: Vec<SecurityRequirement>,
+    hidden: bool,
+}
+
+impl FinishOpenapiOptions {
+    /// Create default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set tag of operation (`openapi` by default).
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Set summary of operation (`OpenAPI specification` by default).
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Add security requirement to operation.
+    pub fn security<N, S>(mut self, name: N, scopes: S) -> Self
+    where
+        N: Into<String>,
+        S: IntoIterator<Item = String>,
+    {
+        let mut security = SecurityRequirement::new();
+        let _ = security.insert(name.into(), scopes.into_iter().collect());
+        self.security.push(security);
+        self
+    }
+
+    /// Don't document operation in specification (it is still served).
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    pub(super) fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    pub(super) fn apply_to(&self, operation: &mut Operation) {
+        if let Some(tag) = &self.tag {
+            operation.tags = vec![tag.clone()];
+        }
+        if let Some(summary) = &self.summary {
+            operation.summary = Some(summary.clone());
+        }
+        if !self.security.is_empty() {
+            operation
+                .security
+                .get_or_insert_with(Vec::new)
+                .extend(self.security.iter().cloned());
+        }
+    }
+}
@@ -177,10 +177,10 @@ mod tests {
     use super::*;
     use crate::{
         axum_integration::{MethodRouter, Router},
-        Components,
+        Components, Error,
     };
 
-    fn openapi_generator(_: &mut Components) -> Result<Operation, anyhow::Error> {
+    fn openapi_generator(_: &mut Components) -> Result<Operation, Error> {
         unimplemented!()
     }
 
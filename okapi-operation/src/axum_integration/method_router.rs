@@ -2,16 +2,16 @@ use std::{collections::HashMap, convert::Infallible, fmt};
 
 use axum::{
     error_handling::HandleError,
-    extract::Request,
+    extract::{Request, State},
     handler::Handler,
-    http::Method,
+    http::{Method, StatusCode},
     response::IntoResponse,
-    routing::{MethodFilter, MethodRouter as AxumMethodRouter, Route},
+    routing::{any, any_service, MethodFilter, MethodRouter as AxumMethodRouter, Route},
 };
 use tower::{Layer, Service};
 
 use super::handler_traits::{HandlerWithOperation, ServiceWithOperation};
-use crate::OperationGenerator;
+use crate::{operation_meta::OperationEntry, OperationMeta};
 
 macro_rules! top_level_service_fn {
     (
@@ -47,6 +47,22 @@ macro_rules! top_level_handler_fn {
         {
             on(MethodFilter::$method, handler)
         }
+
+        paste::paste! {
+            #[doc = concat!("Same as [`", stringify!($name), "`], but allows to override operation metadata for this route.")]
+            ///
+            /// See [`MethodRouter::on_with`] for details.
+            pub fn [<$name _with>]<I, H, T, S, F>(handler: I, f: F) -> MethodRouter<S, Infallible>
+            where
+                I: Into<HandlerWithOperation<H, T, S>>,
+                H: Handler<T, S>,
+                T: 'static,
+                S: Clone + Send + Sync + 'static,
+                F: Fn(&mut OperationMeta) + Send + Sync + 'static,
+            {
+                on_with(MethodFilter::$method, handler, f)
+            }
+        }
     };
 }
 
@@ -85,6 +101,22 @@ macro_rules! chained_handler_fn {
         {
             self.on(MethodFilter::$method, handler)
         }
+
+        paste::paste! {
+            #[doc = concat!("Same as [`Self::", stringify!($name), "`], but allows to override operation metadata for this route.")]
+            ///
+            /// See [`MethodRouter::on_with`] for details.
+            pub fn [<$name _with>]<I, H, T, F>(self, handler: I, f: F) -> Self
+            where
+                I: Into<HandlerWithOperation<H, T, S>>,
+                H: Handler<T, S>,
+                T: 'static,
+                S: Send + Sync + 'static,
+                F: Fn(&mut OperationMeta) + Send + Sync + 'static,
+            {
+                self.on_with(MethodFilter::$method, handler, f)
+            }
+        }
     };
 }
 
@@ -119,6 +151,44 @@ where
     MethodRouter::new().on(filter, handler)
 }
 
+pub fn on_with<I, H, T, S, F>(filter: MethodFilter, handler: I, f: F) -> MethodRouter<S, Infallible>
+where
+    I: Into<HandlerWithOperation<H, T, S>>,
+    H: Handler<T, S>,
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+    F: Fn(&mut OperationMeta) + Send + Sync + 'static,
+{
+    MethodRouter::new().on_with(filter, handler, f)
+}
+
+/// Route requests with custom method (like `QUERY` or WebDAV methods) to the given handler.
+///
+/// See [`MethodRouter::on_custom`] for details.
+pub fn on_custom<I, H, T, S>(method: Method, handler: I) -> MethodRouter<S, Infallible>
+where
+    I: Into<HandlerWithOperation<H, T, S>>,
+    H: Handler<T, S>,
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    MethodRouter::new().on_custom(method, handler)
+}
+
+/// Route requests with custom method (like WebDAV methods) to the given service.
+///
+/// See [`MethodRouter::on_custom_service`] for details.
+pub fn on_custom_service<I, Svc, S>(method: Method, svc: I) -> MethodRouter<S, Infallible>
+where
+    I: Into<ServiceWithOperation<Svc, Infallible>>,
+    Svc: Service<Request, Error = Infallible> + Clone + Send + 'static,
+    Svc::Response: IntoResponse + 'static,
+    Svc::Future: Send + 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    MethodRouter::new().on_custom_service(method, svc)
+}
+
 top_level_handler_fn!(delete, DELETE);
 top_level_handler_fn!(get, GET);
 top_level_handler_fn!(head, HEAD);
@@ -128,133 +198,119 @@ top_level_handler_fn!(post, POST);
 top_level_handler_fn!(put, PUT);
 top_level_handler_fn!(trace, TRACE);
 
+/// Methods, supported by [`MethodFilter`], with their filters.
+const METHODS: [(MethodFilter, &Method); 9] = [
+    (MethodFilter::CONNECT, &Method::CONNECT),
+    (MethodFilter::GET, &Method::GET),
+    (MethodFilter::HEAD, &Method::HEAD),
+    (MethodFilter::DELETE, &Method::DELETE),
+    (MethodFilter::OPTIONS, &Method::OPTIONS),
+    (MethodFilter::PATCH, &Method::PATCH),
+    (MethodFilter::POST, &Method::POST),
+    (MethodFilter::PUT, &Method::PUT),
+    (MethodFilter::TRACE, &Method::TRACE),
+];
+
+/// Operations of method router by method (including methods, not supported by
+/// [`MethodFilter`]).
 #[derive(Clone, Default)]
 pub(super) struct MethodRouterOperations {
-    get: Option<OperationGenerator>,
-    head: Option<OperationGenerator>,
-    delete: Option<OperationGenerator>,
-    options: Option<OperationGenerator>,
-    patch: Option<OperationGenerator>,
-    post: Option<OperationGenerator>,
-    put: Option<OperationGenerator>,
-    trace: Option<OperationGenerator>,
+    operations: HashMap<Method, OperationEntry>,
+    /// Methods, handled without operation.
+    undocumented: Vec<Method>,
+    /// Whether router handles methods, which are not known (router is converted from
+    /// [`axum::routing::MethodRouter`]).
+    opaque: bool,
+    /// Whether methods without handler are handled by fallback (instead of being rejected with
+    /// `405 Method Not Allowed`).
+    fallback: bool,
 }
 
 impl MethodRouterOperations {
-    fn on(mut self, filter: MethodFilter, operation: Option<OperationGenerator>) -> Self {
-        if is_filter_present(filter, MethodFilter::GET) {
-            self.get = operation;
-        }
-        if is_filter_present(filter, MethodFilter::HEAD) {
-            self.head = operation;
-        }
-        if is_filter_present(filter, MethodFilter::DELETE) {
-            self.delete = operation;
-        }
-        if is_filter_present(filter, MethodFilter::OPTIONS) {
-            self.options = operation;
-        }
-        if is_filter_present(filter, MethodFilter::PATCH) {
-            self.patch = operation;
-        }
-        if is_filter_present(filter, MethodFilter::POST) {
-            self.post = operation;
-        }
-        if is_filter_present(filter, MethodFilter::PUT) {
-            self.put = operation;
-        }
-        if is_filter_present(filter, MethodFilter::TRACE) {
-            self.trace = operation;
+    fn on(mut self, filter: MethodFilter, operation: Option<OperationEntry>) -> Self {
+        for (method_filter, method) in METHODS {
+            if is_filter_present(filter, method_filter) {
+                self = self.on_method(method.clone(), operation.clone());
+            }
         }
         self
     }
 
-    pub(super) fn merge(self, other: Self) -> Self {
-        macro_rules! merge {
-            ( $first:ident, $second:ident ) => {
-                match ($first, $second) {
-                    (Some(_), Some(_)) => panic!(concat!(
-                        "Overlapping method operation. Cannot merge two method operation that both define `",
-                        stringify!($first),
-                        "`"
-                    )),
-                    (Some(svc), None) => Some(svc),
-                    (None, Some(svc)) => Some(svc),
-                    (None, None) => None,
-                }
-            };
+    pub(super) fn on_method(mut self, method: Method, operation: Option<OperationEntry>) -> Self {
+        self.undocumented.retain(|x| x != method);
+        match operation {
+            Some(operation) => {
+                let _ = self.operations.insert(method, operation);
+            }
+            None => {
+                let _ = self.operations.remove(&method);
+                self.undocumented.push(method);
+            }
         }
+        self
+    }
 
-        let Self {
-            get,
-            head,
-            delete,
-            options,
-            patch,
-            post,
-            put,
-            trace,
-        } = self;
-
-        let Self {
-            get: get_other,
-            head: head_other,
-            delete: delete_other,
-            options: options_other,
-            patch: patch_other,
-            post: post_other,
-            put: put_other,
-            trace: trace_other,
-        } = other;
-
-        let get = merge!(get, get_other);
-        let head = merge!(head, head_other);
-        let delete = merge!(delete, delete_other);
-        let options = merge!(options, options_other);
-        let patch = merge!(patch, patch_other);
-        let post = merge!(post, post_other);
-        let put = merge!(put, put_other);
-        let trace = merge!(trace, trace_other);
+    pub(super) fn merge(mut self, other: Self) -> Self {
+        for (method, operation) in other.operations {
+            if self.operations.contains_key(&method) {
+                panic!(
+                    "Overlapping method operation. Cannot merge two method operation that both \
+                     define `{}`",
+                    method.as_str().to_lowercase()
+                );
+            }
+            let _ = self.operations.insert(method, operation);
+        }
+        self.undocumented.extend(other.undocumented);
+        self.opaque |= other.opaque;
+        self.fallback |= other.fallback;
+        self
+    }
 
-        Self {
-            get,
-            head,
-            delete,
-            options,
-            patch,
-            post,
-            put,
-            trace,
-        }
+    /// Apply function to all operations.
+    pub(super) fn map_operations<F>(mut self, f: F) -> Self
+    where
+        F: Fn(OperationEntry) -> OperationEntry,
+    {
+        self.operations = self
+            .operations
+            .into_iter()
+            .map(|(method, operation)| (method, f(operation)))
+            .collect();
+        self
     }
 
-    pub(crate) fn into_map(self) -> HashMap<Method, OperationGenerator> {
-        let mut map = HashMap::new();
-        if let Some(m) = self.get {
-            let _ = map.insert(Method::GET, m);
-        }
-        if let Some(m) = self.head {
-            let _ = map.insert(Method::HEAD, m);
-        }
-        if let Some(m) = self.delete {
-            let _ = map.insert(Method::DELETE, m);
-        }
-        if let Some(m) = self.options {
-            let _ = map.insert(Method::OPTIONS, m);
-        }
-        if let Some(m) = self.patch {
-            let _ = map.insert(Method::PATCH, m);
-        }
-        if let Some(m) = self.post {
-            let _ = map.insert(Method::POST, m);
-        }
-        if let Some(m) = self.put {
-            let _ = map.insert(Method::PUT, m);
-        }
-        if let Some(m) = self.trace {
-            let _ = map.insert(Method::TRACE, m);
-        }
-        map
+    /// Methods, handled by router (with or without operation).
+    pub(super) fn methods(&self) -> impl Iterator<Item = &Method> {
+        self.operations.keys().chain(&self.undocumented)
+    }
+
+    /// Methods, handled with operation.
+    pub(super) fn documented(&self) -> impl Iterator<Item = &Method> {
+        self.operations.keys()
+    }
+
+    pub(super) fn undocumented(&self) -> &[Method] {
+        &self.undocumented
+    }
+
+    pub(super) fn is_opaque(&self) -> bool {
+        self.opaque
+    }
+
+    /// Whether router rejects requests with methods without handler.
+    pub(super) fn rejects_unhandled(&self) -> bool {
+        !self.opaque && !self.fallback
+    }
+
+    /// Mark methods, which are not known, as intentionally undocumented.
+    pub(super) fn known(mut self) -> Self {
+        self.opaque = false;
+        self
+    }
+
+    pub(crate) fn into_map(self) -> HashMap<Method, OperationEntry> {
+        self.operations
     }
 }
 
@@ -263,6 +319,114 @@ impl MethodRouterOperations {
 pub struct MethodRouter<S = (), E = Infallible> {
     pub(super) axum_method_router: AxumMethodRouter<S, E>,
     pub(super) operations: MethodRouterOperations,
+    /// Routes of custom methods, installed into axum method router when it is mounted.
+    pub(super) custom_methods: CustomMethods<S, E>,
+}
+
+/// Routes of custom methods (which are not supported by [`MethodFilter`]) and fallback,
+/// dispatched by single fallback of axum method router.
+///
+/// Dispatcher is installed only when method router is converted into axum one (so routers
+/// with custom methods can be merged, since axum doesn't allow merging routers, which both
+/// have fallback).
+pub(super) struct CustomMethods<S, E = Infallible> {
+    routes: Vec<(Method, AxumMethodRouter<S, E>)>,
+    fallback: Option<AxumMethodRouter<S, E>>,
+}
+
+impl<S, E> Clone for CustomMethods<S, E> {
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+            fallback: self.fallback.clone(),
+        }
+    }
+}
+
+impl<S, E> Default for CustomMethods<S, E> {
+    fn default() -> Self {
+        Self {
+            routes: Vec::new(),
+            fallback: None,
+        }
+    }
+}
+
+impl<S, E> CustomMethods<S, E> {
+    pub(super) fn is_empty(&self) -> bool {
+        self.routes.is_empty() && self.fallback.is_none()
+    }
+
+    fn insert(&mut self, method: Method, route: AxumMethodRouter<S, E>) {
+        self.routes.retain(|(x, _)| *x != method);
+        self.routes.push((method, route));
+    }
+
+    /// Apply function to routes and fallback.
+    fn map<S2, E2, F>(self, f: F) -> CustomMethods<S2, E2>
+    where
+        F: Fn(AxumMethodRouter<S, E>) -> AxumMethodRouter<S2, E2>,
+    {
+        CustomMethods {
+            routes: self
+                .routes
+                .into_iter()
+                .map(|(method, route)| (method, f(route)))
+                .collect(),
+            fallback: self.fallback.map(f),
+        }
+    }
+
+    /// Merge custom methods of two routers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if both routers handle same custom method or both have fallback (same as
+    /// [`axum::routing::MethodRouter::merge`]).
+    pub(super) fn merge(mut self, other: Self) -> Self {
+        for (method, route) in other.routes {
+            if self.routes.iter().any(|(x, _)| *x == method) {
+                panic!(
+                    "Overlapping method route. Cannot add two method routes that both handle `{method}`"
+                );
+            }
+            self.routes.push((method, route));
+        }
+        self.fallback = match (self.fallback, other.fallback) {
+            (Some(_), Some(_)) => {
+                panic!("Cannot merge two method routers that both define a fallback")
+            }
+            (lhs, rhs) => lhs.or(rhs),
+        };
+        self
+    }
+}
+
+impl<S> CustomMethods<S, Infallible>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    /// Set fallback of axum method router, which dispatches custom methods.
+    pub(super) fn install(self, router: AxumMethodRouter<S>) -> AxumMethodRouter<S> {
+        let Self { routes, fallback } = self;
+        match (routes.is_empty(), fallback) {
+            (true, None) => router,
+            (true, Some(fallback)) => router.fallback(fallback),
+            (false, fallback) => {
+                router.fallback(|State(state): State<S>, request: Request| async move {
+                    let route = routes
+                        .iter()
+                        .find(|(method, _)| method == request.method())
+                        .map(|(_, route)| route.clone())
+                        .or(fallback);
+                    match route {
+                        Some(route) => route.call(request, state).await,
+                        None => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+                    }
+                })
+            }
+        }
+    }
 }
 
 impl<S, E> fmt::Debug for MethodRouter<S, E> {
@@ -284,7 +448,11 @@ impl<S, E> From<AxumMethodRouter<S, E>> for MethodRouter<S, E> {
     fn from(value: AxumMethodRouter<S, E>) -> Self {
         Self {
             axum_method_router: value,
-            operations: Default::default(),
+            operations: MethodRouterOperations {
+                opaque: true,
+                ..Default::default()
+            },
+            custom_methods: CustomMethods::default(),
         }
     }
 }
@@ -306,7 +474,54 @@ where
 
         Self {
             axum_method_router: self.axum_method_router.on(filter, handler),
-            operations: self.operations.on(filter, operation),
+            operations: self
+                .operations
+                .on(filter, operation.map(OperationEntry::from)),
+            ..self
+        }
+    }
+
+    /// Same as [`Self::on`], but allows to override operation metadata (summary, tags,
+    /// operation ID, etc.) for this route.
+    ///
+    /// This is useful when the same handler is mounted on multiple routes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use okapi_operation::{*, axum_integration::*};
+    /// #[openapi(summary = "List users")]
+    /// async fn list_users() {}
+    ///
+    /// let app: Router = Router::new()
+    ///     .route("/users", get(openapi_handler!(list_users)))
+    ///     .route(
+    ///         "/v2/users",
+    ///         get_with(openapi_handler!(list_users), |op| {
+    ///             op.operation_id = Some("list_users_v2".into());
+    ///             op.tags.push("v2".into());
+    ///         }),
+    ///     );
+    /// ```
+    pub fn on_with<I, H, T, F>(self, filter: MethodFilter, handler: I, f: F) -> Self
+    where
+        I: Into<HandlerWithOperation<H, T, S>>,
+        H: Handler<T, S>,
+        T: 'static,
+        S: Send + Sync + 'static,
+        F: Fn(&mut OperationMeta) + Send + Sync + 'static,
+    {
+        let HandlerWithOperation {
+            handler, operation, ..
+        } = handler.into();
+
+        Self {
+            axum_method_router: self.axum_method_router.on(filter, handler),
+            operations: self.operations.on(
+                filter,
+                operation.map(|x| OperationEntry::from(x).customize(f)),
+            ),
+            ..self
         }
     }
 
@@ -319,16 +534,90 @@ where
     chained_handler_fn!(put, PUT);
     chained_handler_fn!(trace, TRACE);
 
-    pub fn fallback<H, T>(self, handler: H) -> Self
+    /// Route requests with custom method (like `QUERY` or WebDAV methods) to the given handler.
+    ///
+    /// Methods, supported by [`MethodFilter`], are routed with [`Self::on`]. Custom methods are
+    /// dispatched by fallback of underlying [`axum::routing::MethodRouter`] (requests with
+    /// other methods are passed to [`Self::fallback`], if set), so `fallback_service` shouldn't be
+    /// used together with custom methods. Dispatcher is installed when route is mounted, so
+    /// custom methods of same path can be added by different routes or merged routers.
+    ///
+    /// Operations of custom methods are documented according to
+    /// [`BuilderOptions::custom_methods`](crate::BuilderOptions::custom_methods).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use okapi_operation::{*, axum_integration::*};
+    /// # use axum::http::Method;
+    /// #[openapi]
+    /// async fn search() {}
+    ///
+    /// let query = Method::from_bytes(b"QUERY").unwrap();
+    /// let app: Router = Router::new().route("/users", on_custom(query, openapi_handler!(search)));
+    /// ```
+    pub fn on_custom<I, H, T>(mut self, method: Method, handler: I) -> Self
     where
+        I: Into<HandlerWithOperation<H, T, S>>,
         H: Handler<T, S>,
         T: 'static,
         S: Send + Sync + 'static,
     {
-        Self {
-            axum_method_router: self.axum_method_router.fallback(handler),
-            ..self
+        if let Ok(filter) = MethodFilter::try_from(method.clone()) {
+            return self.on(filter, handler);
         }
+        let HandlerWithOperation {
+            handler, operation, ..
+        } = handler.into();
+
+        self.custom_methods.insert(method.clone(), any(handler));
+        self.operations = self
+            .operations
+            .on_method(method, operation.map(OperationEntry::from));
+        self
+    }
+
+    /// Same as [`Self::on_custom`], but for services.
+    pub fn on_custom_service<I, Svc>(mut self, method: Method, svc: I) -> Self
+    where
+        I: Into<ServiceWithOperation<Svc, Infallible>>,
+        Svc: Service<Request, Error = Infallible> + Clone + Send + 'static,
+        Svc::Response: IntoResponse + 'static,
+        Svc::Future: Send + 'static,
+        S: Send + Sync + 'static,
+    {
+        if let Ok(filter) = MethodFilter::try_from(method.clone()) {
+            return self.on_service(filter, svc);
+        }
+        let ServiceWithOperation {
+            service, operation, ..
+        } = svc.into();
+
+        self.custom_methods
+            .insert(method.clone(), any_service(service));
+        self.operations = self
+            .operations
+            .on_method(method, operation.map(OperationEntry::from));
+        self
+    }
+
+    pub fn fallback<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+        S: Send + Sync + 'static,
+    {
+        self.custom_methods.fallback = Some(any(handler));
+        self.operations.fallback = true;
+        self
+    }
+
+    /// Convert method router into [`axum::routing::MethodRouter`], dropping related OpenAPI definitions.
+    pub fn into_axum(self) -> AxumMethodRouter<S>
+    where
+        S: Send + Sync + 'static,
+    {
+        self.custom_methods.install(self.axum_method_router)
     }
 }
 
@@ -340,14 +629,10 @@ where
         Self {
             axum_method_router: AxumMethodRouter::new(),
             operations: Default::default(),
+            custom_methods: CustomMethods::default(),
         }
     }
 
-    /// Convert method router into [`axum::routing::MethodRouter`], dropping related OpenAPI definitions.
-    pub fn into_axum(self) -> AxumMethodRouter<S, E> {
-        self.axum_method_router
-    }
-
     pub fn on_service<I, Svc>(self, filter: MethodFilter, svc: I) -> Self
     where
         I: Into<ServiceWithOperation<Svc, E>>,
@@ -360,7 +645,10 @@ where
         } = svc.into();
         Self {
             axum_method_router: self.axum_method_router.on_service(filter, service),
-            operations: self.operations.on(filter, operation),
+            operations: self
+                .operations
+                .on(filter, operation.map(OperationEntry::from)),
+            ..self
         }
     }
 
@@ -381,6 +669,10 @@ where
     {
         Self {
             axum_method_router: self.axum_method_router.fallback_service(svc),
+            operations: MethodRouterOperations {
+                fallback: true,
+                ..self.operations
+            },
             ..self
         }
     }
@@ -397,8 +689,9 @@ where
         NewError: 'static,
     {
         MethodRouter {
-            axum_method_router: self.axum_method_router.layer(layer),
+            axum_method_router: self.axum_method_router.layer(layer.clone()),
             operations: self.operations,
+            custom_methods: self.custom_methods.map(|x| x.layer(layer.clone())),
         }
     }
 
@@ -411,9 +704,15 @@ where
         E: 'static,
         S: 'static,
     {
+        let CustomMethods { routes, fallback } = self.custom_methods;
+        let routes = routes
+            .into_iter()
+            .map(|(method, route)| (method, route.route_layer(layer.clone())))
+            .collect();
         MethodRouter {
             axum_method_router: self.axum_method_router.route_layer(layer),
             operations: self.operations,
+            custom_methods: CustomMethods { routes, fallback },
         }
     }
 
@@ -421,6 +720,7 @@ where
         MethodRouter {
             axum_method_router: self.axum_method_router.merge(other.axum_method_router),
             operations: self.operations.merge(other.operations),
+            custom_methods: self.custom_methods.merge(other.custom_methods),
         }
     }
 
@@ -435,13 +735,15 @@ where
         S: 'static,
     {
         MethodRouter {
-            axum_method_router: self.axum_method_router.handle_error(f),
+            axum_method_router: self.axum_method_router.handle_error(f.clone()),
             operations: self.operations,
+            custom_methods: self.custom_methods.map(|x| x.handle_error(f.clone())),
         }
     }
 
     pub fn with_state<S2>(self, state: S) -> MethodRouter<S2, E> {
         MethodRouter {
+            custom_methods: self.custom_methods.map(|x| x.with_state(state.clone())),
             axum_method_router: self.axum_method_router.with_state(state),
             operations: self.operations,
         }
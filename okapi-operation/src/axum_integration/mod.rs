@@ -4,20 +4,35 @@
 pub use paste::paste;
 
 pub use self::{
+    cors::DocumentedCors,
+    finish::{FinishOpenapiOptions, FinishOptions, SpecFilter, Ui, DEFAULT_UI_PATH},
     handler_traits::{HandlerExt, HandlerWithOperation, ServiceExt, ServiceWithOperation},
     method_router::*,
+    operation_ids::{OperationIds, OperationInfo},
+    operations::{OperationDescriptor, RoutesOperations},
     router::{Router, DEFAULT_OPENAPI_PATH},
+    trait_impls::ApiResponse,
+    versioned::{version_prefix, VersionedRouter},
 };
 
+#[cfg(feature = "tracing")]
+pub use self::trace::{operation_span, OperationTrace, OperationTraceLayer};
+
+#[cfg(feature = "tracing")]
+mod trace;
 #[cfg(feature = "yaml")]
 mod yaml;
 
+mod cors;
+mod finish;
 mod handler_traits;
 mod method_router;
+mod operation_ids;
 mod operations;
 mod router;
 mod trait_impls;
-mod utils;
+pub(crate) mod utils;
+mod versioned;
 
 use axum::{
     extract::State,
@@ -49,6 +64,11 @@ use crate::*;
     crate = "crate"
 )]
 pub async fn serve_openapi_spec(spec: State<OpenApi>, headers: HeaderMap) -> Response {
+    spec_response(&spec.0, &headers)
+}
+
+/// Respond with specification in format, requested by `Accept` header.
+fn spec_response(spec: &OpenApi, headers: &HeaderMap) -> Response {
     let accept_header = headers
         .get(ACCEPT)
         .and_then(|h| h.to_str().ok())
@@ -56,9 +76,9 @@ pub async fn serve_openapi_spec(spec: State<OpenApi>, headers: HeaderMap) -> Res
 
     match accept_header {
         #[cfg(feature = "yaml")]
-        Some(accept_header) if accept_header.contains("yaml") => yaml::Yaml(spec.0).into_response(),
+        Some(accept_header) if accept_header.contains("yaml") => yaml::Yaml(spec).into_response(),
         Some(accept_header) if accept_header.contains("json") | accept_header.contains("*/*") => {
-            Json(spec.0).into_response()
+            Json(spec).into_response()
         }
         Some(_) => {
             let status = StatusCode::BAD_REQUEST;
@@ -75,7 +95,7 @@ pub async fn serve_openapi_spec(spec: State<OpenApi>, headers: HeaderMap) -> Res
         }
         None => {
             // Defaults to json
-            Json(spec.0).into_response()
+            Json(spec).into_response()
         }
     }
 }
@@ -85,6 +105,19 @@ pub async fn serve_openapi_spec(spec: State<OpenApi>, headers: HeaderMap) -> Res
 #[rustfmt::skip]
 #[macro_export]
 macro_rules! openapi_handler {
+    // Entry point for trait methods (`<Type as Trait>::method`)
+    (< $ty:ty as $($tr:ident)::+ > :: $fn_name:ident $(:: <$($gen_param:tt),+>)?) => {
+        $crate::axum_integration::paste!{
+            {
+                #[allow(unused_imports)]
+                use $crate::axum_integration::{HandlerExt, ServiceExt};
+
+                <$ty as $($tr)::+>::$fn_name :: <$($($gen_param),+)?>
+                    .with_openapi(<$ty as $($tr)::+>::[<$fn_name __openapi>])
+            }
+        }
+    };
+
     // Entry point
     ($($va:ident)::+ $(:: <$($gen_param:tt),+>)?) => {
         $crate::openapi_handler!(@inner $($va)+; ; $($($gen_param)+)?)
@@ -0,0 +1,98 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    extract::MatchedPath,
+    http::{Method, Request},
+};
+use okapi::openapi3::Operation;
+
+/// Lookup table of operations by matched routes, built while generating specification.
+///
+/// Table is added to request extensions by [`Router::finish_openapi`](super::Router::finish_openapi)
+/// (and other methods, generating specification), so middlewares (added to [`Router`](super::Router)
+/// before generating specification) can label metrics or traces with the same `operationId`s,
+/// as in specification:
+///
+/// ```rust
+/// # use okapi_operation::{axum_integration::*, *};
+/// use axum::{extract::Request, middleware::{self, Next}, response::Response};
+///
+/// #[openapi(operation_id = "list_users")]
+/// async fn list_users() {}
+///
+/// async fn record_metrics(request: Request, next: Next) -> Response {
+///     let operation_id = OperationIds::for_request(&request).unwrap_or("unknown").to_owned();
+///     let response = next.run(request).await;
+///     // Record metrics, labeled with `operation_id`
+///     response
+/// }
+///
+/// let app = Router::<()>::new()
+///     .route("/users", get(openapi_handler!(list_users)))
+///     .layer(middleware::from_fn(record_metrics))
+///     .finish_openapi("/openapi", "Demo", "1.0.0")
+///     .expect("no problem");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OperationIds(Arc<HashMap<String, HashMap<Method, OperationInfo>>>);
+
+/// Identifying fields of operation, documented in specification.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OperationInfo {
+    /// `operationId` of operation.
+    pub operation_id: Option<String>,
+    /// Tags of operation.
+    pub tags: Vec<String>,
+}
+
+impl From<Operation> for OperationInfo {
+    fn from(value: Operation) -> Self {
+        Self {
+            operation_id: value.operation_id,
+            tags: value.tags,
+        }
+    }
+}
+
+impl OperationIds {
+    pub(super) fn new(operations: HashMap<String, HashMap<Method, OperationInfo>>) -> Self {
+        Self(Arc::new(operations))
+    }
+
+    /// Get `operationId` by matched route (in Axum format, like `/users/:id`) and method.
+    ///
+    /// `HEAD` requests fall back to `GET` operation (since Axum handles them with `GET`
+    /// handlers).
+    pub fn get(&self, matched_path: &str, method: &Method) -> Option<&str> {
+        self.info(matched_path, method)?.operation_id.as_deref()
+    }
+
+    /// Get [`OperationInfo`] by matched route and method (like [`get`](Self::get)).
+    pub fn info(&self, matched_path: &str, method: &Method) -> Option<&OperationInfo> {
+        let operations = self.0.get(matched_path)?;
+        operations.get(method).or_else(|| {
+            (*method == Method::HEAD)
+                .then(|| operations.get(&Method::GET))
+                .flatten()
+        })
+    }
+
+    /// Get `operationId` of request by its [`MatchedPath`] and method.
+    ///
+    /// Returns `None` if request has no matched route or lookup table (i.e. middleware is
+    /// applied after generating specification), or if route has no `operationId`.
+    pub fn for_request<B>(request: &Request<B>) -> Option<&str> {
+        Self::info_for_request(request)?.operation_id.as_deref()
+    }
+
+    /// Get [`OperationInfo`] of request by its [`MatchedPath`] and method (like
+    /// [`for_request`](Self::for_request)).
+    pub fn info_for_request<B>(request: &Request<B>) -> Option<&OperationInfo> {
+        let matched_path = request.extensions().get::<MatchedPath>()?;
+        request
+            .extensions()
+            .get::<Self>()?
+            .info(matched_path.as_str(), request.method())
+    }
+}
@@ -1,12 +1,17 @@
 use std::collections::HashMap;
 
 use axum::http::Method;
+use serde::Serialize;
 
-use super::method_router::MethodRouterOperations;
-use crate::OperationGenerator;
+use super::{method_router::MethodRouterOperations, utils::convert_axum_path_to_openapi};
+use crate::{
+    operation_meta::OperationEntry, AnyOperationGenerator, Error, OpenApiBuilder,
+    OperationGenerator,
+};
 
+/// Operations of routes, collected by [`Router`](super::Router).
 #[derive(Clone, Default)]
-pub struct RoutesOperations(pub(super) HashMap<String, HashMap<Method, OperationGenerator>>);
+pub struct RoutesOperations(pub(super) HashMap<String, HashMap<Method, OperationEntry>>);
 
 impl RoutesOperations {
     pub(super) fn new(routes_operations: HashMap<String, MethodRouterOperations>) -> Self {
@@ -14,7 +19,7 @@ impl RoutesOperations {
             routes_operations
                 .into_iter()
                 .filter_map(|(path, operations)| {
-                    let op_map: HashMap<Method, OperationGenerator> = operations.into_map();
+                    let op_map: HashMap<Method, OperationEntry> = operations.into_map();
                     if op_map.is_empty() {
                         None
                     } else {
@@ -26,11 +31,36 @@ impl RoutesOperations {
     }
 
     pub fn get(&self, path: &str, method: &Method) -> Option<&OperationGenerator> {
-        self.0.get(path).and_then(|x| x.get(method))
+        self.0
+            .get(path)
+            .and_then(|x| x.get(method))
+            .and_then(|x| x.sync_generator())
     }
 
-    pub fn get_path(&self, path: &str) -> Option<&HashMap<Method, OperationGenerator>> {
-        self.0.get(path)
+    pub fn get_path(&self, path: &str) -> Option<HashMap<Method, OperationGenerator>> {
+        self.0.get(path).map(|x| {
+            x.iter()
+                .filter_map(|(method, entry)| Some((method.clone(), *entry.sync_generator()?)))
+                .collect()
+        })
+    }
+
+    /// Iterate over routes paths (in Axum format), methods and generators of operations
+    /// (both synchronous and asynchronous), sorted by path and method.
+    pub fn iter(&self) -> std::vec::IntoIter<(&str, &Method, AnyOperationGenerator)> {
+        let mut operations: Vec<_> = self
+            .0
+            .iter()
+            .flat_map(|(path, methods)| {
+                methods
+                    .iter()
+                    .map(move |(method, entry)| (path.as_str(), method, entry.generator))
+            })
+            .collect();
+        operations.sort_by(|(lpath, lmethod, _), (rpath, rmethod, _)| {
+            (lpath, lmethod.as_str()).cmp(&(rpath, rmethod.as_str()))
+        });
+        operations.into_iter()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -42,10 +72,84 @@ impl RoutesOperations {
             .iter()
             .flat_map(|(path, methods)| {
                 let path = path.clone();
-                methods
-                    .iter()
-                    .map(move |(method, op)| ((path.clone(), method.clone()), *op))
+                methods.iter().filter_map(move |(method, op)| {
+                    Some(((path.clone(), method.clone()), *op.sync_generator()?))
+                })
             })
             .collect()
     }
+
+    /// Generate machine-readable inventory of operations (like for registering metrics of
+    /// every operation in advance), sorted by route and method.
+    ///
+    /// Operations are generated with default [`OpenApiBuilder`], use
+    /// [`inventory_with`](Self::inventory_with) (or
+    /// [`Router::operation_inventory`](super::Router::operation_inventory)) to apply builder
+    /// options (like inferring `operationId`).
+    pub fn inventory(&self) -> Result<Vec<OperationDescriptor>, Error> {
+        self.inventory_with(OpenApiBuilder::default())
+    }
+
+    /// Generate inventory of operations (like [`inventory`](Self::inventory)), adding them to
+    /// specified builder.
+    pub fn inventory_with(
+        &self,
+        mut builder: OpenApiBuilder,
+    ) -> Result<Vec<OperationDescriptor>, Error> {
+        let mut routes = Vec::new();
+        for (route, method, mut entry) in self.clone().into_entries() {
+            let path = convert_axum_path_to_openapi(&route);
+            entry.wildcard = path.wildcard;
+            builder.try_operation_entry(path.path.clone(), method.clone(), entry)?;
+            routes.push((route, method, path.path));
+        }
+        let spec = builder.build()?;
+        let mut inventory: Vec<_> = routes
+            .into_iter()
+            .filter_map(|(route, method, path)| {
+                let (path, operation) = builder.route_operation(&spec, &path, &method)?;
+                Some(OperationDescriptor {
+                    operation_id: operation.operation_id,
+                    method: method.as_str().into(),
+                    path,
+                    route,
+                })
+            })
+            .collect();
+        inventory.sort_by(|l, r| (&l.route, &l.method).cmp(&(&r.route, &r.method)));
+        Ok(inventory)
+    }
+
+    pub(super) fn into_entries(self) -> impl Iterator<Item = (String, Method, OperationEntry)> {
+        self.0.into_iter().flat_map(|(path, methods)| {
+            methods
+                .into_iter()
+                .map(move |(method, entry)| (path.clone(), method, entry))
+        })
+    }
+}
+
+impl<'a> IntoIterator for &'a RoutesOperations {
+    type Item = (&'a str, &'a Method, AnyOperationGenerator);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Entry of inventory of operations (see [`RoutesOperations::inventory`]).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct OperationDescriptor {
+    /// `operationId` of operation.
+    pub operation_id: Option<String>,
+    /// HTTP method (in uppercase, like `GET`).
+    pub method: String,
+    /// Path template in specification (like `/users/{id}`).
+    pub path: String,
+    /// Route in Axum format (like `/users/:id`), as returned by
+    /// [`MatchedPath`](axum::extract::MatchedPath).
+    pub route: String,
 }
@@ -1,18 +1,31 @@
 use std::{collections::HashMap, convert::Infallible, fmt};
 
 use axum::{
-    extract::Request, handler::Handler, http::Method, response::IntoResponse, routing::Route,
-    Router as AxumRouter,
+    extract::Request,
+    handler::Handler,
+    http::Method,
+    response::{Html, IntoResponse},
+    routing::{MethodFilter, MethodRouter as AxumMethodRouter, Route},
+    Extension, Router as AxumRouter,
 };
+use okapi::openapi3::Operation;
 use tower::{Layer, Service};
 
 use super::{
+    cors::{preflight_generator, preflight_operation, DocumentedCors},
+    finish::{FinishOpenapiOptions, FinishOptions},
     get,
-    method_router::{MethodRouter, MethodRouterOperations},
-    operations::RoutesOperations,
-    utils::convert_axum_path_to_openapi,
+    handler_traits::HandlerWithOperation,
+    method_router::{CustomMethods, MethodRouter, MethodRouterOperations},
+    on, on_custom,
+    operation_ids::{OperationIds, OperationInfo},
+    operations::{OperationDescriptor, RoutesOperations},
+    utils::{convert_axum_path_to_openapi, convert_openapi_path_to_axum, join_nested_path},
+    versioned::VersionedRouter,
+};
+use crate::{
+    operation_meta::OperationEntry, Components, Error, OpenApiBuilder, OperationGenerator,
 };
-use crate::OpenApiBuilder;
 
 pub const DEFAULT_OPENAPI_PATH: &str = "/openapi";
 
@@ -21,10 +34,22 @@ pub const DEFAULT_OPENAPI_PATH: &str = "/openapi";
 /// This replacement cannot be used as [`Service`] instead require explicit
 /// convertion of this type to `axum::Router`. This is done to ensure that
 /// OpenAPI specification generated and mounted.
+///
+/// With `into-axum-router` feature `From<Router>` is implemented for `axum::Router`,
+/// which allows to use this router where `axum::Router` is expected (i.e. when
+/// migrating application incrementally). Such conversion doesn't mount specification.
 pub struct Router<S = ()> {
     axum_router: AxumRouter<S>,
     routes_operations_map: HashMap<String, MethodRouterOperations>,
+    /// Routes of custom methods by path, which are not yet installed into axum router (so
+    /// routes of same path, added by different calls, are dispatched together).
+    custom_methods: HashMap<String, CustomMethods<S>>,
     openapi_builder_template: OpenApiBuilder,
+    fallback_operation: Option<OperationGenerator>,
+    allow_undocumented: bool,
+    /// Paths (in Axum format), at which routers and services with unknown routes (like
+    /// plain [`axum::Router`]) are mounted.
+    opaque_routers: Vec<String>,
 }
 
 impl<S> From<AxumRouter<S>> for Router<S> {
@@ -32,11 +57,25 @@ impl<S> From<AxumRouter<S>> for Router<S> {
         Self {
             axum_router: value,
             routes_operations_map: Default::default(),
+            custom_methods: HashMap::new(),
             openapi_builder_template: OpenApiBuilder::default(),
+            fallback_operation: None,
+            allow_undocumented: true,
+            opaque_routers: vec!["/".into()],
         }
     }
 }
 
+#[cfg(feature = "into-axum-router")]
+impl<S> From<Router<S>> for AxumRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn from(value: Router<S>) -> Self {
+        value.install_custom_methods().axum_router
+    }
+}
+
 impl<S> Default for Router<S>
 where
     S: Clone + Send + Sync + 'static,
@@ -64,10 +103,19 @@ where
         Self {
             axum_router: AxumRouter::new(),
             routes_operations_map: HashMap::new(),
+            custom_methods: HashMap::new(),
             openapi_builder_template: OpenApiBuilder::default(),
+            fallback_operation: None,
+            allow_undocumented: true,
+            opaque_routers: Vec::new(),
         }
     }
 
+    /// Create router with routes, grouped by API version (see [`VersionedRouter`]).
+    pub fn versioned() -> VersionedRouter<S> {
+        VersionedRouter::new()
+    }
+
     /// Add another route to the router.
     ///
     /// This method works for both [`MethodRouter`] and one from axum.
@@ -92,20 +140,177 @@ where
     where
         R: Into<MethodRouter<S>>,
     {
-        let method_router = method_router.into();
+        let MethodRouter {
+            axum_method_router,
+            operations,
+            custom_methods,
+        } = method_router.into();
 
         // Merge operations
         let s = self.routes_operations_map.entry(path.into()).or_default();
-        *s = s.clone().merge(method_router.operations);
+        *s = s.clone().merge(operations);
+
+        if !custom_methods.is_empty() {
+            let s = self.custom_methods.entry(path.into()).or_default();
+            *s = std::mem::take(s).merge(custom_methods);
+        }
 
         Self {
-            axum_router: self
-                .axum_router
-                .route(path, method_router.axum_method_router),
+            axum_router: self.axum_router.route(path, axum_method_router),
             ..self
         }
     }
 
+    /// Add route to the router only if `enabled` is `true` (i.e. when endpoint is enabled by
+    /// runtime flag), otherwise route is neither served nor documented.
+    pub fn route_if<R>(self, enabled: bool, path: &str, method_router: R) -> Self
+    where
+        R: Into<MethodRouter<S>>,
+    {
+        if enabled {
+            self.route(path, method_router)
+        } else {
+            self
+        }
+    }
+
+    /// Add route for handler, which declares its path and method with
+    /// `#[openapi(path = "...", method = "...")]`.
+    ///
+    /// Declared path (in OpenAPI format, like `/users/{id}`) is converted to Axum format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if handler has no OpenAPI operation or its operation doesn't declare route.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use okapi_operation::{*, axum_integration::*};
+    /// #[openapi(path = "/users/{id}", method = "get")]
+    /// async fn get_user() {}
+    ///
+    /// let app = Router::<()>::new().route_auto(openapi_handler!(get_user));
+    /// ```
+    pub fn route_auto<I, H, T>(self, handler: I) -> Self
+    where
+        I: Into<HandlerWithOperation<H, T, S>>,
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        let handler = handler.into();
+        let Some((path, method)) = handler.operation.and_then(Components::declared_route) else {
+            panic!("Handler should have OpenAPI operation with declared path and method");
+        };
+        let method_router = match MethodFilter::try_from(method.clone()) {
+            Ok(filter) => on(filter, handler),
+            Err(_) => on_custom(method, handler),
+        };
+        self.route(&convert_openapi_path_to_axum(&path), method_router)
+    }
+
+    /// Add route with [`axum::routing::MethodRouter`], documenting its methods with
+    /// specified operation generators.
+    ///
+    /// Useful for adopting specification generation in existing routing code. Methods without
+    /// generator are not documented (and not reported by
+    /// [`allow_undocumented`](Self::allow_undocumented)).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use axum::http::Method;
+    /// # use okapi_operation::{*, axum_integration::*};
+    /// #[openapi]
+    /// async fn handler() {}
+    ///
+    /// let app = Router::<()>::new().route_documented(
+    ///     "/",
+    ///     axum::routing::get(handler),
+    ///     [(Method::GET, handler__openapi as OperationGenerator)],
+    /// );
+    /// ```
+    pub fn route_documented<I>(
+        self,
+        path: &str,
+        method_router: AxumMethodRouter<S>,
+        operations: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = (Method, OperationGenerator)>,
+    {
+        let mut method_router = MethodRouter::from(method_router);
+        method_router.operations = method_router.operations.known();
+        for (method, generator) in operations {
+            method_router.operations = method_router
+                .operations
+                .on_method(method, Some(generator.into()));
+        }
+        self.route(path, method_router)
+    }
+
+    /// Add route with [`axum::routing::MethodRouter`], which is intentionally not documented
+    /// (like health checks or internal endpoints).
+    ///
+    /// Route is served like any other route (layers, applied to router afterwards, are applied
+    /// to it), but is neither documented, nor reported as undocumented (see
+    /// [`allow_undocumented`](Self::allow_undocumented)).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use okapi_operation::axum_integration::*;
+    /// let app = Router::<()>::new()
+    ///     .route_undocumented("/health", axum::routing::get(|| async {}))
+    ///     .allow_undocumented(false);
+    /// assert!(app.finish_openapi("/openapi", "Demo", "1.0.0").is_ok());
+    /// ```
+    pub fn route_undocumented(self, path: &str, method_router: AxumMethodRouter<S>) -> Self {
+        Self {
+            axum_router: self.axum_router.route(path, method_router),
+            ..self
+        }
+    }
+
+    /// Merge [`OpenApiBuilder`] template of nested or merged router into template of this
+    /// router, returning other router.
+    ///
+    /// Definitions of this router win: only tags and components, missing in this template, are
+    /// added. Global security requirements and servers of other template are applied to its
+    /// operations, which don't define their own. Other fields (like info) are ignored.
+    fn absorb_template(&mut self, mut other: Router<S>) -> Router<S> {
+        let mut template = std::mem::take(&mut other.openapi_builder_template);
+        self.openapi_builder_template.merge_template(&template);
+
+        let spec = template.spec_mut();
+        let security = std::mem::take(&mut spec.security);
+        let servers = std::mem::take(&mut spec.servers);
+        if security.is_empty() && servers.is_empty() {
+            return other;
+        }
+        other.customize_operations(move |operation| {
+            if operation.security.is_none() && !security.is_empty() {
+                operation.security = Some(security.clone());
+            }
+            if operation.servers.is_none() && !servers.is_empty() {
+                operation.servers = Some(servers.clone());
+            }
+        });
+        other
+    }
+
+    /// Add function for modifying all operations of router.
+    pub(super) fn customize_operations<F>(&mut self, f: F)
+    where
+        F: Fn(&mut Operation) + Clone + Send + Sync + 'static,
+    {
+        for operations in self.routes_operations_map.values_mut() {
+            let f = f.clone();
+            *operations = std::mem::take(operations)
+                .map_operations(move |entry| entry.customize_operation(f.clone()));
+        }
+    }
+
     /// Add another route to the router that calls a [`Service`].
     ///
     /// For details see [`axum::Router::route_service`].
@@ -148,12 +353,18 @@ where
     where
         R: Into<Router<S>>,
     {
-        let router = router.into();
+        let router = self.absorb_template(router.into()).install_custom_methods();
         for (inner_path, operation) in router.routes_operations_map.into_iter() {
             let _ = self
                 .routes_operations_map
-                .insert(format!("{}{}", path, inner_path), operation);
+                .insert(join_nested_path(path, &inner_path), operation);
         }
+        self.opaque_routers.extend(
+            router
+                .opaque_routers
+                .iter()
+                .map(|inner_path| join_nested_path(path, inner_path)),
+        );
         Self {
             axum_router: self.axum_router.nest(path, router.axum_router),
             ..self
@@ -163,12 +374,13 @@ where
     /// Like `nest`, but accepts an arbitrary [`Service`].
     ///
     /// For details see [`axum::Router::nest_service`].
-    pub fn nest_service<Svc>(self, path: &str, svc: Svc) -> Self
+    pub fn nest_service<Svc>(mut self, path: &str, svc: Svc) -> Self
     where
         Svc: Service<Request, Error = Infallible> + Clone + Send + 'static,
         Svc::Response: IntoResponse,
         Svc::Future: Send + 'static,
     {
+        self.opaque_routers.push(path.into());
         Self {
             axum_router: self.axum_router.nest_service(path, svc),
             ..self
@@ -199,9 +411,17 @@ where
     where
         R: Into<Router<S>>,
     {
-        let other = other.into();
-        self.routes_operations_map
-            .extend(other.routes_operations_map);
+        let other = self.absorb_template(other.into());
+        for (path, operations) in other.routes_operations_map {
+            let s = self.routes_operations_map.entry(path).or_default();
+            *s = std::mem::take(s).merge(operations);
+        }
+        for (path, custom_methods) in other.custom_methods {
+            let s = self.custom_methods.entry(path).or_default();
+            *s = std::mem::take(s).merge(custom_methods);
+        }
+        self.fallback_operation = self.fallback_operation.or(other.fallback_operation);
+        self.opaque_routers.extend(other.opaque_routers);
         Self {
             axum_router: self.axum_router.merge(other.axum_router),
             ..self
@@ -219,11 +439,46 @@ where
         <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
         <L::Service as Service<Request>>::Future: Send + 'static,
     {
+        let this = self.install_custom_methods();
         Router {
-            axum_router: self.axum_router.layer(layer),
-            routes_operations_map: self.routes_operations_map,
-            openapi_builder_template: self.openapi_builder_template,
+            axum_router: this.axum_router.layer(layer),
+            routes_operations_map: this.routes_operations_map,
+            custom_methods: HashMap::new(),
+            openapi_builder_template: this.openapi_builder_template,
+            fallback_operation: this.fallback_operation,
+            allow_undocumented: this.allow_undocumented,
+            opaque_routers: this.opaque_routers,
+        }
+    }
+
+    /// Apply CORS layer, wrapped in [`DocumentedCors`], to the router, documenting `OPTIONS`
+    /// preflight requests for all routes, added before this call.
+    ///
+    /// Preflight operation documents allowed methods (ones, handled by route, and `OPTIONS`)
+    /// and headers in response headers. Routes with documented `OPTIONS` operation are left
+    /// unchanged.
+    pub fn cors_layer<L>(mut self, cors: DocumentedCors<L>) -> Router<S>
+    where
+        L: Layer<Route> + Clone + Send + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        let allow_headers = cors.allow_headers.join(", ");
+        for operations in self.routes_operations_map.values_mut() {
+            let mut methods: Vec<_> = operations.methods().map(Method::as_str).collect();
+            if methods.contains(&Method::OPTIONS.as_str()) {
+                continue;
+            }
+            methods.push(Method::OPTIONS.as_str());
+            methods.sort_unstable();
+            let operation = preflight_operation(&methods.join(", "), &allow_headers);
+            let entry = OperationEntry::from(preflight_generator as OperationGenerator)
+                .customize_operation(move |x| *x = operation.clone());
+            *operations = std::mem::take(operations).on_method(Method::OPTIONS, Some(entry));
         }
+        self.layer(cors.layer)
     }
 
     /// Apply a [`tower::Layer`] to the router that will only run if the request matches a route.
@@ -237,10 +492,15 @@ where
         <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
         <L::Service as Service<Request>>::Future: Send + 'static,
     {
+        let this = self.install_custom_methods();
         Router {
-            axum_router: self.axum_router.route_layer(layer),
-            routes_operations_map: self.routes_operations_map,
-            openapi_builder_template: self.openapi_builder_template,
+            axum_router: this.axum_router.route_layer(layer),
+            routes_operations_map: this.routes_operations_map,
+            custom_methods: HashMap::new(),
+            openapi_builder_template: this.openapi_builder_template,
+            fallback_operation: this.fallback_operation,
+            allow_undocumented: this.allow_undocumented,
+            opaque_routers: this.opaque_routers,
         }
     }
 
@@ -251,7 +511,8 @@ where
     ///
     /// # Note
     ///
-    /// This method doesn't add anything to OpenaAPI spec.
+    /// This method doesn't add anything to OpenaAPI spec (use
+    /// [`fallback_documented`](Self::fallback_documented) instead).
     pub fn fallback<H, T>(self, handler: H) -> Self
     where
         H: Handler<T, S>,
@@ -259,6 +520,45 @@ where
     {
         Router {
             axum_router: self.axum_router.fallback(handler),
+            fallback_operation: None,
+            ..self
+        }
+    }
+
+    /// Add a fallback handler to the router, documenting it with handler's operation.
+    ///
+    /// Since OpenAPI has no way to describe catch-all routes, operation is put into
+    /// `x-fallback` extension of specification (see [`OpenApiBuilder::fallback_operation`]).
+    ///
+    /// For details see [`axum::Router::fallback`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use okapi_operation::{*, axum_integration::*};
+    /// #[openapi(
+    ///     summary = "Not found",
+    ///     responses(
+    ///         ignore_return_type = true,
+    ///         response(status = "404", description = "Not found", content = "String")
+    ///     )
+    /// )]
+    /// async fn not_found() -> (http::StatusCode, &'static str) {
+    ///     (http::StatusCode::NOT_FOUND, "Not found")
+    /// }
+    ///
+    /// let app = Router::<()>::new().fallback_documented(openapi_handler!(not_found));
+    /// ```
+    pub fn fallback_documented<I, H, T>(self, handler: I) -> Self
+    where
+        I: Into<HandlerWithOperation<H, T, S>>,
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        let handler = handler.into();
+        Router {
+            axum_router: self.axum_router.fallback(handler.handler),
+            fallback_operation: handler.operation,
             ..self
         }
     }
@@ -278,6 +578,7 @@ where
     {
         Router {
             axum_router: self.axum_router.fallback_service(svc),
+            fallback_operation: None,
             ..self
         }
     }
@@ -286,24 +587,48 @@ where
     ///
     /// For details see [`axum::Router::with_state`].
     pub fn with_state<S2>(self, state: S) -> Router<S2> {
+        let this = self.install_custom_methods();
         Router {
-            axum_router: self.axum_router.with_state(state),
-            routes_operations_map: self.routes_operations_map,
-            openapi_builder_template: self.openapi_builder_template,
+            axum_router: this.axum_router.with_state(state),
+            routes_operations_map: this.routes_operations_map,
+            custom_methods: HashMap::new(),
+            openapi_builder_template: this.openapi_builder_template,
+            fallback_operation: this.fallback_operation,
+            allow_undocumented: this.allow_undocumented,
+            opaque_routers: this.opaque_routers,
         }
     }
 
     /// Separate router into [`axum::Router`] and list of operations.
     pub fn into_parts(self) -> (AxumRouter<S>, RoutesOperations) {
+        let this = self.install_custom_methods();
         (
-            self.axum_router,
-            RoutesOperations::new(self.routes_operations_map),
+            this.axum_router,
+            RoutesOperations::new(this.routes_operations_map),
         )
     }
 
     /// Get inner [`axum::Router`].
     pub fn axum_router(&self) -> AxumRouter<S> {
-        self.axum_router.clone()
+        self.custom_methods.iter().fold(
+            self.axum_router.clone(),
+            |router, (path, custom_methods)| {
+                router.route(path, custom_methods.clone().install(Default::default()))
+            },
+        )
+    }
+
+    /// Install dispatchers of custom methods into axum router.
+    ///
+    /// Called before operations, after which routes of same path can't be merged anymore
+    /// (like adding layers or converting into axum router).
+    fn install_custom_methods(mut self) -> Self {
+        for (path, custom_methods) in std::mem::take(&mut self.custom_methods) {
+            self.axum_router = self
+                .axum_router
+                .route(&path, custom_methods.install(Default::default()));
+        }
+        self
     }
 
     /// Get list of operations.
@@ -311,6 +636,29 @@ where
         RoutesOperations::new(self.routes_operations_map.clone())
     }
 
+    /// Paths of documented operations (in OpenAPI format), as they would appear in
+    /// specification, sorted and deduplicated.
+    ///
+    /// Path rewrites of builder (like [`OpenApiBuilder::path_prefix`]) are not applied.
+    pub fn openapi_paths(&self) -> Vec<String> {
+        let mut paths: Vec<_> = self
+            .routes_operations()
+            .iter()
+            .map(|(path, _, _)| convert_axum_path_to_openapi(path).path)
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    /// Generate inventory of operations of router (see [`RoutesOperations::inventory`]), using
+    /// [`OpenApiBuilder`] template of router (so inferred `operationId`s and path prefixes
+    /// are the same, as in specification).
+    pub fn operation_inventory(&self) -> Result<Vec<OperationDescriptor>, Error> {
+        self.routes_operations()
+            .inventory_with(self.openapi_builder_template.clone())
+    }
+
     /// Generate [`OpenApiBuilder`] from current router.
     ///
     /// Generated builder will be based on current builder template,
@@ -318,18 +666,109 @@ where
     ///
     /// If template was not set, then [`OpenApiBuilder::default()`] is used.
     pub fn generate_openapi_builder(&self) -> OpenApiBuilder {
-        let routes = self.routes_operations().openapi_operation_generators();
         let mut builder = self.openapi_builder_template.clone();
         // Don't use try_operations since duplicates should be checked
         // when mounting route to axum router.
-        builder.operations(
-            routes
-                .into_iter()
-                .map(|((x, y), z)| (convert_axum_path_to_openapi(&x), y, z)),
-        );
+        for (path, method, mut entry) in self.routes_operations().into_entries() {
+            let path = convert_axum_path_to_openapi(&path);
+            entry.wildcard = path.wildcard;
+            let _ = builder.try_operation_entry(path.path, method, entry);
+        }
+        let mut undocumented: Vec<_> = self
+            .routes_operations_map
+            .iter()
+            .flat_map(|(path, operations)| {
+                operations
+                    .undocumented()
+                    .iter()
+                    .map(move |method| (convert_axum_path_to_openapi(path).path, method))
+            })
+            .collect();
+        undocumented.sort_by(|(lpath, lmethod), (rpath, rmethod)| {
+            (lpath, lmethod.as_str()).cmp(&(rpath, rmethod.as_str()))
+        });
+        for (path, method) in undocumented {
+            builder.undocumented_route(path, method.clone());
+        }
+        let mut allowed: Vec<_> = self
+            .routes_operations_map
+            .iter()
+            .filter(|(_, operations)| operations.rejects_unhandled())
+            .map(|(path, operations)| {
+                let mut methods: Vec<_> = operations.methods().cloned().collect();
+                // Axum handles `HEAD` requests with `GET` handler
+                if methods.contains(&Method::GET) && !methods.contains(&Method::HEAD) {
+                    methods.push(Method::HEAD);
+                }
+                methods.sort_by(|lhs, rhs| lhs.as_str().cmp(rhs.as_str()));
+                (convert_axum_path_to_openapi(path).path, methods)
+            })
+            .collect();
+        allowed.sort_by(|(lpath, _), (rpath, _)| lpath.cmp(rpath));
+        for (path, methods) in allowed {
+            builder.allowed_methods(path, methods);
+        }
+        if let Some(generator) = self.fallback_operation {
+            builder.fallback_operation(generator);
+        }
+        if !self.allow_undocumented {
+            builder.deny_undocumented_routes(self.undocumented_routes());
+        }
         builder
     }
 
+    /// Document `HEAD` operations for routes with `GET` operation (Axum handles `HEAD`
+    /// requests with `GET` handlers).
+    ///
+    /// See [`BuilderOptions::document_head_for_get`](crate::BuilderOptions::document_head_for_get).
+    pub fn document_head_for_get(mut self, document_head_for_get: bool) -> Self {
+        self.openapi_builder_template
+            .document_head_for_get(document_head_for_get);
+        self
+    }
+
+    /// Allow routes, handled without OpenAPI operation (`true` by default).
+    ///
+    /// If `false`, building specification (with [`finish_openapi`](Self::finish_openapi),
+    /// [`OpenApiBuilder`], returned by
+    /// [`generate_openapi_builder`](Self::generate_openapi_builder), and other methods)
+    /// returns [`Error::UndocumentedRoutes`] if any route (including routes of nested and
+    /// merged routers) has method, handled without operation, or is added with
+    /// [`axum::routing::MethodRouter`] (which methods are not known). Plain [`axum::Router`]s
+    /// (merged or nested) and services, nested with [`nest_service`](Self::nest_service), are
+    /// reported as `<path>/*`, since their routes are not known. Routes, which are
+    /// undocumented intentionally, should be added with
+    /// [`route_undocumented`](Self::route_undocumented).
+    pub fn allow_undocumented(mut self, allow_undocumented: bool) -> Self {
+        self.allow_undocumented = allow_undocumented;
+        self
+    }
+
+    /// Routes (in OpenAPI format), handled without operation: `<METHOD> <path>` for known
+    /// methods and `<path>` for routes with unknown methods.
+    fn undocumented_routes(&self) -> Vec<String> {
+        let mut routes: Vec<_> = self
+            .routes_operations_map
+            .iter()
+            .flat_map(|(path, operations)| {
+                let path = convert_axum_path_to_openapi(path).path;
+                let methods = operations
+                    .undocumented()
+                    .iter()
+                    .map(|method| format!("{method} {path}"));
+                let opaque = operations.is_opaque().then(|| path.clone());
+                methods.chain(opaque).collect::<Vec<_>>()
+            })
+            .chain(self.opaque_routers.iter().map(|path| {
+                let path = convert_axum_path_to_openapi(path).path;
+                format!("{}/*", path.trim_end_matches('/'))
+            }))
+            .collect();
+        routes.sort();
+        routes.dedup();
+        routes
+    }
+
     /// Set [`OpenApiBuilder`] template for this router.
     ///
     /// By default [`OpenApiBuilder::default()`] is used.
@@ -380,29 +819,125 @@ where
     /// # };
     /// ```
     pub fn finish_openapi<'a>(
-        mut self,
+        self,
         serve_path: impl Into<Option<&'a str>>,
         title: impl Into<String>,
         version: impl Into<String>,
-    ) -> Result<AxumRouter<S>, anyhow::Error> {
-        let serve_path = serve_path.into().unwrap_or(DEFAULT_OPENAPI_PATH);
+    ) -> Result<AxumRouter<S>, Error> {
+        self.finish_openapi_with_options(
+            serve_path,
+            title,
+            version,
+            FinishOpenapiOptions::default(),
+        )
+    }
 
-        // Don't use try_operation since duplicates should be checked
-        // when mounting route to axum router.
-        let spec = self
-            .generate_openapi_builder()
-            .operation(
-                convert_axum_path_to_openapi(serve_path),
+    /// Generate OpenAPI specification, mount it to inner router and return inner [`axum::Router`]
+    /// (like [`finish_openapi`](Self::finish_openapi)), customizing operation, which serves
+    /// specification.
+    pub fn finish_openapi_with_options<'a>(
+        self,
+        serve_path: impl Into<Option<&'a str>>,
+        title: impl Into<String>,
+        version: impl Into<String>,
+        options: FinishOpenapiOptions,
+    ) -> Result<AxumRouter<S>, Error> {
+        self.finish_openapi_with(
+            FinishOptions::new()
+                .path(serve_path.into().unwrap_or(DEFAULT_OPENAPI_PATH))
+                .title(title)
+                .version(version)
+                .operation(options),
+        )
+    }
+
+    /// Generate OpenAPI specification, mount it to inner router at specified path and return
+    /// inner [`axum::Router`].
+    ///
+    /// Specification metadata (title, version, etc.) is taken from [`OpenApiBuilder`] template.
+    pub fn into_service_with_spec_at(self, path: &str) -> Result<AxumRouter<S>, Error> {
+        self.finish_openapi_with(FinishOptions::new().path(path))
+    }
+
+    /// Generate OpenAPI specification, mount it (and documentation UI, if enabled) to inner
+    /// router and return inner [`axum::Router`].
+    ///
+    /// Unlike [`finish_openapi`](Self::finish_openapi), title and version are taken from
+    /// [`OpenApiBuilder`] template, unless set in options.
+    pub fn finish_openapi_with(mut self, options: FinishOptions) -> Result<AxumRouter<S>, Error> {
+        let FinishOptions {
+            path,
+            title,
+            version,
+            operation,
+            ui,
+            ui_path,
+            filter,
+        } = options;
+
+        let mut builder = self.generate_openapi_builder();
+        self = self.install_custom_methods();
+        let spec_documented = !operation.is_hidden();
+        if spec_documented {
+            let entry =
+                OperationEntry::from(super::serve_openapi_spec__openapi as OperationGenerator)
+                    .customize_operation(move |x| operation.apply_to(x));
+            // Don't use try_operation since duplicates should be checked
+            // when mounting route to axum router.
+            let _ = builder.try_operation_entry(
+                convert_axum_path_to_openapi(&path).path,
                 Method::GET,
-                super::serve_openapi_spec__openapi,
-            )
-            .title(title)
-            .version(version)
-            .build()?;
+                entry,
+            );
+        }
+        if let Some(title) = title {
+            builder.title(title);
+        }
+        if let Some(version) = version {
+            builder.version(version);
+        }
+        let spec = builder.build()?;
 
-        self = self.route(serve_path, get(super::serve_openapi_spec).with_state(spec));
+        let mut routes: Vec<_> = self
+            .routes_operations_map
+            .iter()
+            .flat_map(|(path, operations)| {
+                operations
+                    .documented()
+                    .map(move |method| (path.clone(), method.clone()))
+            })
+            .collect();
+        if spec_documented {
+            routes.push((path.clone(), Method::GET));
+        }
+        let mut operation_ids: HashMap<String, HashMap<Method, OperationInfo>> = HashMap::new();
+        for (path, method) in routes {
+            let spec_path = convert_axum_path_to_openapi(&path).path;
+            if let Some((_, operation)) = builder.route_operation(&spec, &spec_path, &method) {
+                operation_ids
+                    .entry(path)
+                    .or_default()
+                    .insert(method, OperationInfo::from(operation));
+            }
+        }
+
+        self = match filter {
+            Some(filter) => self.route(
+                &path,
+                get(super::finish::serve_filtered_spec)
+                    .with_state(super::finish::FilteredSpec::new(spec, filter)),
+            ),
+            None => self.route(&path, get(super::serve_openapi_spec).with_state(spec)),
+        };
+        if let Some(html) = ui.html(&path) {
+            self.axum_router = self
+                .axum_router
+                .route(&ui_path, axum::routing::get(move || async { Html(html) }));
+        }
 
-        Ok(self.axum_router)
+        Ok(self
+            .axum_router
+            .layer(Extension(OperationIds::new(operation_ids))))
     }
 }
 
@@ -420,7 +955,7 @@ mod tests {
         Components,
     };
 
-    fn openapi_generator(_: &mut Components) -> Result<Operation, anyhow::Error> {
+    fn openapi_generator(_: &mut Components) -> Result<Operation, Error> {
         unimplemented!()
     }
 
@@ -440,6 +975,91 @@ mod tests {
         };
     }
 
+    #[test]
+    fn route_documented() {
+        let ops = Router::<()>::new()
+            .route_documented(
+                "/users",
+                axum_get(|| async {}).post(|| async {}),
+                [(Method::GET, openapi_generator as OperationGenerator)],
+            )
+            .routes_operations();
+        assert!(ops.get("/users", &Method::GET).is_some());
+        assert!(ops.get("/users", &Method::POST).is_none());
+    }
+
+    #[test]
+    fn iterate_operations() {
+        let router = Router::<()>::new()
+            .route(
+                "/users/:id",
+                get((|| async {}).with_openapi(openapi_generator))
+                    .post((|| async {}).with_openapi(openapi_generator)),
+            )
+            .route("/health", get(|| async {}))
+            .route("/", get((|| async {}).with_openapi(openapi_generator)));
+        let operations: Vec<_> = router
+            .routes_operations()
+            .iter()
+            .map(|(path, method, _)| (path.to_owned(), method.clone()))
+            .collect();
+        assert_eq!(
+            operations,
+            [
+                ("/".into(), Method::GET),
+                ("/users/:id".into(), Method::GET),
+                ("/users/:id".into(), Method::POST),
+            ]
+        );
+        assert_eq!(router.openapi_paths(), ["/", "/users/{id}"]);
+
+        fn async_generator(
+            components: &mut Components,
+        ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Operation, Error>> + '_>>
+        {
+            Box::pin(async move { openapi_generator(components) })
+        }
+
+        let mut operations = router.routes_operations();
+        let _ = operations.0.entry("/reports".into()).or_default().insert(
+            Method::GET,
+            OperationEntry::from(async_generator as crate::AsyncOperationGenerator),
+        );
+        assert!(matches!(
+            operations.iter().find(|(path, _, _)| *path == "/reports"),
+            Some((_, &Method::GET, crate::AnyOperationGenerator::Async(_)))
+        ));
+        assert_eq!(operations.iter().len(), 4);
+    }
+
+    #[test]
+    fn strict_undocumented() {
+        let strict = || {
+            Router::<()>::new()
+                .route("/users", get((|| async {}).with_openapi(openapi_generator)))
+                .route_undocumented("/health", axum_get(|| async {}))
+                .allow_undocumented(false)
+        };
+        assert!(strict().undocumented_routes().is_empty());
+
+        let router = strict()
+            .route("/users/:id", get(|| async {}))
+            .nest("/legacy", Router::new().route("/", axum_get(|| async {})));
+        assert_eq!(router.undocumented_routes(), ["/legacy", "GET /users/{id}"]);
+        assert!(matches!(
+            router.generate_openapi_builder().build(),
+            Err(Error::UndocumentedRoutes { routes }) if routes.len() == 2
+        ));
+        assert!(matches!(
+            router.finish_openapi("/openapi", "title", "version"),
+            Err(Error::UndocumentedRoutes { routes }) if routes.len() == 2
+        ));
+
+        let plain = || AxumRouter::new().route("/", axum_get(|| async {}));
+        let router = strict().merge(plain()).nest("/admin/:tenant", plain());
+        assert_eq!(router.undocumented_routes(), ["/*", "/admin/{tenant}/*"]);
+    }
+
     #[test]
     fn mount() {
         let router = Router::new().route("/get", get(|| async {})).route(
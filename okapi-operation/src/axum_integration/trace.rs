@@ -0,0 +1,97 @@
+use std::task::{Context, Poll};
+
+use axum::http::Request;
+use tower::{Layer, Service};
+use tracing::{field, Span};
+
+use super::{OperationIds, OperationInfo};
+
+/// Name of span field, containing `operationId` of request.
+const OPERATION_ID_FIELD: &str = "operation_id";
+/// Name of span field, containing comma-separated tags of operation of request.
+const OPERATION_TAGS_FIELD: &str = "operation_tags";
+
+/// Layer, which resolves operation of request (see [`OperationIds`]), adds its
+/// [`OperationInfo`] to request extensions and records `operation_id` and `operation_tags`
+/// fields of current span.
+///
+/// Fields are recorded only if span declares them, like span, created by [`operation_span`]
+/// (which can be used with `TraceLayer::make_span_with` of `tower-http`). Layer should be
+/// added to [`Router`](super::Router) before generating specification and after tracing layer
+/// (so it is called inside request span):
+///
+/// ```ignore
+/// use okapi_operation::axum_integration::*;
+/// use tower_http::trace::TraceLayer;
+///
+/// let app = Router::new()
+///     .route("/users", get(openapi_handler!(list_users)))
+///     .layer(OperationTraceLayer)
+///     .layer(TraceLayer::new_for_http().make_span_with(operation_span))
+///     .finish_openapi("/openapi", "Demo", "1.0.0")?;
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OperationTraceLayer;
+
+impl<S> Layer<S> for OperationTraceLayer {
+    type Service = OperationTrace<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        OperationTrace { inner }
+    }
+}
+
+/// Service, created by [`OperationTraceLayer`].
+#[derive(Clone, Debug)]
+pub struct OperationTrace<S> {
+    inner: S,
+}
+
+impl<S, B> Service<Request<B>> for OperationTrace<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        if let Some(info) = OperationIds::info_for_request(&request).cloned() {
+            record_operation(&Span::current(), &info);
+            let _ = request.extensions_mut().insert(info);
+        }
+        self.inner.call(request)
+    }
+}
+
+/// Create `request` span with `method`, `uri`, `operation_id` and `operation_tags` fields.
+///
+/// Operation fields are filled, if operation of request can be resolved (i.e. span is created
+/// by layer, added to [`Router`](super::Router) before generating specification), otherwise
+/// they can be recorded later by [`OperationTraceLayer`].
+pub fn operation_span<B>(request: &Request<B>) -> Span {
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        operation_id = field::Empty,
+        operation_tags = field::Empty,
+    );
+    if let Some(info) = OperationIds::info_for_request(request) {
+        record_operation(&span, info);
+    }
+    span
+}
+
+fn record_operation(span: &Span, info: &OperationInfo) {
+    if let Some(operation_id) = &info.operation_id {
+        let _ = span.record(OPERATION_ID_FIELD, operation_id.as_str());
+    }
+    if !info.tags.is_empty() {
+        let _ = span.record(OPERATION_TAGS_FIELD, info.tags.join(",").as_str());
+    }
+}
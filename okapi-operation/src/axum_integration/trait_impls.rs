@@ -1,4 +1,8 @@
-use axum::{response::Html, Form, Json};
+use axum::{
+    response::{Html, IntoResponse, Response as AxumResponse},
+    Form, Json,
+};
+use http::StatusCode;
 use mime::{APPLICATION_JSON, APPLICATION_WWW_FORM_URLENCODED, TEXT_HTML};
 use okapi::{
     map,
@@ -7,10 +11,32 @@ use okapi::{
 };
 
 use crate::{
-    impl_to_media_types_for_wrapper, impl_to_responses_for_wrapper, Components, ToMediaTypes,
-    ToResponses,
+    impl_to_media_types_for_wrapper, impl_to_responses_for_wrapper, Components, Error, OrStatus,
+    ToMediaTypes, ToResponses,
 };
 
+/// Response, which can be documented with [`ToResponses`].
+///
+/// Use it in place of [`IntoResponse`] as opaque return type of handler
+/// (`impl ApiResponse`), so [`openapi`](crate::openapi) macro can infer responses from it.
+pub trait ApiResponse: IntoResponse + ToResponses {}
+
+impl<T> ApiResponse for T where T: IntoResponse + ToResponses {}
+
+impl<T, const STATUS: u16> IntoResponse for OrStatus<T, STATUS>
+where
+    T: IntoResponse,
+{
+    fn into_response(self) -> AxumResponse {
+        match self.0 {
+            Some(value) => value.into_response(),
+            None => StatusCode::from_u16(STATUS)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+                .into_response(),
+        }
+    }
+}
+
 // Json
 impl_to_media_types_for_wrapper!(Json<T>, APPLICATION_JSON.to_string());
 impl_to_responses_for_wrapper!(Json<T>);
@@ -21,7 +47,7 @@ impl_to_responses_for_wrapper!(Form<T>);
 
 // Html
 impl<T> ToMediaTypes for Html<T> {
-    fn generate(_components: &mut Components) -> Result<Map<String, MediaType>, anyhow::Error> {
+    fn generate(_components: &mut Components) -> Result<Map<String, MediaType>, Error> {
         Ok(map! {
             TEXT_HTML.to_string() => MediaType::default()
         })
@@ -29,7 +55,7 @@ impl<T> ToMediaTypes for Html<T> {
 }
 
 impl<T> ToResponses for Html<T> {
-    fn generate(components: &mut Components) -> Result<Responses, anyhow::Error> {
+    fn generate(components: &mut Components) -> Result<Responses, Error> {
         Ok(Responses {
             responses: map! {
                 "200".into() =>  RefOr::Object(Response {
@@ -41,3 +67,31 @@ impl<T> ToResponses for Html<T> {
         })
     }
 }
+
+// axum-msgpack
+#[cfg(feature = "axum-msgpack")]
+impl_to_media_types_for_wrapper!(axum_msgpack::MsgPack<T>, "application/msgpack");
+#[cfg(feature = "axum-msgpack")]
+impl_to_responses_for_wrapper!(axum_msgpack::MsgPack<T>);
+#[cfg(feature = "axum-msgpack")]
+impl_to_media_types_for_wrapper!(axum_msgpack::MsgPackRaw<T>, "application/msgpack");
+#[cfg(feature = "axum-msgpack")]
+impl_to_responses_for_wrapper!(axum_msgpack::MsgPackRaw<T>);
+
+// axum-streams
+/// Format of stream is chosen at runtime, so response is documented without content.
+/// Content can be documented with `responses` attribute of [`openapi`](crate::openapi) macro.
+#[cfg(feature = "axum-streams")]
+impl ToResponses for axum_streams::StreamBodyAs<'static> {
+    fn generate(_components: &mut Components) -> Result<Responses, Error> {
+        Ok(Responses {
+            responses: map! {
+                "200".into() => RefOr::Object(Response {
+                    description: "Streamed response".into(),
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        })
+    }
+}
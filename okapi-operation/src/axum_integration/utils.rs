@@ -1,13 +1,109 @@
+/// Path in OpenAPI format, converted from Axum path.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct OpenApiPath {
+    pub(crate) path: String,
+    /// Name of wildcard parameter (`{*rest}` or `*rest`), if any.
+    pub(crate) wildcard: Option<String>,
+}
+
 /// Convert Axum path with templates to OpenAPI format.
-pub(crate) fn convert_axum_path_to_openapi(path: &str) -> String {
-    path.split('/')
+///
+/// Supports `{param}` and `{*wildcard}` syntax, as well as legacy `:param` and `*wildcard`.
+pub(crate) fn convert_axum_path_to_openapi(path: &str) -> OpenApiPath {
+    let mut wildcard = None;
+    let path = path
+        .split('/')
         .map(|x| {
-            if x.starts_with(':') {
-                format!("{{{}}}", x.trim_matches(':'))
+            if let Some(name) = x
+                .strip_prefix("{*")
+                .and_then(|x| x.strip_suffix('}'))
+                .or_else(|| x.strip_prefix('*'))
+            {
+                wildcard = Some(name.to_owned());
+                format!("{{{name}}}")
+            } else if let Some(name) = x.strip_prefix(':') {
+                format!("{{{name}}}")
             } else {
                 x.into()
             }
         })
         .collect::<Vec<_>>()
+        .join("/");
+    OpenApiPath { path, wildcard }
+}
+
+/// Convert OpenAPI path template (`/users/{id}`) to Axum format (`/users/:id`).
+pub(crate) fn convert_openapi_path_to_axum(path: &str) -> String {
+    path.split('/')
+        .map(
+            |x| match x.strip_prefix('{').and_then(|x| x.strip_suffix('}')) {
+                Some(name) => format!(":{name}"),
+                None => x.into(),
+            },
+        )
+        .collect::<Vec<_>>()
         .join("/")
 }
+
+/// Join path of nested router with path of its route (like Axum does).
+pub(crate) fn join_nested_path(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    match path {
+        "" | "/" if prefix.is_empty() => "/".into(),
+        "" | "/" => prefix.into(),
+        _ if path.starts_with('/') => format!("{prefix}{path}"),
+        _ => format!("{prefix}/{path}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_path() {
+        for (axum_path, path, wildcard) in [
+            ("/", "/", None),
+            ("/users/:id", "/users/{id}", None),
+            (
+                "/users/{id}/posts/{post_id}",
+                "/users/{id}/posts/{post_id}",
+                None,
+            ),
+            ("/files/*path", "/files/{path}", Some("path")),
+            ("/files/{*path}", "/files/{path}", Some("path")),
+            (
+                "/:version/files/{*path}",
+                "/{version}/files/{path}",
+                Some("path"),
+            ),
+        ] {
+            assert_eq!(
+                convert_axum_path_to_openapi(axum_path),
+                OpenApiPath {
+                    path: path.into(),
+                    wildcard: wildcard.map(Into::into)
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn convert_openapi_path() {
+        for (path, axum_path) in [
+            ("/", "/"),
+            ("/users/{id}", "/users/:id"),
+            ("/users/{id}/posts/{post_id}", "/users/:id/posts/:post_id"),
+        ] {
+            assert_eq!(convert_openapi_path_to_axum(path), axum_path);
+        }
+    }
+
+    #[test]
+    fn join_path() {
+        assert_eq!(join_nested_path("/api", "/"), "/api");
+        assert_eq!(join_nested_path("/api/", "/users"), "/api/users");
+        assert_eq!(join_nested_path("/api", "/users/{id}"), "/api/users/{id}");
+        assert_eq!(join_nested_path("/", "/"), "/");
+    }
+}
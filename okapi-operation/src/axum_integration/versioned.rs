@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+
+use axum::Router as AxumRouter;
+use okapi::openapi3::Tag;
+
+use super::{
+    finish::FinishOptions,
+    method_router::MethodRouter,
+    router::{Router, DEFAULT_OPENAPI_PATH},
+};
+use crate::{Error, OpenApiBuilder};
+
+/// Path prefix of API version (`/v{version}`).
+pub fn version_prefix(version: u32) -> String {
+    format!("/v{version}")
+}
+
+/// Router with routes, grouped by API version.
+///
+/// Routes of each version are mounted under `/v{version}` prefix (see [`version_prefix`]).
+/// Specification can be either combined (operations are tagged with `v{version}` tag) or
+/// separate for each version.
+///
+/// # Example
+///
+/// ```rust
+/// # use okapi_operation::{*, axum_integration::*};
+/// #[openapi]
+/// async fn list_users() {}
+///
+/// #[openapi]
+/// async fn list_users_v2() {}
+///
+/// let app = Router::<()>::versioned()
+///     .route_v(1, "/users", get(openapi_handler!(list_users)))
+///     .route_v(2, "/users", get(openapi_handler!(list_users_v2)))
+///     // Serves `/v1/openapi` and `/v2/openapi`
+///     .finish_openapi_per_version("Demo")
+///     .unwrap();
+/// ```
+pub struct VersionedRouter<S = ()> {
+    versions: BTreeMap<u32, Router<S>>,
+    openapi_builder_template: OpenApiBuilder,
+}
+
+impl<S> Default for VersionedRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> VersionedRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    /// Create new router.
+    pub fn new() -> Self {
+        Self {
+            versions: BTreeMap::new(),
+            openapi_builder_template: OpenApiBuilder::default(),
+        }
+    }
+
+    /// Add route to specified version (path is relative to version prefix).
+    pub fn route_v<R>(mut self, version: u32, path: &str, method_router: R) -> Self
+    where
+        R: Into<MethodRouter<S>>,
+    {
+        let router = self.versions.remove(&version).unwrap_or_default();
+        let _ = self
+            .versions
+            .insert(version, router.route(path, method_router));
+        self
+    }
+
+    /// Modify router of specified version (i.e. for nesting routers or adding layers).
+    pub fn version<F>(mut self, version: u32, f: F) -> Self
+    where
+        F: FnOnce(Router<S>) -> Router<S>,
+    {
+        let router = self.versions.remove(&version).unwrap_or_default();
+        let _ = self.versions.insert(version, f(router));
+        self
+    }
+
+    /// Get mutable reference to [`OpenApiBuilder`] template, used for all versions.
+    pub fn openapi_builder_template_mut(&mut self) -> &mut OpenApiBuilder {
+        &mut self.openapi_builder_template
+    }
+
+    /// Combine all versions into single router with `v{version}` tag on operations.
+    pub fn into_router(self) -> Router<S> {
+        let mut combined = Router::new();
+        combined.set_openapi_builder_template(self.openapi_builder_template);
+        for (version, mut router) in self.versions {
+            let tag = format!("v{version}");
+            combined.openapi_builder_template_mut().tag(Tag {
+                name: tag.clone(),
+                description: Some(format!("API version {version}")),
+                ..Default::default()
+            });
+            router.customize_operations(move |operation| {
+                if !operation.tags.contains(&tag) {
+                    operation.tags.push(tag.clone());
+                }
+            });
+            combined = combined.nest(&version_prefix(version), router);
+        }
+        combined
+    }
+
+    /// Routers of versions, nested under version prefixes, with builder template.
+    pub fn into_routers(self) -> BTreeMap<u32, Router<S>> {
+        self.versions
+            .into_iter()
+            .map(|(version, router)| {
+                let mut nested = Router::new();
+                nested.set_openapi_builder_template(self.openapi_builder_template.clone());
+                (version, nested.nest(&version_prefix(version), router))
+            })
+            .collect()
+    }
+
+    /// Generate separate specification for each version, mount them at
+    /// `/v{version}/openapi` and return [`axum::Router`] with all versions.
+    ///
+    /// Version of specification is set to API version.
+    pub fn finish_openapi_per_version(
+        self,
+        title: impl Into<String>,
+    ) -> Result<AxumRouter<S>, Error> {
+        let title = title.into();
+        let mut app = AxumRouter::new();
+        for (version, router) in self.into_routers() {
+            let options = FinishOptions::new()
+                .path(format!("{}{DEFAULT_OPENAPI_PATH}", version_prefix(version)))
+                .title(title.clone())
+                .version(version.to_string());
+            app = app.merge(router.finish_openapi_with(options)?);
+        }
+        Ok(app)
+    }
+}
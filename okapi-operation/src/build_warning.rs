@@ -0,0 +1,102 @@
+use std::fmt;
+
+use http::Method;
+
+/// Non-fatal issue, found while building specification.
+///
+/// Returned by [`OpenApiBuilder::build_with_report`](crate::OpenApiBuilder::build_with_report)
+/// and [`OpenApiBuilder::build_async_with_report`](crate::OpenApiBuilder::build_async_with_report).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildWarning {
+    /// Route is handled by handler or service without OpenAPI operation.
+    UndocumentedRoute { path: String, method: Method },
+    /// Route is not documented according to
+    /// [`BuilderOptions::wildcard_routes`](crate::BuilderOptions::wildcard_routes).
+    SkippedRoute { path: String, method: Method },
+    /// `operationId` was inferred according to
+    /// [`BuilderOptions::infer_operation_id`](crate::BuilderOptions::infer_operation_id).
+    InferredOperationId {
+        path: String,
+        method: Method,
+        operation_id: String,
+    },
+    /// Path parameter, not documented by operation, was added to it.
+    InferredPathParameter {
+        path: String,
+        method: Method,
+        name: String,
+    },
+    /// Operation is mounted on route, which doesn't match path and method, declared in
+    /// [`openapi`](crate::openapi) macro.
+    ///
+    /// Path matches, if route path ends with declared path (so operations of nested
+    /// routers are not reported).
+    DeclaredRouteMismatch {
+        path: String,
+        method: Method,
+        declared_path: String,
+        declared_method: Method,
+    },
+    /// Tag is defined multiple times in specification.
+    DuplicateTag { name: String },
+    /// Schema of recursive type is referenced, though
+    /// [subschemas are inlined](crate::ComponentsBuilder::inline_subschemas).
+    RecursiveSchema { name: String },
+    /// Handler argument is recognized neither as body, nor as known extractor, so it may
+    /// be undocumented (reported only with `warn_unknown_extractors` attribute of
+    /// [`openapi`](crate::openapi) macro).
+    UnknownExtractor {
+        path: String,
+        method: Method,
+        type_name: String,
+    },
+}
+
+impl fmt::Display for BuildWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndocumentedRoute { path, method } => {
+                write!(f, "{method} {path} is not documented")
+            }
+            Self::SkippedRoute { path, method } => {
+                write!(f, "{method} {path} is skipped")
+            }
+            Self::InferredOperationId {
+                path,
+                method,
+                operation_id,
+            } => write!(
+                f,
+                "{method} {path} has inferred operationId `{operation_id}`"
+            ),
+            Self::InferredPathParameter { path, method, name } => {
+                write!(f, "{method} {path} has inferred path parameter `{name}`")
+            }
+            Self::DeclaredRouteMismatch {
+                path,
+                method,
+                declared_path,
+                declared_method,
+            } => write!(
+                f,
+                "{method} {path} is declared as {declared_method} {declared_path}"
+            ),
+            Self::DuplicateTag { name } => write!(f, "Tag `{name}` is defined multiple times"),
+            Self::RecursiveSchema { name } => {
+                write!(
+                    f,
+                    "Recursive schema `{name}` is referenced instead of inlined"
+                )
+            }
+            Self::UnknownExtractor {
+                path,
+                method,
+                type_name,
+            } => write!(
+                f,
+                "{method} {path} has argument of unknown type `{type_name}`"
+            ),
+        }
+    }
+}
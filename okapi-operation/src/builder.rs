@@ -1,18 +1,137 @@
-use anyhow::{bail, Context};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::BufReader,
+    path::Path,
+    sync::Arc,
+};
+
 use http::Method;
 use indexmap::IndexMap;
-use okapi::openapi3::{
-    Contact, ExternalDocs, License, OpenApi, SecurityRequirement, SecurityScheme, Server, Tag,
+use okapi::{
+    openapi3::{
+        Components as OkapiComponents, Contact, ExternalDocs, Header, License, OpenApi, Operation,
+        Parameter, PathItem, RefOr, RequestBody, Response, SecurityRequirement, SecurityScheme,
+        Server, Tag,
+    },
+    schemars::{
+        schema::{InstanceType, SchemaObject},
+        JsonSchema,
+    },
+    Map,
+};
+use serde::Deserialize;
+
+use crate::{
+    build_warning::BuildWarning,
+    builder_options::{
+        method_path_slug, BuilderOptions, CustomMethods, DuplicateOperationId, WildcardRoutes,
+    },
+    cargo_package::CargoPackage,
+    components::{Components, OperationContext},
+    discriminator::infer_discriminators,
+    header::{header, parameter},
+    i18n::{resolve_descriptions, DescriptionResolver},
+    merge::{merge_paths, merge_tags},
+    operation_meta::OperationEntry,
+    rate_limit::add_rate_limit_responses,
+    read_write::split_read_write_schemas,
+    stats::SpecStats,
+    walk::{
+        all_path_item_operations, check_path_item_operations, for_each_path_item_operation_mut,
+        has_path_item_operations, insert_path_item_operation, is_standard_method,
+        retain_path_item_operations,
+    },
+    AnyOperationGenerator, AsyncOperationGenerator, Error, MergeStrategy, OperationGenerator,
 };
 
-use crate::{components::Components, OperationGenerator};
+/// Order of items (paths, schemas, tags) in generated specification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortMode {
+    /// Sort items by name.
+    Alphabetical,
+    /// Keep items in order they were added.
+    InsertionOrder,
+    /// Group paths by tag of their first operation (in order of tags in specification,
+    /// untagged paths last), sorting paths by name inside group.
+    ///
+    /// For schemas and tags it is the same as [`SortMode::Alphabetical`].
+    TagGrouped,
+}
+
+/// Filter of operations by path, method and generated operation.
+type OperationFilter = Arc<dyn Fn(&str, &Method, &Operation) -> bool + Send + Sync>;
+
+/// Generated operation with context, set by its generator.
+#[derive(Clone)]
+struct CachedOperation {
+    operation: Operation,
+    context: OperationContext,
+}
+
+/// Generated operations by path and method.
+type OperationCache = HashMap<(String, Method), CachedOperation>;
+
+/// Partial specification, which can be included into builder.
+#[derive(Deserialize)]
+struct SpecFragment {
+    #[serde(default)]
+    paths: Map<String, PathItem>,
+    #[serde(default)]
+    components: Option<OkapiComponents>,
+    #[serde(default)]
+    tags: Vec<Tag>,
+    #[serde(default)]
+    security: Vec<SecurityRequirement>,
+}
+
+impl From<SpecFragment> for OpenApi {
+    fn from(value: SpecFragment) -> Self {
+        Self {
+            paths: value.paths,
+            components: value.components,
+            tags: value.tags,
+            security: value.security,
+            ..Default::default()
+        }
+    }
+}
+
+fn open_fragment(path: &Path) -> Result<BufReader<File>, anyhow::Error> {
+    Ok(BufReader::new(File::open(path)?))
+}
 
 /// OpenAPI specificatrion builder.
 #[derive(Clone)]
 pub struct OpenApiBuilder {
     spec: OpenApi,
     components: Components,
-    operations: IndexMap<(String, Method), OperationGenerator>,
+    operations: IndexMap<(String, Method), OperationEntry>,
+    paths_order: SortMode,
+    schemas_order: SortMode,
+    tags_order: SortMode,
+    path_prefix: Option<String>,
+    strip_prefix: Option<String>,
+    fallback: Option<OperationGenerator>,
+    excluded_extensions: Vec<String>,
+    rate_limited: Vec<OperationFilter>,
+    registered_schemas: Vec<fn(&mut Components)>,
+    description_resolver: Option<Arc<dyn DescriptionResolver>>,
+    /// Generated operations, reused by following builds (enabled by [`OpenApiBuilder::freeze`]).
+    operation_cache: Option<OperationCache>,
+    options: BuilderOptions,
+    tag_order: Vec<String>,
+    tag_groups: IndexMap<String, Vec<String>>,
+    skipped_routes: Vec<(String, Method)>,
+    /// Paths, under which operations are documented (before adding prefix), by paths and
+    /// methods, under which they are added.
+    documented_paths: HashMap<(String, Method), String>,
+    undocumented_routes: Vec<(String, Method)>,
+    /// Paths, for which router rejects requests with unhandled methods, with handled methods.
+    allowed_methods: Vec<(String, Vec<Method>)>,
+    /// Undocumented routes, which are not allowed (build fails, if any).
+    denied_undocumented_routes: Vec<String>,
+    warnings: Vec<BuildWarning>,
 }
 
 impl Default for OpenApiBuilder {
@@ -25,6 +144,26 @@ impl Default for OpenApiBuilder {
             spec,
             components: Components::new(Default::default()),
             operations: IndexMap::new(),
+            paths_order: SortMode::Alphabetical,
+            schemas_order: SortMode::InsertionOrder,
+            tags_order: SortMode::InsertionOrder,
+            path_prefix: None,
+            strip_prefix: None,
+            fallback: None,
+            excluded_extensions: Vec::new(),
+            rate_limited: Vec::new(),
+            registered_schemas: Vec::new(),
+            description_resolver: None,
+            operation_cache: None,
+            options: BuilderOptions::default(),
+            tag_order: Vec::new(),
+            tag_groups: IndexMap::new(),
+            skipped_routes: Vec::new(),
+            documented_paths: HashMap::new(),
+            undocumented_routes: Vec::new(),
+            allowed_methods: Vec::new(),
+            denied_undocumented_routes: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 }
@@ -49,6 +188,34 @@ impl OpenApiBuilder {
         self
     }
 
+    /// Create new builder with specified title and version, containing all operations,
+    /// registered with `#[openapi(collect(...))]`.
+    ///
+    /// Throws an error if same (path, method) pair is registered multiple times.
+    #[cfg(feature = "auto-collect")]
+    pub fn from_inventory(title: &str, version: &str) -> Result<Self, Error> {
+        let mut this = Self::new(title, version);
+        this.try_inventory_operations()?;
+        Ok(this)
+    }
+
+    /// Add all operations, registered with `#[openapi(collect(...))]`.
+    ///
+    /// Throws an error if any (path, method) pair is already present.
+    #[cfg(feature = "auto-collect")]
+    pub fn try_inventory_operations(&mut self) -> Result<&mut Self, Error> {
+        for op in crate::collected_operations() {
+            self.try_operation(op.path(), op.method(), op.generator())?;
+        }
+        Ok(self)
+    }
+
+    /// Set builder options.
+    pub fn set_options(&mut self, options: BuilderOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+
     /// Add single operation.
     ///
     /// Throws an error if (path, method) pair is already present.
@@ -57,17 +224,25 @@ impl OpenApiBuilder {
         path: T,
         method: Method,
         generator: OperationGenerator,
-    ) -> Result<&mut Self, anyhow::Error>
+    ) -> Result<&mut Self, Error>
     where
         T: Into<String>,
     {
-        let path = path.into();
+        self.try_operation_entry(path.into(), method, generator.into())
+    }
+
+    pub(crate) fn try_operation_entry(
+        &mut self,
+        path: String,
+        method: Method,
+        entry: OperationEntry,
+    ) -> Result<&mut Self, Error> {
         if self
             .operations
-            .insert((path.clone(), method.clone()), generator)
+            .insert((path.clone(), method.clone()), entry)
             .is_some()
         {
-            bail!("{method} {path} is already present in specification");
+            return Err(Error::DuplicateOperation { path, method });
         };
         Ok(self)
     }
@@ -75,7 +250,7 @@ impl OpenApiBuilder {
     /// Add multiple operations.
     ///
     /// Throws an error if any (path, method) pair is already present.
-    pub fn try_operations<I, S>(&mut self, operations: I) -> Result<&mut Self, anyhow::Error>
+    pub fn try_operations<I, S>(&mut self, operations: I) -> Result<&mut Self, Error>
     where
         I: Iterator<Item = (S, Method, OperationGenerator)>,
         S: Into<String>,
@@ -116,6 +291,126 @@ impl OpenApiBuilder {
         self
     }
 
+    /// Add single asynchronous operation.
+    ///
+    /// Specification with asynchronous operations can be built only with
+    /// [`build_async`](Self::build_async). Throws an error if (path, method) pair is already
+    /// present.
+    pub fn try_async_operation<T>(
+        &mut self,
+        path: T,
+        method: Method,
+        generator: AsyncOperationGenerator,
+    ) -> Result<&mut Self, Error>
+    where
+        T: Into<String>,
+    {
+        self.try_operation_entry(path.into(), method, generator.into())
+    }
+
+    /// Add single asynchronous operation.
+    ///
+    /// Specification with asynchronous operations can be built only with
+    /// [`build_async`](Self::build_async). Replaces operation if (path, method) pair is
+    /// already present.
+    pub fn async_operation<T>(
+        &mut self,
+        path: T,
+        method: Method,
+        generator: AsyncOperationGenerator,
+    ) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        let _ = self.try_async_operation(path, method, generator);
+        self
+    }
+
+    /// Merge paths, components, tags and security requirements from other specification.
+    ///
+    /// Overlapping definitions are resolved according to `strategy`. Other fields (info,
+    /// servers, etc.) of `other` are ignored. If merge fails, builder is left unchanged.
+    ///
+    /// # NOTE
+    ///
+    /// Operations, added to builder with generators, take precedence over operations from
+    /// merged specifications.
+    pub fn merge_spec(
+        &mut self,
+        other: OpenApi,
+        strategy: MergeStrategy,
+    ) -> Result<&mut Self, Error> {
+        let mut spec = self.spec.clone();
+        let mut components = self.components.clone();
+        merge_paths(&mut spec.paths, other.paths, strategy).map_err(Error::Merge)?;
+        if let Some(other_components) = other.components {
+            components
+                .merge_okapi_components(other_components, strategy)
+                .map_err(Error::Merge)?;
+        }
+        merge_tags(&mut spec.tags, other.tags, strategy).map_err(Error::Merge)?;
+        for security in other.security {
+            if !spec.security.contains(&security) {
+                spec.security.push(security);
+            }
+        }
+        self.spec = spec;
+        self.components = components;
+        Ok(self)
+    }
+
+    /// Parse partial specification from JSON file and merge it into builder.
+    ///
+    /// Only paths, components, tags and security requirements are used from file (all of them
+    /// are optional). Specification is merged with default [`MergeStrategy`] (see
+    /// [`Self::merge_spec`]).
+    pub fn include_json(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        let fragment: SpecFragment = open_fragment(path)
+            .and_then(|reader| Ok(serde_json::from_reader(reader)?))
+            .map_err(|source| Error::Include {
+                file: path.into(),
+                source,
+            })?;
+        self.merge_spec(fragment.into(), MergeStrategy::default())
+    }
+
+    /// Prefix of references to schemas (see
+    /// [`ComponentsBuilder::definitions_path`](crate::ComponentsBuilder::definitions_path)).
+    #[cfg(feature = "testing")]
+    pub(crate) fn definitions_path(&self) -> &str {
+        self.components.definitions_path()
+    }
+
+    /// Import operations from `google.api.http` annotations of gRPC services in serialized
+    /// `FileDescriptorSet` and merge them into builder (see [`crate::grpc_gateway`]).
+    #[cfg(feature = "grpc-gateway")]
+    pub fn include_grpc_gateway(&mut self, descriptor_set: &[u8]) -> Result<&mut Self, Error> {
+        let spec = crate::grpc_gateway::import_with_definitions_path(
+            descriptor_set,
+            self.components.definitions_path(),
+        )
+        .map_err(|err| Error::Import(err.into()))?;
+        self.merge_spec(spec, MergeStrategy::default())
+    }
+
+    /// Parse partial specification from YAML file and merge it into builder.
+    ///
+    /// Only paths, components, tags and security requirements are used from file (all of them
+    /// are optional). Specification is merged with default [`MergeStrategy`] (see
+    /// [`Self::merge_spec`]).
+    #[cfg(feature = "yaml")]
+    pub fn include_yaml(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        let fragment: SpecFragment = open_fragment(path)
+            .and_then(|reader| Ok(serde_yaml::from_reader(reader)?))
+            .map_err(|source| Error::Include {
+                file: path.into(),
+                source,
+            })?;
+        self.merge_spec(fragment.into(), MergeStrategy::default())
+    }
+
     /// Access inner [`okapi::openapi3::OpenApi`].
     ///
     /// **Warning!** This allows raw access to underlying `OpenApi` object,
@@ -140,30 +435,531 @@ impl OpenApiBuilder {
         self
     }
 
+    /// Set order of paths in specification.
+    ///
+    /// [`SortMode::Alphabetical`] by default.
+    pub fn sort_paths(&mut self, mode: SortMode) -> &mut Self {
+        self.paths_order = mode;
+        self
+    }
+
+    /// Set order of schemas in specification.
+    ///
+    /// [`SortMode::InsertionOrder`] by default (i.e. order in which schemas were generated).
+    pub fn sort_schemas(&mut self, mode: SortMode) -> &mut Self {
+        self.schemas_order = mode;
+        self
+    }
+
+    /// Set summary of path item (shared by all operations of path).
+    pub fn path_summary(
+        &mut self,
+        path: impl Into<String>,
+        summary: impl Into<String>,
+    ) -> &mut Self {
+        self.spec.paths.entry(path.into()).or_default().summary = Some(summary.into());
+        self
+    }
+
+    /// Set description of path item (shared by all operations of path).
+    pub fn path_description(
+        &mut self,
+        path: impl Into<String>,
+        description: impl Into<String>,
+    ) -> &mut Self {
+        self.spec.paths.entry(path.into()).or_default().description = Some(description.into());
+        self
+    }
+
+    /// Set order of tags in specification.
+    ///
+    /// [`SortMode::InsertionOrder`] by default.
+    pub fn sort_tags(&mut self, mode: SortMode) -> &mut Self {
+        self.tags_order = mode;
+        self
+    }
+
+    /// Prepend prefix (like `/api/v1`) to all paths of specification.
+    ///
+    /// Useful when service is located behind gateway, which routes requests with base path
+    /// to it. Applied after [`strip_prefix`](Self::strip_prefix).
+    pub fn path_prefix(&mut self, prefix: impl Into<String>) -> &mut Self {
+        self.path_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Remove prefix (like `/internal`) from paths of specification.
+    ///
+    /// Prefix is removed only at segment boundary (i.e. `/internal` is removed from
+    /// `/internal/users`, but not from `/internals`). Paths without prefix are left unchanged.
+    pub fn strip_prefix(&mut self, prefix: impl Into<String>) -> &mut Self {
+        self.strip_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Document fallback (catch-all) handler with operation generator.
+    ///
+    /// OpenAPI has no way to describe catch-all routes, so generated operation is put into
+    /// `x-fallback` extension of specification.
+    pub fn fallback_operation(&mut self, generator: OperationGenerator) -> &mut Self {
+        self.fallback = Some(generator);
+        self
+    }
+
+    /// Exclude operations with specified extension (like `x-experimental`) from specification,
+    /// unless its value is `false` or `null`.
+    ///
+    /// Paths without operations are removed. Useful for hiding experimental endpoints unless
+    /// runtime flag enables them.
+    pub fn exclude_operations_with_extension(&mut self, name: impl Into<String>) -> &mut Self {
+        self.excluded_extensions.push(name.into());
+        self
+    }
+
+    /// Document rate-limit headers and `429` response (like [`RateLimited`](crate::RateLimited))
+    /// for operations, for which `filter` returns `true`.
+    ///
+    /// Filter receives path (before applying [`path_prefix`](Self::path_prefix)), method and
+    /// operation. Useful when rate limits are enforced by middleware, like
+    /// `|path, _, _| path.starts_with("/api")`.
+    pub fn document_rate_limits<F>(&mut self, filter: F) -> &mut Self
+    where
+        F: Fn(&str, &Method, &Operation) -> bool + Send + Sync + 'static,
+    {
+        self.rate_limited.push(Arc::new(filter));
+        self
+    }
+
+    /// Add schema of type to `components.schemas`, even if it isn't used by any operation.
+    ///
+    /// Useful for shared models, which are consumed only by clients (like webhook payloads).
+    /// Several types can be registered with [`register_schemas`](crate::register_schemas).
+    pub fn register_schema<T: JsonSchema>(&mut self) -> &mut Self {
+        self.registered_schemas.push(|components| {
+            let _ = components.schema_for::<T>();
+        });
+        self
+    }
+
+    /// Replace schema of type with provided one everywhere it appears.
+    ///
+    /// See [`Components::override_schema_for`].
+    pub fn override_schema_for<T: JsonSchema>(&mut self, schema: SchemaObject) -> &mut Self {
+        self.components.override_schema_for::<T>(schema);
+        self
+    }
+
+    /// Set `discriminator` of schema of enum, tagged with serde.
+    ///
+    /// See [`Components::set_discriminator`].
+    pub fn discriminator<T: JsonSchema>(&mut self) -> &mut Self {
+        self.components.set_discriminator::<T>();
+        self
+    }
+
+    /// Set `discriminator` of schema of enum with specified discriminator property.
+    ///
+    /// See [`Components::set_discriminator_property`].
+    pub fn discriminator_property<T: JsonSchema>(
+        &mut self,
+        property_name: impl Into<String>,
+    ) -> &mut Self {
+        self.components
+            .set_discriminator_property::<T>(property_name);
+        self
+    }
+
+    /// Resolve texts of specification (descriptions, summaries, titles), which are keys of
+    /// localized texts, with resolver.
+    ///
+    /// Resolver is applied when specification is built, so specification for every locale can
+    /// be built by setting resolver of that locale before building. See [`DescriptionResolver`].
+    pub fn description_resolver<R>(&mut self, resolver: R) -> &mut Self
+    where
+        R: DescriptionResolver + 'static,
+    {
+        self.description_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Document `HEAD` operations for paths with `GET` operation.
+    ///
+    /// See [`BuilderOptions::document_head_for_get`].
+    pub fn document_head_for_get(&mut self, document_head_for_get: bool) -> &mut Self {
+        self.options.document_head_for_get = document_head_for_get;
+        self
+    }
+
     /// Generate [`okapi::openapi3::OpenApi`] specification.
     ///
+    /// This method can be called repeatedly on the same object. Returns an error if builder
+    /// contains asynchronous operations (use [`build_async`](Self::build_async) instead).
+    pub fn build(&mut self) -> Result<OpenApi, Error> {
+        let mut spec = self.spec.clone();
+        let mut operation_ids = OperationIds::default();
+        self.start_build()?;
+        for ((path, method), entry) in sorted_operations(&self.operations, self.paths_order) {
+            let cached = self.cached_operation(&path, &method);
+            let generated = cached.is_none();
+            let operation = match (cached, entry.generator) {
+                (Some(operation), _) => Ok(operation),
+                (None, AnyOperationGenerator::Sync(generator)) => generator(&mut self.components),
+                (None, AnyOperationGenerator::Async(_)) => Err(Error::AsyncGenerator),
+            };
+            self.add_operation(
+                &mut spec,
+                &mut operation_ids,
+                path,
+                method,
+                &entry,
+                operation,
+                generated,
+            )?;
+        }
+        operation_ids.assign_inferred(&mut spec, &self.options, &mut self.warnings)?;
+        self.finish(spec)
+    }
+
+    /// Generate [`okapi::openapi3::OpenApi`] specification, supporting asynchronous
+    /// operations.
+    ///
+    /// Operations are generated sequentially in the same order as in [`build`](Self::build).
     /// This method can be called repeatedly on the same object.
-    pub fn build(&mut self) -> Result<OpenApi, anyhow::Error> {
+    pub async fn build_async(&mut self) -> Result<OpenApi, Error> {
         let mut spec = self.spec.clone();
+        let mut operation_ids = OperationIds::default();
+        self.start_build()?;
+        for ((path, method), entry) in sorted_operations(&self.operations, self.paths_order) {
+            let cached = self.cached_operation(&path, &method);
+            let generated = cached.is_none();
+            let operation = match (cached, entry.generator) {
+                (Some(operation), _) => Ok(operation),
+                (None, AnyOperationGenerator::Sync(generator)) => generator(&mut self.components),
+                (None, AnyOperationGenerator::Async(generator)) => {
+                    generator(&mut self.components).await
+                }
+            };
+            self.add_operation(
+                &mut spec,
+                &mut operation_ids,
+                path,
+                method,
+                &entry,
+                operation,
+                generated,
+            )?;
+        }
+        operation_ids.assign_inferred(&mut spec, &self.options, &mut self.warnings)?;
+        self.finish(spec)
+    }
 
-        self.operations.sort_by(|lkey, _, rkey, _| {
-            let lkey_str = (&lkey.0, lkey.1.as_str());
-            let rkey_str = (&rkey.0, rkey.1.as_str());
-            lkey_str.cmp(&rkey_str)
-        });
+    /// Prepare components for generating operation, returning cached operation (if operation
+    /// cache is enabled and contains it).
+    fn cached_operation(&mut self, path: &str, method: &Method) -> Option<Operation> {
+        start_operation(&mut self.components, path, method);
+        take_cached(&self.operation_cache, &mut self.components, path, method)
+    }
 
-        for ((path, method), generator) in &self.operations {
-            try_add_path(
-                &mut spec,
+    /// Add operation of entry to specification, caching it (if it was generated).
+    #[allow(clippy::too_many_arguments)]
+    fn add_operation(
+        &mut self,
+        spec: &mut OpenApi,
+        operation_ids: &mut OperationIds,
+        path: String,
+        method: Method,
+        entry: &OperationEntry,
+        operation: Result<Operation, Error>,
+        generated: bool,
+    ) -> Result<(), Error> {
+        if generated {
+            store_cached(
+                &mut self.operation_cache,
                 &mut self.components,
-                path,
-                method.clone(),
-                *generator,
-            )
-            .with_context(|| format!("Failed to add {method} {path}"))?;
+                &path,
+                &method,
+                &operation,
+            );
+        }
+        let documented_path = try_add_path(
+            spec,
+            &mut self.components,
+            &self.options,
+            operation_ids,
+            &mut self.warnings,
+            &path,
+            method.clone(),
+            entry,
+            operation,
+        )?;
+        match documented_path {
+            Some(documented_path) => {
+                let _ = self
+                    .documented_paths
+                    .insert((path, method), documented_path);
+            }
+            None => self.skipped_routes.push((path, method)),
+        }
+        Ok(())
+    }
+
+    /// Generate [`okapi::openapi3::OpenApi`] specification (like [`build`](Self::build)),
+    /// returning non-fatal issues, found while building.
+    pub fn build_with_report(&mut self) -> Result<(OpenApi, Vec<BuildWarning>), Error> {
+        let spec = self.build()?;
+        Ok((spec, std::mem::take(&mut self.warnings)))
+    }
+
+    /// Generate specification (like [`build`](Self::build)) and collect its statistics.
+    pub fn stats(&mut self) -> Result<SpecStats, Error> {
+        self.build().map(|spec| SpecStats::of(&spec))
+    }
+
+    /// Generate specification (like [`build`](Self::build)) and freeze it into snapshot,
+    /// which can be incrementally rebuilt with [`FrozenSpec::rebuild_with`].
+    pub fn freeze(&self) -> Result<FrozenSpec, Error> {
+        let mut builder = self.clone();
+        let _ = builder.operation_cache.get_or_insert_with(HashMap::new);
+        let spec = builder.build()?;
+        Ok(FrozenSpec { builder, spec })
+    }
+
+    /// Generate [`okapi::openapi3::OpenApi`] specification (like
+    /// [`build_async`](Self::build_async)), returning non-fatal issues, found while building.
+    pub async fn build_async_with_report(&mut self) -> Result<(OpenApi, Vec<BuildWarning>), Error> {
+        let spec = self.build_async().await?;
+        Ok((spec, std::mem::take(&mut self.warnings)))
+    }
+
+    /// Generate specification (like [`build`](Self::build)) and serialize it to
+    /// pretty-printed JSON.
+    pub fn build_json_pretty(&mut self) -> Result<String, Error> {
+        let spec = self.build()?;
+        serde_json::to_string_pretty(&spec).map_err(|err| Error::Serialize(err.into()))
+    }
+
+    /// Generate specification (like [`build`](Self::build)) and serialize it to YAML.
+    #[cfg(feature = "yaml")]
+    pub fn build_yaml(&mut self) -> Result<String, Error> {
+        let spec = self.build()?;
+        serde_yaml::to_string(&spec).map_err(|err| Error::Serialize(err.into()))
+    }
+
+    /// Merge template of nested router into this builder.
+    ///
+    /// Tags and components, missing in this builder, are added (existing ones are kept).
+    #[cfg(feature = "axum")]
+    pub(crate) fn merge_template(&mut self, other: &OpenApiBuilder) {
+        // Merge strategy only fills missing fields of existing tags and never fails
+        let _ = merge_tags(
+            &mut self.spec.tags,
+            other.spec.tags.clone(),
+            MergeStrategy::Merge,
+        );
+        self.components.fill_missing_from(&other.components);
+    }
+
+    /// Record route, which is handled without OpenAPI operation.
+    #[cfg(feature = "axum")]
+    pub(crate) fn undocumented_route(&mut self, path: String, method: Method) {
+        self.undocumented_routes.push((path, method));
+    }
+
+    /// Record path, for which router rejects requests with methods other than specified.
+    #[cfg(feature = "axum")]
+    pub(crate) fn allowed_methods(&mut self, path: String, methods: Vec<Method>) {
+        self.allowed_methods.push((path, methods));
+    }
+
+    /// Fail build with [`Error::UndocumentedRoutes`], if routes are not empty.
+    #[cfg(feature = "axum")]
+    pub(crate) fn deny_undocumented_routes(&mut self, routes: Vec<String>) {
+        self.denied_undocumented_routes = routes;
+    }
+
+    /// Operation, added with specified path and method, in specification, generated by last
+    /// build, with path, under which it is documented.
+    #[cfg(feature = "axum")]
+    pub(crate) fn route_operation(
+        &self,
+        spec: &OpenApi,
+        path: &str,
+        method: &Method,
+    ) -> Option<(String, Operation)> {
+        let documented_path = self.documented_paths.get(&(path.into(), method.clone()))?;
+        let documented_path = self.options.normalize_path(rewrite_path(
+            documented_path,
+            self.strip_prefix.as_deref(),
+            self.path_prefix.as_deref(),
+        ));
+        let (_, operation) = all_path_item_operations(spec.paths.get(&documented_path)?)
+            .find(|(x, _)| x == method)?;
+        Some((documented_path, operation.into_owned()))
+    }
+
+    /// Reset state of previous build.
+    fn start_build(&mut self) -> Result<(), Error> {
+        if !self.denied_undocumented_routes.is_empty() {
+            return Err(Error::UndocumentedRoutes {
+                routes: self.denied_undocumented_routes.clone(),
+            });
+        }
+        self.skipped_routes.clear();
+        self.documented_paths.clear();
+        self.warnings = self
+            .undocumented_routes
+            .iter()
+            .map(|(path, method)| BuildWarning::UndocumentedRoute {
+                path: path.clone(),
+                method: method.clone(),
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// Routes (path and method), which were not documented during last build.
+    ///
+    /// See [`BuilderOptions::wildcard_routes`](crate::BuilderOptions::wildcard_routes).
+    pub fn skipped_routes(&self) -> &[(String, Method)] {
+        &self.skipped_routes
+    }
+
+    /// Put components into specification and apply sorting.
+    fn finish(&mut self, mut spec: OpenApi) -> Result<OpenApi, Error> {
+        for item in spec.paths.values() {
+            check_path_item_operations(item)?;
+        }
+        if let Some(generator) = self.fallback {
+            let _ = self.components.take_operation_context();
+            self.components.set_route(None);
+            let operation =
+                generator(&mut self.components).map_err(|err| Error::Fallback(Box::new(err)))?;
+            let operation =
+                serde_json::to_value(operation).map_err(|err| Error::Serialize(err.into()))?;
+            let _ = spec.extensions.insert("x-fallback".into(), operation);
+        }
+        for register in &self.registered_schemas {
+            register(&mut self.components);
+        }
+        let recursive = self.components.apply_to(&mut spec)?;
+        self.warnings.extend(
+            recursive
+                .into_iter()
+                .map(|name| BuildWarning::RecursiveSchema { name }),
+        );
+        if self.options.infer_discriminators {
+            if let Some(components) = spec.components.as_mut() {
+                infer_discriminators(&mut components.schemas, self.components.definitions_path());
+            }
+        }
+        if self.options.split_read_write_schemas {
+            split_read_write_schemas(&mut spec, self.components.definitions_path())?;
+        }
+        if !self.excluded_extensions.is_empty() {
+            exclude_operations(&mut spec, &self.excluded_extensions)?;
+        }
+        if !self.rate_limited.is_empty() {
+            document_rate_limits(&mut spec, &self.rate_limited)?;
+        }
+        if self.options.document_head_for_get {
+            add_head_operations(&mut spec, &self.options)?;
+        }
+        if self.options.framework_responses {
+            add_framework_responses(&mut spec, &self.allowed_methods, self.fallback.is_none())?;
+        }
+        if self.path_prefix.is_some()
+            || self.strip_prefix.is_some()
+            || self.options.normalizes_paths()
+        {
+            let mut paths = Map::new();
+            for (path, item) in std::mem::take(&mut spec.paths) {
+                let path = self.options.normalize_path(rewrite_path(
+                    &path,
+                    self.strip_prefix.as_deref(),
+                    self.path_prefix.as_deref(),
+                ));
+                // Rewritten paths may collide, different operations with same method are an error
+                if let Some(existing) = paths.get(&path) {
+                    let conflict = all_path_item_operations(&item).find(|(method, operation)| {
+                        all_path_item_operations(existing).any(|(x_method, x_operation)| {
+                            x_method == method && x_operation != *operation
+                        })
+                    });
+                    if let Some((method, _)) = conflict {
+                        return Err(Error::Merge(anyhow::anyhow!(
+                            "{method} {path} is defined multiple times after rewriting paths"
+                        )));
+                    }
+                }
+                merge_paths(
+                    &mut paths,
+                    Map::from_iter([(path, item)]),
+                    MergeStrategy::Merge,
+                )
+                .map_err(Error::Merge)?;
+            }
+            spec.paths = paths;
         }
 
-        spec.components = Some(self.components.okapi_components()?);
+        let mut tag_names = HashSet::new();
+        for tag in &spec.tags {
+            if !tag_names.insert(&tag.name) {
+                self.warnings.push(BuildWarning::DuplicateTag {
+                    name: tag.name.clone(),
+                });
+            }
+        }
+
+        match self.paths_order {
+            SortMode::Alphabetical => spec.paths.sort_keys(),
+            SortMode::InsertionOrder => {}
+            SortMode::TagGrouped => {
+                let tags = spec.tags.clone();
+                spec.paths.sort_by(|lpath, litem, rpath, ritem| {
+                    let lkey = (tag_group(&tags, litem), lpath);
+                    let rkey = (tag_group(&tags, ritem), rpath);
+                    lkey.cmp(&rkey)
+                });
+            }
+        }
+        if self.schemas_order != SortMode::InsertionOrder {
+            if let Some(components) = spec.components.as_mut() {
+                components.schemas.sort_keys();
+            }
+        }
+        if self.tags_order != SortMode::InsertionOrder {
+            spec.tags.sort_by(|l, r| l.name.cmp(&r.name));
+        }
+        if !self.tag_order.is_empty() {
+            for name in &self.tag_order {
+                if !spec.tags.iter().any(|x| &x.name == name) {
+                    spec.tags.push(Tag {
+                        name: name.clone(),
+                        ..Default::default()
+                    });
+                }
+            }
+            let position = |tag: &Tag| {
+                self.tag_order
+                    .iter()
+                    .position(|x| x == &tag.name)
+                    .unwrap_or(self.tag_order.len())
+            };
+            spec.tags.sort_by_key(position);
+        }
+        if !self.tag_groups.is_empty() {
+            let groups = self
+                .tag_groups
+                .iter()
+                .map(|(name, tags)| serde_json::json!({ "name": name, "tags": tags }))
+                .collect();
+            let _ = spec
+                .extensions
+                .insert("x-tagGroups".into(), serde_json::Value::Array(groups));
+        }
+        if let Some(ref resolver) = self.description_resolver {
+            resolve_descriptions(&mut spec, resolver.as_ref());
+        }
 
         Ok(spec)
     }
@@ -186,6 +982,24 @@ impl OpenApiBuilder {
         self
     }
 
+    /// Set specification version to version of Cargo package.
+    ///
+    /// Use [`cargo_package`](crate::cargo_package) macro to get metadata of your crate.
+    pub fn version_from_cargo(&mut self, package: CargoPackage) -> &mut Self {
+        self.version(package.version)
+    }
+
+    /// Set specification title to name of Cargo package, and description to package
+    /// description (if it is not empty and description is not set).
+    ///
+    /// Use [`cargo_package`](crate::cargo_package) macro to get metadata of your crate.
+    pub fn title_from_cargo(&mut self, package: CargoPackage) -> &mut Self {
+        if !package.description.is_empty() && self.spec.info.description.is_none() {
+            self.description(package.description);
+        }
+        self.title(package.name)
+    }
+
     /// Add description to specification.
     pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
         self.spec.info.description = Some(description.into());
@@ -204,6 +1018,45 @@ impl OpenApiBuilder {
         self
     }
 
+    /// Add license with [SPDX](https://spdx.org/licenses/) identifier (like `Apache-2.0`) to
+    /// specification.
+    ///
+    /// Identifier is used as license name, URL of license is set to its page in SPDX license
+    /// list and identifier itself is set as `x-spdx-identifier` extension (`identifier` field is
+    /// defined only in OpenAPI 3.1).
+    pub fn license_spdx(&mut self, identifier: impl Into<String>) -> &mut Self {
+        let identifier = identifier.into();
+        let mut license = License {
+            name: identifier.clone(),
+            url: Some(format!("https://spdx.org/licenses/{identifier}.html")),
+            ..Default::default()
+        };
+        let _ = license
+            .extensions
+            .insert("x-spdx-identifier".into(), identifier.into());
+        self.license(license)
+    }
+
+    /// Set contact email of specification, keeping other contact fields.
+    pub fn contact_email(&mut self, email: impl Into<String>) -> &mut Self {
+        self.spec
+            .info
+            .contact
+            .get_or_insert_with(Default::default)
+            .email = Some(email.into());
+        self
+    }
+
+    /// Add extension (like `x-logo`) to info object of specification.
+    pub fn info_extension(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> &mut Self {
+        let _ = self.spec.info.extensions.insert(name.into(), value.into());
+        self
+    }
+
     /// Add terms_of_service to specification.
     pub fn terms_of_service(&mut self, terms_of_service: impl Into<String>) -> &mut Self {
         self.spec.info.terms_of_service = Some(terms_of_service.into());
@@ -222,6 +1075,35 @@ impl OpenApiBuilder {
         self
     }
 
+    /// Set order of tags in specification.
+    ///
+    /// Listed tags go first in specified order (tags, which are not present in specification,
+    /// are added), other tags follow them in order, defined by [`Self::sort_tags`].
+    pub fn tag_order<I, S>(&mut self, tags: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tag_order = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Add group of tags to `x-tagGroups` extension of specification.
+    ///
+    /// This extension is supported by some UIs (i.e. Redoc) for grouping tags in navigation.
+    /// Group with the same name is replaced.
+    pub fn tag_group<N, I, S>(&mut self, name: N, tags: I) -> &mut Self
+    where
+        N: Into<String>,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let _ = self
+            .tag_groups
+            .insert(name.into(), tags.into_iter().map(Into::into).collect());
+        self
+    }
+
     /// Set external documentation for specification.
     pub fn external_docs(&mut self, docs: ExternalDocs) -> &mut Self {
         let _ = self.spec.external_docs.insert(docs);
@@ -236,40 +1118,587 @@ impl OpenApiBuilder {
         self.components.add_security_scheme(name, sec);
         self
     }
+
+    /// Add reusable response definition to specification.
+    pub fn response<N>(&mut self, name: N, response: Response) -> &mut Self
+    where
+        N: Into<String>,
+    {
+        self.components.add_response(name, response);
+        self
+    }
+
+    /// Add reusable parameter definition to specification.
+    pub fn parameter<N>(&mut self, name: N, parameter: Parameter) -> &mut Self
+    where
+        N: Into<String>,
+    {
+        self.components.add_parameter(name, parameter);
+        self
+    }
+
+    /// Add reusable request body definition to specification.
+    pub fn request_body<N>(&mut self, name: N, request_body: RequestBody) -> &mut Self
+    where
+        N: Into<String>,
+    {
+        self.components.add_request_body(name, request_body);
+        self
+    }
+
+    /// Add reusable header definition to specification.
+    pub fn header<N>(&mut self, name: N, header: Header) -> &mut Self
+    where
+        N: Into<String>,
+    {
+        self.components.add_header(name, header);
+        self
+    }
+}
+
+/// Register schemas of several types with [`OpenApiBuilder::register_schema`].
+///
+/// ```rust
+/// # use okapi_operation::*;
+/// # use okapi::schemars::*;
+/// #[derive(JsonSchema)]
+/// struct OrderCreated {
+///     id: u64,
+/// }
+///
+/// #[derive(JsonSchema)]
+/// struct OrderCancelled {
+///     id: u64,
+/// }
+///
+/// let mut builder = OpenApiBuilder::new("Demo", "1.0.0");
+/// let spec = register_schemas!(builder, OrderCreated, OrderCancelled).build()?;
+/// assert_eq!(spec.components.unwrap().schemas.len(), 2);
+/// # Ok::<(), Error>(())
+/// ```
+#[macro_export]
+macro_rules! register_schemas {
+    ($builder:expr, $($ty:ty),+ $(,)?) => {
+        $builder$(.register_schema::<$ty>())+
+    };
+}
+
+/// Immutable snapshot of built specification, created by [`OpenApiBuilder::freeze`].
+///
+/// Keeps operations and schemas, generated for snapshot, so specification with new or
+/// changed routes can be rebuilt without calling generators of other operations (useful for
+/// dev servers with hot reload).
+#[derive(Clone)]
+pub struct FrozenSpec {
+    builder: OpenApiBuilder,
+    spec: OpenApi,
+}
+
+impl FrozenSpec {
+    /// Built specification.
+    pub fn spec(&self) -> &OpenApi {
+        &self.spec
+    }
+
+    /// Take built specification.
+    pub fn into_spec(self) -> OpenApi {
+        self.spec
+    }
+
+    /// Rebuild specification with additional operations, creating new snapshot.
+    ///
+    /// Operations for already present (path, method) pairs are replaced. Only generators of
+    /// passed operations are called, other operations and schemas are reused from this
+    /// snapshot (schemas, which are no longer used by replaced operations, are kept).
+    pub fn rebuild_with<I, S>(&self, operations: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (S, Method, OperationGenerator)>,
+        S: Into<String>,
+    {
+        let mut builder = self.builder.clone();
+        for (path, method, generator) in operations {
+            let key = (path.into(), method);
+            if let Some(cache) = builder.operation_cache.as_mut() {
+                let _ = cache.remove(&key);
+            }
+            let _ = builder.operations.insert(key, generator.into());
+        }
+        let spec = builder.build()?;
+        Ok(Self { builder, spec })
+    }
+}
+
+/// Get operation from cache (if enabled), restoring context of its generator.
+fn take_cached(
+    cache: &Option<OperationCache>,
+    components: &mut Components,
+    path: &str,
+    method: &Method,
+) -> Option<Operation> {
+    let cached = cache.as_ref()?.get(&(path.to_owned(), method.clone()))?;
+    components.set_operation_context(cached.context.clone());
+    Some(cached.operation.clone())
+}
+
+/// Put successfully generated operation into cache (if enabled).
+fn store_cached(
+    cache: &mut Option<OperationCache>,
+    components: &mut Components,
+    path: &str,
+    method: &Method,
+    operation: &Result<Operation, Error>,
+) {
+    let (Some(cache), Ok(operation)) = (cache.as_mut(), operation) else {
+        return;
+    };
+    let context = components.take_operation_context();
+    let _ = cache.insert(
+        (path.to_owned(), method.clone()),
+        CachedOperation {
+            operation: operation.clone(),
+            context: context.clone(),
+        },
+    );
+    components.set_operation_context(context);
+}
+
+/// Remove operations with any of specified extensions (and paths without operations).
+fn exclude_operations(spec: &mut OpenApi, extensions: &[String]) -> Result<(), Error> {
+    retain_operations(spec, |operation| {
+        !extensions.iter().any(|name| {
+            operation
+                .extensions
+                .get(name)
+                .is_some_and(|x| !x.is_null() && x != &serde_json::Value::Bool(false))
+        })
+    })
+}
+
+/// Retain only operations, for which `f` returns `true`, and remove paths without operations.
+///
+/// Returns an error (leaving specification partially filtered) if path item contains invalid
+/// operations of custom methods.
+pub(crate) fn retain_operations<F>(spec: &mut OpenApi, mut f: F) -> Result<(), Error>
+where
+    F: FnMut(&Operation) -> bool,
+{
+    for item in spec.paths.values_mut() {
+        retain_path_item_operations(item, |_, operation| f(operation))?;
+    }
+    spec.paths.retain(|_, item| has_path_item_operations(item));
+    Ok(())
+}
+
+/// Add `HEAD` operations without response bodies for paths with `GET` operation.
+///
+/// `operationId`s of added operations are deduplicated against existing ones according to
+/// [`BuilderOptions::duplicate_operation_id`].
+fn add_head_operations(spec: &mut OpenApi, options: &BuilderOptions) -> Result<(), Error> {
+    let mut operation_ids: HashSet<_> = spec
+        .paths
+        .values()
+        .flat_map(all_path_item_operations)
+        .filter_map(|(_, operation)| operation.operation_id.clone())
+        .collect();
+    for (path, item) in spec.paths.iter_mut() {
+        let (Some(get), None) = (&item.get, &item.head) else {
+            continue;
+        };
+        let mut head = get.clone();
+        if let Some(operation_id) = head.operation_id.take() {
+            let operation_id = deduplicate_operation_id(
+                format!("{operation_id}_head"),
+                &operation_ids,
+                options,
+                path,
+                &Method::HEAD,
+            )?;
+            let _ = operation_ids.insert(operation_id.clone());
+            head.operation_id = Some(operation_id);
+        }
+        for response in head
+            .responses
+            .responses
+            .values_mut()
+            .chain(head.responses.default.as_mut())
+        {
+            if let RefOr::Object(response) = response {
+                response.content.clear();
+            }
+        }
+        item.head = Some(head);
+    }
+    Ok(())
+}
+
+fn document_rate_limits(spec: &mut OpenApi, filters: &[OperationFilter]) -> Result<(), Error> {
+    for (path, item) in spec.paths.iter_mut() {
+        for_each_path_item_operation_mut(item, |method, operation| {
+            if filters.iter().any(|f| f(path, method, operation)) {
+                add_rate_limit_responses(&mut operation.responses);
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Add responses, returned by router itself: `405` to operations on paths, for which router
+/// rejects unhandled methods, and `404` (if router has no fallback) to components.
+fn add_framework_responses(
+    spec: &mut OpenApi,
+    allowed_methods: &[(String, Vec<Method>)],
+    not_found: bool,
+) -> Result<(), Error> {
+    for (path, methods) in allowed_methods {
+        let Some(item) = spec.paths.get_mut(path) else {
+            continue;
+        };
+        let allowed = methods
+            .iter()
+            .map(Method::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        for_each_path_item_operation_mut(item, |_, operation| {
+            let responses = &mut operation.responses.responses;
+            if responses.contains_key("405") {
+                return;
+            }
+            let schema = SchemaObject {
+                instance_type: Some(InstanceType::String.into()),
+                ..Default::default()
+            };
+            let description = format!("Methods, allowed for path ({allowed})");
+            let allow = header(schema, Some(&description), true);
+            let _ = responses.insert(
+                "405".into(),
+                RefOr::Object(Response {
+                    description: "Method not allowed".into(),
+                    headers: Map::from_iter([("Allow".into(), RefOr::Object(allow))]),
+                    ..Default::default()
+                }),
+            );
+        })?;
+    }
+    if not_found {
+        let _ = spec
+            .components
+            .get_or_insert_with(Default::default)
+            .responses
+            .entry("NotFound".into())
+            .or_insert_with(|| {
+                RefOr::Object(Response {
+                    description: "No route matches request path".into(),
+                    ..Default::default()
+                })
+            });
+    }
+    Ok(())
+}
+
+/// Strip and then prepend prefix to path.
+fn rewrite_path(path: &str, strip: Option<&str>, prefix: Option<&str>) -> String {
+    let mut path = path;
+    if let Some(strip) = strip.map(|x| x.trim_end_matches('/')) {
+        if let Some(rest) = path.strip_prefix(strip) {
+            if rest.is_empty() {
+                path = "/";
+            } else if rest.starts_with('/') {
+                path = rest;
+            }
+        }
+    }
+    match prefix.map(|x| x.trim_end_matches('/')) {
+        Some(prefix) if path == "/" && !prefix.is_empty() => prefix.to_owned(),
+        Some(prefix) => format!("{prefix}{path}"),
+        None => path.to_owned(),
+    }
+}
+
+/// Key for grouping paths by tag: tags, present in specification, go first (in order of
+/// appearance), then other tags (by name), then untagged paths.
+fn tag_group(tags: &[Tag], item: &PathItem) -> (usize, Option<String>) {
+    let tag = all_path_item_operations(item).find_map(|(_, x)| x.tags.first().cloned());
+    match tag {
+        Some(tag) => match tags.iter().position(|x| x.name == tag) {
+            Some(idx) => (idx, None),
+            None => (tags.len(), Some(tag)),
+        },
+        None => (tags.len() + 1, None),
+    }
+}
+
+/// Operation IDs of specification being built.
+///
+/// Inferred IDs are assigned after all operations are generated, so explicitly specified IDs
+/// are reserved first and inferred IDs never take them.
+#[derive(Default)]
+struct OperationIds {
+    explicit: HashSet<String>,
+    /// Path, method and inferred ID of operations without explicit ID.
+    inferred: Vec<(String, Method, String)>,
 }
 
+impl OperationIds {
+    /// Deduplicate and set inferred IDs to operations.
+    fn assign_inferred(
+        self,
+        spec: &mut OpenApi,
+        options: &BuilderOptions,
+        warnings: &mut Vec<BuildWarning>,
+    ) -> Result<(), Error> {
+        let mut operation_ids = self.explicit;
+        for (path, method, id) in self.inferred {
+            let id = deduplicate_operation_id(id, &operation_ids, options, &path, &method)?;
+            let _ = operation_ids.insert(id.clone());
+            if let Some(item) = spec.paths.get_mut(&path) {
+                for_each_path_item_operation_mut(item, |x_method, operation| {
+                    if *x_method == method {
+                        operation.operation_id = Some(id.clone());
+                    }
+                })?;
+            }
+            warnings.push(BuildWarning::InferredOperationId {
+                path,
+                method,
+                operation_id: id,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn deduplicate_operation_id(
+    id: String,
+    operation_ids: &HashSet<String>,
+    options: &BuilderOptions,
+    path: &str,
+    method: &Method,
+) -> Result<String, Error> {
+    if !operation_ids.contains(&id) {
+        return Ok(id);
+    }
+    match options.duplicate_operation_id {
+        DuplicateOperationId::Error => Err(Error::DuplicateOperationId {
+            operation_id: id,
+            path: path.into(),
+            method: method.clone(),
+        }),
+        DuplicateOperationId::Suffix => {
+            let base = format!("{id}_{}", method_path_slug(path, method));
+            let mut id = base.clone();
+            let mut idx = 2;
+            while operation_ids.contains(&id) {
+                id = format!("{base}_{idx}");
+                idx += 1;
+            }
+            Ok(id)
+        }
+    }
+}
+
+/// Operations in order of generation.
+fn sorted_operations(
+    operations: &IndexMap<(String, Method), OperationEntry>,
+    order: SortMode,
+) -> Vec<((String, Method), OperationEntry)> {
+    let mut operations: Vec<_> = operations
+        .iter()
+        .map(|(key, entry)| (key.clone(), entry.clone()))
+        .collect();
+    if order != SortMode::InsertionOrder {
+        operations.sort_by(|(lkey, _), (rkey, _)| {
+            let lkey_str = (&lkey.0, lkey.1.as_str());
+            let rkey_str = (&rkey.0, rkey.1.as_str());
+            lkey_str.cmp(&rkey_str)
+        });
+    }
+    operations
+}
+
+/// Prepare components for calling generator of operation.
+fn start_operation(components: &mut Components, path: &str, method: &Method) {
+    let _ = components.take_operation_context();
+    components.set_route(Some((path.to_owned(), method.clone())));
+}
+
+/// Add operation to specification.
+///
+/// Returns path, under which operation is documented, or `None`, if operation is skipped.
+#[allow(clippy::too_many_arguments)]
 fn try_add_path(
     spec: &mut OpenApi,
     components: &mut Components,
+    options: &BuilderOptions,
+    operation_ids: &mut OperationIds,
+    warnings: &mut Vec<BuildWarning>,
     path: &str,
     method: Method,
-    generator: OperationGenerator,
-) -> Result<(), anyhow::Error> {
-    let operation_schema = generator(components)?;
+    entry: &OperationEntry,
+    operation: Result<Operation, Error>,
+) -> Result<Option<String>, Error> {
+    components.set_route(None);
+    let generator_error = |source| Error::Generator {
+        path: path.into(),
+        method: method.clone(),
+        source: Box::new(source),
+    };
+    let mut operation_schema = operation.map_err(generator_error)?;
+    let OperationContext {
+        handler_name,
+        path_override,
+        declared_route,
+        unknown_extractors,
+    } = components.take_operation_context();
+    for type_name in unknown_extractors {
+        warnings.push(BuildWarning::UnknownExtractor {
+            path: path.into(),
+            method: method.clone(),
+            type_name,
+        });
+    }
+    if let Some((declared_path, declared_method)) = declared_route {
+        if !path.ends_with(&declared_path) || method != declared_method {
+            warnings.push(BuildWarning::DeclaredRouteMismatch {
+                path: path.into(),
+                method: method.clone(),
+                declared_path,
+                declared_method,
+            });
+        }
+    }
+    let wildcard = match &path_override {
+        Some(path) => entry
+            .wildcard
+            .as_ref()
+            .filter(|name| path.contains(&format!("{{{name}}}"))),
+        None if options.wildcard_routes == WildcardRoutes::Skip && entry.wildcard.is_some() => {
+            warnings.push(BuildWarning::SkippedRoute {
+                path: path.into(),
+                method,
+            });
+            return Ok(None);
+        }
+        None => entry.wildcard.as_ref(),
+    };
+    let path = path_override.as_deref().unwrap_or(path);
+    entry.apply_customizations(&mut operation_schema);
+    match &operation_schema.operation_id {
+        Some(id) => {
+            let _ = operation_ids.explicit.insert(id.clone());
+        }
+        None if options.infer_operation_id => {
+            if let Some(id) =
+                options
+                    .operation_id_strategy
+                    .operation_id(path, &method, handler_name.as_deref())
+            {
+                operation_ids
+                    .inferred
+                    .push((path.into(), method.clone(), id));
+            }
+        }
+        None => {}
+    }
+    if let Some(name) = wildcard {
+        add_wildcard_parameter(&mut operation_schema, components, name);
+    }
+    if options.infer_path_parameters {
+        for name in add_path_parameters(&mut operation_schema, components, path) {
+            warnings.push(BuildWarning::InferredPathParameter {
+                path: path.into(),
+                method: method.clone(),
+                name,
+            });
+        }
+    }
+    if let Some(generate) = options.default_error_responses {
+        let responses = &mut operation_schema.responses;
+        let error_responses = generate(components).map_err(generator_error)?;
+        if responses.default.is_none() {
+            responses.default = error_responses.default;
+        }
+        for (status, response) in error_responses.responses {
+            let _ = responses.responses.entry(status).or_insert(response);
+        }
+    }
     let path_str = path;
+    if !is_standard_method(&method) && options.custom_methods != CustomMethods::AdditionalOperations
+    {
+        return Err(Error::UnsupportedMethod {
+            path: path_str.into(),
+            method,
+        });
+    }
     let path = spec.paths.entry(path.into()).or_default();
-    if method == Method::DELETE {
-        path.delete = Some(operation_schema);
-    } else if method == Method::GET {
-        path.get = Some(operation_schema);
-    } else if method == Method::HEAD {
-        path.head = Some(operation_schema);
-    } else if method == Method::OPTIONS {
-        path.options = Some(operation_schema);
-    } else if method == Method::PATCH {
-        path.patch = Some(operation_schema);
-    } else if method == Method::POST {
-        path.post = Some(operation_schema);
-    } else if method == Method::PUT {
-        path.put = Some(operation_schema);
-    } else if method == Method::TRACE {
-        path.trace = Some(operation_schema);
-    } else {
-        return Err(anyhow::anyhow!(
-            "Unsupported method {method} (at {path_str})"
-        ));
+    insert_path_item_operation(path, &method, operation_schema)
+        .map_err(|err| Error::Serialize(err.into()))?;
+    Ok(Some(path_str.into()))
+}
+
+/// Add path parameters, which are present in path template, but not documented by operation.
+///
+/// Returns names of added parameters.
+fn add_path_parameters(
+    operation: &mut Operation,
+    components: &mut Components,
+    path: &str,
+) -> Vec<String> {
+    let names = path
+        .split('/')
+        .filter_map(|x| x.strip_prefix('{').and_then(|x| x.strip_suffix('}')));
+    let mut added = Vec::new();
+    for name in names {
+        if find_path_parameter(operation, components, name).is_none() {
+            operation
+                .parameters
+                .push(RefOr::Object(path_parameter(name, components)));
+            added.push(name.to_owned());
+        }
     }
-    Ok(())
+    added
+}
+
+/// Document wildcard path parameter (which matches rest of path) with `x-wildcard` extension.
+fn add_wildcard_parameter(operation: &mut Operation, components: &mut Components, name: &str) {
+    let index = match find_path_parameter(operation, components, name) {
+        Some(index) => index,
+        None => {
+            let mut parameter = path_parameter(name, components);
+            parameter.description = Some("Rest of path".into());
+            operation.parameters.push(RefOr::Object(parameter));
+            operation.parameters.len() - 1
+        }
+    };
+    // Referenced parameters are left as is
+    if let RefOr::Object(parameter) = &mut operation.parameters[index] {
+        let _ = parameter
+            .extensions
+            .insert("x-wildcard".into(), serde_json::Value::Bool(true));
+    }
+}
+
+/// Find index of path parameter with specified name.
+fn find_path_parameter(
+    operation: &Operation,
+    components: &Components,
+    name: &str,
+) -> Option<usize> {
+    operation.parameters.iter().position(|x| {
+        let parameter = match x {
+            RefOr::Object(x) => Some(x),
+            RefOr::Ref(x) => components.parameter_by_reference(&x.reference),
+        };
+        parameter.is_some_and(|x| x.location == "path" && x.name == name)
+    })
+}
+
+/// Required string path parameter.
+fn path_parameter(name: &str, components: &mut Components) -> Parameter {
+    parameter(
+        name,
+        "path",
+        header(components.schema_for::<String>(), None, true),
+    )
 }
 
 /// Ensures that a builder always generates the same file every time, by not relying on
@@ -301,3 +1730,484 @@ fn ensure_builder_deterministic() {
         assert_eq!(built_specs[i - 1], built_specs[i]);
     }
 }
+
+#[test]
+fn invalid_custom_method_operation() {
+    let mut builder = OpenApiBuilder::new("title", "version");
+    let mut item = PathItem::default();
+    let _ = item.extensions.insert(
+        "x-additionalOperations".into(),
+        serde_json::json!({"QUERY": {"responses": 42}}),
+    );
+    let _ = builder.spec_mut().paths.insert("/search".into(), item);
+    let err = builder
+        .build()
+        .expect_err("Built spec with invalid operation");
+    assert!(matches!(err, Error::InvalidDefinition(_)));
+}
+
+#[test]
+fn path_prefix() {
+    use okapi::openapi3::Operation;
+
+    assert_eq!(
+        rewrite_path("/users", None, Some("/api/v1/")),
+        "/api/v1/users"
+    );
+    assert_eq!(rewrite_path("/", None, Some("/api")), "/api");
+    assert_eq!(rewrite_path("/int/users", Some("/int"), None), "/users");
+    assert_eq!(rewrite_path("/int", Some("/int/"), None), "/");
+    assert_eq!(rewrite_path("/internal", Some("/int"), None), "/internal");
+    assert_eq!(rewrite_path("/int/a", Some("/int"), Some("/api")), "/api/a");
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .operation("/int/users", Method::GET, |_| Ok(Operation::default()))
+        .operation("/users", Method::POST, |_| Ok(Operation::default()))
+        .strip_prefix("/int")
+        .path_prefix("/api");
+    let spec = builder.build().expect("Failed to build spec");
+    assert_eq!(spec.paths.keys().collect::<Vec<_>>(), ["/api/users"]);
+    let item = &spec.paths["/api/users"];
+    assert!(item.get.is_some() && item.post.is_some());
+
+    builder.operation("/int/users", Method::POST, |_| {
+        Ok(Operation {
+            summary: Some("Other".into()),
+            ..Default::default()
+        })
+    });
+    assert!(matches!(builder.build(), Err(Error::Merge(_))));
+}
+
+#[test]
+fn info_helpers() {
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .contact(Contact {
+            name: Some("Team".into()),
+            ..Default::default()
+        })
+        .contact_email("team@example.com")
+        .license_spdx("Apache-2.0")
+        .info_extension("x-logo", serde_json::json!({ "url": "logo.png" }));
+    let spec = builder.build().expect("Failed to build spec");
+    let info = serde_json::to_value(&spec.info).expect("Serializable");
+    assert_eq!(
+        info,
+        serde_json::json!({
+            "title": "title",
+            "version": "version",
+            "contact": { "name": "Team", "email": "team@example.com" },
+            "license": {
+                "name": "Apache-2.0",
+                "url": "https://spdx.org/licenses/Apache-2.0.html",
+                "x-spdx-identifier": "Apache-2.0",
+            },
+            "x-logo": { "url": "logo.png" },
+        })
+    );
+}
+
+#[test]
+fn cargo_metadata() {
+    let mut builder = OpenApiBuilder::default();
+    builder
+        .title_from_cargo(crate::cargo_package!())
+        .version_from_cargo(crate::cargo_package!());
+    let spec = builder.build().expect("Failed to build spec");
+    assert_eq!(spec.info.title, "okapi-operation");
+    assert_eq!(spec.info.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        spec.info.description.as_deref(),
+        Some(env!("CARGO_PKG_DESCRIPTION"))
+    );
+}
+
+#[test]
+fn build_serialized() {
+    let mut builder = OpenApiBuilder::new("title", "version");
+    let json = builder.build_json_pretty().expect("Failed to build spec");
+    assert!(json.contains("\n  \"openapi\": \"3.0.0\""));
+    #[cfg(feature = "yaml")]
+    {
+        let yaml = builder.build_yaml().expect("Failed to build spec");
+        assert!(yaml.contains("\nopenapi: 3.0.0\n"));
+    }
+}
+
+#[test]
+fn framework_responses() {
+    use okapi::openapi3::Operation;
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .set_options(BuilderOptions::new().framework_responses(true))
+        .operation("/users", Method::GET, |_| Ok(Operation::default()))
+        .operation("/users/{id}", Method::GET, |_| Ok(Operation::default()));
+    builder
+        .allowed_methods
+        .push(("/users".into(), vec![Method::GET, Method::HEAD]));
+    let spec = builder.build().expect("Failed to build spec");
+
+    let responses = |path: &str| {
+        let operation = spec.paths[path].get.as_ref().expect("Operation");
+        operation
+            .responses
+            .responses
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(responses("/users"), ["405"]);
+    assert!(responses("/users/{id}").is_empty());
+    let components = spec.components.as_ref().expect("Components");
+    assert!(components.responses.contains_key("NotFound"));
+
+    builder.fallback_operation(|_| Ok(Operation::default()));
+    let spec = builder.build().expect("Failed to build spec");
+    assert!(!spec
+        .components
+        .is_some_and(|x| x.responses.contains_key("NotFound")));
+}
+
+#[test]
+fn head_for_get() {
+    use okapi::openapi3::Operation;
+
+    fn get(components: &mut Components) -> Result<Operation, Error> {
+        Ok(Operation {
+            operation_id: Some("get_user".into()),
+            responses: <String as crate::ToResponses>::generate(components)?,
+            ..Default::default()
+        })
+    }
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .document_head_for_get(true)
+        .operation("/users/{id}", Method::GET, get)
+        .operation("/users", Method::GET, get)
+        .operation("/users", Method::HEAD, |_| Ok(Operation::default()));
+    let spec = builder.build().expect("Failed to build spec");
+
+    let item = &spec.paths["/users/{id}"];
+    let head = item.head.as_ref().expect("HEAD operation");
+    assert_eq!(head.operation_id.as_deref(), Some("get_user_head"));
+    let RefOr::Object(response) = &head.responses.responses["200"] else {
+        panic!("Response should be inline");
+    };
+    assert!(response.content.is_empty());
+    assert_ne!(item.get, item.head);
+    assert_eq!(spec.paths["/users"].head, Some(Operation::default()));
+}
+
+#[test]
+fn head_for_get_duplicate_operation_id() {
+    use okapi::openapi3::Operation;
+
+    fn operation(operation_id: &str) -> Operation {
+        Operation {
+            operation_id: Some(operation_id.into()),
+            ..Default::default()
+        }
+    }
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .document_head_for_get(true)
+        .operation("/users/{id}", Method::GET, |_| Ok(operation("get_user")))
+        .operation("/users/{id}", Method::POST, |_| {
+            Ok(operation("get_user_head"))
+        });
+    assert!(matches!(
+        builder.build(),
+        Err(Error::DuplicateOperationId { operation_id, method, .. })
+            if operation_id == "get_user_head" && method == Method::HEAD
+    ));
+
+    builder.set_options(
+        BuilderOptions::new()
+            .document_head_for_get(true)
+            .duplicate_operation_id(DuplicateOperationId::Suffix),
+    );
+    let spec = builder.build().expect("Failed to build spec");
+    let item = &spec.paths["/users/{id}"];
+    let head = item.head.as_ref().expect("HEAD operation");
+    assert_eq!(
+        head.operation_id.as_deref(),
+        Some("get_user_head_head_users_id")
+    );
+    assert_eq!(
+        item.post.as_ref().and_then(|x| x.operation_id.as_deref()),
+        Some("get_user_head")
+    );
+}
+
+#[test]
+fn exclude_by_extension() {
+    fn operation(value: serde_json::Value) -> Operation {
+        let mut operation = Operation::default();
+        let _ = operation.extensions.insert("x-experimental".into(), value);
+        operation
+    }
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .operation("/stable", Method::GET, |_| Ok(Operation::default()))
+        .operation("/stable", Method::POST, |_| {
+            Ok(operation(serde_json::Value::Bool(true)))
+        })
+        .operation("/disabled", Method::GET, |_| {
+            Ok(operation(serde_json::Value::Bool(false)))
+        })
+        .operation("/experimental", Method::GET, |_| {
+            Ok(operation(serde_json::Value::Bool(true)))
+        })
+        .operation("/search", Method::from_bytes(b"QUERY").unwrap(), |_| {
+            Ok(operation(serde_json::Value::Bool(true)))
+        });
+    let spec = builder.build().expect("Failed to build spec");
+    assert_eq!(spec.paths.len(), 4);
+
+    let spec = builder
+        .exclude_operations_with_extension("x-experimental")
+        .build()
+        .expect("Failed to build spec");
+    assert_eq!(
+        spec.paths.keys().collect::<Vec<_>>(),
+        ["/disabled", "/stable"]
+    );
+    assert!(spec.paths["/stable"].post.is_none());
+}
+
+#[test]
+fn path_descriptions() {
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .path_summary("/users/{id}", "User")
+        .path_description("/users/{id}", "User resource")
+        .operation("/users/{id}", Method::GET, |_| Ok(Operation::default()))
+        .path_prefix("/api");
+    let spec = builder.build().expect("Failed to build spec");
+    let item = &spec.paths["/api/users/{id}"];
+    assert_eq!(item.summary.as_deref(), Some("User"));
+    assert_eq!(item.description.as_deref(), Some("User resource"));
+    assert!(item.get.is_some());
+}
+
+#[test]
+fn path_normalization() {
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .set_options(
+            BuilderOptions::new()
+                .collapse_slashes(true)
+                .trailing_slash(crate::TrailingSlash::Strip),
+        )
+        .operation("/users/", Method::GET, |_| Ok(Operation::default()))
+        .path_summary("/users//", "Users")
+        .path_prefix("/api//");
+    let spec = builder
+        .operation("/users//", Method::POST, |_| Ok(Operation::default()))
+        .build()
+        .expect("Failed to build spec");
+    assert_eq!(spec.paths.keys().collect::<Vec<_>>(), ["/api/users"]);
+    assert!(spec.paths["/api/users"].post.is_some());
+    assert_eq!(spec.paths["/api/users"].summary.as_deref(), Some("Users"));
+
+    // Options are applied to operations, added before them
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .operation("/users/", Method::GET, |_| Ok(Operation::default()))
+        .operation("//users", Method::GET, |_| {
+            Ok(Operation {
+                summary: Some("List users".into()),
+                ..Default::default()
+            })
+        });
+    assert_eq!(
+        builder.build().expect("Failed to build spec").paths.len(),
+        2
+    );
+    builder.set_options(
+        BuilderOptions::new()
+            .collapse_slashes(true)
+            .trailing_slash(crate::TrailingSlash::Strip),
+    );
+    assert!(matches!(builder.build(), Err(Error::Merge(_))));
+}
+
+#[test]
+fn rate_limits() {
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .operation("/api/users", Method::GET, |_| Ok(Operation::default()))
+        .operation("/api/users", Method::from_bytes(b"QUERY").unwrap(), |_| {
+            Ok(Operation::default())
+        })
+        .operation("/health", Method::GET, |_| Ok(Operation::default()))
+        .document_rate_limits(|path, _, _| path.starts_with("/api"));
+    let spec = builder.build().expect("Failed to build spec");
+    assert!(
+        spec.paths["/api/users"].extensions["x-additionalOperations"]["QUERY"]["responses"]
+            .get("429")
+            .is_some()
+    );
+    let responses = &spec.paths["/api/users"].get.as_ref().unwrap().responses;
+    let Some(RefOr::Object(response)) = responses.responses.get("429") else {
+        panic!("429 response should be documented");
+    };
+    assert!(response.headers.contains_key("Retry-After"));
+    assert!(spec.paths["/health"]
+        .get
+        .as_ref()
+        .unwrap()
+        .responses
+        .responses
+        .is_empty());
+}
+
+#[test]
+fn registered_schemas() {
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi::schemars")]
+    #[allow(dead_code)]
+    struct Payload {
+        id: u64,
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .register_schema::<Payload>()
+        .register_schema::<String>()
+        .build()
+        .expect("Failed to build spec");
+    let schemas = spec.components.expect("Components missing").schemas;
+    assert_eq!(schemas.keys().collect::<Vec<_>>(), ["Payload"]);
+}
+
+#[test]
+fn freeze_and_rebuild() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn counted(components: &mut Components) -> Result<Operation, Error> {
+        let _ = CALLS.fetch_add(1, Ordering::SeqCst);
+        components.set_operation_context(OperationContext {
+            handler_name: Some("counted".into()),
+            ..Default::default()
+        });
+        Ok(Operation::default())
+    }
+
+    fn orders(_: &mut Components) -> Result<Operation, Error> {
+        Ok(Operation::default())
+    }
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .set_options(BuilderOptions::default().infer_operation_id(true))
+        .operation("/users", Method::GET, counted);
+    let frozen = builder.freeze().expect("Failed to build spec");
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+    let rebuilt = frozen
+        .rebuild_with([("/orders", Method::GET, orders as OperationGenerator)])
+        .expect("Failed to rebuild spec");
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    assert_eq!(rebuilt.spec().paths.len(), 2);
+    // Context of cached generator is restored
+    assert_eq!(
+        rebuilt.spec().paths["/users"]
+            .get
+            .as_ref()
+            .unwrap()
+            .operation_id
+            .as_deref(),
+        Some("counted")
+    );
+    assert_eq!(frozen.spec().paths.len(), 1);
+
+    let replaced = rebuilt
+        .rebuild_with([("/users", Method::GET, orders as OperationGenerator)])
+        .expect("Failed to rebuild spec");
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    assert!(replaced.into_spec().paths["/users"]
+        .get
+        .as_ref()
+        .unwrap()
+        .operation_id
+        .is_none());
+}
+
+#[test]
+fn sort_modes() {
+    use okapi::openapi3::Operation;
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .operation("/c", Method::GET, |_| Ok(Operation::default()))
+        .operation("/b", Method::GET, |_| {
+            Ok(Operation {
+                tags: vec!["b".into()],
+                ..Default::default()
+            })
+        })
+        .operation("/a", Method::GET, |_| Ok(Operation::default()))
+        .tag(Tag {
+            name: "b".into(),
+            ..Default::default()
+        })
+        .tag(Tag {
+            name: "a".into(),
+            ..Default::default()
+        });
+    let paths = |builder: &mut OpenApiBuilder| {
+        builder
+            .build()
+            .expect("Failed to build spec")
+            .paths
+            .into_keys()
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(paths(&mut builder), ["/a", "/b", "/c"]);
+    builder.sort_paths(SortMode::InsertionOrder);
+    assert_eq!(paths(&mut builder), ["/c", "/b", "/a"]);
+    builder.sort_paths(SortMode::TagGrouped);
+    assert_eq!(paths(&mut builder), ["/b", "/a", "/c"]);
+
+    builder.sort_tags(SortMode::Alphabetical);
+    let tags = builder.build().expect("Failed to build spec").tags;
+    assert_eq!(
+        tags.into_iter().map(|x| x.name).collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+}
+
+#[test]
+fn tag_order_and_groups() {
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .tag(Tag {
+            name: "orders".into(),
+            ..Default::default()
+        })
+        .tag(Tag {
+            name: "users".into(),
+            ..Default::default()
+        })
+        .tag_order(["roles", "users"])
+        .tag_group("Accounts", ["users", "roles"]);
+    let spec = builder.build().expect("Failed to build spec");
+
+    assert_eq!(
+        spec.tags.into_iter().map(|x| x.name).collect::<Vec<_>>(),
+        ["roles", "users", "orders"]
+    );
+    assert_eq!(
+        spec.extensions["x-tagGroups"],
+        serde_json::json!([{ "name": "Accounts", "tags": ["users", "roles"] }])
+    );
+}
@@ -0,0 +1,354 @@
+use std::{fmt, sync::Arc};
+
+use http::Method;
+use okapi::openapi3::Responses;
+
+use crate::{Components, Error, ToResponses};
+
+type CustomOperationId = Arc<dyn Fn(&str, &Method, Option<&str>) -> Option<String> + Send + Sync>;
+type ResponsesGenerator = fn(&mut Components) -> Result<Responses, Error>;
+
+/// Options of [`OpenApiBuilder`](crate::OpenApiBuilder).
+#[derive(Clone, Debug, Default)]
+pub struct BuilderOptions {
+    pub(crate) infer_operation_id: bool,
+    pub(crate) operation_id_strategy: OperationIdStrategy,
+    pub(crate) duplicate_operation_id: DuplicateOperationId,
+    pub(crate) default_error_responses: Option<ResponsesGenerator>,
+    pub(crate) infer_path_parameters: bool,
+    pub(crate) wildcard_routes: WildcardRoutes,
+    pub(crate) custom_methods: CustomMethods,
+    pub(crate) framework_responses: bool,
+    pub(crate) document_head_for_get: bool,
+    pub(crate) split_read_write_schemas: bool,
+    pub(crate) infer_discriminators: bool,
+    pub(crate) collapse_slashes: bool,
+    pub(crate) trailing_slash: TrailingSlash,
+    pub(crate) lowercase_paths: bool,
+}
+
+impl BuilderOptions {
+    /// Create default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Infer `operationId` for operations, which doesn't have it set explicitly.
+    ///
+    /// `operationId` is generated with [`operation_id_strategy`](Self::operation_id_strategy).
+    /// `false` by default.
+    pub fn infer_operation_id(mut self, infer_operation_id: bool) -> Self {
+        self.infer_operation_id = infer_operation_id;
+        self
+    }
+
+    /// Set strategy of inferring `operationId`.
+    ///
+    /// [`OperationIdStrategy::FunctionName`] by default.
+    pub fn operation_id_strategy(mut self, operation_id_strategy: OperationIdStrategy) -> Self {
+        self.operation_id_strategy = operation_id_strategy;
+        self
+    }
+
+    /// Set how inferred `operationId`, which is already used by another operation, is handled.
+    ///
+    /// Explicitly specified `operationId`s are reserved before inferring, so inferred
+    /// `operationId` is deduplicated even if conflicting operation is added after it.
+    /// [`DuplicateOperationId::Error`] by default.
+    pub fn duplicate_operation_id(mut self, duplicate_operation_id: DuplicateOperationId) -> Self {
+        self.duplicate_operation_id = duplicate_operation_id;
+        self
+    }
+
+    /// Add path parameters, which are present in path template (like `id` in `/users/{id}`),
+    /// but not documented by operation, as required string parameters.
+    ///
+    /// `false` by default.
+    pub fn infer_path_parameters(mut self, infer_path_parameters: bool) -> Self {
+        self.infer_path_parameters = infer_path_parameters;
+        self
+    }
+
+    /// Set how routes with wildcard segment (like `/files/*path`) are documented.
+    ///
+    /// Routes with operations, which set `path_override`, are always documented under
+    /// overriding path. [`WildcardRoutes::Parameterized`] by default.
+    pub fn wildcard_routes(mut self, wildcard_routes: WildcardRoutes) -> Self {
+        self.wildcard_routes = wildcard_routes;
+        self
+    }
+
+    /// Set how operations of methods, which can't be documented in OpenAPI 3.0 (like `QUERY`
+    /// or WebDAV methods), are handled.
+    ///
+    /// [`CustomMethods::AdditionalOperations`] by default.
+    pub fn custom_methods(mut self, custom_methods: CustomMethods) -> Self {
+        self.custom_methods = custom_methods;
+        self
+    }
+
+    /// Document responses, returned by router itself: `405 Method Not Allowed` for operations
+    /// on paths, for which router rejects methods without handler (i.e. route has no fallback),
+    /// and `404 Not Found` as `NotFound` response component (unless router has fallback).
+    ///
+    /// Router structure is known only for routes, added via `axum_integration::Router`. `405` response is added
+    /// only if it is not documented by operation itself. `false` by default.
+    pub fn framework_responses(mut self, framework_responses: bool) -> Self {
+        self.framework_responses = framework_responses;
+        self
+    }
+
+    /// Document `HEAD` operations for paths with `GET` operation (Axum handles `HEAD` requests
+    /// with `GET` handlers), unless `HEAD` is documented explicitly.
+    ///
+    /// `HEAD` operation is a copy of `GET` one without response bodies, its `operationId` (if
+    /// any) gets `_head` suffix and is deduplicated according to
+    /// [`duplicate_operation_id`](Self::duplicate_operation_id). `false` by default.
+    pub fn document_head_for_get(mut self, document_head_for_get: bool) -> Self {
+        self.document_head_for_get = document_head_for_get;
+        self
+    }
+
+    /// Split schemas with `readOnly`/`writeOnly` properties into `<Name>Request` (without
+    /// `readOnly` properties) and `<Name>Response` (without `writeOnly` properties) schemas.
+    ///
+    /// Schemas, referencing split schemas, are split too. Request bodies and parameters
+    /// reference `Request` schemas, responses reference `Response` ones. Schemas, which are no
+    /// longer referenced, are removed. `false` by default.
+    pub fn split_read_write_schemas(mut self, split_read_write_schemas: bool) -> Self {
+        self.split_read_write_schemas = split_read_write_schemas;
+        self
+    }
+
+    /// Set `discriminator` of every schema, which is `oneOf` of variants, tagged with the same
+    /// property (like schemas of internally or adjacently tagged enums).
+    ///
+    /// See [`Components::set_discriminator`]. `false` by default.
+    pub fn infer_discriminators(mut self, infer_discriminators: bool) -> Self {
+        self.infer_discriminators = infer_discriminators;
+        self
+    }
+
+    /// Replace repeated slashes in paths of operations with single one (`/users//{id}` becomes
+    /// `/users/{id}`).
+    ///
+    /// Like other path normalization options, applied to all paths of specification (after
+    /// [`OpenApiBuilder::path_prefix`](crate::OpenApiBuilder::path_prefix)) when it is built.
+    /// `false` by default.
+    pub fn collapse_slashes(mut self, collapse_slashes: bool) -> Self {
+        self.collapse_slashes = collapse_slashes;
+        self
+    }
+
+    /// Set how trailing slash in paths of operations is handled.
+    ///
+    /// [`TrailingSlash::Keep`] by default.
+    pub fn trailing_slash(mut self, trailing_slash: TrailingSlash) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    /// Convert paths of operations to lowercase (names of path parameters are kept as is).
+    ///
+    /// `false` by default.
+    pub fn lowercase_paths(mut self, lowercase_paths: bool) -> Self {
+        self.lowercase_paths = lowercase_paths;
+        self
+    }
+
+    /// Add responses of type `T` to every operation.
+    ///
+    /// Responses are added only for statuses (including `default`), which are not documented
+    /// by operation itself. Useful for documenting common error type, like
+    /// [`ProblemDetails`](crate::ProblemDetails).
+    pub fn default_error_responses<T: ToResponses>(mut self) -> Self {
+        self.default_error_responses = Some(T::generate);
+        self
+    }
+}
+
+/// Handling of inferred `operationId`, which is already used by another operation (i.e. when
+/// same handler is used for multiple routes).
+///
+/// Explicitly set `operationId` is never changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateOperationId {
+    /// Return an error.
+    #[default]
+    Error,
+    /// Append suffix, derived from method and path (`list_users` becomes
+    /// `list_users_get_v2_users`).
+    Suffix,
+}
+
+/// Handling of routes with wildcard segment (like `/files/*path`), which matches rest of path.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WildcardRoutes {
+    /// Document route with wildcard as path parameter (`/files/{path}`), marked with
+    /// `x-wildcard` extension.
+    #[default]
+    Parameterized,
+    /// Don't document route. Skipped routes are available with
+    /// [`OpenApiBuilder::skipped_routes`](crate::OpenApiBuilder::skipped_routes).
+    Skip,
+}
+
+/// Handling of trailing slash in paths of operations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// Keep paths as is.
+    #[default]
+    Keep,
+    /// Remove trailing slash (`/users/` becomes `/users`, root path `/` is unchanged).
+    Strip,
+}
+
+/// Handling of operations of methods, which can't be documented in OpenAPI 3.0 (anything except
+/// `GET`, `PUT`, `POST`, `DELETE`, `OPTIONS`, `HEAD`, `PATCH` and `TRACE`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CustomMethods {
+    /// Put operations into `x-additionalOperations` extension of path item, keyed by method
+    /// name (following `additionalOperations` field of OpenAPI 3.2).
+    #[default]
+    AdditionalOperations,
+    /// Return [`Error::UnsupportedMethod`](crate::Error::UnsupportedMethod).
+    Error,
+}
+
+/// Strategy of inferring `operationId`.
+#[derive(Clone, Default)]
+pub enum OperationIdStrategy {
+    /// Use name of handler function (only for handlers, annotated with
+    /// [`openapi`](crate::openapi) macro).
+    #[default]
+    FunctionName,
+    /// Use method and path (`GET /users/{id}` becomes `get_users_id`).
+    MethodPath,
+    /// Use custom function, which receives path, method and name of handler function (if
+    /// known).
+    Custom(CustomOperationId),
+}
+
+impl OperationIdStrategy {
+    /// Create [`OperationIdStrategy::Custom`] from function.
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&str, &Method, Option<&str>) -> Option<String> + Send + Sync + 'static,
+    {
+        Self::Custom(Arc::new(f))
+    }
+
+    pub(crate) fn operation_id(
+        &self,
+        path: &str,
+        method: &Method,
+        handler_name: Option<&str>,
+    ) -> Option<String> {
+        match self {
+            Self::FunctionName => handler_name.map(ToOwned::to_owned),
+            Self::MethodPath => Some(method_path_slug(path, method)),
+            Self::Custom(f) => f(path, method, handler_name),
+        }
+    }
+}
+
+impl fmt::Debug for OperationIdStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FunctionName => f.write_str("FunctionName"),
+            Self::MethodPath => f.write_str("MethodPath"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl BuilderOptions {
+    /// Whether any of path normalization options is enabled.
+    pub(crate) fn normalizes_paths(&self) -> bool {
+        self.collapse_slashes || self.trailing_slash == TrailingSlash::Strip || self.lowercase_paths
+    }
+
+    /// Apply path normalization options to path of operation.
+    pub(crate) fn normalize_path(&self, path: String) -> String {
+        let mut path = if self.collapse_slashes && path.contains("//") {
+            let mut collapsed = String::with_capacity(path.len());
+            for c in path.chars() {
+                if !(c == '/' && collapsed.ends_with('/')) {
+                    collapsed.push(c);
+                }
+            }
+            collapsed
+        } else {
+            path
+        };
+        if self.trailing_slash == TrailingSlash::Strip {
+            let len = path.trim_end_matches('/').len().max(1);
+            path.truncate(len);
+        }
+        if self.lowercase_paths {
+            let mut depth = 0usize;
+            path = path
+                .chars()
+                .map(|c| {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    if depth == 0 {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c
+                    }
+                })
+                .collect();
+        }
+        path
+    }
+}
+
+pub(crate) fn method_path_slug(path: &str, method: &Method) -> String {
+    let mut slug = method.as_str().to_lowercase();
+    for word in path
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|x| !x.is_empty())
+    {
+        slug.push('_');
+        slug.push_str(&word.to_lowercase());
+    }
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_path() {
+        let options = BuilderOptions::new()
+            .collapse_slashes(true)
+            .trailing_slash(TrailingSlash::Strip)
+            .lowercase_paths(true);
+        let normalize = |path: &str| options.normalize_path(path.into());
+        assert_eq!(normalize("/Users//{userId}/"), "/users/{userId}");
+        assert_eq!(normalize("//"), "/");
+        assert_eq!(normalize("/"), "/");
+        assert_eq!(
+            BuilderOptions::new().normalize_path("/Users//".into()),
+            "/Users//"
+        );
+    }
+
+    #[test]
+    fn method_path() {
+        assert_eq!(method_path_slug("/", &Method::GET), "get");
+        assert_eq!(
+            method_path_slug("/users/{id}/posts", &Method::POST),
+            "post_users_id_posts"
+        );
+        assert_eq!(
+            method_path_slug("/users/:user-id", &Method::DELETE),
+            "delete_users_user_id"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Canonical serialization of specification (for snapshot tests).
+
+use okapi::openapi3::OpenApi;
+use serde_json::Value;
+
+/// Serialize specification to pretty-printed JSON with recursively sorted keys.
+///
+/// Output doesn't depend on order of operations, schemas or map types, so it could be used
+/// in snapshot tests. Output always ends with newline.
+pub fn to_canonical_json(spec: &OpenApi) -> Result<String, serde_json::Error> {
+    let value = canonical_value(spec)?;
+    let mut output = serde_json::to_string_pretty(&value)?;
+    output.push('\n');
+    Ok(output)
+}
+
+/// Serialize specification to YAML with recursively sorted keys.
+///
+/// See [`to_canonical_json`] for details.
+#[cfg(feature = "yaml")]
+pub fn to_canonical_yaml(spec: &OpenApi) -> Result<String, serde_yaml::Error> {
+    let value = canonical_value(spec).map_err(serde::ser::Error::custom)?;
+    let mut output = serde_yaml::to_string(&value)?;
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+fn canonical_value(spec: &OpenApi) -> Result<Value, serde_json::Error> {
+    serde_json::to_value(spec).map(sort_keys)
+}
+
+/// Recursively sort keys of objects.
+pub(crate) fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+    use okapi::openapi3::Operation;
+
+    use super::*;
+    use crate::{Components, Error, OpenApiBuilder, SortMode};
+
+    fn spec(paths: &[&str], sort_mode: SortMode) -> OpenApi {
+        fn operation(_: &mut Components) -> Result<Operation, Error> {
+            Ok(Operation::default())
+        }
+
+        let mut builder = OpenApiBuilder::new("title", "1.0.0");
+        builder.sort_paths(sort_mode);
+        for path in paths {
+            builder.operation(*path, Method::GET, operation);
+        }
+        builder.build().expect("Schema generation shouldn't fail")
+    }
+
+    #[test]
+    fn stable_order() {
+        let a = spec(&["/b", "/a"], SortMode::InsertionOrder);
+        let b = spec(&["/a", "/b"], SortMode::InsertionOrder);
+        assert_eq!(a.paths.keys().next().map(String::as_str), Some("/b"));
+        assert_eq!(
+            to_canonical_json(&a).expect("Serializable"),
+            to_canonical_json(&b).expect("Serializable")
+        );
+
+        let json = to_canonical_json(&a).expect("Serializable");
+        assert!(json.ends_with("}\n"));
+        assert!(json.find("\"/a\"") < json.find("\"/b\""));
+        assert!(json.find("\"info\"") < json.find("\"openapi\""));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml() {
+        let yaml = to_canonical_yaml(&spec(&["/b", "/a"], SortMode::InsertionOrder))
+            .expect("Serializable");
+        assert!(yaml.find("/a:") < yaml.find("/b:"));
+        assert!(yaml.ends_with('\n'));
+    }
+}
@@ -0,0 +1,34 @@
+/// Metadata of Cargo package, captured at compile time of user's crate with
+/// [`cargo_package`](crate::cargo_package) macro.
+///
+/// Used by [`OpenApiBuilder::version_from_cargo`](crate::OpenApiBuilder::version_from_cargo)
+/// and [`OpenApiBuilder::title_from_cargo`](crate::OpenApiBuilder::title_from_cargo).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CargoPackage {
+    /// Package name (`CARGO_PKG_NAME`).
+    pub name: &'static str,
+    /// Package version (`CARGO_PKG_VERSION`).
+    pub version: &'static str,
+    /// Package description (`CARGO_PKG_DESCRIPTION`), empty if not set.
+    pub description: &'static str,
+}
+
+/// Capture [`CargoPackage`] metadata of crate, in which macro is called.
+///
+/// ```rust
+/// # use okapi_operation::*;
+/// let mut builder = OpenApiBuilder::default();
+/// builder
+///     .title_from_cargo(cargo_package!())
+///     .version_from_cargo(cargo_package!());
+/// ```
+#[macro_export]
+macro_rules! cargo_package {
+    () => {
+        $crate::CargoPackage {
+            name: ::core::env!("CARGO_PKG_NAME"),
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            description: ::core::env!("CARGO_PKG_DESCRIPTION"),
+        }
+    };
+}
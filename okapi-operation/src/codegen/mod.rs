@@ -0,0 +1,442 @@
+//! Generation of Rust code from existing OpenAPI specification (spec-first approach).
+//!
+//! [`Codegen`] generates types for schemas from specification and trait with method stub
+//! for every operation, annotated with [`openapi`](crate::openapi) macro. Generated code
+//! should be saved to file (usually from build script or test) and included in crate.
+
+use std::fmt::Write;
+
+use anyhow::{bail, Context};
+use okapi::{
+    openapi3::{
+        Header, MediaType, OpenApi, Operation, Parameter, ParameterValue, RefOr, RequestBody,
+        Response,
+    },
+    schemars::schema::SchemaObject,
+    Map,
+};
+
+use self::{
+    names::{snake_case, upper_camel_case, Names},
+    types::{doc_comment, Types},
+};
+use crate::walk::all_path_item_operations;
+
+mod names;
+mod types;
+
+const DEFAULT_CRATE_NAME: &str = "okapi_operation";
+const DEFAULT_JSON_WRAPPER: &str = "axum::Json";
+
+/// Header of generated file.
+const HEADER: &str = "// Generated by okapi-operation codegen, do not edit.\n";
+
+/// Generator of types and handler trait from OpenAPI specification.
+///
+/// Generated code requires `serde` (with `derive` feature) and `serde_json` dependencies.
+///
+/// ```
+/// # use okapi_operation::{codegen::Codegen, OpenApi};
+/// let spec: OpenApi = serde_json::from_str(r#"{
+///     "openapi": "3.0.0",
+///     "info": { "title": "Demo", "version": "1.0.0" },
+///     "paths": {
+///         "/ping": {
+///             "get": {
+///                 "operationId": "ping",
+///                 "responses": { "200": { "description": "Pong" } }
+///             }
+///         }
+///     }
+/// }"#).unwrap();
+/// let code = Codegen::new("DemoApi").generate(&spec).unwrap();
+/// assert!(code.contains("pub trait DemoApi {"));
+/// assert!(code.contains("async fn ping();"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Codegen {
+    trait_name: String,
+    crate_name: String,
+    json_wrapper: String,
+}
+
+impl Codegen {
+    /// Create generator of trait with specified name.
+    pub fn new(trait_name: impl Into<String>) -> Self {
+        Self {
+            trait_name: trait_name.into(),
+            crate_name: DEFAULT_CRATE_NAME.into(),
+            json_wrapper: DEFAULT_JSON_WRAPPER.into(),
+        }
+    }
+
+    /// Set path to `okapi_operation` crate in generated code (if crate is renamed).
+    pub fn crate_name(mut self, crate_name: impl Into<String>) -> Self {
+        self.crate_name = crate_name.into();
+        self
+    }
+
+    /// Set path to wrapper type for JSON bodies (`axum::Json` by default).
+    ///
+    /// Wrapper should implement [`ToMediaTypes`](crate::ToMediaTypes) with
+    /// `application/json` media type.
+    pub fn json_wrapper(mut self, json_wrapper: impl Into<String>) -> Self {
+        self.json_wrapper = json_wrapper.into();
+        self
+    }
+
+    /// Generate code from specification.
+    pub fn generate(&self, spec: &OpenApi) -> Result<String, anyhow::Error> {
+        let components = spec.components.clone().unwrap_or_default();
+        let mut generator = Generator {
+            codegen: self,
+            components: &components,
+            types: Types::new(&self.crate_name, &components.schemas)?,
+            type_names: Names::for_types(),
+            method_names: Names::default(),
+        };
+
+        let mut operations = Vec::new();
+        for (path, item) in &spec.paths {
+            for (method, operation) in all_path_item_operations(item) {
+                operations.push((path, method, operation, &item.parameters));
+            }
+        }
+        operations.sort_by(|a, b| (a.0, a.1.as_str()).cmp(&(b.0, b.1.as_str())));
+
+        let mut methods = Vec::with_capacity(operations.len());
+        for (path, method, operation, path_parameters) in operations {
+            methods.push(
+                generator
+                    .method(path, method.as_str(), &operation, path_parameters)
+                    .with_context(|| format!("Failed to generate {method} {path}"))?,
+            );
+        }
+
+        let mut code = HEADER.to_owned();
+        for item in generator.types.into_items() {
+            let _ = write!(code, "\n{item}");
+        }
+        let _ = write!(
+            code,
+            "\n#[allow(async_fn_in_trait)]\npub trait {} {{\n{}}}\n",
+            self.trait_name,
+            methods.join("\n")
+        );
+        Ok(code)
+    }
+}
+
+/// State of single code generation.
+struct Generator<'a> {
+    codegen: &'a Codegen,
+    components: &'a okapi::openapi3::Components,
+    types: Types<'a>,
+    /// Names of types, generated for operations.
+    type_names: Names,
+    method_names: Names,
+}
+
+impl Generator<'_> {
+    fn method(
+        &mut self,
+        path: &str,
+        method: &str,
+        operation: &Operation,
+        path_parameters: &[RefOr<Parameter>],
+    ) -> Result<String, anyhow::Error> {
+        let fn_name = self.method_names.unique(snake_case(
+            operation
+                .operation_id
+                .as_deref()
+                .unwrap_or(&format!("{method}_{path}")),
+        ));
+        let type_prefix = upper_camel_case(&fn_name);
+
+        let crate_name = &self.codegen.crate_name;
+        let mut attrs = Vec::new();
+        if crate_name != DEFAULT_CRATE_NAME {
+            attrs.push(format!("crate = {crate_name:?}"));
+        }
+        if let Some(operation_id) = &operation.operation_id {
+            attrs.push(format!("operation_id = {operation_id:?}"));
+        }
+        if let Some(summary) = &operation.summary {
+            attrs.push(format!("summary = {summary:?}"));
+        }
+        if let Some(description) = &operation.description {
+            attrs.push(format!("description = {description:?}"));
+        }
+        if !operation.tags.is_empty() {
+            attrs.push(format!("tags = {:?}", operation.tags.join(",")));
+        }
+        if operation.deprecated {
+            attrs.push("deprecated = true".into());
+        }
+        attrs.push(format!("path = {path:?}"));
+        attrs.push(format!("method = {method:?}"));
+
+        // Operation parameters override path-level parameters with same name and location
+        let mut parameters: Map<(String, String), Parameter> = Map::new();
+        for parameter in path_parameters.iter().chain(&operation.parameters) {
+            let parameter = resolve(self.components, parameter, "parameters", |x| &x.parameters)?;
+            let _ = parameters.insert(
+                (parameter.name.clone(), parameter.location.clone()),
+                parameter.clone(),
+            );
+        }
+        if !parameters.is_empty() {
+            let mut values = Vec::new();
+            for parameter in parameters.values() {
+                values.push(self.parameter(parameter, &type_prefix)?);
+            }
+            attrs.push(list("parameters", values, 2));
+        }
+
+        let mut responses = vec!["ignore_return_type = true".to_owned()];
+        let mut return_type = None;
+        for (status, response) in &operation.responses.responses {
+            let response = resolve(self.components, response, "responses", |x| &x.responses)?;
+            let hint = if status.starts_with('2') && return_type.is_none() {
+                format!("{type_prefix}Response")
+            } else {
+                format!("{type_prefix}{}Response", upper_camel_case(status))
+            };
+            let (attr, ty) = self.response(response, Some(status), &hint)?;
+            if status.starts_with('2') && return_type.is_none() && !ty.ends_with("::Empty") {
+                return_type = Some(ty);
+            }
+            responses.push(attr);
+        }
+        if let Some(response) = &operation.responses.default {
+            let response = resolve(self.components, response, "responses", |x| &x.responses)?;
+            let hint = format!("{type_prefix}DefaultResponse");
+            responses.push(self.response(response, None, &hint)?.0);
+        }
+        attrs.push(list("responses", responses, 2));
+
+        if let Some(security) = &operation.security {
+            let mut schemes = Vec::new();
+            for requirement in security {
+                if requirement.len() > 1 {
+                    bail!("Security requirement with multiple schemes is not supported");
+                }
+                for (name, scopes) in requirement {
+                    let mut values = vec![format!("name = {name:?}")];
+                    if !scopes.is_empty() {
+                        values.push(format!("scopes = {:?}", scopes.join(",")));
+                    }
+                    schemes.push(format!("security_scheme({})", values.join(", ")));
+                }
+            }
+            if !schemes.is_empty() {
+                attrs.push(list("security", schemes, 2));
+            }
+        }
+
+        let mut args = String::new();
+        if let Some(body) = &operation.request_body {
+            let body = resolve(self.components, body, "requestBodies", |x| {
+                &x.request_bodies
+            })?;
+            args = self.request_body(body, &format!("{type_prefix}Request"))?;
+        }
+
+        let mut method_code = doc_comment(operation.summary.as_deref(), "    ");
+        let _ = writeln!(
+            method_code,
+            "    {}",
+            list(&format!("#[{crate_name}::openapi"), attrs, 1) + "]"
+        );
+        let _ = write!(method_code, "    async fn {fn_name}({args})");
+        if let Some(return_type) = return_type {
+            let _ = write!(method_code, " -> {return_type}");
+        }
+        method_code.push_str(";\n");
+        Ok(method_code)
+    }
+
+    fn parameter(
+        &mut self,
+        parameter: &Parameter,
+        type_prefix: &str,
+    ) -> Result<String, anyhow::Error> {
+        let location = match parameter.location.as_str() {
+            x @ ("path" | "query" | "header" | "cookie") => x,
+            x => bail!("Unknown location '{x}' of parameter '{}'", parameter.name),
+        };
+        let schema = match &parameter.value {
+            ParameterValue::Schema { schema, .. } => Some(schema),
+            ParameterValue::Content { content } => {
+                content.values().next().and_then(|x| x.schema.as_ref())
+            }
+        };
+        let hint = self.type_names.unique(format!(
+            "{type_prefix}{}",
+            upper_camel_case(&parameter.name)
+        ));
+        let schema = self.schema_type(schema, &hint)?;
+
+        let mut values = vec![format!("name = {:?}", parameter.name)];
+        if let Some(description) = &parameter.description {
+            values.push(format!("description = {description:?}"));
+        }
+        if parameter.required && location != "path" {
+            values.push("required = true".into());
+        }
+        if parameter.deprecated {
+            values.push("deprecated = true".into());
+        }
+        values.push(format!("schema = {schema:?}"));
+        Ok(format!("{location}({})", values.join(", ")))
+    }
+
+    /// Returns attribute and Rust type of response.
+    fn response(
+        &mut self,
+        response: &Response,
+        status: Option<&str>,
+        hint: &str,
+    ) -> Result<(String, String), anyhow::Error> {
+        let mut values = Vec::new();
+        if let Some(status) = status {
+            values.push(format!("status = {status:?}"));
+        }
+        values.push(format!("description = {:?}", response.description));
+        let (ty, content_type) = self.content(&response.content, hint)?;
+        values.push(format!("content = {ty:?}"));
+        if let Some(content_type) = content_type {
+            values.push(format!("content_type = {content_type:?}"));
+        }
+
+        if !response.headers.is_empty() {
+            let mut headers = Vec::new();
+            for (name, header) in &response.headers {
+                let header = resolve(self.components, header, "headers", |x| &x.headers)?;
+                headers.push(self.header(name, header, hint)?);
+            }
+            values.push(list("headers", headers, 4));
+        }
+
+        let name = if status.is_some() {
+            "response"
+        } else {
+            "default_response"
+        };
+        Ok((list(name, values, 3), ty))
+    }
+
+    fn header(&mut self, name: &str, header: &Header, hint: &str) -> Result<String, anyhow::Error> {
+        let schema = match &header.value {
+            ParameterValue::Schema { schema, .. } => Some(schema),
+            ParameterValue::Content { content } => {
+                content.values().next().and_then(|x| x.schema.as_ref())
+            }
+        };
+        let hint = self
+            .type_names
+            .unique(format!("{hint}{}", upper_camel_case(name)));
+        let schema = self.schema_type(schema, &hint)?;
+
+        let mut values = vec![format!("name = {name:?}")];
+        if let Some(description) = &header.description {
+            values.push(format!("description = {description:?}"));
+        }
+        if header.required {
+            values.push("required = true".into());
+        }
+        if header.deprecated {
+            values.push("deprecated = true".into());
+        }
+        values.push(format!("schema = {schema:?}"));
+        Ok(format!("header({})", values.join(", ")))
+    }
+
+    /// Returns function arguments with request body.
+    fn request_body(&mut self, body: &RequestBody, hint: &str) -> Result<String, anyhow::Error> {
+        let (ty, content_type) = self.content(&body.content, hint)?;
+        let mut values = Vec::new();
+        if let Some(description) = &body.description {
+            values.push(format!("description = {description:?}"));
+        }
+        if body.required {
+            values.push("required = true".into());
+        }
+        if let Some(content_type) = content_type {
+            values.push(format!("content_type = {content_type:?}"));
+        }
+        Ok(format!("#[body({})] body: {ty}", values.join(", ")))
+    }
+
+    /// Rust type of content and its MIME type (if it should be overridden).
+    ///
+    /// Only first media type is used.
+    fn content(
+        &mut self,
+        content: &Map<String, MediaType>,
+        hint: &str,
+    ) -> Result<(String, Option<String>), anyhow::Error> {
+        let Some((mime, media_type)) = content.first() else {
+            return Ok((format!("{}::Empty", self.codegen.crate_name), None));
+        };
+        let essence = mime.split(';').next().unwrap_or_default().trim();
+        if essence == "application/json" || essence.ends_with("+json") {
+            let hint = self.type_names.unique(hint.into());
+            let ty = self.schema_type(media_type.schema.as_ref(), &hint)?;
+            let content_type = (mime != "application/json").then(|| mime.clone());
+            Ok((format!("{}<{ty}>", self.codegen.json_wrapper), content_type))
+        } else if essence.starts_with("text/") {
+            let content_type = (mime != "text/plain").then(|| mime.clone());
+            Ok(("String".into(), content_type))
+        } else {
+            Ok(("Vec<u8>".into(), Some(mime.clone())))
+        }
+    }
+
+    fn schema_type(
+        &mut self,
+        schema: Option<&SchemaObject>,
+        hint: &str,
+    ) -> Result<String, anyhow::Error> {
+        match schema {
+            Some(schema) => self.types.type_for(schema, hint),
+            None => Ok("serde_json::Value".into()),
+        }
+    }
+}
+
+/// Resolve reference to one of components.
+fn resolve<'a, T>(
+    components: &'a okapi::openapi3::Components,
+    value: &'a RefOr<T>,
+    kind: &str,
+    section: impl Fn(&'a okapi::openapi3::Components) -> &'a Map<String, RefOr<T>>,
+) -> Result<&'a T, anyhow::Error> {
+    let mut value = value;
+    // Limit depth to avoid infinite loop on cyclic references
+    for _ in 0..16 {
+        match value {
+            RefOr::Object(x) => return Ok(x),
+            RefOr::Ref(x) => {
+                value = x
+                    .reference
+                    .strip_prefix(&format!("#/components/{kind}/"))
+                    .and_then(|name| section(components).get(name))
+                    .ok_or_else(|| anyhow::anyhow!("Unresolvable reference '{}'", x.reference))?;
+            }
+        }
+    }
+    bail!("Too deep chain of references in {kind}")
+}
+
+/// Format list attribute, placing each value on separate line.
+fn list(name: &str, values: Vec<String>, depth: usize) -> String {
+    let indent = "    ".repeat(depth + 1);
+    let mut result = format!("{name}(\n");
+    for value in values {
+        let _ = writeln!(result, "{indent}{value},");
+    }
+    result.push_str(&"    ".repeat(depth));
+    result.push(')');
+    result
+}
@@ -0,0 +1,135 @@
+//! Conversion of names from specification into Rust identifiers.
+
+use std::collections::HashSet;
+
+/// Rust keywords, which can be used as raw identifiers.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "union", "unsafe", "unsized",
+    "use", "virtual", "where", "while", "yield",
+];
+
+/// Identifiers, which can't be used even as raw identifiers.
+const RESERVED: &[&str] = &["self", "Self", "super", "crate", "_"];
+
+/// Type names, used by generated code itself.
+const USED_TYPES: &[&str] = &["Option", "String", "Vec", "HashMap", "Value", "Empty"];
+
+/// Split name into lowercase words (on non-alphanumeric characters and case boundaries).
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev: Option<char> = None;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev = None;
+            continue;
+        }
+        let boundary = prev.is_some_and(|p| {
+            (p.is_ascii_lowercase() || p.is_ascii_digit()) && c.is_ascii_uppercase()
+        });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c.to_ascii_lowercase());
+        prev = Some(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Convert name to `snake_case` identifier (field or function name).
+pub(super) fn snake_case(name: &str) -> String {
+    let ident = words(name).join("_");
+    let ident = match ident.chars().next() {
+        None => "value".into(),
+        Some(c) if c.is_ascii_digit() => format!("_{ident}"),
+        Some(_) => ident,
+    };
+    if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else if RESERVED.contains(&ident.as_str()) {
+        format!("{ident}_")
+    } else {
+        ident
+    }
+}
+
+/// Convert name to `UpperCamelCase` identifier (type or variant name).
+pub(super) fn upper_camel_case(name: &str) -> String {
+    let ident: String = words(name)
+        .into_iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    match ident.chars().next() {
+        None => "Value".into(),
+        Some(c) if c.is_ascii_digit() => format!("V{ident}"),
+        Some(_) if RESERVED.contains(&ident.as_str()) => format!("{ident}Type"),
+        Some(_) => ident,
+    }
+}
+
+/// Set of used names, which generates unique names by adding numeric suffix.
+#[derive(Debug, Default)]
+pub(super) struct Names(HashSet<String>);
+
+impl Names {
+    /// Reserve names of types, used by generated code.
+    pub(super) fn for_types() -> Self {
+        Self(USED_TYPES.iter().map(|x| x.to_string()).collect())
+    }
+
+    /// Get unique name, based on provided one.
+    pub(super) fn unique(&mut self, name: String) -> String {
+        let mut candidate = name.clone();
+        let mut n = 2;
+        while !self.0.insert(candidate.clone()) {
+            candidate = format!("{name}{n}");
+            n += 1;
+        }
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_names() {
+        for (name, snake, camel) in [
+            ("userId", "user_id", "UserId"),
+            ("user-name", "user_name", "UserName"),
+            ("X-Request-ID", "x_request_id", "XRequestId"),
+            ("HTTPStatus", "httpstatus", "Httpstatus"),
+            ("type", "r#type", "Type"),
+            ("self", "self_", "SelfType"),
+            ("2fa", "_2fa", "V2fa"),
+            ("", "value", "Value"),
+        ] {
+            assert_eq!(snake_case(name), snake, "{name}");
+            assert_eq!(upper_camel_case(name), camel, "{name}");
+        }
+    }
+
+    #[test]
+    fn unique_names() {
+        let mut names = Names::for_types();
+        assert_eq!(names.unique("User".into()), "User");
+        assert_eq!(names.unique("User".into()), "User2");
+        assert_eq!(names.unique("String".into()), "String2");
+    }
+}
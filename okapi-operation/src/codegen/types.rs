@@ -0,0 +1,440 @@
+//! Generation of Rust types from JSON schemas.
+
+use std::fmt::Write;
+
+use okapi::{
+    schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec},
+    Map,
+};
+
+use super::names::{snake_case, upper_camel_case, Names};
+
+const SCHEMAS_PREFIX: &str = "#/components/schemas/";
+
+/// Type for values, which can't be described by Rust type.
+const ANY_TYPE: &str = "serde_json::Value";
+
+/// Generator of Rust types from schemas of specification.
+pub(super) struct Types<'a> {
+    crate_name: &'a str,
+    names: Names,
+    /// Schema name -> Rust type name.
+    schema_names: Map<String, String>,
+    items: Vec<String>,
+}
+
+impl<'a> Types<'a> {
+    /// Create generator, generating types for every schema from components.
+    pub(super) fn new(
+        crate_name: &'a str,
+        schemas: &Map<String, SchemaObject>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut this = Self {
+            crate_name,
+            names: Names::for_types(),
+            schema_names: Map::new(),
+            items: Vec::new(),
+        };
+        // Names are reserved first, so references could be resolved in any order
+        for name in schemas.keys() {
+            let type_name = this.names.unique(upper_camel_case(name));
+            let _ = this.schema_names.insert(name.clone(), type_name);
+        }
+        for (name, schema) in schemas {
+            let type_name = this.schema_names[name].clone();
+            if !this.named_item(&type_name, schema)? {
+                let ty = this.type_for(schema, &type_name)?;
+                this.items.push(format!(
+                    "{}pub type {type_name} = {ty};\n",
+                    doc_comment(description(schema), "")
+                ));
+            }
+        }
+        Ok(this)
+    }
+
+    /// Generated items (structs, enums and type aliases).
+    pub(super) fn into_items(self) -> Vec<String> {
+        self.items
+    }
+
+    /// Get Rust type for schema, generating new items for inline objects and enums.
+    ///
+    /// `name_hint` is used as name of generated item.
+    pub(super) fn type_for(
+        &mut self,
+        schema: &SchemaObject,
+        name_hint: &str,
+    ) -> Result<String, anyhow::Error> {
+        if let Some(reference) = &schema.reference {
+            let name = reference
+                .strip_prefix(SCHEMAS_PREFIX)
+                .and_then(|name| self.schema_names.get(name))
+                .ok_or_else(|| anyhow::anyhow!("Unresolvable schema reference '{reference}'"))?;
+            return Ok(wrap_nullable(schema, name.clone()));
+        }
+
+        if let Some(subschemas) = &schema.subschemas {
+            let variants = [&subschemas.all_of, &subschemas.one_of, &subschemas.any_of];
+            let ty = match variants.map(|x| x.as_deref().map(non_null_schemas)) {
+                [Some((inner, _)), None, None] if inner.len() == 1 => {
+                    self.type_for(inner[0], name_hint)?
+                }
+                [None, Some((inner, nullable)), None] | [None, None, Some((inner, nullable))]
+                    if inner.len() == 1 =>
+                {
+                    let ty = self.type_for(inner[0], name_hint)?;
+                    if nullable {
+                        format!("Option<{ty}>")
+                    } else {
+                        ty
+                    }
+                }
+                _ => ANY_TYPE.into(),
+            };
+            return Ok(wrap_nullable(schema, ty));
+        }
+
+        let ty = match instance_type(schema) {
+            _ if is_string_enum(schema) => {
+                let name = self.names.unique(name_hint.into());
+                let _ = self.named_item(&name, schema)?;
+                name
+            }
+            Some(InstanceType::String) => "String".into(),
+            Some(InstanceType::Integer) => match schema.format.as_deref() {
+                Some("int8") => "i8",
+                Some("int16") => "i16",
+                Some("int32") => "i32",
+                Some("uint8") => "u8",
+                Some("uint16") => "u16",
+                Some("uint32") => "u32",
+                Some("uint64") => "u64",
+                _ => "i64",
+            }
+            .into(),
+            Some(InstanceType::Number) => match schema.format.as_deref() {
+                Some("float") => "f32",
+                _ => "f64",
+            }
+            .into(),
+            Some(InstanceType::Boolean) => "bool".into(),
+            Some(InstanceType::Array) => {
+                let items = schema.array.as_ref().and_then(|x| match &x.items {
+                    Some(SingleOrVec::Single(items)) => Some(items.as_ref()),
+                    _ => None,
+                });
+                match items {
+                    Some(Schema::Object(items)) => {
+                        format!(
+                            "Vec<{}>",
+                            self.type_for(items, &format!("{name_hint}Item"))?
+                        )
+                    }
+                    _ => format!("Vec<{ANY_TYPE}>"),
+                }
+            }
+            Some(InstanceType::Object) | None if has_properties(schema) => {
+                let name = self.names.unique(name_hint.into());
+                let _ = self.named_item(&name, schema)?;
+                name
+            }
+            Some(InstanceType::Object) => {
+                let values = schema
+                    .object
+                    .as_ref()
+                    .and_then(|x| x.additional_properties.as_deref());
+                match values {
+                    Some(Schema::Object(values)) => format!(
+                        "std::collections::HashMap<String, {}>",
+                        self.type_for(values, &format!("{name_hint}Value"))?
+                    ),
+                    _ => format!("std::collections::HashMap<String, {ANY_TYPE}>"),
+                }
+            }
+            _ => ANY_TYPE.into(),
+        };
+        Ok(wrap_nullable(schema, ty))
+    }
+
+    /// Generate struct (for object with properties) or enum (for string enumeration).
+    ///
+    /// Returns `false`, if schema can't be represented by named item.
+    fn named_item(&mut self, name: &str, schema: &SchemaObject) -> Result<bool, anyhow::Error> {
+        if is_string_enum(schema) {
+            self.enum_item(name, schema);
+            Ok(true)
+        } else if schema.reference.is_none()
+            && schema.subschemas.is_none()
+            && has_properties(schema)
+        {
+            self.struct_item(name, schema)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn derives(&self, extra: &str) -> String {
+        let crate_name = self.crate_name;
+        format!(
+            "#[derive(Debug, Clone, {extra}PartialEq, serde::Serialize, serde::Deserialize, \
+             {crate_name}::JsonSchema)]\n#[schemars(crate = \"{crate_name}::schemars\")]\n"
+        )
+    }
+
+    fn struct_item(&mut self, name: &str, schema: &SchemaObject) -> Result<(), anyhow::Error> {
+        let object = schema.object.as_deref().expect("Checked by has_properties");
+        let mut fields = String::new();
+        let mut field_names = Names::default();
+        for (property, property_schema) in &object.properties {
+            let Schema::Object(property_schema) = property_schema else {
+                continue;
+            };
+            let field = field_names.unique(snake_case(property));
+            let ty = self.type_for(
+                property_schema,
+                &format!("{name}{}", upper_camel_case(property)),
+            )?;
+            let mut serde_attrs = Vec::new();
+            if field.trim_start_matches("r#") != property {
+                serde_attrs.push(format!("rename = {property:?}"));
+            }
+            let ty = if object.required.contains(property) || ty.starts_with("Option<") {
+                ty
+            } else {
+                serde_attrs.push("default, skip_serializing_if = \"Option::is_none\"".into());
+                format!("Option<{ty}>")
+            };
+            fields.push_str(&doc_comment(description(property_schema), "    "));
+            if !serde_attrs.is_empty() {
+                let _ = writeln!(fields, "    #[serde({})]", serde_attrs.join(", "));
+            }
+            let _ = writeln!(fields, "    pub {field}: {ty},");
+        }
+        self.items.push(format!(
+            "{}{}pub struct {name} {{\n{fields}}}\n",
+            doc_comment(description(schema), ""),
+            self.derives("")
+        ));
+        Ok(())
+    }
+
+    fn enum_item(&mut self, name: &str, schema: &SchemaObject) {
+        let mut variants = String::new();
+        let mut variant_names = Names::default();
+        for value in schema.enum_values.iter().flatten() {
+            let Some(value) = value.as_str() else {
+                continue;
+            };
+            let variant = variant_names.unique(upper_camel_case(value));
+            if variant != value {
+                let _ = writeln!(variants, "    #[serde(rename = {value:?})]");
+            }
+            let _ = writeln!(variants, "    {variant},");
+        }
+        self.items.push(format!(
+            "{}{}pub enum {name} {{\n{variants}}}\n",
+            doc_comment(description(schema), ""),
+            self.derives("Copy, Eq, Hash, ")
+        ));
+    }
+}
+
+/// Format description as doc comment.
+pub(super) fn doc_comment(description: Option<&str>, indent: &str) -> String {
+    description
+        .map(|x| {
+            x.trim()
+                .lines()
+                .map(|line| format!("{indent}/// {line}").trim_end().to_owned() + "\n")
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn description(schema: &SchemaObject) -> Option<&str> {
+    schema
+        .metadata
+        .as_ref()
+        .and_then(|x| x.description.as_deref())
+}
+
+/// Single non-null type of schema.
+fn instance_type(schema: &SchemaObject) -> Option<InstanceType> {
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(ty)) => Some(**ty),
+        Some(SingleOrVec::Vec(types)) => {
+            let mut types = types.iter().filter(|x| **x != InstanceType::Null);
+            match (types.next(), types.next()) {
+                (Some(ty), None) => Some(*ty),
+                _ => None,
+            }
+        }
+        None => None,
+    }
+}
+
+/// Whether schema allows `null` (with `nullable` of OpenAPI 3.0 or `null` type).
+fn is_nullable(schema: &SchemaObject) -> bool {
+    schema.extensions.get("nullable") == Some(&serde_json::Value::Bool(true))
+        || matches!(
+            &schema.instance_type,
+            Some(SingleOrVec::Vec(types)) if types.contains(&InstanceType::Null)
+        )
+}
+
+fn wrap_nullable(schema: &SchemaObject, ty: String) -> String {
+    if is_nullable(schema) && !ty.starts_with("Option<") {
+        format!("Option<{ty}>")
+    } else {
+        ty
+    }
+}
+
+fn is_string_enum(schema: &SchemaObject) -> bool {
+    matches!(instance_type(schema), None | Some(InstanceType::String))
+        && schema
+            .enum_values
+            .as_ref()
+            .is_some_and(|values| !values.is_empty() && values.iter().all(|x| x.is_string()))
+}
+
+fn has_properties(schema: &SchemaObject) -> bool {
+    schema
+        .object
+        .as_ref()
+        .is_some_and(|x| !x.properties.is_empty())
+}
+
+/// Object schemas from subschemas, except `null` ones, and whether `null` was present.
+fn non_null_schemas(schemas: &[Schema]) -> (Vec<&SchemaObject>, bool) {
+    let mut nullable = false;
+    let mut result = Vec::new();
+    for schema in schemas {
+        match schema {
+            Schema::Object(x)
+                if x.instance_type == Some(SingleOrVec::Single(Box::new(InstanceType::Null))) =>
+            {
+                nullable = true
+            }
+            Schema::Object(x) => result.push(x),
+            Schema::Bool(_) => {}
+        }
+    }
+    (result, nullable)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema(value: serde_json::Value) -> SchemaObject {
+        serde_json::from_value(value).expect("Valid schema")
+    }
+
+    #[test]
+    fn primitive_types() {
+        let mut types = Types::new("okapi_operation", &Map::new()).expect("No schemas");
+        for (value, ty) in [
+            (json!({"type": "string"}), "String"),
+            (
+                json!({"type": "string", "nullable": true}),
+                "Option<String>",
+            ),
+            (
+                json!({"type": ["integer", "null"], "format": "int32"}),
+                "Option<i32>",
+            ),
+            (json!({"type": "number"}), "f64"),
+            (json!({"type": "boolean"}), "bool"),
+            (
+                json!({"type": "array", "items": {"type": "string"}}),
+                "Vec<String>",
+            ),
+            (
+                json!({"type": "object", "additionalProperties": {"type": "integer"}}),
+                "std::collections::HashMap<String, i64>",
+            ),
+            (json!({"allOf": [{"type": "boolean"}]}), "bool"),
+            (
+                json!({"anyOf": [{"type": "boolean"}, {"type": "null"}]}),
+                "Option<bool>",
+            ),
+            (
+                json!({"oneOf": [{"type": "boolean"}, {"type": "string"}]}),
+                ANY_TYPE,
+            ),
+            (json!({}), ANY_TYPE),
+        ] {
+            assert_eq!(
+                types
+                    .type_for(&schema(value.clone()), "T")
+                    .expect("Valid schema"),
+                ty,
+                "{value}"
+            );
+        }
+        assert!(types.into_items().is_empty());
+    }
+
+    #[test]
+    fn named_types() {
+        let schemas = [
+            (
+                "user".to_string(),
+                schema(json!({
+                    "type": "object",
+                    "description": "User.",
+                    "required": ["id", "status"],
+                    "properties": {
+                        "id": {"type": "integer", "format": "int64"},
+                        "displayName": {"type": "string"},
+                        "status": {"$ref": "#/components/schemas/Status"},
+                        "address": {
+                            "type": "object",
+                            "properties": {"city": {"type": "string"}}
+                        }
+                    }
+                })),
+            ),
+            (
+                "Status".to_string(),
+                schema(json!({"type": "string", "enum": ["active", "Blocked"]})),
+            ),
+            (
+                "Ids".to_string(),
+                schema(json!({"type": "array", "items": {"type": "integer"}})),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let items = Types::new("okapi_operation", &schemas)
+            .expect("Valid schemas")
+            .into_items();
+        let derives = "#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, \
+                       okapi_operation::JsonSchema)]\n\
+                       #[schemars(crate = \"okapi_operation::schemars\")]\n";
+        assert_eq!(
+            items,
+            vec![
+                format!("{derives}pub struct UserAddress {{\n    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    pub city: Option<String>,\n}}\n"),
+                format!(
+                    "/// User.\n{derives}pub struct User {{\n    \
+                     #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    \
+                     pub address: Option<UserAddress>,\n    \
+                     #[serde(rename = \"displayName\", default, skip_serializing_if = \"Option::is_none\")]\n    \
+                     pub display_name: Option<String>,\n    pub id: i64,\n    pub status: Status,\n}}\n"
+                ),
+                "#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize, \
+                 okapi_operation::JsonSchema)]\n\
+                 #[schemars(crate = \"okapi_operation::schemars\")]\n\
+                 pub enum Status {\n    #[serde(rename = \"active\")]\n    Active,\n    Blocked,\n}\n"
+                    .to_string(),
+                "pub type Ids = Vec<i64>;\n".to_string(),
+            ]
+        );
+    }
+}
@@ -1,15 +1,157 @@
+use std::{borrow::Cow, collections::HashMap, fmt::Debug, sync::Arc};
+
+use http::Method;
+use indexmap::IndexMap;
 use okapi::{
-    openapi3::{RefOr, SchemaObject, SecurityScheme},
+    openapi3::{
+        Header, OpenApi, Parameter, RefOr, RequestBody, Response, SchemaObject, SecurityScheme,
+    },
     schemars::{
-        gen::{SchemaGenerator, SchemaSettings},
+        gen::{GenVisitor, SchemaGenerator, SchemaSettings},
+        visit::Visitor,
         JsonSchema,
     },
 };
 
+#[cfg(feature = "macro")]
+use crate::walk::SCHEMA_REFERENCE_PREFIX;
+use crate::{
+    discriminator::set_discriminator,
+    merge::{merge_components, MergeStrategy},
+    nullable::{NullableStrategy, NullableVisitor},
+    recursion::{inline_recursive_schemas, recursive_schemas, RecursionPolicy},
+    walk::{for_each_reference, Referenceable},
+    Error,
+};
+
+type RenameSchemas = Arc<dyn Fn(&str, &SchemaObject) -> String + Send + Sync>;
+
+/// Parse schema, provided as JSON.
+///
+/// Used for `schema_json` attribute of [`openapi`](crate::openapi) macro. References to
+/// schema components (`#/components/schemas/<name>`) are rewritten according to
+/// [definitions path](ComponentsBuilder::definitions_path).
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub fn schema_from_json(components: &Components, json: &str) -> Result<SchemaObject, Error> {
+    struct RewriteReferences<'a>(&'a Components);
+
+    impl Visitor for RewriteReferences<'_> {
+        fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+            if let Some(reference) = schema.reference.as_mut() {
+                *reference = component_reference(self.0, reference);
+            }
+            okapi::schemars::visit::visit_schema_object(self, schema)
+        }
+    }
+
+    let mut schema = serde_json::from_str(json)
+        .map_err(|err| Error::InvalidDefinition(anyhow::anyhow!("Invalid JSON schema: {err}")))?;
+    RewriteReferences(components).visit_schema_object(&mut schema);
+    Ok(schema)
+}
+
+/// Reference to component, specified in [`openapi`](crate::openapi) macro.
+///
+/// References to schema components (`#/components/schemas/<name>`) are rewritten according
+/// to [definitions path](ComponentsBuilder::definitions_path), other references are left
+/// as is.
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub fn component_reference(components: &Components, reference: &str) -> String {
+    match reference.strip_prefix(SCHEMA_REFERENCE_PREFIX) {
+        Some(name) => format!("{}{name}", components.definitions_path()),
+        None => reference.to_owned(),
+    }
+}
+
+/// Constraints, specified on parameter in [`openapi`](crate::openapi) macro.
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct SchemaConstraints {
+    pub default: Option<&'static str>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    pub pattern: Option<&'static str>,
+}
+
+/// Merge constraints into schema.
+///
+/// Default value is used as is for string schemas and parsed as JSON otherwise (falling back
+/// to string, if it is not valid JSON). Referenced schemas are wrapped into `allOf`, since
+/// siblings of `$ref` are ignored.
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub fn apply_schema_constraints(
+    mut schema: SchemaObject,
+    constraints: SchemaConstraints,
+) -> SchemaObject {
+    use okapi::schemars::schema::{InstanceType, Schema, SingleOrVec};
+
+    if schema.is_ref() {
+        schema = SchemaObject {
+            subschemas: Some(Box::new(okapi::schemars::schema::SubschemaValidation {
+                all_of: Some(vec![Schema::Object(schema)]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+    }
+    if let Some(default) = constraints.default {
+        let is_string = matches!(
+            schema.instance_type,
+            Some(SingleOrVec::Single(ref x)) if **x == InstanceType::String
+        );
+        let value = if is_string {
+            serde_json::Value::String(default.into())
+        } else {
+            serde_json::from_str(default)
+                .unwrap_or_else(|_| serde_json::Value::String(default.into()))
+        };
+        schema.metadata().default = Some(value);
+    }
+    if let Some(minimum) = constraints.minimum {
+        schema.number().minimum = Some(minimum);
+    }
+    if let Some(maximum) = constraints.maximum {
+        schema.number().maximum = Some(maximum);
+    }
+    if let Some(pattern) = constraints.pattern {
+        schema.string().pattern = Some(pattern.into());
+    }
+    schema
+}
+
+/// Strategy for naming schemas of different types with the same name.
+///
+/// Strategy is applied to types, which schemas are requested via [`Components::schema_for`]
+/// (i.e. types of request bodies, responses and parameters). Nested types (like types of
+/// fields) are always named by [`SchemaNameStrategy::Numbered`] strategy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchemaNameStrategy {
+    /// Add numeric suffix to name of every next type (`Request`, `Request2`, ...).
+    #[default]
+    Numbered,
+    /// Return an error.
+    Error,
+    /// Suffix colliding names with module path of type (`Request_my_crate_users`).
+    ModulePathSuffix,
+    /// Use full path of type as name (`my_crate.users.Request`).
+    FullPath,
+}
+
 /// Builder for [`Components`]
 pub struct ComponentsBuilder {
     components: okapi::openapi3::Components,
-    inline_subschemas: bool,
+    schema_settings: Option<SchemaSettings>,
+    definitions_path: Option<String>,
+    inline_subschemas: Option<bool>,
+    name_strategy: SchemaNameStrategy,
+    nullable_strategy: Option<NullableStrategy>,
+    recursion_policy: RecursionPolicy,
+    rename_schemas: Option<RenameSchemas>,
+    visitors: Vec<Box<dyn GenVisitor>>,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -17,7 +159,14 @@ impl Default for ComponentsBuilder {
     fn default() -> Self {
         Self {
             components: Default::default(),
-            inline_subschemas: false,
+            schema_settings: None,
+            definitions_path: None,
+            inline_subschemas: None,
+            name_strategy: SchemaNameStrategy::default(),
+            nullable_strategy: None,
+            recursion_policy: RecursionPolicy::default(),
+            rename_schemas: None,
+            visitors: Vec::new(),
         }
     }
 }
@@ -28,20 +177,140 @@ impl ComponentsBuilder {
         self
     }
 
+    /// Set settings of schema generator.
+    ///
+    /// Allows to configure everything, supported by `schemars` (like handling of `Option`
+    /// fields, meta-schema or `bool` schemas), so generated schemas match conventions of
+    /// organization. [`definitions_path`](Self::definitions_path),
+    /// [`inline_subschemas`](Self::inline_subschemas) (if set) and
+    /// [visitors](Self::with_schema_visitor) are applied on top of these settings.
+    ///
+    /// [`SchemaSettings::openapi3`] by default.
+    ///
+    /// ```
+    /// # use okapi_operation::{schemars::gen::SchemaSettings, ComponentsBuilder};
+    /// let components = ComponentsBuilder::default()
+    ///     .schema_settings(SchemaSettings::openapi3().with(|settings| {
+    ///         settings.option_nullable = false;
+    ///         settings.option_add_null_type = true;
+    ///     }))
+    ///     .build();
+    /// ```
+    pub fn schema_settings(mut self, settings: SchemaSettings) -> Self {
+        self.schema_settings = Some(settings);
+        self
+    }
+
+    /// Set prefix of references to schemas (like `schemas.yaml#/` for specification, which is
+    /// later split into multiple files).
+    ///
+    /// Prefix is used by schema generator and for references in `schema_json` attributes of
+    /// [`openapi`](crate::openapi) macro (written as `#/components/schemas/<name>`). Schemas
+    /// are still put into `components.schemas` of generated specification.
+    ///
+    /// `#/components/schemas/` by default.
+    pub fn definitions_path(mut self, definitions_path: impl Into<String>) -> Self {
+        self.definitions_path = Some(definitions_path.into());
+        self
+    }
+
     /// Enable or disable subschemas [inlining](https://docs.rs/schemars/latest/schemars/gen/struct.SchemaSettings.html#structfield.inline_subschemas).
     ///
     /// `false` by default.
     pub fn inline_subschemas(mut self, inline_subschemas: bool) -> Self {
-        self.inline_subschemas = inline_subschemas;
+        self.inline_subschemas = Some(inline_subschemas);
+        self
+    }
+
+    /// Set strategy for naming schemas of different types with the same name.
+    ///
+    /// [`SchemaNameStrategy::Numbered`] by default.
+    pub fn name_strategy(mut self, name_strategy: SchemaNameStrategy) -> Self {
+        self.name_strategy = name_strategy;
+        self
+    }
+
+    /// Set representation of nullable values (like `Option<T>`) in generated schemas.
+    ///
+    /// Nullable schemas are converted by visitor (applied before
+    /// [custom visitors](Self::with_schema_visitor)), so the same representation is used
+    /// regardless of [schema settings](Self::schema_settings). Note, that
+    /// [`NullableStrategy::NullType`] is supported only by OpenAPI 3.1, so version of
+    /// specification should be set accordingly.
+    ///
+    /// If not set, schemas are generated as configured by schema settings
+    /// ([`NullableStrategy::Nullable`] for default settings).
+    pub fn nullable_strategy(mut self, nullable_strategy: NullableStrategy) -> Self {
+        self.nullable_strategy = Some(nullable_strategy);
+        self
+    }
+
+    /// Set how schemas of recursive types are emitted.
+    ///
+    /// Recursive types are referenced even if [subschemas are inlined](Self::inline_subschemas)
+    /// (such schemas are reported as [`BuildWarning::RecursiveSchema`](crate::BuildWarning::RecursiveSchema)).
+    /// [`RecursionPolicy::Reference`] by default.
+    pub fn recursion_policy(mut self, recursion_policy: RecursionPolicy) -> Self {
+        self.recursion_policy = recursion_policy;
+        self
+    }
+
+    /// Set function for renaming schemas.
+    ///
+    /// Function is called with name (after applying [name strategy](Self::name_strategy)) and
+    /// schema for every schema in specification (including schemas in provided
+    /// [`okapi::openapi3::Components`]) and returns new name of schema. References to schemas
+    /// are updated accordingly. Could be used for applying naming conventions to all schemas.
+    pub fn rename_schemas<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str, &SchemaObject) -> String + Send + Sync + 'static,
+    {
+        self.rename_schemas = Some(Arc::new(f));
+        self
+    }
+
+    /// Add [visitor](okapi::schemars::visit::Visitor), which will be applied to every
+    /// generated schema.
+    ///
+    /// Visitors are applied in order of registration, after default visitors of
+    /// [`SchemaSettings::openapi3`].
+    pub fn with_schema_visitor<V>(mut self, visitor: V) -> Self
+    where
+        V: Visitor + Debug + Clone + 'static,
+    {
+        self.visitors.push(Box::new(visitor));
         self
     }
 
     pub fn build(self) -> Components {
-        let mut generator_settings = SchemaSettings::openapi3();
-        generator_settings.inline_subschemas = self.inline_subschemas;
+        let mut generator_settings = self
+            .schema_settings
+            .unwrap_or_else(SchemaSettings::openapi3);
+        if let Some(definitions_path) = self.definitions_path {
+            generator_settings.definitions_path = definitions_path;
+        }
+        if let Some(inline_subschemas) = self.inline_subschemas {
+            generator_settings.inline_subschemas = inline_subschemas;
+        }
+        if let Some(nullable_strategy) = self.nullable_strategy {
+            generator_settings
+                .visitors
+                .push(Box::new(NullableVisitor(nullable_strategy)));
+        }
+        generator_settings.visitors.extend(self.visitors);
         Components {
             generator: generator_settings.into_generator(),
             components: self.components,
+            name_strategy: self.name_strategy,
+            recursion_policy: self.recursion_policy,
+            rename_schemas: self.rename_schemas,
+            schema_types: IndexMap::new(),
+            route: None,
+            context: OperationContext::default(),
+            route_probe: false,
+            schema_cache: HashMap::new(),
+            schema_overrides: HashMap::new(),
+            discriminators: Vec::new(),
         }
     }
 }
@@ -51,6 +320,67 @@ impl ComponentsBuilder {
 pub struct Components {
     generator: SchemaGenerator,
     components: okapi::openapi3::Components,
+    name_strategy: SchemaNameStrategy,
+    recursion_policy: RecursionPolicy,
+    rename_schemas: Option<RenameSchemas>,
+    /// Generated schema name -> (schema name of type, schema ID of type).
+    schema_types: IndexMap<String, (String, Cow<'static, str>)>,
+    /// Path and method of currently generated operation.
+    route: Option<(String, Method)>,
+    /// Context, set by generator of currently generated operation.
+    context: OperationContext,
+    /// Whether generator is called only to get declared route.
+    route_probe: bool,
+    /// Schema ID of type -> schema, returned by [`Components::schema_for`] (with applied
+    /// visitors).
+    schema_cache: HashMap<Cow<'static, str>, SchemaObject>,
+    /// Schema ID of non-referenceable type -> schema, replacing it.
+    schema_overrides: HashMap<Cow<'static, str>, SchemaObject>,
+    /// Schema ID of type, schema name of type and discriminator property name.
+    discriminators: Vec<(Cow<'static, str>, String, Option<String>)>,
+}
+
+/// Context of operation, set by its generator (generated by [`openapi`](crate::openapi)
+/// macro).
+#[derive(Clone, Debug, Default)]
+#[doc(hidden)]
+pub struct OperationContext {
+    /// Name of handler function.
+    pub(crate) handler_name: Option<String>,
+    /// Path, which replaces path of route of operation.
+    pub(crate) path_override: Option<String>,
+    /// Path and method, declared by operation.
+    pub(crate) declared_route: Option<(String, Method)>,
+    /// Types of handler arguments, not recognized as extractors.
+    pub(crate) unknown_extractors: Vec<String>,
+}
+
+#[cfg(feature = "macro")]
+impl OperationContext {
+    pub fn new(handler_name: &str) -> Self {
+        Self {
+            handler_name: Some(handler_name.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    pub fn path_override(mut self, path: &str) -> Self {
+        self.path_override = Some(path.to_owned());
+        self
+    }
+
+    pub fn declared_route(mut self, path: &str, method: &str) -> Result<Self, Error> {
+        let method = Method::from_bytes(method.as_bytes()).map_err(|err| {
+            Error::InvalidDefinition(anyhow::anyhow!("Invalid method `{method}`: {err}"))
+        })?;
+        self.declared_route = Some((path.to_owned(), method));
+        Ok(self)
+    }
+
+    pub fn unknown_extractor(mut self, ty: &str) -> Self {
+        self.unknown_extractors.push(ty.to_owned());
+        self
+    }
 }
 
 impl Components {
@@ -61,14 +391,142 @@ impl Components {
     }
 
     /// Get schema for type.
+    ///
+    /// Schemas are cached by [schema ID](JsonSchema::schema_id), so repeated calls for the
+    /// same type are cheap.
     pub fn schema_for<T: JsonSchema>(&mut self) -> SchemaObject {
-        let mut object = self.generator.subschema_for::<T>().into_object();
+        let id = T::schema_id();
+        if let Some(object) = self.schema_cache.get(&id) {
+            return object.clone();
+        }
+
+        let mut object = match self.schema_overrides.get(&id) {
+            Some(schema) => schema.clone(),
+            None => self.generator.subschema_for::<T>().into_object(),
+        };
+        if let Some(name) = object
+            .reference
+            .as_ref()
+            .and_then(|x| x.strip_prefix(&self.generator.settings().definitions_path))
+        {
+            let _ = self
+                .schema_types
+                .entry(name.to_owned())
+                .or_insert_with(|| (T::schema_name(), id.clone()));
+        }
         for visitor in self.generator.visitors_mut() {
             visitor.visit_schema_object(&mut object);
         }
+        let _ = self.schema_cache.insert(id, object.clone());
         object
     }
 
+    /// Replace schema of type with provided one.
+    ///
+    /// Useful for foreign types (like `chrono::DateTime<Utc>`), which schemas lack format or
+    /// examples. Overrides are keyed by [schema ID](JsonSchema::schema_id) of type. Schema of
+    /// referenceable type (which is put into `components.schemas`) is replaced everywhere
+    /// it appears, including fields of other types. Schema of non-referenceable type (like
+    /// `std::net::Ipv4Addr`) is replaced only where it is requested for type itself (like
+    /// request bodies, responses and parameters of this type), since inlined schemas of
+    /// fields don't identify their types (use `#[schemars(with = "...")]` on such fields).
+    ///
+    /// ```rust
+    /// # use okapi_operation::{*, okapi::openapi3::SchemaObject};
+    /// let mut components = ComponentsBuilder::default().build();
+    /// let schema: SchemaObject = serde_json::from_value(serde_json::json!({
+    ///     "type": "string",
+    ///     "format": "ipv4",
+    ///     "example": "127.0.0.1"
+    /// }))?;
+    /// components.override_schema_for::<std::net::Ipv4Addr>(schema.clone());
+    /// assert_eq!(components.schema_for::<std::net::Ipv4Addr>(), schema);
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn override_schema_for<T: JsonSchema>(&mut self, schema: SchemaObject) {
+        self.schema_cache.clear();
+        if T::is_referenceable() {
+            let _ = self
+                .generator
+                .definitions_mut()
+                .insert(T::schema_name(), schema.into());
+        } else {
+            let _ = self.schema_overrides.insert(T::schema_id(), schema);
+        }
+    }
+
+    /// Set `discriminator` of schema of enum, tagged with serde (internally or adjacently).
+    ///
+    /// Discriminator property (`propertyName`) and `mapping` are inferred from schemas of
+    /// variants. Variants, which are not references, are moved into separate schemas (named
+    /// `<Enum><Tag>`), so `mapping` could reference them. Schema is left unchanged, if it is
+    /// not `oneOf` of tagged variants.
+    ///
+    /// See also [`BuilderOptions::infer_discriminators`](crate::BuilderOptions::infer_discriminators).
+    pub fn set_discriminator<T: JsonSchema>(&mut self) {
+        self.push_discriminator::<T>(None);
+    }
+
+    /// Set `discriminator` of schema of enum (like [`set_discriminator`](Self::set_discriminator))
+    /// with specified discriminator property.
+    pub fn set_discriminator_property<T: JsonSchema>(&mut self, property_name: impl Into<String>) {
+        self.push_discriminator::<T>(Some(property_name.into()));
+    }
+
+    fn push_discriminator<T: JsonSchema>(&mut self, property_name: Option<String>) {
+        let id = T::schema_id();
+        self.discriminators.retain(|(x, ..)| *x != id);
+        self.discriminators
+            .push((id, T::schema_name(), property_name));
+    }
+
+    /// Prefix of references to schemas (see [`ComponentsBuilder::definitions_path`]).
+    pub fn definitions_path(&self) -> &str {
+        &self.generator.settings().definitions_path
+    }
+
+    /// Set context of currently generated operation.
+    #[doc(hidden)]
+    pub fn set_operation_context(&mut self, context: OperationContext) {
+        self.context = context;
+    }
+
+    /// Take context, set by generator of current operation.
+    pub(crate) fn take_operation_context(&mut self) -> OperationContext {
+        std::mem::take(&mut self.context)
+    }
+
+    /// Path template (in OpenAPI format, like `/users/{id}`) and method of currently
+    /// generated operation.
+    ///
+    /// Available inside [`OperationGenerator`](crate::OperationGenerator), when operation is
+    /// generated by [`OpenApiBuilder`](crate::OpenApiBuilder).
+    pub fn current_route(&self) -> Option<(&str, &Method)> {
+        self.route
+            .as_ref()
+            .map(|(path, method)| (path.as_str(), method))
+    }
+
+    pub(crate) fn set_route(&mut self, route: Option<(String, Method)>) {
+        self.route = route;
+    }
+
+    /// Whether generator should return right after declaring its route.
+    #[doc(hidden)]
+    pub fn is_route_probe(&self) -> bool {
+        self.route_probe
+    }
+
+    /// Get path and method, declared by generator (with `path` and `method` attributes of
+    /// [`openapi`](crate::openapi) macro), without generating operation.
+    #[cfg(feature = "axum")]
+    pub(crate) fn declared_route(generator: crate::OperationGenerator) -> Option<(String, Method)> {
+        let mut components = Self::new(Default::default());
+        components.route_probe = true;
+        let _ = generator(&mut components);
+        components.take_operation_context().declared_route
+    }
+
     /// Add security scheme to components.
     pub fn add_security_scheme<N>(&mut self, name: N, sec: SecurityScheme)
     where
@@ -79,11 +537,227 @@ impl Components {
             .insert(name.into(), RefOr::Object(sec));
     }
 
+    /// Add reusable response to components.
+    ///
+    /// It can be referenced as `#/components/responses/<name>`.
+    pub fn add_response<N>(&mut self, name: N, response: Response)
+    where
+        N: Into<String>,
+    {
+        self.components
+            .responses
+            .insert(name.into(), RefOr::Object(response));
+    }
+
+    /// Add reusable parameter to components.
+    ///
+    /// It can be referenced as `#/components/parameters/<name>`.
+    pub fn add_parameter<N>(&mut self, name: N, parameter: Parameter)
+    where
+        N: Into<String>,
+    {
+        self.components
+            .parameters
+            .insert(name.into(), RefOr::Object(parameter));
+    }
+
+    /// Add reusable request body to components.
+    ///
+    /// It can be referenced as `#/components/requestBodies/<name>`.
+    pub fn add_request_body<N>(&mut self, name: N, request_body: RequestBody)
+    where
+        N: Into<String>,
+    {
+        self.components
+            .request_bodies
+            .insert(name.into(), RefOr::Object(request_body));
+    }
+
+    /// Add reusable header to components.
+    ///
+    /// It can be referenced as `#/components/headers/<name>`.
+    pub fn add_header<N>(&mut self, name: N, header: Header)
+    where
+        N: Into<String>,
+    {
+        self.components
+            .headers
+            .insert(name.into(), RefOr::Object(header));
+    }
+
+    /// Find reusable parameter by reference (`#/components/parameters/<name>`).
+    pub(crate) fn parameter_by_reference(&self, reference: &str) -> Option<&Parameter> {
+        let name = reference.strip_prefix(<Parameter as Referenceable>::REFERENCE_PREFIX)?;
+        match self.components.parameters.get(name)? {
+            RefOr::Object(x) => Some(x),
+            RefOr::Ref(_) => None,
+        }
+    }
+
+    /// Merge components of other specification.
+    pub(crate) fn merge_okapi_components(
+        &mut self,
+        other: okapi::openapi3::Components,
+        strategy: MergeStrategy,
+    ) -> Result<(), anyhow::Error> {
+        merge_components(&mut self.components, other, strategy)
+    }
+
+    /// Add components, which are missing in these components, from other ones.
+    ///
+    /// Existing components are kept.
+    #[cfg(feature = "axum")]
+    pub(crate) fn fill_missing_from(&mut self, other: &Components) {
+        fn fill<T: Clone>(base: &mut okapi::Map<String, T>, other: &okapi::Map<String, T>) {
+            for (name, value) in other {
+                let _ = base.entry(name.clone()).or_insert_with(|| value.clone());
+            }
+        }
+
+        let (base, other) = (&mut self.components, &other.components);
+        fill(&mut base.schemas, &other.schemas);
+        fill(&mut base.responses, &other.responses);
+        fill(&mut base.parameters, &other.parameters);
+        fill(&mut base.examples, &other.examples);
+        fill(&mut base.request_bodies, &other.request_bodies);
+        fill(&mut base.headers, &other.headers);
+        fill(&mut base.security_schemes, &other.security_schemes);
+        fill(&mut base.links, &other.links);
+        fill(&mut base.callbacks, &other.callbacks);
+        fill(&mut base.extensions, &other.extensions);
+    }
+
+    /// Generate [`okapi::openapi3::Components`] and put it into specification.
+    ///
+    /// References to renamed schemas are updated in whole specification. Returns names of
+    /// recursive schemas, which are referenced despite inlining of subschemas.
+    pub(crate) fn apply_to(&mut self, spec: &mut OpenApi) -> Result<Vec<String>, Error> {
+        let renames = self.schema_renames()?;
+        spec.components = Some(self.okapi_components(&renames)?);
+        if !renames.is_empty() {
+            let definitions_path = self.generator.settings().definitions_path.clone();
+            for_each_reference(spec, |reference| {
+                if let Some(new_name) = reference
+                    .strip_prefix(&definitions_path)
+                    .and_then(|name| renames.get(name))
+                {
+                    *reference = format!("{definitions_path}{new_name}");
+                }
+            });
+        }
+        let schemas = &mut spec.components.as_mut().expect("Set above").schemas;
+        let recursive = recursive_schemas(schemas, self.definitions_path());
+        match (self.recursion_policy, recursive.first()) {
+            (_, None) | (RecursionPolicy::Reference, _) => {}
+            (RecursionPolicy::InlineDepth(depth), _) => {
+                inline_recursive_schemas(schemas, &recursive, depth, self.definitions_path())
+            }
+            (RecursionPolicy::Error, Some(name)) => {
+                return Err(Error::RecursiveSchema { name: name.clone() })
+            }
+        }
+        for (id, schema_name, property_name) in &self.discriminators {
+            let name = self
+                .schema_types
+                .iter()
+                .find(|(_, (_, x))| x == id)
+                .map_or(schema_name, |(name, _)| name);
+            let name = renames.get(name).unwrap_or(name);
+            let _ = set_discriminator(
+                schemas,
+                name,
+                property_name.as_deref(),
+                self.definitions_path(),
+            );
+        }
+        if self.generator.settings().inline_subschemas {
+            Ok(recursive)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Generate new names for schemas according to name strategy and renaming function.
+    fn schema_renames(&self) -> Result<HashMap<String, String>, Error> {
+        let mut renames = HashMap::new();
+
+        for (name, (base_name, id)) in &self.schema_types {
+            let collides = name != base_name
+                || self
+                    .schema_types
+                    .iter()
+                    .any(|(other_name, (other_base_name, _))| {
+                        other_name != name && other_base_name == base_name
+                    });
+            let new_name = match self.name_strategy {
+                SchemaNameStrategy::Numbered => continue,
+                SchemaNameStrategy::Error if collides => {
+                    return Err(Error::SchemaConflict {
+                        name: base_name.clone(),
+                    })
+                }
+                SchemaNameStrategy::Error => continue,
+                SchemaNameStrategy::ModulePathSuffix if collides => {
+                    let module_path = id
+                        .strip_suffix(base_name.as_str())
+                        .and_then(|x| x.strip_suffix("::"))
+                        .unwrap_or(id);
+                    format!(
+                        "{base_name}_{}",
+                        sanitize_schema_name(&module_path.replace("::", "_"))
+                    )
+                }
+                SchemaNameStrategy::ModulePathSuffix => continue,
+                SchemaNameStrategy::FullPath => sanitize_schema_name(&id.replace("::", ".")),
+            };
+            if &new_name != name {
+                let _ = renames.insert(name.clone(), new_name);
+            }
+        }
+
+        if let Some(rename) = &self.rename_schemas {
+            let schemas = self
+                .components
+                .schemas
+                .iter()
+                .map(|(name, schema)| (name.clone(), schema.clone()))
+                .chain(
+                    self.generator
+                        .definitions()
+                        .iter()
+                        .map(|(name, schema)| (name.clone(), schema.clone().into_object())),
+                );
+            for (name, schema) in schemas {
+                let new_name = rename(renames.get(&name).unwrap_or(&name), &schema);
+                if new_name != name {
+                    let _ = renames.insert(name, new_name);
+                } else {
+                    let _ = renames.remove(&name);
+                }
+            }
+        }
+
+        Ok(renames)
+    }
+
     /// Generate [`okapi::openapi3::Components`].
-    pub(crate) fn okapi_components(
+    fn okapi_components(
         &mut self,
-    ) -> Result<okapi::openapi3::Components, anyhow::Error> {
+        renames: &HashMap<String, String>,
+    ) -> Result<okapi::openapi3::Components, Error> {
         let mut components = self.components.clone();
+        if !renames.is_empty() {
+            components.schemas = Default::default();
+            for (name, schema_object) in &self.components.schemas {
+                let name = renames.get(name).unwrap_or(name);
+                if components.schemas.contains_key(name) {
+                    return Err(Error::SchemaConflict { name: name.clone() });
+                }
+                let _ = components
+                    .schemas
+                    .insert(name.clone(), schema_object.clone());
+            }
+        }
         for (name, mut schema_object) in self
             .generator
             .definitions()
@@ -94,11 +768,25 @@ impl Components {
             for visitor in self.generator.visitors_mut() {
                 visitor.visit_schema_object(&mut schema_object);
             }
+            let name = renames.get(&name).cloned().unwrap_or(name);
             if components.schemas.contains_key(&name) {
-                return Err(anyhow::anyhow!("Multiple schemas found for '{}'", name));
+                return Err(Error::SchemaConflict { name });
             }
             let _ = components.schemas.insert(name, schema_object);
         }
         Ok(components)
     }
 }
+
+/// Replace characters, not allowed in component names, with `_`.
+fn sanitize_schema_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
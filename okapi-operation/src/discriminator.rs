@@ -0,0 +1,262 @@
+use okapi::{
+    openapi3::SchemaObject,
+    schemars::schema::{InstanceType, Schema},
+    Map,
+};
+
+/// Extension, containing discriminator of schema (`discriminator` is not supported by
+/// `schemars`).
+const DISCRIMINATOR_EXTENSION: &str = "discriminator";
+
+/// Set `discriminator` of `oneOf` schema, which variants are distinguished by value of
+/// property (like schemas of internally or adjacently tagged enums).
+///
+/// If `property_name` is not specified, first property, which has single value in every
+/// variant, is used. Variants, which are not references, are moved into separate schemas
+/// (named `<Name><Tag>`), so discriminator could map values to them. Returns `false` (leaving
+/// schemas unchanged), if schema is not `oneOf` of tagged variants.
+pub(crate) fn set_discriminator(
+    schemas: &mut Map<String, SchemaObject>,
+    name: &str,
+    property_name: Option<&str>,
+    definitions_path: &str,
+) -> bool {
+    let Some(schema) = schemas.get(name) else {
+        return false;
+    };
+    if schema.extensions.contains_key(DISCRIMINATOR_EXTENSION) {
+        return false;
+    }
+    let Some(variants) = schema
+        .subschemas
+        .as_ref()
+        .and_then(|x| x.one_of.as_ref())
+        .filter(|x| !x.is_empty())
+    else {
+        return false;
+    };
+    let Some(variants) = variants
+        .iter()
+        .map(|x| match x {
+            Schema::Object(x) => Some(x),
+            Schema::Bool(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return false;
+    };
+    let resolve = |variant: &'_ SchemaObject| -> Option<SchemaObject> {
+        match &variant.reference {
+            Some(reference) => schemas
+                .get(reference.strip_prefix(definitions_path)?)
+                .cloned(),
+            None => Some(variant.clone()),
+        }
+    };
+    let Some(resolved) = variants
+        .iter()
+        .map(|x| resolve(x))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return false;
+    };
+
+    let property_name = match property_name {
+        Some(x) => x.to_owned(),
+        None => {
+            let Some(x) = resolved[0]
+                .object
+                .as_ref()
+                .and_then(|object| {
+                    object
+                        .properties
+                        .keys()
+                        .find(|x| resolved.iter().all(|y| tag_value(y, x).is_some()))
+                })
+                .cloned()
+            else {
+                return false;
+            };
+            x
+        }
+    };
+    let Some(tags) = resolved
+        .iter()
+        .map(|x| tag_value(x, &property_name))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return false;
+    };
+
+    let mut mapping = serde_json::Map::new();
+    let mut new_variants = Vec::new();
+    let mut new_schemas = Vec::new();
+    for (variant, tag) in variants.into_iter().zip(tags) {
+        let reference = match &variant.reference {
+            Some(reference) => reference.clone(),
+            None => {
+                let variant_name = format!("{name}{}", pascal_case(&tag));
+                if schemas.contains_key(&variant_name)
+                    || new_schemas.iter().any(|(x, _)| *x == variant_name)
+                {
+                    return false;
+                }
+                new_schemas.push((variant_name.clone(), variant.clone()));
+                format!("{definitions_path}{variant_name}")
+            }
+        };
+        new_variants.push(Schema::Object(SchemaObject::new_ref(reference.clone())));
+        let _ = mapping.insert(tag, serde_json::Value::String(reference));
+    }
+
+    let schema = schemas.get_mut(name).expect("Checked above");
+    schema.subschemas().one_of = Some(new_variants);
+    let _ = schema.extensions.insert(
+        DISCRIMINATOR_EXTENSION.into(),
+        serde_json::json!({
+            "propertyName": property_name,
+            "mapping": mapping,
+        }),
+    );
+    schemas.extend(new_schemas);
+    true
+}
+
+/// Set discriminators of all schemas, which are `oneOf` of tagged variants.
+pub(crate) fn infer_discriminators(
+    schemas: &mut Map<String, SchemaObject>,
+    definitions_path: &str,
+) {
+    for name in schemas.keys().cloned().collect::<Vec<_>>() {
+        let _ = set_discriminator(schemas, &name, None, definitions_path);
+    }
+}
+
+/// Single string value of required property of object schema.
+fn tag_value(schema: &SchemaObject, property_name: &str) -> Option<String> {
+    let object = schema.object.as_ref()?;
+    if !object.required.contains(property_name) {
+        return None;
+    }
+    let Schema::Object(property) = object.properties.get(property_name)? else {
+        return None;
+    };
+    if property
+        .instance_type
+        .as_ref()
+        .is_some_and(|x| *x != InstanceType::String.into())
+    {
+        return None;
+    }
+    let value = match (&property.const_value, property.enum_values.as_deref()) {
+        (Some(value), _) => value,
+        (None, Some([value])) => value,
+        _ => return None,
+    };
+    value.as_str().map(str::to_owned)
+}
+
+/// Convert tag value (like `credit_card` or `credit-card`) into `PascalCase` (`CreditCard`).
+fn pascal_case(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .map(|x| {
+            let mut chars = x.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::walk::SCHEMA_REFERENCE_PREFIX;
+
+    fn schema(value: serde_json::Value) -> SchemaObject {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn internally_tagged() {
+        let mut schemas = okapi::map! {
+            "Payment".into() => schema(serde_json::json!({
+                "oneOf": [
+                    {
+                        "type": "object",
+                        "required": ["kind"],
+                        "properties": { "kind": { "type": "string", "enum": ["cash"] } }
+                    },
+                    {
+                        "type": "object",
+                        "required": ["kind", "number"],
+                        "properties": {
+                            "kind": { "type": "string", "enum": ["credit_card"] },
+                            "number": { "type": "string" }
+                        }
+                    },
+                    { "$ref": "#/components/schemas/Voucher" }
+                ]
+            })),
+            "Voucher".into() => schema(serde_json::json!({
+                "type": "object",
+                "required": ["kind"],
+                "properties": { "kind": { "type": "string", "enum": ["voucher"] } }
+            })),
+        };
+        infer_discriminators(&mut schemas, SCHEMA_REFERENCE_PREFIX);
+
+        assert_eq!(
+            schemas.keys().collect::<Vec<_>>(),
+            ["Payment", "Voucher", "PaymentCash", "PaymentCreditCard"]
+        );
+        let payment = serde_json::to_value(&schemas["Payment"]).unwrap();
+        assert_eq!(
+            payment["discriminator"],
+            serde_json::json!({
+                "propertyName": "kind",
+                "mapping": {
+                    "cash": "#/components/schemas/PaymentCash",
+                    "credit_card": "#/components/schemas/PaymentCreditCard",
+                    "voucher": "#/components/schemas/Voucher",
+                }
+            })
+        );
+        assert_eq!(
+            payment["oneOf"][1]["$ref"],
+            "#/components/schemas/PaymentCreditCard"
+        );
+    }
+
+    #[test]
+    fn untagged() {
+        let mut schemas = okapi::map! {
+            "Value".into() => schema(serde_json::json!({
+                "oneOf": [{ "type": "string" }, { "type": "integer" }]
+            })),
+        };
+        assert!(!set_discriminator(
+            &mut schemas,
+            "Value",
+            None,
+            SCHEMA_REFERENCE_PREFIX
+        ));
+        assert!(!set_discriminator(
+            &mut schemas,
+            "Value",
+            Some("type"),
+            SCHEMA_REFERENCE_PREFIX
+        ));
+        assert_eq!(schemas.len(), 1);
+    }
+
+    #[test]
+    fn pascal_case_tags() {
+        assert_eq!(pascal_case("credit_card"), "CreditCard");
+        assert_eq!(pascal_case("credit-card"), "CreditCard");
+        assert_eq!(pascal_case("Cash"), "Cash");
+    }
+}
@@ -0,0 +1,134 @@
+use std::{fmt, path::PathBuf};
+
+use http::Method;
+
+/// Error of building specification with [`OpenApiBuilder`](crate::OpenApiBuilder) or generating
+/// definitions with traits (like [`ToResponses`](crate::ToResponses)).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Operation with same path and method is already added to builder.
+    DuplicateOperation { path: String, method: Method },
+    /// Inferred `operationId` is already used by another operation.
+    DuplicateOperationId {
+        operation_id: String,
+        path: String,
+        method: Method,
+    },
+    /// Method can't be documented in OpenAPI specification.
+    UnsupportedMethod { path: String, method: Method },
+    /// Schema name is used by multiple schemas.
+    SchemaConflict { name: String },
+    /// Schema of recursive type is generated with
+    /// [`RecursionPolicy::Error`](crate::RecursionPolicy::Error).
+    RecursiveSchema { name: String },
+    /// Routes are handled without OpenAPI operation, while it is not allowed by
+    /// `Router::allow_undocumented` of axum integration.
+    UndocumentedRoutes { routes: Vec<String> },
+    /// Generator of operation (or its default responses) failed.
+    Generator {
+        path: String,
+        method: Method,
+        source: Box<Error>,
+    },
+    /// Asynchronous operation generator is used with [`OpenApiBuilder::build`](crate::OpenApiBuilder::build).
+    AsyncGenerator,
+    /// Generator of fallback operation failed.
+    Fallback(Box<Error>),
+    /// Definitions (of specifications, responses or media types) conflict with each other
+    /// while merging.
+    Merge(anyhow::Error),
+    /// Definition, provided in [`openapi`](crate::openapi) macro attributes or specification,
+    /// is invalid (like malformed JSON schema, content type, which can't be applied, or
+    /// operation in `x-additionalOperations` extension).
+    InvalidDefinition(anyhow::Error),
+    /// Specification file can't be read or parsed.
+    Include {
+        file: PathBuf,
+        source: anyhow::Error,
+    },
+    /// Built specification can't be serialized.
+    Serialize(anyhow::Error),
+    /// Definitions can't be imported from other format (like Protobuf descriptors).
+    Import(anyhow::Error),
+    /// Error of user-defined generator or trait implementation.
+    Custom(anyhow::Error),
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(value: anyhow::Error) -> Self {
+        Self::Custom(value)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateOperation { path, method } => {
+                write!(f, "{method} {path} is already present in specification")
+            }
+            Self::DuplicateOperationId {
+                operation_id,
+                path,
+                method,
+            } => write!(
+                f,
+                "Inferred operationId '{operation_id}' of {method} {path} is already used by another operation"
+            ),
+            Self::UnsupportedMethod { path, method } => {
+                write!(f, "Unsupported method {method} (at {path})")
+            }
+            Self::SchemaConflict { name } => {
+                write!(f, "Schema name '{name}' is used by multiple schemas")
+            }
+            Self::RecursiveSchema { name } => write!(f, "Schema '{name}' is recursive"),
+            Self::UndocumentedRoutes { routes } => {
+                write!(f, "Routes are not documented: {}", routes.join(", "))
+            }
+            Self::Generator { path, method, .. } => write!(f, "Failed to generate {method} {path}"),
+            Self::AsyncGenerator => f.write_str(
+                "Asynchronous operation can't be generated by `build`, use `build_async`",
+            ),
+            Self::Fallback(_) => f.write_str("Failed to generate fallback operation"),
+            Self::Merge(_) => f.write_str("Failed to merge definitions"),
+            Self::InvalidDefinition(_) => f.write_str("Invalid definition"),
+            Self::Include { file, .. } => write!(f, "Failed to include {}", file.display()),
+            Self::Serialize(_) => f.write_str("Failed to serialize specification"),
+            Self::Import(_) => f.write_str("Failed to import definitions"),
+            Self::Custom(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Generator { source, .. } | Self::Fallback(source) => Some(source.as_ref()),
+            Self::Merge(source)
+            | Self::InvalidDefinition(source)
+            | Self::Include { source, .. }
+            | Self::Serialize(source)
+            | Self::Import(source) => Some(source.as_ref()),
+            Self::Custom(err) => err.source(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let err = Error::DuplicateOperationId {
+            operation_id: "getUser".into(),
+            path: "/users/{id}".into(),
+            method: Method::GET,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Inferred operationId 'getUser' of GET /users/{id} is already used by another operation"
+        );
+    }
+}
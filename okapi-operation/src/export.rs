@@ -0,0 +1,366 @@
+//! Export of specification into human-readable documentation.
+
+use std::fmt::Write;
+
+use http::Method;
+use okapi::{
+    openapi3::{Components, OpenApi, Operation, Parameter, ParameterValue, RequestBody, Response},
+    schemars::schema::{InstanceType, SchemaObject, SingleOrVec},
+};
+
+use crate::walk::{path_item_operations, reference_name, resolve_ref};
+
+/// Title of section with operations without tags.
+const UNTAGGED_SECTION: &str = "Other";
+
+/// Generate Markdown documentation of endpoints.
+///
+/// Documentation contains section per tag (in order of tags in specification, then in order of
+/// first usage; operations without tags are put into `Other` section) with description of
+/// every operation and tables of its parameters and responses. Operations with multiple tags
+/// are documented in every section. References to parameters, request bodies and responses
+/// are resolved from components of specification.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use okapi_operation::*;
+/// let spec = OpenApiBuilder::new("Demo", "1.0.0").build()?;
+/// std::fs::write("API.md", export::markdown(&spec))?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn markdown(spec: &OpenApi) -> String {
+    let components = spec.components.clone().unwrap_or_default();
+    let mut output = String::new();
+
+    let _ = writeln!(output, "# {} {}", spec.info.title, spec.info.version);
+    if let Some(ref description) = spec.info.description {
+        let _ = write!(output, "\n{}\n", description.trim());
+    }
+
+    for (tag, operations) in group_by_tag(spec) {
+        let _ = write!(output, "\n## {tag}\n");
+        if let Some(description) = spec
+            .tags
+            .iter()
+            .find(|x| x.name == tag)
+            .and_then(|x| x.description.as_ref())
+        {
+            let _ = write!(output, "\n{}\n", description.trim());
+        }
+        for (path, method, operation) in operations {
+            write_operation(&mut output, &components, path, &method, operation);
+        }
+    }
+    output
+}
+
+/// Path, method and operation.
+type OperationEntry<'a> = (&'a str, Method, &'a Operation);
+
+/// Operations, grouped by tags.
+fn group_by_tag(spec: &OpenApi) -> Vec<(&str, Vec<OperationEntry<'_>>)> {
+    let mut groups: Vec<(&str, Vec<_>)> = spec
+        .tags
+        .iter()
+        .map(|x| (x.name.as_str(), Vec::new()))
+        .collect();
+    let mut untagged = Vec::new();
+    for (path, item) in &spec.paths {
+        for (method, operation) in path_item_operations(item) {
+            if operation.tags.is_empty() {
+                untagged.push((path.as_str(), method.clone(), operation));
+            }
+            for tag in &operation.tags {
+                let idx = match groups.iter().position(|(name, _)| name == tag) {
+                    Some(idx) => idx,
+                    None => {
+                        groups.push((tag, Vec::new()));
+                        groups.len() - 1
+                    }
+                };
+                groups[idx]
+                    .1
+                    .push((path.as_str(), method.clone(), operation));
+            }
+        }
+    }
+    if !untagged.is_empty() {
+        groups.push((UNTAGGED_SECTION, untagged));
+    }
+    groups.retain(|(_, operations)| !operations.is_empty());
+    groups
+}
+
+fn write_operation(
+    output: &mut String,
+    components: &Components,
+    path: &str,
+    method: &Method,
+    operation: &Operation,
+) {
+    let _ = write!(output, "\n### `{method} {path}`\n");
+    if operation.deprecated {
+        let _ = write!(output, "\n**Deprecated**\n");
+    }
+    if let Some(ref summary) = operation.summary {
+        let _ = write!(output, "\n{}\n", summary.trim());
+    }
+    if let Some(ref description) = operation.description {
+        let _ = write!(output, "\n{}\n", description.trim());
+    }
+
+    let parameters: Vec<_> = operation
+        .parameters
+        .iter()
+        .filter_map(|x| resolve_ref(components, x))
+        .collect();
+    if !parameters.is_empty() {
+        let _ = write!(
+            output,
+            "\n#### Parameters\n\n| Name | In | Required | Type | Description |\n| --- | --- | --- | --- | --- |\n"
+        );
+        for parameter in parameters {
+            write_parameter(output, parameter);
+        }
+    }
+
+    if let Some(body) = operation
+        .request_body
+        .as_ref()
+        .and_then(|x| resolve_ref(components, x))
+    {
+        write_request_body(output, body);
+    }
+
+    let responses: Vec<_> = operation
+        .responses
+        .default
+        .iter()
+        .map(|x| ("default", x))
+        .chain(
+            operation
+                .responses
+                .responses
+                .iter()
+                .map(|(status, x)| (status.as_str(), x)),
+        )
+        .filter_map(|(status, x)| Some((status, resolve_ref(components, x)?)))
+        .collect();
+    if !responses.is_empty() {
+        let _ = write!(
+            output,
+            "\n#### Responses\n\n| Status | Description | Content |\n| --- | --- | --- |\n"
+        );
+        for (status, response) in responses {
+            write_response(output, status, response);
+        }
+    }
+}
+
+fn write_parameter(output: &mut String, parameter: &Parameter) {
+    let schema_type = match parameter.value {
+        ParameterValue::Schema { ref schema, .. } => schema_type(schema),
+        ParameterValue::Content { ref content } => content_types(content.keys()),
+    };
+    let _ = writeln!(
+        output,
+        "| `{}` | {} | {} | {} | {} |",
+        parameter.name,
+        parameter.location,
+        yes_no(parameter.required),
+        schema_type,
+        table_cell(parameter.description.as_deref().unwrap_or_default()),
+    );
+}
+
+fn write_request_body(output: &mut String, body: &RequestBody) {
+    let _ = write!(output, "\n#### Request body\n\n");
+    if let Some(ref description) = body.description {
+        let _ = write!(output, "{}\n\n", description.trim());
+    }
+    let _ = write!(
+        output,
+        "| Content | Type | Required |\n| --- | --- | --- |\n"
+    );
+    for (content_type, media_type) in &body.content {
+        let _ = writeln!(
+            output,
+            "| `{content_type}` | {} | {} |",
+            media_type
+                .schema
+                .as_ref()
+                .map_or_else(String::new, schema_type),
+            yes_no(body.required),
+        );
+    }
+}
+
+fn write_response(output: &mut String, status: &str, response: &Response) {
+    let content = response
+        .content
+        .iter()
+        .map(|(content_type, media_type)| match media_type.schema {
+            Some(ref schema) => format!("`{content_type}` ({})", schema_type(schema)),
+            None => format!("`{content_type}`"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let _ = writeln!(
+        output,
+        "| {status} | {} | {content} |",
+        table_cell(&response.description),
+    );
+}
+
+/// Short description of schema type (like `string`, `array of User`, `User`).
+fn schema_type(schema: &SchemaObject) -> String {
+    if let Some(ref reference) = schema.reference {
+        let name = reference_name(reference).unwrap_or_else(|| reference.clone());
+        return format!("`{name}`");
+    }
+    let instance_type = match schema.instance_type {
+        Some(SingleOrVec::Single(ref x)) => Some(**x),
+        Some(SingleOrVec::Vec(ref x)) => x.iter().find(|x| **x != InstanceType::Null).copied(),
+        None => None,
+    };
+    let name = match instance_type {
+        Some(InstanceType::Array) => {
+            let items = schema
+                .array
+                .as_ref()
+                .and_then(|x| x.items.as_ref())
+                .and_then(|x| match x {
+                    SingleOrVec::Single(x) => Some(x.clone().into_object()),
+                    SingleOrVec::Vec(_) => None,
+                });
+            return match items {
+                Some(items) => format!("array of {}", schema_type(&items)),
+                None => "array".into(),
+            };
+        }
+        Some(InstanceType::Null) => "null",
+        Some(InstanceType::Boolean) => "boolean",
+        Some(InstanceType::Object) => "object",
+        Some(InstanceType::Number) => "number",
+        Some(InstanceType::String) => "string",
+        Some(InstanceType::Integer) => "integer",
+        None if schema.object.is_some() => "object",
+        None => return String::new(),
+    };
+    match schema.format {
+        Some(ref format) => format!("{name} ({format})"),
+        None => name.into(),
+    }
+}
+
+fn content_types<'a>(content_types: impl Iterator<Item = &'a String>) -> String {
+    content_types
+        .map(|x| format!("`{x}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Escape text for using in table cell.
+fn table_cell(text: &str) -> String {
+    text.trim().replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+    use okapi::openapi3::{MediaType, RefOr, Responses, Tag};
+
+    use super::*;
+    use crate::OpenApiBuilder;
+
+    #[test]
+    fn markdown() {
+        let mut builder = OpenApiBuilder::new("Users", "1.0.0");
+        builder
+            .operation("/users/{id}", Method::GET, |components| {
+                Ok(Operation {
+                    tags: vec!["users".into()],
+                    summary: Some("Get user".into()),
+                    parameters: vec![RefOr::Object(Parameter {
+                        name: "id".into(),
+                        location: "path".into(),
+                        description: Some("ID of user | UUID".into()),
+                        required: true,
+                        deprecated: false,
+                        allow_empty_value: false,
+                        value: ParameterValue::Schema {
+                            style: None,
+                            explode: None,
+                            allow_reserved: false,
+                            schema: components.schema_for::<u64>(),
+                            example: None,
+                            examples: None,
+                        },
+                        extensions: Default::default(),
+                    })],
+                    responses: Responses {
+                        responses: okapi::map! {
+                            "200".into() => RefOr::Object(Response {
+                                description: "User".into(),
+                                content: okapi::map! {
+                                    "application/json".into() => MediaType {
+                                        schema: Some(components.schema_for::<Vec<String>>()),
+                                        ..Default::default()
+                                    },
+                                },
+                                ..Default::default()
+                            }),
+                        },
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+            })
+            .operation("/health", Method::GET, |_| Ok(Operation::default()))
+            .tag(Tag {
+                name: "users".into(),
+                description: Some("Users management".into()),
+                ..Default::default()
+            });
+        let spec = builder.build().expect("Failed to build spec");
+
+        assert_eq!(
+            super::markdown(&spec),
+            "# Users 1.0.0
+
+## users
+
+Users management
+
+### `GET /users/{id}`
+
+Get user
+
+#### Parameters
+
+| Name | In | Required | Type | Description |
+| --- | --- | --- | --- | --- |
+| `id` | path | yes | integer (uint64) | ID of user \\| UUID |
+
+#### Responses
+
+| Status | Description | Content |
+| --- | --- | --- |
+| 200 | User | `application/json` (array of string) |
+
+## Other
+
+### `GET /health`
+"
+        );
+    }
+}
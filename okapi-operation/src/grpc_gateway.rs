@@ -0,0 +1,810 @@
+//! Import of operations from [`google.api.http`](https://cloud.google.com/endpoints/docs/grpc-service-config/reference/rpc/google.api#httprule)
+//! annotations of gRPC services (as used by gRPC-gateway and similar transcoding proxies).
+//!
+//! Annotations are read from serialized `FileDescriptorSet`, which could be produced with
+//! `prost_build::Config::file_descriptor_set_path` (or `protoc --descriptor_set_out`
+//! with `--include_imports`):
+//!
+//! ```rust,ignore
+//! // build.rs
+//! prost_build::Config::new()
+//!     .file_descriptor_set_path(format!("{}/descriptor.bin", std::env::var("OUT_DIR")?))
+//!     .compile_protos(&["proto/users.proto"], &["proto"])?;
+//!
+//! // main.rs
+//! let mut builder = OpenApiBuilder::new("Users", "1.0.0");
+//! builder.include_grpc_gateway(include_bytes!(concat!(env!("OUT_DIR"), "/descriptor.bin")))?;
+//! ```
+//!
+//! Request and response messages are converted into schemas according to
+//! [JSON mapping](https://protobuf.dev/programming-guides/proto3/#json) of Protobuf.
+
+use std::collections::HashMap;
+
+use okapi::{
+    openapi3::{
+        Components, MediaType, OpenApi, Operation, PathItem, RefOr, RequestBody, Response,
+        Responses,
+    },
+    schemars::schema::{
+        ArrayValidation, InstanceType, ObjectValidation, Schema, SchemaObject, SingleOrVec,
+    },
+    Map,
+};
+use prost::Message;
+
+use crate::{
+    header::{header, parameter},
+    walk::{insert_path_item_operation, SCHEMA_REFERENCE_PREFIX},
+};
+
+/// Convert `google.api.http` annotations of services from serialized `FileDescriptorSet`
+/// into specification.
+///
+/// Every HTTP binding (including additional bindings) of method becomes operation with
+/// `operationId` `<Service>_<Method>` (with numeric suffix for additional bindings), tagged
+/// with name of service. Fields of request message are bound to path, body and query
+/// parameters according to annotation. Schemas of messages and enums are put into components
+/// under their full names (like `users.v1.User`) and referenced with `#/components/schemas/`
+/// prefix. Methods without annotations are skipped.
+pub fn import(descriptor_set: &[u8]) -> Result<OpenApi, prost::DecodeError> {
+    import_with_definitions_path(descriptor_set, SCHEMA_REFERENCE_PREFIX)
+}
+
+/// Same as [`import`], but schemas are referenced with specified definitions path (see
+/// [`ComponentsBuilder::definitions_path`](crate::ComponentsBuilder::definitions_path)).
+pub(crate) fn import_with_definitions_path(
+    descriptor_set: &[u8],
+    definitions_path: &str,
+) -> Result<OpenApi, prost::DecodeError> {
+    let set = FileDescriptorSet::decode(descriptor_set)?;
+    let mut importer = Importer {
+        types: TypeIndex::new(&set),
+        components: Components::default(),
+        definitions_path,
+    };
+    let mut spec = OpenApi::default();
+    for file in &set.file {
+        for service in &file.service {
+            let service_name = service.name.as_deref().unwrap_or_default();
+            for method in &service.method {
+                let Some(rule) = method.options.as_ref().and_then(|x| x.http.as_ref()) else {
+                    continue;
+                };
+                let operation_id = format!(
+                    "{service_name}_{}",
+                    method.name.as_deref().unwrap_or_default()
+                );
+                let bindings = std::iter::once(rule).chain(&rule.additional_bindings);
+                for (idx, binding) in bindings.enumerate() {
+                    let Some((http_method, template)) = binding.pattern() else {
+                        continue;
+                    };
+                    let (path, path_fields) = convert_path_template(template);
+                    let mut operation = importer.operation(method, binding, &path_fields);
+                    operation.tags = vec![service_name.into()];
+                    operation.operation_id = Some(if idx == 0 {
+                        operation_id.clone()
+                    } else {
+                        format!("{operation_id}{}", idx + 1)
+                    });
+                    let item = spec.paths.entry(path).or_default();
+                    set_operation(item, &http_method, operation);
+                }
+            }
+        }
+    }
+    spec.components = Some(importer.components);
+    Ok(spec)
+}
+
+/// Put operation into path item (operations of custom methods are put into
+/// `x-additionalOperations` extension).
+fn set_operation(item: &mut PathItem, method: &str, operation: Operation) {
+    if let Ok(method) = http::Method::from_bytes(method.as_bytes()) {
+        let _ = insert_path_item_operation(item, &method, operation);
+    }
+}
+
+/// Convert gRPC path template (`/v1/{name=users/*}:get`) into OpenAPI path
+/// (`/v1/{name}:get`), returning names of bound fields.
+fn convert_path_template(template: &str) -> (String, Vec<String>) {
+    let mut path = String::with_capacity(template.len());
+    let mut fields = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        path.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let variable = &rest[start + 1..start + len];
+        let field = variable.split('=').next().unwrap_or_default().trim();
+        path.push('{');
+        path.push_str(field);
+        path.push('}');
+        fields.push(field.to_owned());
+        rest = &rest[start + len + 1..];
+    }
+    path.push_str(rest);
+    (path, fields)
+}
+
+struct Importer<'a> {
+    types: TypeIndex<'a>,
+    components: Components,
+    definitions_path: &'a str,
+}
+
+impl Importer<'_> {
+    fn operation(
+        &mut self,
+        method: &MethodDescriptorProto,
+        rule: &HttpRule,
+        path_fields: &[String],
+    ) -> Operation {
+        let input_type = method.input_type.as_deref().unwrap_or_default();
+        let output_type = method.output_type.as_deref().unwrap_or_default();
+        let mut parameters = Vec::new();
+        for field in path_fields {
+            let schema = self
+                .field_path_schema(input_type, field)
+                .unwrap_or_else(|| instance_type(InstanceType::String, None));
+            parameters.push(parameter(field, "path", header(schema, None, true)));
+        }
+
+        let body = rule.body.as_deref().unwrap_or_default();
+        let request_body = match body {
+            "" => None,
+            "*" => Some(self.message_schema(input_type)),
+            field => self.field_path_schema(input_type, field),
+        };
+        if body != "*" {
+            for field in self.types.message_fields(input_type) {
+                let name = field.name.as_deref().unwrap_or_default();
+                if name == body || path_fields.iter().any(|x| x == name) {
+                    continue;
+                }
+                // Messages can't be passed in query (except well-known wrappers)
+                if field.is_message() && well_known_schema(field.type_ref()).is_none() {
+                    continue;
+                }
+                let schema = self.field_schema(field);
+                parameters.push(parameter(
+                    field.json_field_name(),
+                    "query",
+                    header(schema, None, false),
+                ));
+            }
+        }
+
+        let response_schema = match rule.response_body.as_deref().unwrap_or_default() {
+            "" => Some(self.message_schema(output_type)),
+            field => self.field_path_schema(output_type, field),
+        };
+
+        Operation {
+            parameters: parameters.into_iter().map(RefOr::Object).collect(),
+            request_body: request_body.map(|schema| {
+                RefOr::Object(RequestBody {
+                    content: json_content(schema),
+                    required: true,
+                    ..Default::default()
+                })
+            }),
+            responses: Responses {
+                responses: okapi::map! {
+                    "200".into() => RefOr::Object(Response {
+                        description: "A successful response.".into(),
+                        content: response_schema.map(json_content).unwrap_or_default(),
+                        ..Default::default()
+                    }),
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Schema of (possibly nested, like `user.id`) field of message.
+    fn field_path_schema(&mut self, message: &str, path: &str) -> Option<SchemaObject> {
+        let mut message = message.to_owned();
+        let mut segments = path.split('.').peekable();
+        while let Some(segment) = segments.next() {
+            let field = self
+                .types
+                .message_fields(&message)
+                .iter()
+                .find(|x| x.name.as_deref() == Some(segment))?;
+            if segments.peek().is_none() {
+                return Some(self.field_schema(field));
+            }
+            message = field.type_ref().to_owned();
+        }
+        None
+    }
+
+    fn field_schema(&mut self, field: &FieldDescriptorProto) -> SchemaObject {
+        if let Some(entry) = self.types.map_entry(field.type_ref()) {
+            let value = entry.iter().find(|x| x.number == Some(2));
+            let value = value.map_or_else(SchemaObject::default, |x| self.field_schema(x));
+            return SchemaObject {
+                instance_type: Some(InstanceType::Object.into()),
+                object: Some(Box::new(ObjectValidation {
+                    additional_properties: Some(Box::new(Schema::Object(value))),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            };
+        }
+        let schema = self.single_field_schema(field);
+        if field.label == Some(LABEL_REPEATED) {
+            SchemaObject {
+                instance_type: Some(InstanceType::Array.into()),
+                array: Some(Box::new(ArrayValidation {
+                    items: Some(SingleOrVec::Single(Box::new(Schema::Object(schema)))),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }
+        } else {
+            schema
+        }
+    }
+
+    fn single_field_schema(&mut self, field: &FieldDescriptorProto) -> SchemaObject {
+        use InstanceType::*;
+
+        match field.r#type.unwrap_or_default() {
+            TYPE_DOUBLE => instance_type(Number, Some("double")),
+            TYPE_FLOAT => instance_type(Number, Some("float")),
+            TYPE_INT64 | TYPE_SFIXED64 | TYPE_SINT64 => instance_type(String, Some("int64")),
+            TYPE_UINT64 | TYPE_FIXED64 => instance_type(String, Some("uint64")),
+            TYPE_INT32 | TYPE_SFIXED32 | TYPE_SINT32 => instance_type(Integer, Some("int32")),
+            TYPE_UINT32 | TYPE_FIXED32 => instance_type(Integer, Some("uint32")),
+            TYPE_BOOL => instance_type(Boolean, None),
+            TYPE_STRING => instance_type(String, None),
+            TYPE_BYTES => instance_type(String, Some("byte")),
+            TYPE_ENUM => self.enum_schema(field.type_ref()),
+            TYPE_MESSAGE | TYPE_GROUP => self.message_schema(field.type_ref()),
+            _ => SchemaObject::default(),
+        }
+    }
+
+    /// Reference to schema of message (generating it, if needed).
+    fn message_schema(&mut self, type_name: &str) -> SchemaObject {
+        if let Some(schema) = well_known_schema(type_name) {
+            return schema;
+        }
+        let name = type_name.trim_start_matches('.');
+        if !self.components.schemas.contains_key(name) {
+            // Insert placeholder first, so recursive messages are referenced
+            let _ = self
+                .components
+                .schemas
+                .insert(name.into(), SchemaObject::default());
+            let mut properties = Map::new();
+            for field in self.types.message_fields(type_name) {
+                let _ = properties.insert(
+                    field.json_field_name(),
+                    Schema::Object(self.field_schema(field)),
+                );
+            }
+            let schema = SchemaObject {
+                instance_type: Some(InstanceType::Object.into()),
+                object: Some(Box::new(ObjectValidation {
+                    properties,
+                    ..Default::default()
+                })),
+                ..Default::default()
+            };
+            let _ = self.components.schemas.insert(name.into(), schema);
+        }
+        SchemaObject::new_ref(format!("{}{name}", self.definitions_path))
+    }
+
+    /// Reference to schema of enum (generating it, if needed).
+    fn enum_schema(&mut self, type_name: &str) -> SchemaObject {
+        if type_name == ".google.protobuf.NullValue" {
+            return instance_type(InstanceType::Null, None);
+        }
+        let name = type_name.trim_start_matches('.');
+        if !self.components.schemas.contains_key(name) {
+            let values = self
+                .types
+                .enums
+                .get(type_name)
+                .map(|x| {
+                    x.value
+                        .iter()
+                        .filter_map(|x| x.name.clone().map(serde_json::Value::String))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let schema = SchemaObject {
+                enum_values: Some(values),
+                ..instance_type(InstanceType::String, None)
+            };
+            let _ = self.components.schemas.insert(name.into(), schema);
+        }
+        SchemaObject::new_ref(format!("{}{name}", self.definitions_path))
+    }
+}
+
+/// Schemas of well-known types, which have special JSON representation.
+fn well_known_schema(type_name: &str) -> Option<SchemaObject> {
+    use InstanceType::*;
+
+    let schema = match type_name {
+        ".google.protobuf.Timestamp" => instance_type(String, Some("date-time")),
+        ".google.protobuf.Duration" | ".google.protobuf.FieldMask" => instance_type(String, None),
+        ".google.protobuf.Struct" | ".google.protobuf.Empty" | ".google.protobuf.Any" => {
+            instance_type(Object, None)
+        }
+        ".google.protobuf.Value" => SchemaObject::default(),
+        ".google.protobuf.ListValue" => instance_type(Array, None),
+        ".google.protobuf.DoubleValue" => instance_type(Number, Some("double")),
+        ".google.protobuf.FloatValue" => instance_type(Number, Some("float")),
+        ".google.protobuf.Int64Value" => instance_type(String, Some("int64")),
+        ".google.protobuf.UInt64Value" => instance_type(String, Some("uint64")),
+        ".google.protobuf.Int32Value" => instance_type(Integer, Some("int32")),
+        ".google.protobuf.UInt32Value" => instance_type(Integer, Some("uint32")),
+        ".google.protobuf.BoolValue" => instance_type(Boolean, None),
+        ".google.protobuf.StringValue" => instance_type(String, None),
+        ".google.protobuf.BytesValue" => instance_type(String, Some("byte")),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+fn instance_type(ty: InstanceType, format: Option<&str>) -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(ty.into()),
+        format: format.map(Into::into),
+        ..Default::default()
+    }
+}
+
+fn json_content(schema: SchemaObject) -> Map<String, MediaType> {
+    okapi::map! {
+        "application/json".into() => MediaType {
+            schema: Some(schema),
+            ..Default::default()
+        },
+    }
+}
+
+/// Messages and enums by full name (like `.users.v1.User`).
+struct TypeIndex<'a> {
+    messages: HashMap<String, &'a DescriptorProto>,
+    enums: HashMap<String, &'a EnumDescriptorProto>,
+}
+
+impl<'a> TypeIndex<'a> {
+    fn new(set: &'a FileDescriptorSet) -> Self {
+        let mut index = Self {
+            messages: HashMap::new(),
+            enums: HashMap::new(),
+        };
+        for file in &set.file {
+            let prefix = match file.package.as_deref() {
+                Some(package) if !package.is_empty() => format!(".{package}"),
+                _ => String::new(),
+            };
+            for message in &file.message_type {
+                index.add_message(&prefix, message);
+            }
+            for value in &file.enum_type {
+                index.add_enum(&prefix, value);
+            }
+        }
+        index
+    }
+
+    fn add_message(&mut self, prefix: &str, message: &'a DescriptorProto) {
+        let name = format!("{prefix}.{}", message.name.as_deref().unwrap_or_default());
+        for nested in &message.nested_type {
+            self.add_message(&name, nested);
+        }
+        for value in &message.enum_type {
+            self.add_enum(&name, value);
+        }
+        let _ = self.messages.insert(name, message);
+    }
+
+    fn add_enum(&mut self, prefix: &str, value: &'a EnumDescriptorProto) {
+        let name = format!("{prefix}.{}", value.name.as_deref().unwrap_or_default());
+        let _ = self.enums.insert(name, value);
+    }
+
+    fn message_fields(&self, type_name: &str) -> &'a [FieldDescriptorProto] {
+        self.messages
+            .get(type_name)
+            .map(|x| x.field.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Fields of map entry message (`key = 1`, `value = 2`).
+    fn map_entry(&self, type_name: &str) -> Option<&'a [FieldDescriptorProto]> {
+        let message = self.messages.get(type_name)?;
+        message
+            .options
+            .as_ref()
+            .and_then(|x| x.map_entry)
+            .unwrap_or_default()
+            .then_some(message.field.as_slice())
+    }
+}
+
+// Minimal subset of `google/protobuf/descriptor.proto` and `google/api/http.proto`.
+// `prost-types` can't be used, since it drops `google.api.http` extension of method options.
+
+const LABEL_REPEATED: i32 = 3;
+
+const TYPE_DOUBLE: i32 = 1;
+const TYPE_FLOAT: i32 = 2;
+const TYPE_INT64: i32 = 3;
+const TYPE_UINT64: i32 = 4;
+const TYPE_INT32: i32 = 5;
+const TYPE_FIXED64: i32 = 6;
+const TYPE_FIXED32: i32 = 7;
+const TYPE_BOOL: i32 = 8;
+const TYPE_STRING: i32 = 9;
+const TYPE_GROUP: i32 = 10;
+const TYPE_MESSAGE: i32 = 11;
+const TYPE_BYTES: i32 = 12;
+const TYPE_UINT32: i32 = 13;
+const TYPE_ENUM: i32 = 14;
+const TYPE_SFIXED32: i32 = 15;
+const TYPE_SFIXED64: i32 = 16;
+const TYPE_SINT32: i32 = 17;
+const TYPE_SINT64: i32 = 18;
+
+#[derive(Clone, PartialEq, Message)]
+struct FileDescriptorSet {
+    #[prost(message, repeated, tag = "1")]
+    file: Vec<FileDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct FileDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    package: Option<String>,
+    #[prost(message, repeated, tag = "4")]
+    message_type: Vec<DescriptorProto>,
+    #[prost(message, repeated, tag = "5")]
+    enum_type: Vec<EnumDescriptorProto>,
+    #[prost(message, repeated, tag = "6")]
+    service: Vec<ServiceDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct DescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    field: Vec<FieldDescriptorProto>,
+    #[prost(message, repeated, tag = "3")]
+    nested_type: Vec<DescriptorProto>,
+    #[prost(message, repeated, tag = "4")]
+    enum_type: Vec<EnumDescriptorProto>,
+    #[prost(message, optional, tag = "7")]
+    options: Option<MessageOptions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MessageOptions {
+    #[prost(bool, optional, tag = "7")]
+    map_entry: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct FieldDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(int32, optional, tag = "3")]
+    number: Option<i32>,
+    #[prost(int32, optional, tag = "4")]
+    label: Option<i32>,
+    #[prost(int32, optional, tag = "5")]
+    r#type: Option<i32>,
+    #[prost(string, optional, tag = "6")]
+    type_name: Option<String>,
+    #[prost(string, optional, tag = "10")]
+    json_name: Option<String>,
+}
+
+impl FieldDescriptorProto {
+    fn type_ref(&self) -> &str {
+        self.type_name.as_deref().unwrap_or_default()
+    }
+
+    fn is_message(&self) -> bool {
+        matches!(self.r#type, Some(TYPE_MESSAGE | TYPE_GROUP))
+    }
+
+    /// Name of field in JSON (`lowerCamelCase` of field name, unless specified explicitly).
+    fn json_field_name(&self) -> String {
+        if let Some(ref json_name) = self.json_name {
+            return json_name.clone();
+        }
+        let mut result = String::new();
+        let mut upper = false;
+        for c in self.name.as_deref().unwrap_or_default().chars() {
+            if c == '_' {
+                upper = true;
+            } else if upper {
+                result.push(c.to_ascii_uppercase());
+                upper = false;
+            } else {
+                result.push(c);
+            }
+        }
+        result
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct EnumDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    value: Vec<EnumValueDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct EnumValueDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ServiceDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    method: Vec<MethodDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MethodDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    input_type: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    output_type: Option<String>,
+    #[prost(message, optional, tag = "4")]
+    options: Option<MethodOptions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MethodOptions {
+    /// `google.api.http` extension.
+    #[prost(message, optional, tag = "72295728")]
+    http: Option<HttpRule>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct HttpRule {
+    #[prost(string, optional, tag = "2")]
+    get: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    put: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    post: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    delete: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    patch: Option<String>,
+    #[prost(message, optional, tag = "8")]
+    custom: Option<CustomHttpPattern>,
+    #[prost(string, optional, tag = "7")]
+    body: Option<String>,
+    #[prost(string, optional, tag = "12")]
+    response_body: Option<String>,
+    #[prost(message, repeated, tag = "11")]
+    additional_bindings: Vec<HttpRule>,
+}
+
+impl HttpRule {
+    /// Method (in uppercase) and path template.
+    fn pattern(&self) -> Option<(String, &str)> {
+        let simple = [
+            ("GET", &self.get),
+            ("PUT", &self.put),
+            ("POST", &self.post),
+            ("DELETE", &self.delete),
+            ("PATCH", &self.patch),
+        ];
+        if let Some((method, path)) = simple
+            .into_iter()
+            .find_map(|(method, path)| Some((method, path.as_deref()?)))
+        {
+            return Some((method.into(), path));
+        }
+        let custom = self.custom.as_ref()?;
+        Some((
+            custom.kind.as_deref()?.to_uppercase(),
+            custom.path.as_deref()?,
+        ))
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct CustomHttpPattern {
+    #[prost(string, optional, tag = "1")]
+    kind: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    path: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn field(name: &str, number: i32, ty: i32, type_name: Option<&str>) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.into()),
+            number: Some(number),
+            label: Some(1),
+            r#type: Some(ty),
+            type_name: type_name.map(Into::into),
+            json_name: None,
+        }
+    }
+
+    fn method(name: &str, input: &str, http: HttpRule) -> MethodDescriptorProto {
+        MethodDescriptorProto {
+            name: Some(name.into()),
+            input_type: Some(format!(".users.v1.{input}")),
+            output_type: Some(".users.v1.User".into()),
+            options: Some(MethodOptions { http: Some(http) }),
+        }
+    }
+
+    fn descriptor_set() -> Vec<u8> {
+        let message = |name: &str, field: Vec<FieldDescriptorProto>| DescriptorProto {
+            name: Some(name.into()),
+            field,
+            ..Default::default()
+        };
+        FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("users.proto".into()),
+                package: Some("users.v1".into()),
+                message_type: vec![
+                    message(
+                        "User",
+                        vec![
+                            field("user_id", 1, TYPE_INT64, None),
+                            field("status", 2, TYPE_ENUM, Some(".users.v1.Status")),
+                            field(
+                                "created_at",
+                                3,
+                                TYPE_MESSAGE,
+                                Some(".google.protobuf.Timestamp"),
+                            ),
+                        ],
+                    ),
+                    message(
+                        "GetUserRequest",
+                        vec![
+                            field("user_id", 1, TYPE_INT64, None),
+                            field("view", 2, TYPE_STRING, None),
+                        ],
+                    ),
+                    message(
+                        "UpdateUserRequest",
+                        vec![
+                            field("user_id", 1, TYPE_INT64, None),
+                            field("user", 2, TYPE_MESSAGE, Some(".users.v1.User")),
+                        ],
+                    ),
+                ],
+                enum_type: vec![EnumDescriptorProto {
+                    name: Some("Status".into()),
+                    value: vec![
+                        EnumValueDescriptorProto {
+                            name: Some("ACTIVE".into()),
+                        },
+                        EnumValueDescriptorProto {
+                            name: Some("BLOCKED".into()),
+                        },
+                    ],
+                }],
+                service: vec![ServiceDescriptorProto {
+                    name: Some("Users".into()),
+                    method: vec![
+                        method(
+                            "GetUser",
+                            "GetUserRequest",
+                            HttpRule {
+                                get: Some("/v1/users/{user_id}".into()),
+                                additional_bindings: vec![HttpRule {
+                                    get: Some("/v1/{user_id=users/*}:get".into()),
+                                    ..Default::default()
+                                }],
+                                ..Default::default()
+                            },
+                        ),
+                        method(
+                            "UpdateUser",
+                            "UpdateUserRequest",
+                            HttpRule {
+                                patch: Some("/v1/users/{user_id}".into()),
+                                body: Some("user".into()),
+                                ..Default::default()
+                            },
+                        ),
+                        MethodDescriptorProto {
+                            name: Some("Internal".into()),
+                            ..Default::default()
+                        },
+                    ],
+                }],
+            }],
+        }
+        .encode_to_vec()
+    }
+
+    #[test]
+    fn path_template() {
+        assert_eq!(
+            convert_path_template("/v1/{name=projects/*/users/*}/{user.id}:get"),
+            (
+                "/v1/{name}/{user.id}:get".to_owned(),
+                vec!["name".to_owned(), "user.id".to_owned()]
+            )
+        );
+    }
+
+    #[test]
+    fn import_descriptor_set() {
+        let spec = import(&descriptor_set()).unwrap();
+        let spec = serde_json::to_value(spec).unwrap();
+        assert_eq!(
+            spec["paths"]["/v1/users/{user_id}"]["get"],
+            json!({
+                "tags": ["Users"],
+                "operationId": "Users_GetUser",
+                "parameters": [
+                    {"name": "user_id", "in": "path", "required": true, "schema": {"type": "string", "format": "int64"}},
+                    {"name": "view", "in": "query", "schema": {"type": "string"}}
+                ],
+                "responses": {
+                    "200": {
+                        "description": "A successful response.",
+                        "content": {"application/json": {"schema": {"$ref": "#/components/schemas/users.v1.User"}}}
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            spec["paths"]["/v1/users/{user_id}"]["patch"]["requestBody"]["content"]
+                ["application/json"]["schema"],
+            json!({"$ref": "#/components/schemas/users.v1.User"})
+        );
+        assert_eq!(
+            spec["paths"]["/v1/{user_id}:get"]["get"]["operationId"],
+            "Users_GetUser2"
+        );
+        assert_eq!(
+            spec["components"]["schemas"],
+            json!({
+                "users.v1.User": {
+                    "type": "object",
+                    "properties": {
+                        "userId": {"type": "string", "format": "int64"},
+                        "status": {"$ref": "#/components/schemas/users.v1.Status"},
+                        "createdAt": {"type": "string", "format": "date-time"}
+                    }
+                },
+                "users.v1.Status": {"type": "string", "enum": ["ACTIVE", "BLOCKED"]}
+            })
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Construction of header objects and parameters.
+
+use okapi::{
+    openapi3::{Header, Parameter, ParameterValue},
+    schemars::schema::SchemaObject,
+};
+
+/// Header object with specified schema.
+pub(crate) fn header(schema: SchemaObject, description: Option<&str>, required: bool) -> Header {
+    Header {
+        description: description.map(Into::into),
+        required,
+        deprecated: false,
+        allow_empty_value: false,
+        value: ParameterValue::Schema {
+            style: None,
+            explode: None,
+            allow_reserved: false,
+            schema,
+            example: None,
+            examples: None,
+        },
+        extensions: Default::default(),
+    }
+}
+
+/// Parameter with specified name and location.
+///
+/// Header object in OpenAPI is a parameter without name and location, so parameter is built
+/// from [`header`].
+pub(crate) fn parameter(name: impl Into<String>, location: &str, header: Header) -> Parameter {
+    Parameter {
+        name: name.into(),
+        location: location.into(),
+        description: header.description,
+        required: header.required,
+        deprecated: header.deprecated,
+        allow_empty_value: header.allow_empty_value,
+        value: header.value,
+        extensions: header.extensions,
+    }
+}
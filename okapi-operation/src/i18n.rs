@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, HashMap};
+
+use okapi::{
+    openapi3::OpenApi,
+    schemars::{
+        schema::SchemaObject,
+        visit::{visit_schema_object, Visitor},
+    },
+};
+
+use crate::walk::{walk_spec, SpecVisitor};
+
+/// Resolver of localized texts.
+///
+/// When set with [`OpenApiBuilder::description_resolver`](crate::OpenApiBuilder::description_resolver),
+/// every human-readable text of specification (title and description of API, summaries
+/// and descriptions of operations, parameters, responses and tags, titles and descriptions
+/// of schemas) is passed to resolver as key. Texts, for which resolver returns `None`, are
+/// left unchanged, so keys can be mixed with plain texts.
+///
+/// Implemented for maps from key to text and for functions, so specification for
+/// different locales can be built by replacing resolver:
+///
+/// ```rust
+/// # use okapi_operation::*;
+/// use std::collections::HashMap;
+///
+/// let mut builder = OpenApiBuilder::new("api.title", "1.0.0");
+/// let de = HashMap::from([("api.title".to_owned(), "Beispiel-API".to_owned())]);
+/// let spec = builder.description_resolver(de).build().unwrap();
+/// assert_eq!(spec.info.title, "Beispiel-API");
+/// ```
+pub trait DescriptionResolver: Send + Sync {
+    /// Resolve text by key.
+    fn resolve(&self, key: &str) -> Option<String>;
+}
+
+impl<F> DescriptionResolver for F
+where
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    fn resolve(&self, key: &str) -> Option<String> {
+        self(key)
+    }
+}
+
+impl<S: std::hash::BuildHasher + Send + Sync> DescriptionResolver for HashMap<String, String, S> {
+    fn resolve(&self, key: &str) -> Option<String> {
+        self.get(key).cloned()
+    }
+}
+
+impl DescriptionResolver for BTreeMap<String, String> {
+    fn resolve(&self, key: &str) -> Option<String> {
+        self.get(key).cloned()
+    }
+}
+
+/// Replace texts of specification, which are keys of resolver.
+pub(crate) fn resolve_descriptions(spec: &mut OpenApi, resolver: &dyn DescriptionResolver) {
+    struct ResolveVisitor<'a> {
+        resolver: &'a dyn DescriptionResolver,
+    }
+
+    impl ResolveVisitor<'_> {
+        fn resolve_optional(&mut self, text: &mut Option<String>) {
+            if let Some(text) = text.as_mut() {
+                self.visit_text(text);
+            }
+        }
+    }
+
+    impl Visitor for ResolveVisitor<'_> {
+        fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+            if let Some(metadata) = schema.metadata.as_mut() {
+                self.resolve_optional(&mut metadata.title);
+                self.resolve_optional(&mut metadata.description);
+            }
+            visit_schema_object(self, schema)
+        }
+    }
+
+    impl SpecVisitor for ResolveVisitor<'_> {
+        fn visit_text(&mut self, text: &mut String) {
+            if let Some(resolved) = self.resolver.resolve(text) {
+                *text = resolved;
+            }
+        }
+    }
+
+    let mut visitor = ResolveVisitor { resolver };
+    visitor.visit_text(&mut spec.info.title);
+    visitor.resolve_optional(&mut spec.info.description);
+    for tag in &mut spec.tags {
+        visitor.resolve_optional(&mut tag.description);
+    }
+    for server in &mut spec.servers {
+        visitor.resolve_optional(&mut server.description);
+    }
+    walk_spec(&mut visitor, spec);
+}
+
+#[cfg(test)]
+mod tests {
+    use okapi::openapi3::{Operation, RefOr, Response, Tag};
+
+    use super::*;
+
+    #[test]
+    fn resolve() {
+        let mut spec = OpenApi::default();
+        spec.tags.push(Tag {
+            name: "users".into(),
+            description: Some("tags.users".into()),
+            ..Default::default()
+        });
+        let mut operation = Operation {
+            summary: Some("users.get.summary".into()),
+            description: Some("Not a key".into()),
+            ..Default::default()
+        };
+        let _ = operation.responses.responses.insert(
+            "200".into(),
+            RefOr::Object(Response {
+                description: "users.get.ok".into(),
+                ..Default::default()
+            }),
+        );
+        spec.paths.entry("/users".into()).or_default().get = Some(operation);
+
+        let resolver = |key: &str| key.ends_with(".summary").then(|| format!("[{key}]"));
+        resolve_descriptions(&mut spec, &resolver);
+        let operation = spec.paths["/users"].get.as_ref().unwrap();
+        assert_eq!(operation.summary.as_deref(), Some("[users.get.summary]"));
+        assert_eq!(operation.description.as_deref(), Some("Not a key"));
+
+        let resolver = BTreeMap::from([
+            ("tags.users".to_owned(), "Benutzer".to_owned()),
+            ("users.get.ok".to_owned(), "Erfolg".to_owned()),
+        ]);
+        resolve_descriptions(&mut spec, &resolver);
+        assert_eq!(spec.tags[0].description.as_deref(), Some("Benutzer"));
+        let operation = spec.paths["/users"].get.as_ref().unwrap();
+        assert!(matches!(
+            &operation.responses.responses["200"],
+            RefOr::Object(x) if x.description == "Erfolg"
+        ));
+    }
+}
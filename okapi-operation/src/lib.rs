@@ -12,34 +12,135 @@ pub use okapi::{
 #[doc(inline)]
 pub use okapi_operation_macro::openapi;
 
+#[cfg(feature = "asyncapi")]
+pub mod asyncapi;
 #[cfg(feature = "axum")]
 pub mod axum_integration;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+pub mod export;
+#[cfg(feature = "grpc-gateway")]
+pub mod grpc_gateway;
+pub mod lint;
+pub mod media;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod resolve;
+pub mod spec;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use std::{future::Future, pin::Pin};
 
 use okapi::openapi3::Operation;
 
+#[cfg(feature = "auto-collect")]
+pub use self::auto_collect::{collected_operations, CollectedOperation};
+#[cfg(feature = "yaml")]
+pub use self::canonical::to_canonical_yaml;
+#[cfg(feature = "problem-details")]
+pub use self::problem_details::ProblemDetails;
 pub use self::{
-    builder::OpenApiBuilder,
-    components::{Components, ComponentsBuilder},
+    api_component::ApiComponent,
+    audience::Audience,
+    build_warning::BuildWarning,
+    builder::{FrozenSpec, OpenApiBuilder, SortMode},
+    builder_options::{
+        BuilderOptions, CustomMethods, DuplicateOperationId, OperationIdStrategy, TrailingSlash,
+        WildcardRoutes,
+    },
+    canonical::to_canonical_json,
+    cargo_package::CargoPackage,
+    components::{Components, ComponentsBuilder, SchemaNameStrategy},
+    error::Error,
+    i18n::DescriptionResolver,
+    merge::{merge_responses, MergeStrategy},
+    nullable::NullableStrategy,
+    operation_meta::OperationMeta,
+    pagination::{CursorPage, Paginated},
+    rate_limit::RateLimited,
+    recursion::RecursionPolicy,
+    stats::SpecStats,
+    to_header_schema::ToHeaderSchema,
     to_media_types::ToMediaTypes,
-    to_responses::ToResponses,
+    to_responses::{OrStatus, ToResponses},
 };
 
+mod api_component;
+mod audience;
+#[cfg(feature = "auto-collect")]
+mod auto_collect;
+mod build_warning;
 mod builder;
+mod builder_options;
+mod canonical;
+mod cargo_package;
 mod components;
+mod discriminator;
+mod error;
+mod header;
+mod i18n;
+mod merge;
+mod nullable;
+mod operation_meta;
+mod pagination;
+#[cfg(feature = "macro")]
+mod patterns;
+#[cfg(feature = "problem-details")]
+mod problem_details;
+mod rate_limit;
+mod read_write;
+mod recursion;
+mod stats;
+mod to_header_schema;
 mod to_media_types;
 mod to_responses;
+mod walk;
 
 /// Empty type alias (for using in attribute values).
 pub type Empty = ();
 
 // TODO: allow return RefOr<Operation>
 /// Operation generator signature.
-pub type OperationGenerator = fn(&mut Components) -> Result<Operation, anyhow::Error>;
+pub type OperationGenerator = fn(&mut Components) -> Result<Operation, Error>;
+
+/// Asynchronous operation generator signature.
+///
+/// Used for generators, which need to perform asynchronous work (i.e. load examples from
+/// files or schema registry). Specification with such generators can be built only with
+/// [`OpenApiBuilder::build_async`].
+pub type AsyncOperationGenerator =
+    for<'a> fn(&'a mut Components) -> Pin<Box<dyn Future<Output = Result<Operation, Error>> + 'a>>;
+
+/// Synchronous or asynchronous operation generator.
+#[derive(Clone, Copy, Debug)]
+pub enum AnyOperationGenerator {
+    Sync(OperationGenerator),
+    Async(AsyncOperationGenerator),
+}
 
 #[cfg(feature = "macro")]
 #[doc(hidden)]
 pub mod _macro_prelude {
+    #[cfg(feature = "auto-collect")]
+    pub use inventory;
     pub use okapi;
 
-    pub use crate::{Components, ToMediaTypes, ToResponses};
+    pub use crate::{
+        components::{
+            apply_schema_constraints, component_reference, schema_from_json, OperationContext,
+            SchemaConstraints,
+        },
+        media::{set_xml_object, XmlObject},
+        merge_responses,
+        patterns::{document_conditional_etag, document_idempotency_key},
+        to_header_schema::{
+            DocumentTypedHeader, SkipTypedHeader, ToHeaderSchema, TypedHeaderProbe,
+        },
+        to_media_types::{merge_media_types, override_content_type},
+        to_responses::{generate_responses_for_future_output, generate_responses_for_output},
+        Components, MergeStrategy, ToMediaTypes, ToResponses,
+    };
 }
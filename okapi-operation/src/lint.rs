@@ -0,0 +1,289 @@
+//! Linting of specification.
+//!
+//! Implements common style rules (like ones of [Spectral](https://github.com/stoplightio/spectral)
+//! OpenAPI ruleset), so they can be checked in tests against output of builder:
+//!
+//! ```rust
+//! # use okapi_operation::*;
+//! let spec = OpenApiBuilder::new("Demo", "1.0.0").build()?;
+//! let violations = lint::run(&spec, lint::RuleSet::all());
+//! assert!(violations.is_empty(), "{violations:#?}");
+//! # Ok::<(), Error>(())
+//! ```
+
+use std::{collections::HashSet, fmt};
+
+use okapi::openapi3::{OpenApi, Operation};
+
+use crate::walk::{escape_pointer, path_item_operations};
+
+/// Lint rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Rule {
+    /// Operation should have non-empty description.
+    OperationDescription,
+    /// Tags of operation should be declared in `tags` of specification.
+    OperationTagDefined,
+    /// Operation should have at least one response.
+    OperationResponses,
+    /// `operationId` should be in `snake_case`.
+    OperationIdSnakeCase,
+}
+
+impl Rule {
+    /// All rules.
+    pub const ALL: &'static [Rule] = &[
+        Self::OperationDescription,
+        Self::OperationTagDefined,
+        Self::OperationResponses,
+        Self::OperationIdSnakeCase,
+    ];
+
+    /// Name of rule (in `kebab-case`, like `operation-description`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OperationDescription => "operation-description",
+            Self::OperationTagDefined => "operation-tag-defined",
+            Self::OperationResponses => "operation-responses",
+            Self::OperationIdSnakeCase => "operation-id-snake-case",
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Set of rules to check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// All rules.
+    pub fn all() -> Self {
+        Self {
+            rules: Rule::ALL.to_vec(),
+        }
+    }
+
+    /// No rules.
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Add rule to set.
+    pub fn with(mut self, rule: Rule) -> Self {
+        if !self.contains(rule) {
+            self.rules.push(rule);
+        }
+        self
+    }
+
+    /// Remove rule from set.
+    pub fn without(mut self, rule: Rule) -> Self {
+        self.rules.retain(|x| *x != rule);
+        self
+    }
+
+    /// Whether rule is in set.
+    pub fn contains(&self, rule: Rule) -> bool {
+        self.rules.contains(&rule)
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Violation of lint rule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Violated rule.
+    pub rule: Rule,
+    /// JSON pointer to violating value (like `/paths/~1users/get/description`).
+    pub pointer: String,
+    /// Description of violation.
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.pointer, self.message, self.rule)
+    }
+}
+
+/// Check specification against rules, returning violations (in order of paths and operations).
+pub fn run(spec: &OpenApi, rules: RuleSet) -> Vec<Violation> {
+    let declared_tags: HashSet<_> = spec.tags.iter().map(|x| x.name.as_str()).collect();
+    let mut violations = Vec::new();
+    for (path, item) in &spec.paths {
+        for (method, operation) in path_item_operations(item) {
+            let pointer = format!(
+                "/paths/{}/{}",
+                escape_pointer(path),
+                method.as_str().to_ascii_lowercase()
+            );
+            check_operation(&rules, &declared_tags, &pointer, operation, &mut violations);
+        }
+    }
+    violations
+}
+
+fn check_operation(
+    rules: &RuleSet,
+    declared_tags: &HashSet<&str>,
+    pointer: &str,
+    operation: &Operation,
+    violations: &mut Vec<Violation>,
+) {
+    let mut violation = |rule, suffix: &str, message: String| {
+        if rules.contains(rule) {
+            violations.push(Violation {
+                rule,
+                pointer: format!("{pointer}{suffix}"),
+                message,
+            });
+        }
+    };
+
+    if operation
+        .description
+        .as_deref()
+        .is_none_or(|x| x.trim().is_empty())
+    {
+        violation(
+            Rule::OperationDescription,
+            "/description",
+            "Operation should have description".into(),
+        );
+    }
+    for (idx, tag) in operation.tags.iter().enumerate() {
+        if !declared_tags.contains(tag.as_str()) {
+            violation(
+                Rule::OperationTagDefined,
+                &format!("/tags/{idx}"),
+                format!("Tag '{tag}' is not declared in specification"),
+            );
+        }
+    }
+    if operation.responses.responses.is_empty() && operation.responses.default.is_none() {
+        violation(
+            Rule::OperationResponses,
+            "/responses",
+            "Operation should have at least one response".into(),
+        );
+    }
+    if let Some(ref operation_id) = operation.operation_id {
+        if !is_snake_case(operation_id) {
+            violation(
+                Rule::OperationIdSnakeCase,
+                "/operationId",
+                format!("operationId '{operation_id}' should be in snake_case"),
+            );
+        }
+    }
+}
+
+fn is_snake_case(value: &str) -> bool {
+    value.starts_with(|c: char| c.is_ascii_lowercase())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !value.contains("__")
+        && !value.ends_with('_')
+}
+
+#[cfg(test)]
+mod tests {
+    use okapi::openapi3::{RefOr, Response, Tag};
+
+    use super::*;
+
+    #[test]
+    fn violations() {
+        let mut spec = OpenApi::default();
+        spec.tags.push(Tag {
+            name: "users".into(),
+            ..Default::default()
+        });
+        let mut valid = Operation {
+            description: Some("Get user".into()),
+            operation_id: Some("get_user".into()),
+            tags: vec!["users".into()],
+            ..Default::default()
+        };
+        let _ = valid
+            .responses
+            .responses
+            .insert("200".into(), RefOr::Object(Response::default()));
+        let invalid = Operation {
+            operation_id: Some("deleteUser".into()),
+            tags: vec!["users".into(), "admin".into()],
+            ..Default::default()
+        };
+        let item = spec.paths.entry("/users/{id}".into()).or_default();
+        item.get = Some(valid);
+        item.delete = Some(invalid);
+
+        let violations: Vec<_> = run(&spec, RuleSet::all())
+            .into_iter()
+            .map(|x| (x.rule, x.pointer))
+            .collect();
+        assert_eq!(
+            violations,
+            [
+                (
+                    Rule::OperationDescription,
+                    "/paths/~1users~1{id}/delete/description".into()
+                ),
+                (
+                    Rule::OperationTagDefined,
+                    "/paths/~1users~1{id}/delete/tags/1".into()
+                ),
+                (
+                    Rule::OperationResponses,
+                    "/paths/~1users~1{id}/delete/responses".into()
+                ),
+                (
+                    Rule::OperationIdSnakeCase,
+                    "/paths/~1users~1{id}/delete/operationId".into()
+                ),
+            ]
+        );
+
+        let violations = run(
+            &spec,
+            RuleSet::empty()
+                .with(Rule::OperationResponses)
+                .with(Rule::OperationIdSnakeCase)
+                .without(Rule::OperationIdSnakeCase),
+        );
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, Rule::OperationResponses);
+    }
+
+    #[test]
+    fn snake_case() {
+        for value in ["get", "get_user", "get_user_2"] {
+            assert!(is_snake_case(value), "{value}");
+        }
+        for value in [
+            "",
+            "getUser",
+            "Get",
+            "get__user",
+            "get_",
+            "_get",
+            "get-user",
+        ] {
+            assert!(!is_snake_case(value), "{value}");
+        }
+    }
+}
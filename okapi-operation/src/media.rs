@@ -0,0 +1,255 @@
+//! Wrappers for documenting non-JSON media types.
+//!
+//! Wrappers are enabled with corresponding features (`xml`, `msgpack`, `cbor`, `csv`).
+
+use okapi::{
+    openapi3::{MediaType, SchemaObject},
+    schemars::schema::{Schema, SubschemaValidation},
+    Map,
+};
+use serde::Serialize;
+
+use crate::Error;
+
+/// [XML object](https://spec.openapis.org/oas/v3.0.3#xml-object) of schema.
+///
+/// Describes how schema is represented in XML (name of element, namespace, etc.).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct XmlObject {
+    /// Name of element or attribute.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// URI of namespace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Prefix for name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Whether value is represented as attribute instead of element.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub attribute: bool,
+    /// Whether array is wrapped into element.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub wrapped: bool,
+}
+
+/// Set [`XmlObject`] to schemas of XML media types (i.e. media types, which contain `xml`,
+/// like `application/xml` or `application/atom+xml`).
+///
+/// If schema is a reference, it is wrapped into `allOf`, since siblings of `$ref` are ignored.
+pub fn set_xml_object(content: &mut Map<String, MediaType>, xml: &XmlObject) -> Result<(), Error> {
+    let value = serde_json::to_value(xml).map_err(|err| Error::Serialize(err.into()))?;
+    for (_, media_type) in content.iter_mut().filter(|(mime, _)| mime.contains("xml")) {
+        let mut schema = media_type.schema.take().unwrap_or_default();
+        if schema.reference.is_some() {
+            schema = SchemaObject {
+                subschemas: Some(Box::new(SubschemaValidation {
+                    all_of: Some(vec![Schema::Object(schema)]),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            };
+        }
+        let _ = schema.extensions.insert("xml".into(), value.clone());
+        media_type.schema = Some(schema);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "cbor")]
+pub use self::cbor::Cbor;
+#[cfg(feature = "csv")]
+pub use self::csv::Csv;
+#[cfg(feature = "msgpack")]
+pub use self::msgpack::MsgPack;
+#[cfg(feature = "xml")]
+pub use self::xml::Xml;
+
+/// Implement `axum` extractor and response for wrapper with provided (de)serialization.
+#[cfg(feature = "axum")]
+#[allow(unused_macros)]
+macro_rules! impl_axum_for_wrapper {
+    (
+        $ty:ident,
+        $mime:expr,
+        deserialize = |$bytes:ident| $deserialize:expr,
+        serialize = |$value:ident| $serialize:expr $(,)?
+    ) => {
+        const _: () = {
+            use axum::{
+                async_trait,
+                body::Bytes,
+                extract::{FromRequest, Request},
+                http::{header, StatusCode},
+                response::{IntoResponse, Response},
+            };
+            use serde::{de::DeserializeOwned, Serialize};
+
+            #[async_trait]
+            impl<T, S> FromRequest<S> for $ty<T>
+            where
+                T: DeserializeOwned,
+                S: Send + Sync,
+            {
+                type Rejection = Response;
+
+                async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+                    let $bytes = Bytes::from_request(req, state)
+                        .await
+                        .map_err(IntoResponse::into_response)?;
+                    $deserialize.map(Self).map_err(|err| {
+                        (
+                            StatusCode::UNPROCESSABLE_ENTITY,
+                            format!("Failed to deserialize {} body: {}", $mime, err),
+                        )
+                            .into_response()
+                    })
+                }
+            }
+
+            impl<T: Serialize> IntoResponse for $ty<T> {
+                fn into_response(self) -> Response {
+                    let $value = self.0;
+                    match $serialize {
+                        Ok(body) => ([(header::CONTENT_TYPE, $mime)], body).into_response(),
+                        Err(err) => {
+                            (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()
+                        }
+                    }
+                }
+            }
+        };
+    };
+}
+
+#[cfg(feature = "xml")]
+mod xml {
+    use crate::{impl_to_media_types_for_wrapper, impl_to_responses_for_wrapper};
+
+    /// XML body (`application/xml`).
+    ///
+    /// With `axum` feature it can be used as extractor and response, (de)serializing
+    /// inner value with [`quick_xml`].
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Xml<T>(pub T);
+
+    impl_to_media_types_for_wrapper!(Xml<T>, "application/xml");
+    impl_to_responses_for_wrapper!(Xml<T>);
+
+    #[cfg(feature = "axum")]
+    impl_axum_for_wrapper!(
+        Xml,
+        "application/xml",
+        deserialize = |bytes| quick_xml::de::from_reader(bytes.as_ref()),
+        serialize = |value| quick_xml::se::to_string(&value),
+    );
+}
+
+#[cfg(feature = "msgpack")]
+mod msgpack {
+    use crate::{impl_to_media_types_for_wrapper, impl_to_responses_for_wrapper};
+
+    /// MessagePack body (`application/msgpack`).
+    ///
+    /// With `axum` feature it can be used as extractor and response, (de)serializing
+    /// inner value with [`rmp_serde`] (structs are serialized as maps).
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct MsgPack<T>(pub T);
+
+    impl_to_media_types_for_wrapper!(MsgPack<T>, "application/msgpack");
+    impl_to_responses_for_wrapper!(MsgPack<T>);
+
+    #[cfg(feature = "axum")]
+    impl_axum_for_wrapper!(
+        MsgPack,
+        "application/msgpack",
+        deserialize = |bytes| rmp_serde::from_slice(&bytes),
+        serialize = |value| rmp_serde::to_vec_named(&value),
+    );
+}
+
+#[cfg(feature = "cbor")]
+mod cbor {
+    use crate::{impl_to_media_types_for_wrapper, impl_to_responses_for_wrapper};
+
+    /// CBOR body (`application/cbor`).
+    ///
+    /// With `axum` feature it can be used as extractor and response, (de)serializing
+    /// inner value with [`ciborium`].
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Cbor<T>(pub T);
+
+    impl_to_media_types_for_wrapper!(Cbor<T>, "application/cbor");
+    impl_to_responses_for_wrapper!(Cbor<T>);
+
+    #[cfg(feature = "axum")]
+    impl_axum_for_wrapper!(
+        Cbor,
+        "application/cbor",
+        deserialize = |bytes| ciborium::from_reader::<T, _>(bytes.as_ref()),
+        serialize = |value| {
+            let mut body = Vec::new();
+            ciborium::into_writer(&value, &mut body).map(|_| body)
+        },
+    );
+}
+
+#[cfg(feature = "csv")]
+mod csv {
+    use okapi::{
+        map,
+        openapi3::{MediaType, RefOr, Response, Responses},
+        schemars::JsonSchema,
+        Map,
+    };
+
+    use crate::{Components, Error, ToMediaTypes, ToResponses};
+
+    /// CSV body (`text/csv`), where every record is represented by `T`.
+    ///
+    /// Schema is array of `T` schemas. With `axum` feature it can be used as extractor and
+    /// response, (de)serializing records (with headers) with [`csv`](::csv) crate.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct Csv<T>(pub Vec<T>);
+
+    impl<T: JsonSchema> ToMediaTypes for Csv<T> {
+        fn generate(components: &mut Components) -> Result<Map<String, MediaType>, Error> {
+            Ok(map! {
+                "text/csv".into() => MediaType {
+                    schema: Some(components.schema_for::<Vec<T>>()),
+                    ..Default::default()
+                }
+            })
+        }
+    }
+
+    impl<T: JsonSchema> ToResponses for Csv<T> {
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
+            Ok(Responses {
+                responses: map! {
+                    "200".into() => RefOr::Object(Response {
+                        content: <Self as ToMediaTypes>::generate(components)?,
+                        ..Default::default()
+                    })
+                },
+                ..Default::default()
+            })
+        }
+    }
+
+    #[cfg(feature = "axum")]
+    impl_axum_for_wrapper!(
+        Csv,
+        "text/csv",
+        deserialize = |bytes| ::csv::Reader::from_reader(bytes.as_ref())
+            .deserialize()
+            .collect::<Result<Vec<T>, _>>(),
+        serialize = |value| {
+            let mut writer = ::csv::Writer::from_writer(Vec::new());
+            value
+                .iter()
+                .try_for_each(|record| writer.serialize(record))
+                .and_then(|_| writer.into_inner().map_err(|err| err.into_error().into()))
+        },
+    );
+}
@@ -0,0 +1,516 @@
+use anyhow::{bail, Context};
+use okapi::{
+    openapi3::{Components, MediaType, Operation, PathItem, RefOr, Response, Responses, Tag},
+    schemars::schema::{Schema, SchemaObject, SubschemaValidation},
+    Map,
+};
+
+use crate::{
+    walk::{insert_path_item_operation, take_path_item_operation, take_path_item_operations},
+    Error,
+};
+
+/// Strategy for resolving overlapping definitions (i.e. responses with same status or
+/// paths of merged specifications).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Merge both definitions. For responses content schemas are combined under `oneOf`. For
+    /// operations responses are merged, parameters, tags and callbacks are combined, and
+    /// missing fields are filled from new operation. Definitions, which can't be combined
+    /// (different components with same name, responses, one of which is reference), are an
+    /// error.
+    #[default]
+    Merge,
+    /// Return an error.
+    Error,
+    /// Replace existing definition with new one.
+    Replace,
+}
+
+/// Merge `other` responses into `base`.
+///
+/// Overlapping responses (with same status or both `default`) resolved according to `strategy`.
+pub fn merge_responses(
+    base: &mut Responses,
+    other: Responses,
+    strategy: MergeStrategy,
+) -> Result<(), Error> {
+    merge_response_maps(base, other, strategy).map_err(Error::Merge)
+}
+
+/// Same as [`merge_responses`], but with error, which can be extended with context.
+pub(crate) fn merge_response_maps(
+    base: &mut Responses,
+    other: Responses,
+    strategy: MergeStrategy,
+) -> Result<(), anyhow::Error> {
+    if let Some(other_default) = other.default {
+        base.default = Some(match base.default.take() {
+            Some(base_default) => merge_response("default", base_default, other_default, strategy)?,
+            None => other_default,
+        });
+    }
+
+    for (status, other_response) in other.responses {
+        if let Some(base_response) = base.responses.get_mut(&status) {
+            let taken = std::mem::replace(base_response, RefOr::Object(Response::default()));
+            *base_response = merge_response(&status, taken, other_response, strategy)?;
+        } else {
+            let _ = base.responses.insert(status, other_response);
+        }
+    }
+
+    for (key, value) in other.extensions {
+        let _ = base.extensions.entry(key).or_insert(value);
+    }
+
+    Ok(())
+}
+
+fn merge_response(
+    status: &str,
+    base: RefOr<Response>,
+    other: RefOr<Response>,
+    strategy: MergeStrategy,
+) -> Result<RefOr<Response>, anyhow::Error> {
+    match strategy {
+        MergeStrategy::Error => Err(anyhow::anyhow!(
+            "Response for status {status} is defined multiple times"
+        )),
+        MergeStrategy::Replace => Ok(other),
+        MergeStrategy::Merge => match (base, other) {
+            (RefOr::Object(base), RefOr::Object(other)) => {
+                Ok(RefOr::Object(merge_response_objects(base, other)))
+            }
+            (base, other) if base == other => Ok(base),
+            _ => Err(anyhow::anyhow!(
+                "Response for status {status} is defined multiple times and cannot be merged, \
+                because one of them is reference"
+            )),
+        },
+    }
+}
+
+fn merge_response_objects(mut base: Response, other: Response) -> Response {
+    if base.description.is_empty() {
+        base.description = other.description;
+    }
+    for (name, header) in other.headers {
+        let _ = base.headers.entry(name).or_insert(header);
+    }
+    for (name, link) in other.links {
+        let _ = base.links.entry(name).or_insert(link);
+    }
+    for (key, value) in other.extensions {
+        let _ = base.extensions.entry(key).or_insert(value);
+    }
+    for (mime, other_media_type) in other.content {
+        if let Some(base_media_type) = base.content.get_mut(&mime) {
+            let taken = std::mem::take(base_media_type);
+            *base_media_type = merge_media_types(taken, other_media_type);
+        } else {
+            let _ = base.content.insert(mime, other_media_type);
+        }
+    }
+    base
+}
+
+fn merge_media_types(mut base: MediaType, other: MediaType) -> MediaType {
+    base.schema = match (base.schema.take(), other.schema) {
+        (Some(base_schema), Some(other_schema)) => Some(one_of(base_schema, other_schema)),
+        (base_schema, other_schema) => base_schema.or(other_schema),
+    };
+    base.example = base.example.or(other.example);
+    base.examples = match (base.examples, other.examples) {
+        (Some(mut base_examples), Some(other_examples)) => {
+            for (name, example) in other_examples {
+                let _ = base_examples.entry(name).or_insert(example);
+            }
+            Some(base_examples)
+        }
+        (base_examples, other_examples) => base_examples.or(other_examples),
+    };
+    for (name, encoding) in other.encoding {
+        let _ = base.encoding.entry(name).or_insert(encoding);
+    }
+    base
+}
+
+/// Combine two schemas under `oneOf`, flattening schemas, which are `oneOf` themselves.
+fn one_of(base: SchemaObject, other: SchemaObject) -> SchemaObject {
+    if base == other {
+        return base;
+    }
+    let mut variants = one_of_variants(base);
+    for variant in one_of_variants(other) {
+        if !variants.contains(&variant) {
+            variants.push(variant);
+        }
+    }
+    one_of_schema(variants)
+}
+
+fn one_of_schema(variants: Vec<Schema>) -> SchemaObject {
+    SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            one_of: Some(variants),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+}
+
+fn one_of_variants(schema: SchemaObject) -> Vec<Schema> {
+    if let Some(variants) = schema
+        .subschemas
+        .as_ref()
+        .and_then(|x| x.one_of.as_ref())
+        .filter(|x| one_of_schema(x.to_vec()) == schema)
+    {
+        return variants.clone();
+    }
+    vec![Schema::Object(schema)]
+}
+
+/// Merge paths of other specification into `base`.
+///
+/// With [`MergeStrategy::Error`] any path, present in both specifications, is an error. Otherwise
+/// path items are merged, and different operations with same method are resolved according to
+/// strategy.
+pub(crate) fn merge_paths(
+    base: &mut Map<String, PathItem>,
+    other: Map<String, PathItem>,
+    strategy: MergeStrategy,
+) -> Result<(), anyhow::Error> {
+    for (path, other_item) in other {
+        let Some(base_item) = base.get_mut(&path) else {
+            let _ = base.insert(path, other_item);
+            continue;
+        };
+        if *base_item == other_item {
+            continue;
+        }
+        if strategy == MergeStrategy::Error {
+            bail!("Path {path} is defined in both specifications");
+        }
+        merge_path_item(&path, base_item, other_item, strategy)?;
+    }
+    Ok(())
+}
+
+fn merge_path_item(
+    path: &str,
+    base: &mut PathItem,
+    mut other: PathItem,
+    strategy: MergeStrategy,
+) -> Result<(), anyhow::Error> {
+    for (method, other_operation) in take_path_item_operations(&mut other)? {
+        let mut base_operation = take_path_item_operation(base, &method)?;
+        merge_operation(
+            path,
+            method.as_str(),
+            &mut base_operation,
+            Some(other_operation),
+            strategy,
+        )?;
+        if let Some(operation) = base_operation {
+            insert_path_item_operation(base, &method, operation)?;
+        }
+    }
+    // Operations are taken above
+    let PathItem {
+        reference,
+        summary,
+        description,
+        servers,
+        parameters,
+        extensions,
+        ..
+    } = other;
+    base.reference = base.reference.take().or(reference);
+    base.summary = base.summary.take().or(summary);
+    base.description = base.description.take().or(description);
+    base.servers = base.servers.take().or(servers);
+    for parameter in parameters {
+        if !base.parameters.contains(&parameter) {
+            base.parameters.push(parameter);
+        }
+    }
+    for (key, value) in extensions {
+        let _ = base.extensions.entry(key).or_insert(value);
+    }
+    Ok(())
+}
+
+fn merge_operation(
+    path: &str,
+    method: &str,
+    base: &mut Option<Operation>,
+    other: Option<Operation>,
+    strategy: MergeStrategy,
+) -> Result<(), anyhow::Error> {
+    match (base.as_mut(), other) {
+        (_, None) => {}
+        (None, other) => *base = other,
+        (Some(base), Some(other)) if *base == other => {}
+        (Some(base), Some(other)) if strategy == MergeStrategy::Replace => *base = other,
+        (Some(base), Some(other)) if strategy == MergeStrategy::Merge => {
+            let taken = std::mem::take(base);
+            *base = merge_operation_objects(taken, other)
+                .with_context(|| format!("Failed to merge {method} {path}"))?;
+        }
+        (Some(_), Some(_)) => bail!("{method} {path} is defined in both specifications"),
+    }
+    Ok(())
+}
+
+fn merge_operation_objects(
+    mut base: Operation,
+    other: Operation,
+) -> Result<Operation, anyhow::Error> {
+    let Operation {
+        tags,
+        summary,
+        description,
+        external_docs,
+        operation_id,
+        parameters,
+        request_body,
+        responses,
+        callbacks,
+        deprecated,
+        security,
+        servers,
+        extensions,
+    } = other;
+    for tag in tags {
+        if !base.tags.contains(&tag) {
+            base.tags.push(tag);
+        }
+    }
+    base.summary = base.summary.or(summary);
+    base.description = base.description.or(description);
+    base.external_docs = base.external_docs.or(external_docs);
+    base.operation_id = base.operation_id.or(operation_id);
+    for parameter in parameters {
+        if !base.parameters.contains(&parameter) {
+            base.parameters.push(parameter);
+        }
+    }
+    base.request_body = base.request_body.or(request_body);
+    merge_response_maps(&mut base.responses, responses, MergeStrategy::Merge)?;
+    for (name, callback) in callbacks {
+        let _ = base.callbacks.entry(name).or_insert(callback);
+    }
+    base.deprecated |= deprecated;
+    base.security = base.security.or(security);
+    base.servers = base.servers.or(servers);
+    for (key, value) in extensions {
+        let _ = base.extensions.entry(key).or_insert(value);
+    }
+    Ok(base)
+}
+
+/// Merge components of other specification into `base`.
+///
+/// Components with same name are resolved according to strategy (identical components are
+/// allowed with any strategy).
+pub(crate) fn merge_components(
+    base: &mut Components,
+    other: Components,
+    strategy: MergeStrategy,
+) -> Result<(), anyhow::Error> {
+    let Components {
+        schemas,
+        responses,
+        parameters,
+        examples,
+        request_bodies,
+        headers,
+        security_schemes,
+        links,
+        callbacks,
+        extensions,
+    } = other;
+    merge_map("Schema", &mut base.schemas, schemas, strategy)?;
+    merge_map("Response", &mut base.responses, responses, strategy)?;
+    merge_map("Parameter", &mut base.parameters, parameters, strategy)?;
+    merge_map("Example", &mut base.examples, examples, strategy)?;
+    merge_map(
+        "Request body",
+        &mut base.request_bodies,
+        request_bodies,
+        strategy,
+    )?;
+    merge_map("Header", &mut base.headers, headers, strategy)?;
+    merge_map(
+        "Security scheme",
+        &mut base.security_schemes,
+        security_schemes,
+        strategy,
+    )?;
+    merge_map("Link", &mut base.links, links, strategy)?;
+    merge_map("Callback", &mut base.callbacks, callbacks, strategy)?;
+    for (key, value) in extensions {
+        let _ = base.extensions.entry(key).or_insert(value);
+    }
+    Ok(())
+}
+
+fn merge_map<T: PartialEq>(
+    kind: &str,
+    base: &mut Map<String, T>,
+    other: Map<String, T>,
+    strategy: MergeStrategy,
+) -> Result<(), anyhow::Error> {
+    for (name, other_value) in other {
+        match base.get_mut(&name) {
+            None => {
+                let _ = base.insert(name, other_value);
+            }
+            Some(base_value) if *base_value == other_value => {}
+            Some(base_value) if strategy == MergeStrategy::Replace => *base_value = other_value,
+            Some(_) => bail!("{kind} '{name}' is defined in both specifications"),
+        }
+    }
+    Ok(())
+}
+
+/// Merge tags of other specification into `base`.
+///
+/// With [`MergeStrategy::Merge`] missing fields of existing tags are filled from other tags.
+pub(crate) fn merge_tags(
+    base: &mut Vec<Tag>,
+    other: Vec<Tag>,
+    strategy: MergeStrategy,
+) -> Result<(), anyhow::Error> {
+    for other_tag in other {
+        let Some(base_tag) = base.iter_mut().find(|x| x.name == other_tag.name) else {
+            base.push(other_tag);
+            continue;
+        };
+        if *base_tag == other_tag {
+            continue;
+        }
+        match strategy {
+            MergeStrategy::Merge => {
+                base_tag.description = base_tag.description.take().or(other_tag.description);
+                base_tag.external_docs = base_tag.external_docs.take().or(other_tag.external_docs);
+                for (key, value) in other_tag.extensions {
+                    let _ = base_tag.extensions.entry(key).or_insert(value);
+                }
+            }
+            MergeStrategy::Error => {
+                bail!("Tag '{}' is defined in both specifications", other_tag.name)
+            }
+            MergeStrategy::Replace => *base_tag = other_tag,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use okapi::{map, schemars::gen::SchemaGenerator};
+
+    use super::*;
+
+    fn responses_for<T: okapi::schemars::JsonSchema>(gen: &mut SchemaGenerator) -> Responses {
+        Responses {
+            responses: map! {
+                "200".into() => RefOr::Object(Response {
+                    content: map! {
+                        "application/json".into() => MediaType {
+                            schema: Some(gen.subschema_for::<T>().into_object()),
+                            ..Default::default()
+                        }
+                    },
+                    ..Default::default()
+                })
+            },
+            ..Default::default()
+        }
+    }
+
+    fn json_schema(responses: &Responses) -> SchemaObject {
+        let RefOr::Object(response) = &responses.responses["200"] else {
+            panic!("200 response should be RefOr::Object");
+        };
+        response.content["application/json"]
+            .schema
+            .clone()
+            .expect("Schema should be present")
+    }
+
+    #[test]
+    fn merge_one_of() {
+        let mut gen = SchemaGenerator::default();
+        let mut base = responses_for::<String>(&mut gen);
+        merge_responses(
+            &mut base,
+            responses_for::<u64>(&mut gen),
+            MergeStrategy::Merge,
+        )
+        .expect("Merge shouldn't fail");
+        merge_responses(
+            &mut base,
+            responses_for::<bool>(&mut gen),
+            MergeStrategy::Merge,
+        )
+        .expect("Merge shouldn't fail");
+        merge_responses(
+            &mut base,
+            responses_for::<u64>(&mut gen),
+            MergeStrategy::Merge,
+        )
+        .expect("Merge shouldn't fail");
+
+        let variants = json_schema(&base)
+            .subschemas
+            .and_then(|x| x.one_of)
+            .expect("oneOf should be present");
+        assert_eq!(
+            variants,
+            vec![
+                gen.subschema_for::<String>(),
+                gen.subschema_for::<u64>(),
+                gen.subschema_for::<bool>(),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_same() {
+        let mut gen = SchemaGenerator::default();
+        let mut base = responses_for::<String>(&mut gen);
+        merge_responses(
+            &mut base,
+            responses_for::<String>(&mut gen),
+            MergeStrategy::Merge,
+        )
+        .expect("Merge shouldn't fail");
+        assert_eq!(
+            json_schema(&base),
+            gen.subschema_for::<String>().into_object()
+        );
+    }
+
+    #[test]
+    fn error_and_replace() {
+        let mut gen = SchemaGenerator::default();
+        let mut base = responses_for::<String>(&mut gen);
+        assert!(merge_responses(
+            &mut base,
+            responses_for::<u64>(&mut gen),
+            MergeStrategy::Error
+        )
+        .is_err());
+        merge_responses(
+            &mut base,
+            responses_for::<u64>(&mut gen),
+            MergeStrategy::Replace,
+        )
+        .expect("Replace shouldn't fail");
+        assert_eq!(json_schema(&base), gen.subschema_for::<u64>().into_object());
+    }
+}
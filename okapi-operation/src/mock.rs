@@ -0,0 +1,389 @@
+//! Mock server, serving responses, derived from specification.
+//!
+//! Every operation of specification is served by route, which returns first successful
+//! (`2xx`, then `default`) response of operation. Body of response is example of its media
+//! type (`example` or first of `examples`), or value, synthesized from schema (using
+//! `examples`, `default`, `const` and `enum` of schemas, or placeholder values of their
+//! types), so frontend teams could run stub API without real backend:
+//!
+//! ```rust
+//! # use okapi_operation::{axum_integration::*, *};
+//! #[derive(serde::Serialize, JsonSchema)]
+//! struct User {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! #[openapi]
+//! async fn get_user() -> axum::Json<User> {
+//!     unimplemented!()
+//! }
+//!
+//! let spec = Router::<()>::new()
+//!     .route("/users/:id", get(openapi_handler!(get_user)))
+//!     .generate_openapi_builder()
+//!     .build()?;
+//! let mock: axum::Router = mock::mock_router(&spec);
+//! # Ok::<(), Error>(())
+//! ```
+//!
+//! References to schemas are resolved by name of schema in components, so specifications with
+//! custom [definitions path](crate::ComponentsBuilder::definitions_path) are supported.
+//! Media types, which are not valid `Content-Type` header values, are skipped.
+//!
+//! Operations of methods, which can't be documented in OpenAPI 3.0 (stored in
+//! `x-additionalOperations` extension), are not served. Paths, which conflict in Axum
+//! (like `/users/{id}` and `/users/{name}`), cause panic, same as in [`axum::Router::route`].
+
+use axum::{
+    body::Body,
+    http::{header::CONTENT_TYPE, HeaderValue, StatusCode},
+    response::Response,
+    routing::{on, MethodFilter},
+    Router,
+};
+use okapi::{
+    openapi3::{ExampleValue, MediaType, OpenApi, Operation, RefOr, Response as OkapiResponse},
+    schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec},
+    Map,
+};
+use serde_json::Value;
+
+use crate::{
+    axum_integration::utils::convert_openapi_path_to_axum,
+    walk::{path_item_operations, reference_name},
+};
+
+/// Maximum depth of schemas (like recursive ones), for which values are synthesized.
+const MAX_DEPTH: usize = 8;
+
+/// Create [`axum::Router`], serving responses of operations of specification.
+pub fn mock_router<S>(spec: &OpenApi) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let mut router = Router::new();
+    for (path, item) in &spec.paths {
+        let path = convert_openapi_path_to_axum(path);
+        for (method, operation) in path_item_operations(item) {
+            let Ok(filter) = MethodFilter::try_from(method) else {
+                continue;
+            };
+            let response = MockResponse::new(spec, operation);
+            router = router.route(
+                &path,
+                on(filter, move || {
+                    let response = response.clone();
+                    async move { response.into_response() }
+                }),
+            );
+        }
+    }
+    router
+}
+
+/// Synthesize value, matching schema (references are resolved in components of
+/// specification).
+pub fn sample_value(spec: &OpenApi, schema: &SchemaObject) -> Value {
+    let empty = Map::new();
+    let schemas = spec.components.as_ref().map_or(&empty, |x| &x.schemas);
+    sample(schema, schemas, 0)
+}
+
+/// Response, returned by mocked operation.
+#[derive(Clone, Debug)]
+struct MockResponse {
+    status: StatusCode,
+    content: Option<(HeaderValue, Value)>,
+}
+
+impl MockResponse {
+    fn new(spec: &OpenApi, operation: &Operation) -> Self {
+        let responses = &operation.responses;
+        let (status, response) = responses
+            .responses
+            .iter()
+            .filter_map(|(status, response)| Some((status.parse::<StatusCode>().ok()?, response)))
+            .filter(|(status, _)| status.is_success())
+            .min_by_key(|(status, _)| *status)
+            .or_else(|| Some((StatusCode::OK, responses.default.as_ref()?)))
+            .unzip();
+        let content = response
+            .and_then(|x| resolve_response(spec, x))
+            .and_then(|x| {
+                let mut content = x.content.iter().filter_map(|(media_type, content)| {
+                    Some((HeaderValue::from_str(media_type).ok()?, content))
+                });
+                let first = content.clone().next();
+                let (media_type, content) = content
+                    .find(|(media_type, _)| is_json(media_type))
+                    .or(first)?;
+                Some((media_type, example(spec, content)))
+            });
+        Self {
+            status: status.unwrap_or(StatusCode::OK),
+            content,
+        }
+    }
+
+    fn into_response(self) -> Response {
+        let mut response = match self.content {
+            Some((media_type, value)) => {
+                let body = match value {
+                    Value::String(x) if !is_json(&media_type) => x,
+                    value => value.to_string(),
+                };
+                let mut response = Response::new(Body::from(body));
+                let _ = response.headers_mut().insert(CONTENT_TYPE, media_type);
+                response
+            }
+            None => Response::new(Body::empty()),
+        };
+        *response.status_mut() = self.status;
+        response
+    }
+}
+
+fn is_json(media_type: &HeaderValue) -> bool {
+    let media_type = media_type.as_bytes();
+    media_type == b"application/json" || media_type.ends_with(b"+json")
+}
+
+fn resolve_response<'a>(
+    spec: &'a OpenApi,
+    response: &'a RefOr<OkapiResponse>,
+) -> Option<&'a OkapiResponse> {
+    match response {
+        RefOr::Object(x) => Some(x),
+        RefOr::Ref(x) => {
+            let name = x.reference.strip_prefix("#/components/responses/")?;
+            match spec.components.as_ref()?.responses.get(name)? {
+                RefOr::Object(x) => Some(x),
+                RefOr::Ref(_) => None,
+            }
+        }
+    }
+}
+
+/// Example of media type or value, synthesized from its schema.
+fn example(spec: &OpenApi, content: &MediaType) -> Value {
+    if let Some(example) = &content.example {
+        return example.clone();
+    }
+    let example = content.examples.as_ref().and_then(|x| {
+        x.values().find_map(|x| match &x.value {
+            ExampleValue::Value(value) => Some(value.clone()),
+            ExampleValue::ExternalValue(_) => None,
+        })
+    });
+    match (example, &content.schema) {
+        (Some(example), _) => example,
+        (None, Some(schema)) => sample_value(spec, schema),
+        (None, None) => Value::Null,
+    }
+}
+
+fn sample(schema: &SchemaObject, schemas: &Map<String, SchemaObject>, depth: usize) -> Value {
+    if depth > MAX_DEPTH {
+        return Value::Null;
+    }
+    if let Some(reference) = &schema.reference {
+        return reference_name(reference)
+            .and_then(|x| schemas.get(&x))
+            .map_or(Value::Null, |x| sample(x, schemas, depth + 1));
+    }
+    if let Some(metadata) = &schema.metadata {
+        if let Some(value) = metadata.examples.first().or(metadata.default.as_ref()) {
+            return value.clone();
+        }
+    }
+    if let Some(value) = &schema.const_value {
+        return value.clone();
+    }
+    if let Some(value) = schema.enum_values.as_ref().and_then(|x| x.first()) {
+        return value.clone();
+    }
+    if let Some(subschemas) = &schema.subschemas {
+        if let Some(all_of) = &subschemas.all_of {
+            let mut merged = serde_json::Map::new();
+            for subschema in all_of {
+                match sample_schema(subschema, schemas, depth) {
+                    Value::Object(x) => merged.extend(x),
+                    value if all_of.len() == 1 => return value,
+                    _ => {}
+                }
+            }
+            return Value::Object(merged);
+        }
+        if let Some(first) = subschemas
+            .one_of
+            .as_ref()
+            .or(subschemas.any_of.as_ref())
+            .and_then(|x| x.first())
+        {
+            return sample_schema(first, schemas, depth);
+        }
+    }
+
+    let instance_type = match &schema.instance_type {
+        Some(SingleOrVec::Single(x)) => Some(**x),
+        Some(SingleOrVec::Vec(x)) => x
+            .iter()
+            .find(|x| **x != InstanceType::Null)
+            .or(x.first())
+            .copied(),
+        None if schema.object.is_some() => Some(InstanceType::Object),
+        None if schema.array.is_some() => Some(InstanceType::Array),
+        None => None,
+    };
+    match instance_type {
+        Some(InstanceType::String) => Value::String(
+            match schema.format.as_deref() {
+                Some("date-time") => "1970-01-01T00:00:00Z",
+                Some("date") => "1970-01-01",
+                Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                Some("email") => "user@example.com",
+                Some("uri") => "https://example.com",
+                _ => "string",
+            }
+            .into(),
+        ),
+        Some(InstanceType::Integer) => schema
+            .number
+            .as_ref()
+            .and_then(|x| x.minimum)
+            .map_or(Value::from(0), |x| Value::from(x as i64)),
+        Some(InstanceType::Number) => schema
+            .number
+            .as_ref()
+            .and_then(|x| x.minimum)
+            .map_or(Value::from(0.0), Value::from),
+        Some(InstanceType::Boolean) => Value::Bool(false),
+        Some(InstanceType::Array) => {
+            let item =
+                schema
+                    .array
+                    .as_ref()
+                    .and_then(|x| x.items.as_ref())
+                    .map(|items| match items {
+                        SingleOrVec::Single(x) => sample_schema(x, schemas, depth),
+                        SingleOrVec::Vec(x) => x
+                            .first()
+                            .map_or(Value::Null, |x| sample_schema(x, schemas, depth)),
+                    });
+            Value::Array(item.into_iter().collect())
+        }
+        Some(InstanceType::Object) => Value::Object(
+            schema
+                .object
+                .iter()
+                .flat_map(|x| &x.properties)
+                .map(|(name, x)| (name.clone(), sample_schema(x, schemas, depth)))
+                .collect(),
+        ),
+        Some(InstanceType::Null) | None => Value::Null,
+    }
+}
+
+fn sample_schema(schema: &Schema, schemas: &Map<String, SchemaObject>, depth: usize) -> Value {
+    match schema {
+        Schema::Object(x) => sample(x, schemas, depth + 1),
+        Schema::Bool(_) => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synthesized_values() {
+        let spec: OpenApi = serde_json::from_value(serde_json::json!({
+            "openapi": "3.0.0",
+            "info": { "title": "title", "version": "version" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "integer", "minimum": 1 },
+                            "email": { "type": "string", "format": "email" },
+                            "role": { "type": "string", "enum": ["admin", "user"] },
+                            "tags": { "type": "array", "items": { "type": "string" } },
+                            "manager": {
+                                "allOf": [{ "$ref": "#/components/schemas/User" }],
+                                "nullable": true
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let value = sample_value(
+            &spec,
+            &SchemaObject::new_ref("#/components/schemas/User".into()),
+        );
+        assert_eq!(value["id"], 1);
+        assert_eq!(value["email"], "user@example.com");
+        assert_eq!(value["role"], "admin");
+        assert_eq!(value["tags"], serde_json::json!(["string"]));
+        assert_eq!(value["manager"]["id"], 1);
+
+        let mut depth = 0;
+        let mut current = &value;
+        while let Some(manager) = current.get("manager").filter(|x| !x.is_null()) {
+            current = manager;
+            depth += 1;
+        }
+        assert!(depth < MAX_DEPTH);
+    }
+
+    #[tokio::test]
+    async fn custom_definitions_path_and_invalid_media_type() {
+        use tower::ServiceExt;
+
+        let spec: OpenApi = serde_json::from_value(serde_json::json!({
+            "openapi": "3.0.0",
+            "info": { "title": "title", "version": "version" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "",
+                                "content": {
+                                    "text/plain\n": { "example": "invalid" },
+                                    "application/json": {
+                                        "schema": { "$ref": "schemas.yaml#/User" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": { "type": "object", "properties": { "id": { "type": "integer" } } }
+                }
+            }
+        }))
+        .unwrap();
+        let response = mock_router::<()>(&spec)
+            .oneshot(
+                axum::http::Request::get("/users")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, r#"{"id":0}"#);
+    }
+}
@@ -0,0 +1,225 @@
+use okapi::schemars::{
+    schema::{InstanceType, Schema, SchemaObject, SingleOrVec, SubschemaValidation},
+    visit::{visit_schema_object, Visitor},
+};
+
+/// Extension of OpenAPI 3.0, marking schema as nullable.
+const NULLABLE_EXTENSION: &str = "nullable";
+
+/// Representation of nullable values (like `Option<T>`) in schemas.
+///
+/// Set with [`ComponentsBuilder::nullable_strategy`](crate::ComponentsBuilder::nullable_strategy).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NullableStrategy {
+    /// `nullable: true` (OpenAPI 3.0).
+    #[default]
+    Nullable,
+    /// `null` in types (`type: [T, "null"]`) or `anyOf` with `null` schema for references
+    /// (OpenAPI 3.1).
+    NullType,
+}
+
+/// Converts nullable schemas into representation of strategy.
+#[derive(Clone, Debug)]
+pub(crate) struct NullableVisitor(pub(crate) NullableStrategy);
+
+impl Visitor for NullableVisitor {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        visit_schema_object(self, schema);
+        match self.0 {
+            NullableStrategy::Nullable => into_nullable(schema),
+            NullableStrategy::NullType => into_null_type(schema),
+        }
+    }
+}
+
+/// Replace `null` type (or `anyOf` with `null` schema) with `nullable: true`.
+fn into_nullable(schema: &mut SchemaObject) {
+    if let Some(SingleOrVec::Vec(types)) = &schema.instance_type {
+        if types.contains(&InstanceType::Null) {
+            let mut types: Vec<_> = types
+                .iter()
+                .filter(|x| **x != InstanceType::Null)
+                .copied()
+                .collect();
+            schema.instance_type = match types.len() {
+                0 => Some(InstanceType::Null.into()),
+                1 => Some(types.remove(0).into()),
+                _ => Some(types.into()),
+            };
+            if schema.instance_type != Some(InstanceType::Null.into()) {
+                set_nullable(schema);
+            }
+        }
+        return;
+    }
+
+    let Some(any_of) = schema.subschemas.as_ref().and_then(|x| x.any_of.as_ref()) else {
+        return;
+    };
+    let [first, second] = any_of.as_slice() else {
+        return;
+    };
+    let other = match (is_null(first), is_null(second)) {
+        (true, false) => second.clone(),
+        (false, true) => first.clone(),
+        _ => return,
+    };
+    let subschemas = schema.subschemas();
+    subschemas.any_of = None;
+    subschemas.all_of.get_or_insert_with(Vec::new).push(other);
+    set_nullable(schema);
+}
+
+/// Replace `nullable: true` with `null` type (or `anyOf` with `null` schema).
+fn into_null_type(schema: &mut SchemaObject) {
+    if schema.extensions.get(NULLABLE_EXTENSION) != Some(&serde_json::Value::Bool(true)) {
+        return;
+    }
+    let _ = schema.extensions.shift_remove(NULLABLE_EXTENSION);
+    if let Some(enum_values) = schema.enum_values.as_mut() {
+        if !enum_values.contains(&serde_json::Value::Null) {
+            enum_values.push(serde_json::Value::Null);
+        }
+    }
+    match schema.instance_type.take() {
+        Some(SingleOrVec::Single(x)) => {
+            schema.instance_type = Some(vec![*x, InstanceType::Null].into());
+        }
+        Some(SingleOrVec::Vec(mut types)) => {
+            if !types.contains(&InstanceType::Null) {
+                types.push(InstanceType::Null);
+            }
+            schema.instance_type = Some(types.into());
+        }
+        None => {
+            // Schema without type (like reference, wrapped into `allOf`)
+            let metadata = schema.metadata.take();
+            let extensions = std::mem::take(&mut schema.extensions);
+            let inner = std::mem::take(schema);
+            let inner = single_all_of(&inner).unwrap_or(Schema::Object(inner));
+            *schema = SchemaObject {
+                metadata,
+                extensions,
+                subschemas: Some(Box::new(SubschemaValidation {
+                    any_of: Some(vec![inner, null_schema()]),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            };
+        }
+    }
+}
+
+/// Single subschema of schema, which contains only `allOf` with this subschema.
+fn single_all_of(schema: &SchemaObject) -> Option<Schema> {
+    let [subschema] = schema.subschemas.as_ref()?.all_of.as_deref()? else {
+        return None;
+    };
+    let wrapper = SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            all_of: Some(vec![subschema.clone()]),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    (*schema == wrapper).then(|| subschema.clone())
+}
+
+fn set_nullable(schema: &mut SchemaObject) {
+    let _ = schema
+        .extensions
+        .insert(NULLABLE_EXTENSION.into(), serde_json::Value::Bool(true));
+}
+
+fn null_schema() -> Schema {
+    Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::Null.into()),
+        ..Default::default()
+    })
+}
+
+fn is_null(schema: &Schema) -> bool {
+    *schema == null_schema()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(strategy: NullableStrategy, value: serde_json::Value) -> serde_json::Value {
+        let mut schema: SchemaObject = serde_json::from_value(value).unwrap();
+        NullableVisitor(strategy).visit_schema_object(&mut schema);
+        serde_json::to_value(schema).unwrap()
+    }
+
+    #[test]
+    fn null_type() {
+        assert_eq!(
+            convert(
+                NullableStrategy::NullType,
+                serde_json::json!({ "type": "string", "nullable": true })
+            ),
+            serde_json::json!({ "type": ["string", "null"] })
+        );
+        assert_eq!(
+            convert(
+                NullableStrategy::NullType,
+                serde_json::json!({
+                    "description": "Owner",
+                    "allOf": [{ "$ref": "#/components/schemas/User" }],
+                    "nullable": true
+                })
+            ),
+            serde_json::json!({
+                "description": "Owner",
+                "anyOf": [{ "$ref": "#/components/schemas/User" }, { "type": "null" }]
+            })
+        );
+        assert_eq!(
+            convert(
+                NullableStrategy::NullType,
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "kind": { "type": "string", "enum": ["a", "b"], "nullable": true }
+                    }
+                })
+            ),
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "kind": { "type": ["string", "null"], "enum": ["a", "b", null] }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn nullable() {
+        assert_eq!(
+            convert(
+                NullableStrategy::Nullable,
+                serde_json::json!({ "type": ["integer", "null"] })
+            ),
+            serde_json::json!({ "type": "integer", "nullable": true })
+        );
+        assert_eq!(
+            convert(
+                NullableStrategy::Nullable,
+                serde_json::json!({
+                    "anyOf": [{ "$ref": "#/components/schemas/User" }, { "type": "null" }]
+                })
+            ),
+            serde_json::json!({
+                "allOf": [{ "$ref": "#/components/schemas/User" }],
+                "nullable": true
+            })
+        );
+        let unchanged = serde_json::json!({ "type": "string", "nullable": true });
+        assert_eq!(
+            convert(NullableStrategy::Nullable, unchanged.clone()),
+            unchanged
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::{fmt, sync::Arc};
+
+use okapi::openapi3::Operation;
+
+use crate::{AnyOperationGenerator, AsyncOperationGenerator, OperationGenerator};
+
+/// Metadata of operation, which can be overridden when operation is mounted on specific route.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OperationMeta {
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub operation_id: Option<String>,
+    pub tags: Vec<String>,
+    pub deprecated: bool,
+    /// Specification extensions (like `x-experimental`).
+    pub extensions: okapi::Map<String, serde_json::Value>,
+}
+
+impl OperationMeta {
+    #[cfg(feature = "axum")]
+    fn take_from(operation: &mut Operation) -> Self {
+        Self {
+            summary: operation.summary.take(),
+            description: operation.description.take(),
+            operation_id: operation.operation_id.take(),
+            tags: std::mem::take(&mut operation.tags),
+            deprecated: operation.deprecated,
+            extensions: std::mem::take(&mut operation.extensions),
+        }
+    }
+
+    #[cfg(feature = "axum")]
+    fn apply_to(self, operation: &mut Operation) {
+        operation.summary = self.summary;
+        operation.description = self.description;
+        operation.operation_id = self.operation_id;
+        operation.tags = self.tags;
+        operation.deprecated = self.deprecated;
+        operation.extensions = self.extensions;
+    }
+}
+
+pub(crate) type OperationCustomizer = Arc<dyn Fn(&mut Operation) + Send + Sync>;
+
+/// Operation generator with customizations, specific to route.
+#[derive(Clone)]
+pub(crate) struct OperationEntry {
+    pub(crate) generator: AnyOperationGenerator,
+    customizer: Option<OperationCustomizer>,
+    /// Name of path parameter, which matches rest of path.
+    pub(crate) wildcard: Option<String>,
+}
+
+impl OperationEntry {
+    pub(crate) fn new(generator: AnyOperationGenerator) -> Self {
+        Self {
+            generator,
+            customizer: None,
+            wildcard: None,
+        }
+    }
+
+    /// Synchronous generator (if entry is not asynchronous).
+    #[cfg(feature = "axum")]
+    pub(crate) fn sync_generator(&self) -> Option<&OperationGenerator> {
+        match &self.generator {
+            AnyOperationGenerator::Sync(x) => Some(x),
+            AnyOperationGenerator::Async(_) => None,
+        }
+    }
+
+    /// Add function for overriding metadata of generated operation.
+    ///
+    /// Functions are applied in order they were added.
+    #[cfg(feature = "axum")]
+    pub(crate) fn customize<F>(self, f: F) -> Self
+    where
+        F: Fn(&mut OperationMeta) + Send + Sync + 'static,
+    {
+        self.customize_operation(move |operation| {
+            let mut meta = OperationMeta::take_from(operation);
+            f(&mut meta);
+            meta.apply_to(operation);
+        })
+    }
+
+    /// Add function for modifying generated operation.
+    ///
+    /// Functions are applied in order they were added.
+    #[cfg(feature = "axum")]
+    pub(crate) fn customize_operation<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut Operation) + Send + Sync + 'static,
+    {
+        self.customizer = Some(match self.customizer.take() {
+            Some(previous) => Arc::new(move |operation: &mut Operation| {
+                previous(operation);
+                f(operation);
+            }),
+            None => Arc::new(f),
+        });
+        self
+    }
+
+    pub(crate) fn apply_customizations(&self, operation: &mut Operation) {
+        if let Some(customizer) = &self.customizer {
+            customizer(operation);
+        }
+    }
+}
+
+impl From<OperationGenerator> for OperationEntry {
+    fn from(value: OperationGenerator) -> Self {
+        Self::new(AnyOperationGenerator::Sync(value))
+    }
+}
+
+impl From<AsyncOperationGenerator> for OperationEntry {
+    fn from(value: AsyncOperationGenerator) -> Self {
+        Self::new(AnyOperationGenerator::Async(value))
+    }
+}
+
+impl fmt::Debug for OperationEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OperationEntry")
+            .field("generator", &self.generator)
+            .field("customized", &self.customizer.is_some())
+            .field("wildcard", &self.wildcard)
+            .finish()
+    }
+}
@@ -0,0 +1,103 @@
+use okapi::{
+    map,
+    openapi3::{Header, MediaType, RefOr, Response, Responses},
+    schemars::JsonSchema,
+    Map,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{header::header, Components, Error, ToMediaTypes, ToResponses};
+
+/// Page of items with page number pagination.
+///
+/// Documented as `200` response with `application/json` media type and `Link` header
+/// ([RFC 8288](https://www.rfc-editor.org/rfc/rfc8288)) with links to other pages.
+/// Query parameters of page could be documented with `pagination` attribute of
+/// [`openapi`](crate::openapi) macro.
+///
+/// With `axum` feature it implements `IntoResponse`, serializing page as JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(crate = "okapi::schemars")]
+pub struct Paginated<T> {
+    /// Items of page.
+    pub items: Vec<T>,
+    /// Number of page (starting from 1).
+    pub page: u64,
+    /// Maximum number of items in page.
+    pub per_page: u64,
+    /// Total number of items (if known).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+}
+
+/// Page of items with cursor pagination.
+///
+/// Documented as `200` response with `application/json` media type and `Link` header
+/// ([RFC 8288](https://www.rfc-editor.org/rfc/rfc8288)) with links to other pages.
+/// Query parameters of page could be documented with `pagination` attribute of
+/// [`openapi`](crate::openapi) macro.
+///
+/// With `axum` feature it implements `IntoResponse`, serializing page as JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(crate = "okapi::schemars")]
+pub struct CursorPage<T> {
+    /// Items of page.
+    pub items: Vec<T>,
+    /// Cursor of next page (if any).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Cursor of previous page (if any).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<String>,
+}
+
+/// Generate `Link` header for page responses.
+fn link_header(components: &mut Components) -> Header {
+    header(
+        components.schema_for::<String>(),
+        Some("Links to other pages (RFC 8288)"),
+        false,
+    )
+}
+
+macro_rules! impl_traits_for_page {
+    ($ty:ident) => {
+        impl<T: JsonSchema> ToMediaTypes for $ty<T> {
+            fn generate(components: &mut Components) -> Result<Map<String, MediaType>, Error> {
+                Ok(map! {
+                    "application/json".into() => MediaType {
+                        schema: Some(components.schema_for::<Self>()),
+                        ..Default::default()
+                    }
+                })
+            }
+        }
+
+        impl<T: JsonSchema> ToResponses for $ty<T> {
+            fn generate(components: &mut Components) -> Result<Responses, Error> {
+                Ok(Responses {
+                    responses: map! {
+                        "200".into() => RefOr::Object(Response {
+                            content: <Self as ToMediaTypes>::generate(components)?,
+                            headers: map! {
+                                "Link".into() => RefOr::Object(link_header(components)),
+                            },
+                            ..Default::default()
+                        })
+                    },
+                    ..Default::default()
+                })
+            }
+        }
+
+        #[cfg(feature = "axum")]
+        impl<T: Serialize> axum::response::IntoResponse for $ty<T> {
+            fn into_response(self) -> axum::response::Response {
+                axum::Json(self).into_response()
+            }
+        }
+    };
+}
+
+impl_traits_for_page!(Paginated);
+impl_traits_for_page!(CursorPage);
@@ -0,0 +1,164 @@
+//! Documentation of common HTTP patterns, used by attributes of [`openapi`](crate::openapi)
+//! macro.
+
+use okapi::{
+    openapi3::{Operation, RefOr, Response},
+    schemars::schema::{InstanceType, SchemaObject},
+};
+
+use crate::header::{header, parameter};
+
+/// Extension, which marks operations, safely retried with `Idempotency-Key` header.
+pub(crate) const IDEMPOTENT_EXTENSION: &str = "x-idempotent";
+
+/// Document `Idempotency-Key` header, `409`/`422` responses and `x-idempotent` extension
+/// (generated by `idempotent` attribute).
+pub fn document_idempotency_key(operation: &mut Operation) {
+    add_header_parameter(
+        operation,
+        "Idempotency-Key",
+        "Unique key, which allows to safely retry request",
+        true,
+    );
+    add_response(
+        operation,
+        "409",
+        "Request with the same idempotency key is being processed",
+    );
+    add_response(
+        operation,
+        "422",
+        "Idempotency key is already used for different request",
+    );
+    let _ = operation
+        .extensions
+        .insert(IDEMPOTENT_EXTENSION.into(), serde_json::Value::Bool(true));
+}
+
+/// Document `If-Match`/`If-None-Match` headers, `ETag` response header and `304`/`412`
+/// responses (generated by `conditional(etag)` attribute).
+pub fn document_conditional_etag(operation: &mut Operation) {
+    add_header_parameter(
+        operation,
+        "If-Match",
+        "Perform request only if current entity tag matches one of listed",
+        false,
+    );
+    add_header_parameter(
+        operation,
+        "If-None-Match",
+        "Perform request only if current entity tag doesn't match any of listed",
+        false,
+    );
+    add_response(operation, "304", "Not modified");
+    add_response(operation, "412", "Precondition failed");
+    for (status, response) in operation.responses.responses.iter_mut() {
+        let RefOr::Object(response) = response else {
+            continue;
+        };
+        if status.starts_with('2') || status == "304" {
+            let _ = response.headers.entry("ETag".into()).or_insert_with(|| {
+                RefOr::Object(header(
+                    string_schema(),
+                    Some("Entity tag of current representation"),
+                    false,
+                ))
+            });
+        }
+    }
+}
+
+/// Add header parameter with string value, unless header is already defined.
+fn add_header_parameter(operation: &mut Operation, name: &str, description: &str, required: bool) {
+    let already_defined = operation.parameters.iter().any(|x| {
+        matches!(x, RefOr::Object(y) if y.location == "header" && y.name.eq_ignore_ascii_case(name))
+    });
+    if already_defined {
+        return;
+    }
+    let header = header(string_schema(), Some(description), required);
+    operation
+        .parameters
+        .push(RefOr::Object(parameter(name, "header", header)));
+}
+
+/// Add response without content, unless response with this status is already defined.
+fn add_response(operation: &mut Operation, status: &str, description: &str) {
+    let _ = operation
+        .responses
+        .responses
+        .entry(status.into())
+        .or_insert_with(|| {
+            RefOr::Object(Response {
+                description: description.into(),
+                ..Default::default()
+            })
+        });
+}
+
+fn string_schema() -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idempotency_key() {
+        let mut operation = Operation::default();
+        operation.responses.responses.insert(
+            "409".into(),
+            RefOr::Object(Response {
+                description: "Custom".into(),
+                ..Default::default()
+            }),
+        );
+        document_idempotency_key(&mut operation);
+        document_idempotency_key(&mut operation);
+        assert_eq!(operation.parameters.len(), 1);
+        assert_eq!(
+            operation.responses.responses.keys().collect::<Vec<_>>(),
+            ["409", "422"]
+        );
+        assert!(matches!(
+            &operation.responses.responses["409"],
+            RefOr::Object(x) if x.description == "Custom"
+        ));
+        assert_eq!(
+            operation.extensions[IDEMPOTENT_EXTENSION],
+            serde_json::Value::Bool(true)
+        );
+    }
+
+    #[test]
+    fn conditional_etag() {
+        let mut operation = Operation::default();
+        operation
+            .responses
+            .responses
+            .insert("200".into(), RefOr::Object(Response::default()));
+        document_conditional_etag(&mut operation);
+        let names: Vec<_> = operation
+            .parameters
+            .iter()
+            .filter_map(|x| match x {
+                RefOr::Object(x) => Some(x.name.as_str()),
+                RefOr::Ref(_) => None,
+            })
+            .collect();
+        assert_eq!(names, ["If-Match", "If-None-Match"]);
+        let etag = |status: &str| {
+            matches!(
+                &operation.responses.responses[status],
+                RefOr::Object(x) if x.headers.contains_key("ETag")
+            )
+        };
+        assert!(etag("200"));
+        assert!(etag("304"));
+        assert!(!etag("412"));
+    }
+}
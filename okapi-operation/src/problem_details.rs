@@ -0,0 +1,128 @@
+use http::StatusCode;
+use okapi::{
+    map,
+    openapi3::{MediaType, RefOr, Response, Responses},
+    schemars::JsonSchema,
+    Map,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{Components, Error, ToMediaTypes, ToResponses};
+
+/// Media type of [`ProblemDetails`].
+const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Problem details for HTTP APIs ([RFC 9457](https://www.rfc-editor.org/rfc/rfc9457)).
+///
+/// Documented as `default` response with `application/problem+json` media type. Could be
+/// added to every operation with [`BuilderOptions::default_error_responses`](crate::BuilderOptions::default_error_responses).
+///
+/// With `axum` feature it implements `IntoResponse`, using [`status`](Self::status) as
+/// status of response (or `500 Internal Server Error`, if not set).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(crate = "okapi::schemars")]
+pub struct ProblemDetails {
+    /// URI reference, which identifies problem type.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    /// Short summary of problem type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// HTTP status code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Explanation specific to this occurrence of problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// URI reference, which identifies this occurrence of problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Extension members.
+    #[serde(flatten)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ProblemDetails {
+    /// Create problem details with status and title (canonical reason of status).
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            title: status.canonical_reason().map(Into::into),
+            status: Some(status.as_u16()),
+            ..Default::default()
+        }
+    }
+
+    /// Set problem type.
+    pub fn with_type(mut self, type_: impl Into<String>) -> Self {
+        self.type_ = Some(type_.into());
+        self
+    }
+
+    /// Set title.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set detail.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Set instance.
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Add extension member.
+    pub fn with_extension(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        let _ = self.extensions.insert(name.into(), value.into());
+        self
+    }
+}
+
+impl ToMediaTypes for ProblemDetails {
+    fn generate(components: &mut Components) -> Result<Map<String, MediaType>, Error> {
+        Ok(map! {
+            PROBLEM_JSON.into() => MediaType {
+                schema: Some(components.schema_for::<Self>()),
+                ..Default::default()
+            }
+        })
+    }
+}
+
+impl ToResponses for ProblemDetails {
+    fn generate(components: &mut Components) -> Result<Responses, Error> {
+        Ok(Responses {
+            default: Some(RefOr::Object(Response {
+                description: "Error".into(),
+                content: <Self as ToMediaTypes>::generate(components)?,
+                ..Default::default()
+            })),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for ProblemDetails {
+    fn into_response(self) -> axum::response::Response {
+        let status = self
+            .status
+            .and_then(|x| StatusCode::from_u16(x).ok())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        match serde_json::to_vec(&self) {
+            Ok(body) => {
+                (status, [(http::header::CONTENT_TYPE, PROBLEM_JSON)], body).into_response()
+            }
+            Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use okapi::{
+    openapi3::{Header, RefOr, Response, Responses},
+    schemars::schema::{InstanceType, SchemaObject},
+};
+
+use crate::{header::header, Components, Error, ToResponses};
+
+/// Rate-limit headers, documented on successful and `429` responses.
+const RATE_LIMIT_HEADERS: &[(&str, &str)] = &[
+    ("X-RateLimit-Limit", "Maximum number of requests in window"),
+    (
+        "X-RateLimit-Remaining",
+        "Number of requests, remaining in current window",
+    ),
+    (
+        "X-RateLimit-Reset",
+        "Number of seconds until current window is reset",
+    ),
+];
+
+/// Response of rate-limited operation.
+///
+/// Documented as responses of `T` with `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+/// `X-RateLimit-Reset` headers on successful (`2XX`) responses, and `429` response with
+/// the same headers and `Retry-After`. Headers themselves are usually set by rate-limiting
+/// middleware, so wrapper doesn't alter response.
+///
+/// Operations, which are rate-limited by middleware without changing handlers, could be
+/// documented with [`OpenApiBuilder::document_rate_limits`](crate::OpenApiBuilder::document_rate_limits).
+///
+/// With `axum` feature it implements `IntoResponse`, forwarding to `T`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimited<T>(pub T);
+
+impl<T: ToResponses> ToResponses for RateLimited<T> {
+    fn generate(components: &mut Components) -> Result<Responses, Error> {
+        let mut responses = T::generate(components)?;
+        add_rate_limit_responses(&mut responses);
+        Ok(responses)
+    }
+}
+
+#[cfg(feature = "axum")]
+impl<T: axum::response::IntoResponse> axum::response::IntoResponse for RateLimited<T> {
+    fn into_response(self) -> axum::response::Response {
+        self.0.into_response()
+    }
+}
+
+/// Add rate-limit headers to successful responses and `429` response (if not defined).
+///
+/// Headers, which are already defined, and referenced responses are left unchanged.
+pub(crate) fn add_rate_limit_responses(responses: &mut Responses) {
+    for (status, response) in responses.responses.iter_mut() {
+        if let (true, RefOr::Object(response)) = (status.starts_with('2'), response) {
+            add_headers(response, RATE_LIMIT_HEADERS);
+        }
+    }
+    let too_many_requests = responses.responses.entry("429".into()).or_insert_with(|| {
+        RefOr::Object(Response {
+            description: "Too many requests".into(),
+            ..Default::default()
+        })
+    });
+    if let RefOr::Object(response) = too_many_requests {
+        add_headers(response, RATE_LIMIT_HEADERS);
+        add_headers(
+            response,
+            &[(
+                "Retry-After",
+                "Number of seconds to wait before making new request",
+            )],
+        );
+    }
+}
+
+fn add_headers(response: &mut Response, headers: &[(&str, &str)]) {
+    for (name, description) in headers {
+        let _ = response
+            .headers
+            .entry((*name).into())
+            .or_insert_with(|| RefOr::Object(integer_header(description)));
+    }
+}
+
+fn integer_header(description: &str) -> Header {
+    let schema = SchemaObject {
+        instance_type: Some(InstanceType::Integer.into()),
+        format: Some("int64".into()),
+        ..Default::default()
+    };
+    header(schema, Some(description), false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_responses() {
+        let mut components = Components::new(Default::default());
+        let responses = <RateLimited<String> as ToResponses>::generate(&mut components).unwrap();
+        let header_names = |status: &str| match &responses.responses[status] {
+            RefOr::Object(x) => x.headers.keys().cloned().collect::<Vec<_>>(),
+            RefOr::Ref(_) => panic!("Response should not be reference"),
+        };
+        assert_eq!(
+            header_names("200"),
+            [
+                "X-RateLimit-Limit",
+                "X-RateLimit-Remaining",
+                "X-RateLimit-Reset"
+            ]
+        );
+        assert_eq!(
+            header_names("429"),
+            [
+                "X-RateLimit-Limit",
+                "X-RateLimit-Remaining",
+                "X-RateLimit-Reset",
+                "Retry-After"
+            ]
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::HashSet;
+
+use okapi::{
+    openapi3::{Components, OpenApi, Operation, PathItem},
+    schemars::{
+        schema::{Schema, SchemaObject},
+        visit::{visit_schema_object, Visitor},
+    },
+};
+
+use crate::{
+    walk::{for_each_path_item_operation_mut, for_each_reference, walk_ref_or, SpecVisitor},
+    Error,
+};
+
+/// Direction of data, described by schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    /// Sent by client (request bodies and parameters), `writeOnly` properties are kept.
+    Request,
+    /// Sent by server (responses), `readOnly` properties are kept.
+    Response,
+}
+
+impl Direction {
+    fn suffix(self) -> &'static str {
+        match self {
+            Self::Request => "Request",
+            Self::Response => "Response",
+        }
+    }
+
+    /// Whether property is not sent in this direction.
+    fn excludes(self, schema: &SchemaObject) -> bool {
+        let Some(metadata) = schema.metadata.as_ref() else {
+            return false;
+        };
+        match self {
+            Self::Request => metadata.read_only,
+            Self::Response => metadata.write_only,
+        }
+    }
+}
+
+/// Split schemas with `readOnly`/`writeOnly` properties (and schemas, referencing them) into
+/// `<Name>Request` and `<Name>Response` schemas, and reference them from requests and
+/// responses respectively.
+///
+/// Schemas, which are no longer referenced after splitting, are removed.
+pub(crate) fn split_read_write_schemas(
+    spec: &mut OpenApi,
+    definitions_path: &str,
+) -> Result<(), Error> {
+    let Some(components) = spec.components.as_ref() else {
+        return Ok(());
+    };
+    let split = schemas_to_split(components, definitions_path);
+    if split.is_empty() {
+        return Ok(());
+    }
+
+    let referenced_before = referenced_schemas(spec, definitions_path);
+    let schemas = &mut spec.components.as_mut().expect("Checked above").schemas;
+    for name in schemas.keys().cloned().collect::<Vec<_>>() {
+        if !split.contains(&name) {
+            continue;
+        }
+        for direction in [Direction::Request, Direction::Response] {
+            let mut schema = schemas[&name].clone();
+            SplitVisitor::new(definitions_path, &split, direction).visit_schema_object(&mut schema);
+            let new_name = format!("{name}{}", direction.suffix());
+            if schemas.contains_key(&new_name) {
+                return Err(Error::SchemaConflict { name: new_name });
+            }
+            let _ = schemas.insert(new_name, schema);
+        }
+    }
+
+    for path_item in spec.paths.values_mut() {
+        split_path_item(path_item, definitions_path, &split)?;
+    }
+    if let Some(components) = spec.components.as_mut() {
+        split_components(components, definitions_path, &split);
+    }
+
+    // Remove schemas, which were replaced (unless referenced before splitting, like ones,
+    // registered explicitly), repeating while references are removed
+    let removable: HashSet<_> = split
+        .iter()
+        .filter(|x| referenced_before.contains(*x))
+        .flat_map(|name| {
+            [
+                name.clone(),
+                format!("{name}{}", Direction::Request.suffix()),
+                format!("{name}{}", Direction::Response.suffix()),
+            ]
+        })
+        .collect();
+    loop {
+        let referenced = referenced_schemas(spec, definitions_path);
+        let schemas = &mut spec.components.as_mut().expect("Checked above").schemas;
+        let before = schemas.len();
+        schemas.retain(|name, _| !removable.contains(name) || referenced.contains(name));
+        if schemas.len() == before {
+            break Ok(());
+        }
+    }
+}
+
+/// Names of schemas with `readOnly`/`writeOnly` properties, and schemas, referencing them.
+fn schemas_to_split(components: &Components, definitions_path: &str) -> HashSet<String> {
+    #[derive(Default)]
+    struct Inspect {
+        marked: bool,
+        references: Vec<String>,
+    }
+
+    impl Visitor for Inspect {
+        fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+            if let Some(object) = schema.object.as_ref() {
+                self.marked |= object.properties.values().any(|x| {
+                    matches!(x, Schema::Object(x) if Direction::Request.excludes(x) || Direction::Response.excludes(x))
+                });
+            }
+            if let Some(reference) = schema.reference.as_ref() {
+                self.references.push(reference.clone());
+            }
+            visit_schema_object(self, schema)
+        }
+    }
+
+    let mut dependencies = Vec::new();
+    let mut split = HashSet::new();
+    for (name, schema) in &components.schemas {
+        let mut inspect = Inspect::default();
+        inspect.visit_schema_object(&mut schema.clone());
+        if inspect.marked {
+            let _ = split.insert(name.clone());
+        }
+        let references: Vec<_> = inspect
+            .references
+            .iter()
+            .filter_map(|x| x.strip_prefix(definitions_path))
+            .map(str::to_owned)
+            .collect();
+        dependencies.push((name.clone(), references));
+    }
+    loop {
+        let before = split.len();
+        for (name, references) in &dependencies {
+            if references.iter().any(|x| split.contains(x)) {
+                let _ = split.insert(name.clone());
+            }
+        }
+        if split.len() == before {
+            break split;
+        }
+    }
+}
+
+fn referenced_schemas(spec: &mut OpenApi, definitions_path: &str) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for_each_reference(spec, |reference| {
+        if let Some(name) = reference.strip_prefix(definitions_path) {
+            let _ = names.insert(name.to_owned());
+        }
+    });
+    names
+}
+
+fn split_path_item(
+    path_item: &mut PathItem,
+    definitions_path: &str,
+    split: &HashSet<String>,
+) -> Result<(), Error> {
+    let mut request = SplitVisitor::new(definitions_path, split, Direction::Request);
+    for parameter in &mut path_item.parameters {
+        walk_ref_or(&mut request, parameter);
+    }
+    for_each_path_item_operation_mut(path_item, |_, operation| {
+        split_operation(operation, definitions_path, split)
+    })
+}
+
+fn split_operation(operation: &mut Operation, definitions_path: &str, split: &HashSet<String>) {
+    let mut request = SplitVisitor::new(definitions_path, split, Direction::Request);
+    for parameter in &mut operation.parameters {
+        walk_ref_or(&mut request, parameter);
+    }
+    if let Some(request_body) = operation.request_body.as_mut() {
+        walk_ref_or(&mut request, request_body);
+    }
+    let mut response = SplitVisitor::new(definitions_path, split, Direction::Response);
+    for value in operation
+        .responses
+        .default
+        .iter_mut()
+        .chain(operation.responses.responses.values_mut())
+    {
+        walk_ref_or(&mut response, value);
+    }
+}
+
+fn split_components(components: &mut Components, definitions_path: &str, split: &HashSet<String>) {
+    let mut request = SplitVisitor::new(definitions_path, split, Direction::Request);
+    for value in components.parameters.values_mut() {
+        walk_ref_or(&mut request, value);
+    }
+    for value in components.request_bodies.values_mut() {
+        walk_ref_or(&mut request, value);
+    }
+    let mut response = SplitVisitor::new(definitions_path, split, Direction::Response);
+    for value in components.responses.values_mut() {
+        walk_ref_or(&mut response, value);
+    }
+    for value in components.headers.values_mut() {
+        walk_ref_or(&mut response, value);
+    }
+}
+
+/// Removes properties, not sent in direction, and replaces references to split schemas.
+struct SplitVisitor<'a> {
+    definitions_path: &'a str,
+    split: &'a HashSet<String>,
+    direction: Direction,
+}
+
+impl<'a> SplitVisitor<'a> {
+    fn new(definitions_path: &'a str, split: &'a HashSet<String>, direction: Direction) -> Self {
+        Self {
+            definitions_path,
+            split,
+            direction,
+        }
+    }
+}
+
+impl Visitor for SplitVisitor<'_> {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(reference) = schema.reference.as_mut() {
+            if let Some(name) = reference.strip_prefix(self.definitions_path) {
+                if self.split.contains(name) {
+                    *reference =
+                        format!("{}{name}{}", self.definitions_path, self.direction.suffix());
+                }
+            }
+        }
+        if let Some(object) = schema.object.as_mut() {
+            let direction = self.direction;
+            object.properties.retain(|name, property| {
+                let excluded = matches!(property, Schema::Object(x) if direction.excludes(x));
+                if excluded {
+                    let _ = object.required.remove(name);
+                }
+                !excluded
+            });
+        }
+        visit_schema_object(self, schema)
+    }
+}
+
+impl SpecVisitor for SplitVisitor<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use okapi::openapi3::{MediaType, RefOr, RequestBody, Response};
+
+    use super::*;
+    use crate::walk::SCHEMA_REFERENCE_PREFIX;
+
+    fn reference(name: &str) -> SchemaObject {
+        SchemaObject::new_ref(format!("{SCHEMA_REFERENCE_PREFIX}{name}"))
+    }
+
+    fn content(name: &str) -> okapi::Map<String, MediaType> {
+        okapi::map! {
+            "application/json".into() => MediaType {
+                schema: Some(reference(name)),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn split() {
+        let user: SchemaObject = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "required": ["id", "name", "password"],
+            "properties": {
+                "id": { "type": "integer", "readOnly": true },
+                "name": { "type": "string" },
+                "password": { "type": "string", "writeOnly": true }
+            }
+        }))
+        .unwrap();
+        let page: SchemaObject = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "items": { "type": "array", "items": { "$ref": "#/components/schemas/User" } }
+            }
+        }))
+        .unwrap();
+        let mut spec = OpenApi {
+            components: Some(Components {
+                schemas: okapi::map! {
+                    "User".into() => user,
+                    "Page".into() => page,
+                    "Unrelated".into() => SchemaObject::default(),
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut operation = Operation {
+            request_body: Some(RefOr::Object(RequestBody {
+                content: content("User"),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let _ = operation.responses.responses.insert(
+            "200".into(),
+            RefOr::Object(Response {
+                content: content("Page"),
+                ..Default::default()
+            }),
+        );
+        spec.paths.entry("/users".into()).or_default().post = Some(operation);
+
+        split_read_write_schemas(&mut spec, SCHEMA_REFERENCE_PREFIX).unwrap();
+
+        let schemas = serde_json::to_value(&spec.components.as_ref().unwrap().schemas).unwrap();
+        let names: Vec<_> = spec.components.as_ref().unwrap().schemas.keys().collect();
+        assert_eq!(
+            names,
+            ["Unrelated", "UserRequest", "UserResponse", "PageResponse"]
+        );
+        assert_eq!(
+            schemas["UserRequest"]["required"],
+            serde_json::json!(["name", "password"])
+        );
+        assert!(schemas["UserResponse"]["properties"]
+            .get("password")
+            .is_none());
+        assert_eq!(
+            schemas["PageResponse"]["properties"]["items"]["items"]["$ref"],
+            "#/components/schemas/UserResponse"
+        );
+        let operation = serde_json::to_value(spec.paths["/users"].post.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            operation["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/UserRequest"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use okapi::{
+    openapi3::SchemaObject,
+    schemars::visit::{visit_schema_object, Visitor},
+    Map,
+};
+
+/// How schemas of recursive types (like trees) are emitted.
+///
+/// Set with [`ComponentsBuilder::recursion_policy`](crate::ComponentsBuilder::recursion_policy).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecursionPolicy {
+    /// Recursive types are always referenced.
+    #[default]
+    Reference,
+    /// Recursive references inside schema of recursive type are replaced with schema itself
+    /// up to specified depth, deeper ones are referenced.
+    InlineDepth(usize),
+    /// Return [`Error::RecursiveSchema`](crate::Error::RecursiveSchema).
+    Error,
+}
+
+/// Names of schemas, which reference themselves (directly or through other schemas).
+pub(crate) fn recursive_schemas(
+    schemas: &Map<String, SchemaObject>,
+    definitions_path: &str,
+) -> Vec<String> {
+    struct References<'a> {
+        definitions_path: &'a str,
+        names: Vec<String>,
+    }
+
+    impl Visitor for References<'_> {
+        fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+            if let Some(name) = schema
+                .reference
+                .as_ref()
+                .and_then(|x| x.strip_prefix(self.definitions_path))
+            {
+                self.names.push(name.to_owned());
+            }
+            visit_schema_object(self, schema)
+        }
+    }
+
+    let references: Map<&str, Vec<String>> = schemas
+        .iter()
+        .map(|(name, schema)| {
+            let mut visitor = References {
+                definitions_path,
+                names: Vec::new(),
+            };
+            visitor.visit_schema_object(&mut schema.clone());
+            (name.as_str(), visitor.names)
+        })
+        .collect();
+    references
+        .keys()
+        .filter(|name| {
+            let mut visited = HashSet::new();
+            let mut stack: Vec<&str> = references[**name].iter().map(String::as_str).collect();
+            while let Some(current) = stack.pop() {
+                if current == **name {
+                    return true;
+                }
+                if visited.insert(current) {
+                    if let Some(next) = references.get(current) {
+                        stack.extend(next.iter().map(String::as_str));
+                    }
+                }
+            }
+            false
+        })
+        .map(|x| (*x).to_owned())
+        .collect()
+}
+
+/// Replace recursive references in schemas of recursive types with referenced schemas up
+/// to `depth`.
+pub(crate) fn inline_recursive_schemas(
+    schemas: &mut Map<String, SchemaObject>,
+    recursive: &[String],
+    depth: usize,
+    definitions_path: &str,
+) {
+    struct Inline<'a> {
+        originals: &'a Map<String, SchemaObject>,
+        recursive: &'a [String],
+        definitions_path: &'a str,
+        depth: usize,
+    }
+
+    impl Visitor for Inline<'_> {
+        fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+            let target = schema
+                .reference
+                .as_ref()
+                .and_then(|x| x.strip_prefix(self.definitions_path))
+                .filter(|x| self.recursive.iter().any(|y| y == x))
+                .and_then(|x| self.originals.get(x));
+            match target {
+                Some(target) if self.depth > 0 => {
+                    let metadata = schema.metadata.take();
+                    *schema = target.clone();
+                    if metadata.is_some() {
+                        schema.metadata = metadata;
+                    }
+                    self.depth -= 1;
+                    visit_schema_object(self, schema);
+                    self.depth += 1;
+                }
+                _ => visit_schema_object(self, schema),
+            }
+        }
+    }
+
+    let originals = schemas.clone();
+    for name in recursive {
+        if let Some(schema) = schemas.get_mut(name) {
+            Inline {
+                originals: &originals,
+                recursive,
+                definitions_path,
+                depth,
+            }
+            .visit_schema_object(schema);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::walk::SCHEMA_REFERENCE_PREFIX;
+
+    fn schemas() -> Map<String, SchemaObject> {
+        serde_json::from_value(serde_json::json!({
+            "Tree": {
+                "type": "object",
+                "properties": {
+                    "children": { "type": "array", "items": { "$ref": "#/components/schemas/Tree" } }
+                }
+            },
+            "Forest": {
+                "type": "array",
+                "items": { "$ref": "#/components/schemas/Tree" }
+            },
+            "Ping": { "properties": { "pong": { "$ref": "#/components/schemas/Pong" } } },
+            "Pong": { "properties": { "ping": { "$ref": "#/components/schemas/Ping" } } }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(
+            recursive_schemas(&schemas(), SCHEMA_REFERENCE_PREFIX),
+            ["Ping", "Pong", "Tree"]
+        );
+    }
+
+    #[test]
+    fn inline_depth() {
+        let mut schemas = schemas();
+        inline_recursive_schemas(&mut schemas, &["Tree".into()], 2, SCHEMA_REFERENCE_PREFIX);
+        let tree = serde_json::to_value(&schemas["Tree"]).unwrap();
+        let level = |value: &serde_json::Value| value["properties"]["children"]["items"].clone();
+        assert_eq!(level(&level(&tree))["type"], "object");
+        assert_eq!(
+            level(&level(&level(&tree)))["$ref"],
+            "#/components/schemas/Tree"
+        );
+        assert_eq!(
+            serde_json::to_value(&schemas["Forest"]).unwrap()["items"]["$ref"],
+            "#/components/schemas/Tree"
+        );
+    }
+}
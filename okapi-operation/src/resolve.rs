@@ -0,0 +1,144 @@
+//! Utilities for resolving references (`$ref`) in specification.
+//!
+//! Both functions are intended to be used on complete specification (i.e. output of
+//! [`OpenApiBuilder::build`](crate::OpenApiBuilder::build)). References to schemas are
+//! matched by definitions path, which was used for generating specification (see
+//! [`ComponentsBuilder::definitions_path`](crate::ComponentsBuilder::definitions_path),
+//! `#/components/schemas/` by default).
+
+use okapi::{
+    openapi3::{Components, OpenApi, Ref, RefOr},
+    schemars::{
+        schema::SchemaObject,
+        visit::{visit_schema_object, Visitor},
+    },
+};
+
+use crate::walk::{walk_components_content, walk_spec, Referenceable, SpecVisitor};
+
+/// Replace all references to components with content of these components.
+///
+/// Recursive schemas can't be inlined, so references to them are left as is. Components
+/// are not removed from specification.
+pub fn flatten_refs(spec: &mut OpenApi, definitions_path: &str) {
+    let Some(mut components) = spec.components.take() else {
+        return;
+    };
+    let original = components.clone();
+    let mut flattener = Flattener {
+        components: &original,
+        definitions_path,
+        schemas_stack: Vec::new(),
+    };
+    walk_spec(&mut flattener, spec);
+    for (name, schema) in components.schemas.iter_mut() {
+        flattener.schemas_stack = vec![name.clone()];
+        visit_schema_object(&mut flattener, schema);
+    }
+    flattener.schemas_stack.clear();
+    walk_components_content(&mut flattener, &mut components);
+    spec.components = Some(components);
+}
+
+/// Replace inlined components with references to them.
+///
+/// This is inverse of [`flatten_refs`]: every schema, response, parameter, request body or
+/// header, which is equal to one of components, is replaced with reference. Only composite
+/// schemas (objects, enums and combinations of schemas) are replaced, so primitive schemas
+/// like `{"type": "string"}` are never turned into references.
+pub fn externalize_refs(spec: &mut OpenApi, definitions_path: &str) {
+    let Some(mut components) = spec.components.take() else {
+        return;
+    };
+    let mut externalizer = Externalizer {
+        components: components.clone(),
+        definitions_path,
+    };
+    walk_spec(&mut externalizer, spec);
+    for schema in components.schemas.values_mut() {
+        visit_schema_object(&mut externalizer, schema);
+    }
+    walk_components_content(&mut externalizer, &mut components);
+    spec.components = Some(components);
+}
+
+struct Flattener<'a> {
+    components: &'a Components,
+    definitions_path: &'a str,
+    schemas_stack: Vec<String>,
+}
+
+impl Visitor for Flattener<'_> {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        let target = schema
+            .reference
+            .as_deref()
+            .and_then(|x| x.strip_prefix(self.definitions_path))
+            .filter(|name| !self.schemas_stack.iter().any(|x| x == name))
+            .and_then(|name| Some((name.to_owned(), self.components.schemas.get(name)?)));
+        match target {
+            Some((name, target)) => {
+                *schema = target.clone();
+                self.schemas_stack.push(name);
+                self.visit_schema_object(schema);
+                let _ = self.schemas_stack.pop();
+            }
+            None => visit_schema_object(self, schema),
+        }
+    }
+}
+
+impl SpecVisitor for Flattener<'_> {
+    fn visit_ref_or<T: Referenceable>(&mut self, value: &mut RefOr<T>) {
+        let mut seen = Vec::new();
+        while let RefOr::Ref(reference) = value {
+            let Some(name) = reference.reference.strip_prefix(T::REFERENCE_PREFIX) else {
+                break;
+            };
+            if seen.iter().any(|x| x == name) {
+                break;
+            }
+            let Some(target) = T::components(self.components).get(name) else {
+                break;
+            };
+            seen.push(name.to_owned());
+            *value = target.clone();
+        }
+    }
+}
+
+struct Externalizer<'a> {
+    components: Components,
+    definitions_path: &'a str,
+}
+
+impl Visitor for Externalizer<'_> {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        let is_composite =
+            schema.object.is_some() || schema.subschemas.is_some() || schema.enum_values.is_some();
+        let name = is_composite
+            .then(|| self.components.schemas.iter().find(|(_, x)| *x == schema))
+            .flatten()
+            .map(|(name, _)| name);
+        match name {
+            Some(name) => {
+                *schema = SchemaObject::new_ref(format!("{}{name}", self.definitions_path))
+            }
+            None => visit_schema_object(self, schema),
+        }
+    }
+}
+
+impl SpecVisitor for Externalizer<'_> {
+    fn visit_ref_or<T: Referenceable>(&mut self, value: &mut RefOr<T>) {
+        if let Some(name) = T::components(&self.components)
+            .iter()
+            .find(|(_, x)| matches!(x, RefOr::Object(_)) && *x == value)
+            .map(|(name, _)| name)
+        {
+            *value = RefOr::Ref(Ref {
+                reference: format!("{}{name}", T::REFERENCE_PREFIX),
+            });
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! Splitting specification into multiple files and querying its content.
+//!
+//! Splitting is useful for huge specifications, stored in git: every file could be reviewed
+//! separately and changes of unrelated endpoints don't conflict with each other.
+//!
+//! Query functions allow tests and tooling to inspect generated specification without
+//! matching on [`RefOr`](okapi::openapi3::RefOr) and optional fields manually.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io,
+    path::Path,
+};
+
+use http::Method;
+use okapi::openapi3::{OpenApi, Operation, PathItem, SchemaObject};
+use serde_json::Value;
+
+use crate::{
+    canonical::sort_keys,
+    walk::{for_each_reference, path_item_operations},
+};
+
+/// Name of root document of split specification.
+pub const ROOT_FILE_NAME: &str = "openapi.json";
+
+/// Name of file (without extension) for path items without tags in [`SplitLayout::ByTag`].
+const UNTAGGED_FILE_NAME: &str = "default";
+
+/// How path items are distributed between files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitLayout {
+    /// File per tag (`tags/<tag>.json`), containing map from path to path item. Path item is
+    /// put into file of first tag of its first operation (or `tags/default.json`, if it
+    /// doesn't have tags).
+    ByTag,
+    /// File per path (`paths/<path>.json`), containing single path item.
+    ByPath,
+}
+
+/// Specification, split into multiple files.
+///
+/// Root document ([`ROOT_FILE_NAME`]) contains everything except path items, which are
+/// replaced with relative references to other files. References to components in these files
+/// point to root document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplitSpec {
+    files: BTreeMap<String, Value>,
+}
+
+impl SplitSpec {
+    /// Root document.
+    pub fn root(&self) -> &Value {
+        &self.files[ROOT_FILE_NAME]
+    }
+
+    /// All files (including root document) by their paths, relative to root document.
+    pub fn files(&self) -> &BTreeMap<String, Value> {
+        &self.files
+    }
+
+    /// Write all files into directory as pretty-printed JSON with sorted keys.
+    ///
+    /// Directory (and its subdirectories) are created, if missing. Existing files are
+    /// overwritten, other files in directory are left as is.
+    pub fn write_to_dir(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        for (name, content) in &self.files {
+            let file = dir.join(name);
+            if let Some(parent) = file.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut output = serde_json::to_string_pretty(content)?;
+            output.push('\n');
+            std::fs::write(file, output)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split specification into root document and per-tag or per-path files.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use okapi_operation::{*, spec::*};
+/// # let openapi = OpenApiBuilder::new("Demo", "1.0.0").build().unwrap();
+/// split(&openapi, SplitLayout::ByTag)?.write_to_dir("api")?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn split(spec: &OpenApi, layout: SplitLayout) -> Result<SplitSpec, serde_json::Error> {
+    let mut root = spec.clone();
+    let paths = std::mem::take(&mut root.paths);
+    let mut file_names = FileNames::default();
+    let mut groups: BTreeMap<String, Vec<(String, PathItem)>> = BTreeMap::new();
+
+    for (path, mut item) in paths {
+        let (file, reference) = match layout {
+            SplitLayout::ByTag => {
+                let tag = path_item_operations(&item)
+                    .next()
+                    .and_then(|(_, operation)| operation.tags.first())
+                    .map_or(UNTAGGED_FILE_NAME, String::as_str);
+                let file = file_names.get("tags", tag);
+                let reference = format!("{file}#/{}", json_pointer_segment(&path));
+                (file, reference)
+            }
+            SplitLayout::ByPath => {
+                let file = file_names.unique("paths", &path);
+                (file.clone(), file)
+            }
+        };
+        rewrite_local_references(&mut item, &path);
+        let _ = root.paths.insert(
+            path.clone(),
+            PathItem {
+                reference: Some(reference),
+                ..Default::default()
+            },
+        );
+        groups.entry(file).or_default().push((path, item));
+    }
+
+    let mut files = BTreeMap::new();
+    let _ = files.insert(ROOT_FILE_NAME.into(), serde_json::to_value(&root)?);
+    for (file, items) in groups {
+        let content = match layout {
+            SplitLayout::ByTag => Value::Object(
+                items
+                    .into_iter()
+                    .map(|(path, item)| Ok((path, serde_json::to_value(item)?)))
+                    .collect::<Result<_, serde_json::Error>>()?,
+            ),
+            SplitLayout::ByPath => {
+                let (_, item) = items.into_iter().next().expect("File has single path item");
+                serde_json::to_value(item)?
+            }
+        };
+        let _ = files.insert(file, content);
+    }
+    Ok(SplitSpec {
+        files: files
+            .into_iter()
+            .map(|(name, content)| (name, sort_keys(content)))
+            .collect(),
+    })
+}
+
+/// Get value at [JSON pointer](https://www.rfc-editor.org/rfc/rfc6901) in specification.
+///
+/// Specification is serialized into JSON for lookup, so value is returned as JSON. Segments
+/// of pointer should be escaped (`~` as `~0` and `/` as `~1`). Returns `None`, if there is
+/// no value at pointer.
+///
+/// # Example
+///
+/// ```rust
+/// # use okapi_operation::{*, okapi::openapi3::Operation};
+/// let openapi = OpenApiBuilder::new("Demo", "1.0.0")
+///     .operation("/users/{id}", http::Method::GET, |_| Ok(Operation::default()))
+///     .build()?;
+/// assert!(spec::query(&openapi, "/paths/~1users~1{id}/get").is_some());
+/// assert_eq!(spec::query(&openapi, "/info/title"), Some(serde_json::json!("Demo")));
+/// # Ok::<(), Error>(())
+/// ```
+pub fn query(spec: &OpenApi, pointer: &str) -> Option<Value> {
+    let mut value = serde_json::to_value(spec).ok()?;
+    value.pointer_mut(pointer).map(Value::take)
+}
+
+/// Get operation by path (as in specification, like `/users/{id}`) and method.
+///
+/// Operations of methods, which can't be documented in OpenAPI 3.0 (stored in
+/// `x-additionalOperations` extension), are not returned.
+pub fn operation_at<'a>(spec: &'a OpenApi, path: &str, method: &Method) -> Option<&'a Operation> {
+    path_item_operations(spec.paths.get(path)?)
+        .find(|(x, _)| x == method)
+        .map(|(_, operation)| operation)
+}
+
+/// Get schema from components of specification by name.
+pub fn schema_named<'a>(spec: &'a OpenApi, name: &str) -> Option<&'a SchemaObject> {
+    spec.components.as_ref()?.schemas.get(name)
+}
+
+/// Rewrite references to root document (`#/...`) into references relative to split file.
+fn rewrite_local_references(item: &mut PathItem, path: &str) {
+    let mut spec = OpenApi::default();
+    let _ = spec.paths.insert(path.into(), std::mem::take(item));
+    for_each_reference(&mut spec, |reference| {
+        if reference.starts_with('#') {
+            *reference = format!("../{ROOT_FILE_NAME}{reference}");
+        }
+    });
+    *item = spec
+        .paths
+        .swap_remove(path)
+        .expect("Path item was inserted");
+}
+
+/// Escape path for using as segment of JSON pointer in URI fragment.
+fn json_pointer_segment(path: &str) -> String {
+    path.replace('%', "%25")
+        .replace('~', "~0")
+        .replace('/', "~1")
+        .replace('{', "%7B")
+        .replace('}', "%7D")
+}
+
+/// Names of generated files.
+#[derive(Default)]
+struct FileNames {
+    /// (Directory, source name) -> file name.
+    names: HashMap<(&'static str, String), String>,
+    used: HashSet<String>,
+}
+
+impl FileNames {
+    /// Same file for the same source name.
+    fn get(&mut self, dir: &'static str, name: &str) -> String {
+        if let Some(file) = self.names.get(&(dir, name.to_owned())) {
+            return file.clone();
+        }
+        let file = self.unique(dir, name);
+        let _ = self.names.insert((dir, name.to_owned()), file.clone());
+        file
+    }
+
+    /// New file for every source name.
+    fn unique(&mut self, dir: &'static str, name: &str) -> String {
+        let base = sanitize_file_name(name);
+        let mut file = format!("{dir}/{base}.json");
+        let mut idx = 2;
+        while !self.used.insert(file.clone()) {
+            file = format!("{dir}/{base}_{idx}.json");
+            idx += 1;
+        }
+        file
+    }
+}
+
+/// File name (without extension) for tag or path (`/users/{id}` -> `users_id`).
+fn sanitize_file_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '.' => result.push(c),
+            '{' | '}' => {}
+            _ if !result.ends_with('_') => result.push('_'),
+            _ => {}
+        }
+    }
+    let result = result.trim_matches(|c| c == '_' || c == '.');
+    if result.is_empty() {
+        "root".into()
+    } else {
+        result.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use okapi::openapi3::{RefOr, Response, Responses};
+    use serde_json::json;
+
+    use super::*;
+    use crate::OpenApiBuilder;
+
+    fn spec() -> OpenApi {
+        fn operation(tag: Option<&str>) -> Operation {
+            Operation {
+                tags: tag.into_iter().map(Into::into).collect(),
+                responses: Responses {
+                    responses: okapi::map! {
+                        "200".into() => RefOr::Ref(okapi::openapi3::Ref {
+                            reference: "#/components/responses/Ok".into(),
+                        }),
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        OpenApiBuilder::new("title", "version")
+            .operation("/users", Method::GET, |_| Ok(operation(Some("users"))))
+            .operation("/users/{id}", Method::GET, |_| Ok(operation(Some("users"))))
+            .operation("/", Method::GET, |_| Ok(operation(None)))
+            .response("Ok", Response::default())
+            .build()
+            .expect("Failed to build spec")
+    }
+
+    #[test]
+    fn by_tag() {
+        let split = split(&spec(), SplitLayout::ByTag).unwrap();
+        assert_eq!(
+            split.files().keys().collect::<Vec<_>>(),
+            ["openapi.json", "tags/default.json", "tags/users.json"]
+        );
+        assert_eq!(
+            split.root()["paths"],
+            json!({
+                "/": {"$ref": "tags/default.json#/~1"},
+                "/users": {"$ref": "tags/users.json#/~1users"},
+                "/users/{id}": {"$ref": "tags/users.json#/~1users~1%7Bid%7D"},
+            })
+        );
+        assert!(split.root()["components"]["responses"].get("Ok").is_some());
+        assert_eq!(
+            split.files()["tags/users.json"]["/users/{id}"]["get"]["responses"]["200"],
+            json!({"$ref": "../openapi.json#/components/responses/Ok"})
+        );
+    }
+
+    #[test]
+    fn by_path() {
+        let split = split(&spec(), SplitLayout::ByPath).unwrap();
+        assert_eq!(
+            split.files().keys().collect::<Vec<_>>(),
+            [
+                "openapi.json",
+                "paths/root.json",
+                "paths/users.json",
+                "paths/users_id.json"
+            ]
+        );
+        assert_eq!(
+            split.root()["paths"]["/users/{id}"],
+            json!({"$ref": "paths/users_id.json"})
+        );
+        assert_eq!(
+            split.files()["paths/users.json"]["get"]["tags"],
+            json!(["users"])
+        );
+    }
+
+    #[test]
+    fn query_spec() {
+        let spec = spec();
+        assert_eq!(
+            query(&spec, "/paths/~1users~1{id}/get/tags/0"),
+            Some(json!("users"))
+        );
+        assert_eq!(query(&spec, "/paths/~1missing"), None);
+        assert!(operation_at(&spec, "/users/{id}", &Method::GET).is_some());
+        assert!(operation_at(&spec, "/users/{id}", &Method::POST).is_none());
+        assert!(operation_at(&spec, "/missing", &Method::GET).is_none());
+        assert!(schema_named(&spec, "Missing").is_none());
+    }
+
+    #[test]
+    fn write_to_dir() {
+        let dir =
+            std::env::temp_dir().join(format!("okapi-operation-split-{}", std::process::id()));
+        let split = split(&spec(), SplitLayout::ByPath).unwrap();
+        split.write_to_dir(&dir).unwrap();
+        let root: Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join(ROOT_FILE_NAME)).unwrap())
+                .unwrap();
+        assert_eq!(&root, split.root());
+        assert!(dir.join("paths/users_id.json").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
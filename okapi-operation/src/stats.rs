@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use okapi::openapi3::{OpenApi, Operation};
+
+use crate::walk::all_path_item_operations;
+
+/// Statistics of specification.
+///
+/// Returned by [`OpenApiBuilder::stats`](crate::OpenApiBuilder::stats). Useful for enforcing
+/// budgets in tests (like failing if endpoint was accidentally dropped):
+///
+/// ```rust
+/// # use okapi_operation::*;
+/// #[openapi(tags = "users")]
+/// async fn get_user() {}
+///
+/// let stats = OpenApiBuilder::new("Demo", "1.0.0")
+///     .operation("/users/{id}", http::Method::GET, get_user__openapi)
+///     .stats()?;
+/// assert_eq!(stats.operations, 1);
+/// assert_eq!(stats.operations_by_tag["users"], 1);
+/// assert_eq!(stats.operations_by_method["GET"], 1);
+/// assert!(stats.json_size < 64 * 1024);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SpecStats {
+    /// Number of paths.
+    pub paths: usize,
+    /// Number of operations.
+    pub operations: usize,
+    /// Number of operations by tag (operations with multiple tags are counted for every tag).
+    pub operations_by_tag: BTreeMap<String, usize>,
+    /// Number of operations without tags.
+    pub untagged_operations: usize,
+    /// Number of operations by method (in uppercase, like `GET`).
+    pub operations_by_method: BTreeMap<String, usize>,
+    /// Number of schemas in `components.schemas`.
+    pub schemas: usize,
+    /// Size of specification, serialized to compact JSON, in bytes.
+    pub json_size: usize,
+}
+
+impl SpecStats {
+    /// Collect statistics of specification.
+    pub fn of(spec: &OpenApi) -> Self {
+        let mut stats = Self {
+            paths: spec.paths.len(),
+            schemas: spec.components.as_ref().map_or(0, |x| x.schemas.len()),
+            json_size: serde_json::to_vec(spec).map_or(0, |x| x.len()),
+            ..Default::default()
+        };
+        for item in spec.paths.values() {
+            for (method, operation) in all_path_item_operations(item) {
+                stats.add_operation(method.as_str(), &operation);
+            }
+        }
+        stats
+    }
+
+    fn add_operation(&mut self, method: &str, operation: &Operation) {
+        self.operations += 1;
+        *self
+            .operations_by_method
+            .entry(method.to_owned())
+            .or_default() += 1;
+        if operation.tags.is_empty() {
+            self.untagged_operations += 1;
+        }
+        for tag in &operation.tags {
+            *self.operations_by_tag.entry(tag.clone()).or_default() += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::walk::ADDITIONAL_OPERATIONS_EXTENSION;
+
+    #[test]
+    fn stats() {
+        let mut spec = OpenApi::default();
+        let item = spec.paths.entry("/users".into()).or_default();
+        item.get = Some(Operation {
+            tags: vec!["users".into(), "admin".into()],
+            ..Default::default()
+        });
+        item.post = Some(Operation::default());
+        let _ = item.extensions.insert(
+            ADDITIONAL_OPERATIONS_EXTENSION.into(),
+            serde_json::json!({ "QUERY": { "tags": ["users"], "responses": {} } }),
+        );
+        let _ = spec.paths.entry("/empty".into()).or_default();
+
+        let stats = SpecStats::of(&spec);
+        assert_eq!(stats.paths, 2);
+        assert_eq!(stats.operations, 3);
+        assert_eq!(
+            stats.operations_by_tag,
+            BTreeMap::from([("admin".into(), 1), ("users".into(), 2)])
+        );
+        assert_eq!(stats.untagged_operations, 1);
+        assert_eq!(
+            stats.operations_by_method,
+            BTreeMap::from([("GET".into(), 1), ("POST".into(), 1), ("QUERY".into(), 1)])
+        );
+        assert_eq!(stats.schemas, 0);
+        assert_eq!(stats.json_size, serde_json::to_vec(&spec).unwrap().len());
+    }
+}
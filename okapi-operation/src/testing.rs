@@ -0,0 +1,162 @@
+//! Helpers for spec-conformance tests.
+//!
+//! All helpers panic with descriptive message on failure, so they could be used like
+//! standard `assert!` macros:
+//!
+//! ```no_run
+//! # use http::Method;
+//! # use okapi_operation::{axum_integration::*, testing::*, *};
+//! #[openapi]
+//! async fn get_user() -> String {
+//!     "user".into()
+//! }
+//!
+//! let router = Router::<()>::new().route("/users/:id", get(openapi_handler!(get_user)));
+//! assert_operation_documented(&router, "/users/{id}", Method::GET);
+//!
+//! let response = http::Response::builder()
+//!     .header("content-type", "text/plain")
+//!     .body("user")
+//!     .unwrap();
+//! assert_response_matches_spec(&router, Method::GET, "/users/1", &response);
+//! ```
+
+use http::Method;
+use okapi::openapi3::{OpenApi, PathItem};
+
+use crate::{
+    axum_integration::{utils::convert_axum_path_to_openapi, Router},
+    validation::{RequestValidator, ResponseValidator},
+    walk::{all_path_item_operations, SCHEMA_REFERENCE_PREFIX},
+    OpenApiBuilder,
+};
+
+/// Source of specification for test helpers.
+pub trait SpecSource {
+    /// Get specification.
+    ///
+    /// Panics if specification can't be built.
+    fn spec(&self) -> OpenApi;
+
+    /// Prefix of references to schemas in specification (see
+    /// [`ComponentsBuilder::definitions_path`](crate::ComponentsBuilder::definitions_path)).
+    fn definitions_path(&self) -> String {
+        SCHEMA_REFERENCE_PREFIX.into()
+    }
+}
+
+impl SpecSource for OpenApi {
+    fn spec(&self) -> OpenApi {
+        self.clone()
+    }
+}
+
+impl SpecSource for OpenApiBuilder {
+    fn spec(&self) -> OpenApi {
+        self.clone()
+            .build()
+            .unwrap_or_else(|err| panic!("Failed to build specification: {err}"))
+    }
+
+    fn definitions_path(&self) -> String {
+        self.definitions_path().into()
+    }
+}
+
+impl<S> SpecSource for Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn spec(&self) -> OpenApi {
+        self.generate_openapi_builder().spec()
+    }
+
+    fn definitions_path(&self) -> String {
+        SpecSource::definitions_path(&self.generate_openapi_builder())
+    }
+}
+
+impl<T: SpecSource + ?Sized> SpecSource for &T {
+    fn spec(&self) -> OpenApi {
+        (**self).spec()
+    }
+
+    fn definitions_path(&self) -> String {
+        (**self).definitions_path()
+    }
+}
+
+/// Assert, that operation with specified path and method is documented.
+///
+/// Path could be specified both in OpenAPI (`/users/{id}`) and Axum (`/users/:id`) formats.
+#[track_caller]
+pub fn assert_operation_documented(source: impl SpecSource, path: &str, method: Method) {
+    let spec = source.spec();
+    let path = convert_axum_path_to_openapi(path).path;
+    let documented = spec
+        .paths
+        .get(&path)
+        .is_some_and(|x| operation_methods(x).contains(&method));
+    if !documented {
+        let operations: Vec<_> = spec
+            .paths
+            .iter()
+            .flat_map(|(path, item)| {
+                operation_methods(item)
+                    .into_iter()
+                    .map(move |method| format!("{method} {path}"))
+            })
+            .collect();
+        panic!(
+            "{method} {path} is not documented, documented operations: [{}]",
+            operations.join(", ")
+        );
+    }
+}
+
+/// Assert, that request matches specification (see [`RequestValidator`]).
+///
+/// Unlike validator, fails if request doesn't match any documented operation.
+#[track_caller]
+pub fn assert_request_matches_spec<B: AsRef<[u8]>>(
+    source: impl SpecSource,
+    request: &http::Request<B>,
+) {
+    let validator =
+        RequestValidator::with_definitions_path(&source.spec(), &source.definitions_path());
+    let (method, path) = (request.method(), request.uri().path());
+    if !validator.has_operation(method, path) {
+        panic!("{method} {path} doesn't match any documented operation");
+    }
+    if let Err(err) = validator.validate(request) {
+        panic!("{method} {path}: {err}");
+    }
+}
+
+/// Assert, that response to request with specified method and path matches specification
+/// (see [`ResponseValidator`]).
+///
+/// Unlike validator, fails if request doesn't match any documented operation.
+#[track_caller]
+pub fn assert_response_matches_spec<B: AsRef<[u8]>>(
+    source: impl SpecSource,
+    method: Method,
+    path: &str,
+    response: &http::Response<B>,
+) {
+    let validator =
+        ResponseValidator::with_definitions_path(&source.spec(), &source.definitions_path());
+    if !validator.has_operation(&method, path) {
+        panic!("{method} {path} doesn't match any documented operation");
+    }
+    if let Err(err) = validator.validate(&method, path, response) {
+        panic!("{err}");
+    }
+}
+
+/// Methods of operations in path item.
+fn operation_methods(item: &PathItem) -> Vec<Method> {
+    all_path_item_operations(item)
+        .map(|(method, _)| method)
+        .collect()
+}
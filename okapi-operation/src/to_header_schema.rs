@@ -0,0 +1,239 @@
+use okapi::openapi3::{SchemaObject, SecurityScheme};
+
+use crate::{Components, Error};
+
+/// Generate name and schema of HTTP header for type.
+///
+/// Used with `header(typed = "...")` attribute of [`openapi`](crate::openapi) macro, so
+/// commonly used headers are described the same way in every operation.
+pub trait ToHeaderSchema {
+    /// Name of header.
+    fn header_name() -> String;
+
+    /// Description of header.
+    fn description() -> Option<String> {
+        None
+    }
+
+    /// Generate schema of header value.
+    fn generate(components: &mut Components) -> Result<SchemaObject, Error>;
+
+    /// Name and definition of security scheme, which header carries (like `Authorization`).
+    ///
+    /// When header is detected from `TypedHeader` argument of handler, security requirement
+    /// is added to operation instead of header parameter.
+    fn security_scheme() -> Option<(String, SecurityScheme)> {
+        None
+    }
+}
+
+/// Probe for documenting `TypedHeader<H>` arguments, detected by [`openapi`](crate::openapi)
+/// macro.
+///
+/// Headers, implementing [`ToHeaderSchema`], are documented via [`DocumentTypedHeader`],
+/// other headers are silently skipped via [`SkipTypedHeader`] (method resolution prefers
+/// impl without autoref).
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub struct TypedHeaderProbe<H>(std::marker::PhantomData<H>);
+
+#[cfg(feature = "macro")]
+impl<H> TypedHeaderProbe<H> {
+    pub fn new() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+#[cfg(feature = "macro")]
+impl<H> Default for TypedHeaderProbe<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub trait DocumentTypedHeader {
+    fn add_typed_header(
+        &self,
+        components: &mut Components,
+        operation: &mut okapi::openapi3::Operation,
+        required: bool,
+    ) -> Result<(), Error>;
+}
+
+#[cfg(feature = "macro")]
+impl<H: ToHeaderSchema> DocumentTypedHeader for TypedHeaderProbe<H> {
+    fn add_typed_header(
+        &self,
+        components: &mut Components,
+        operation: &mut okapi::openapi3::Operation,
+        required: bool,
+    ) -> Result<(), Error> {
+        use okapi::openapi3::RefOr;
+
+        use crate::header::{header, parameter};
+
+        if let Some((name, scheme)) = H::security_scheme() {
+            // Explicitly specified security takes precedence
+            if operation.security.is_none() {
+                components.add_security_scheme(name.clone(), scheme);
+                operation.security = Some(vec![okapi::map! { name => vec![] }]);
+            }
+            return Ok(());
+        }
+        let name = H::header_name();
+        let already_defined = operation.parameters.iter().any(|x| {
+            matches!(x, RefOr::Object(y) if y.location == "header" && y.name.eq_ignore_ascii_case(&name))
+        });
+        if already_defined {
+            return Ok(());
+        }
+        let header = header(
+            H::generate(components)?,
+            H::description().as_deref(),
+            required,
+        );
+        operation
+            .parameters
+            .push(RefOr::Object(parameter(name, "header", header)));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub trait SkipTypedHeader {
+    fn add_typed_header(
+        &self,
+        _components: &mut Components,
+        _operation: &mut okapi::openapi3::Operation,
+        _required: bool,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "macro")]
+impl<H> SkipTypedHeader for &TypedHeaderProbe<H> {}
+
+#[cfg(feature = "headers")]
+mod impls {
+    use headers::{
+        authorization::Credentials, AcceptRanges, AccessControlAllowCredentials,
+        AccessControlAllowHeaders, AccessControlAllowMethods, AccessControlAllowOrigin,
+        AccessControlExposeHeaders, AccessControlMaxAge, AccessControlRequestHeaders,
+        AccessControlRequestMethod, Age, Allow, Authorization, CacheControl, Connection,
+        ContentDisposition, ContentEncoding, ContentLength, ContentLocation, ContentRange,
+        ContentType, Cookie, Date, ETag, Expect, Expires, Header, Host, IfMatch, IfModifiedSince,
+        IfNoneMatch, IfRange, IfUnmodifiedSince, LastModified, Location, Origin, Pragma,
+        ProxyAuthorization, Range, Referer, ReferrerPolicy, RetryAfter, SecWebsocketAccept,
+        SecWebsocketKey, SecWebsocketVersion, Server, SetCookie, StrictTransportSecurity, Te,
+        TransferEncoding, Upgrade, UserAgent, Vary,
+    };
+
+    use super::*;
+
+    macro_rules! impl_to_header_schema_for_headers {
+        ($schema_ty:ty; $($ty:ty),+ $(,)?) => {
+            $(
+                impl ToHeaderSchema for $ty {
+                    fn header_name() -> String {
+                        <$ty as Header>::name().to_string()
+                    }
+
+                    fn generate(components: &mut Components) -> Result<SchemaObject, Error> {
+                        Ok(components.schema_for::<$schema_ty>())
+                    }
+                }
+            )+
+        };
+    }
+
+    impl_to_header_schema_for_headers!(
+        String;
+        AcceptRanges,
+        AccessControlAllowHeaders,
+        AccessControlAllowMethods,
+        AccessControlAllowOrigin,
+        AccessControlExposeHeaders,
+        AccessControlRequestHeaders,
+        AccessControlRequestMethod,
+        Allow,
+        CacheControl,
+        Connection,
+        ContentDisposition,
+        ContentEncoding,
+        ContentLocation,
+        ContentRange,
+        ContentType,
+        Cookie,
+        Date,
+        ETag,
+        Expect,
+        Expires,
+        Host,
+        IfMatch,
+        IfModifiedSince,
+        IfNoneMatch,
+        IfRange,
+        IfUnmodifiedSince,
+        LastModified,
+        Location,
+        Origin,
+        Pragma,
+        Range,
+        Referer,
+        ReferrerPolicy,
+        RetryAfter,
+        SecWebsocketAccept,
+        SecWebsocketKey,
+        SecWebsocketVersion,
+        Server,
+        SetCookie,
+        StrictTransportSecurity,
+        Te,
+        TransferEncoding,
+        Upgrade,
+        UserAgent,
+        Vary,
+    );
+    impl_to_header_schema_for_headers!(u64; AccessControlMaxAge, Age, ContentLength);
+    impl_to_header_schema_for_headers!(bool; AccessControlAllowCredentials);
+
+    impl<C: Credentials> ToHeaderSchema for Authorization<C> {
+        fn header_name() -> String {
+            <Self as Header>::name().to_string()
+        }
+
+        fn generate(components: &mut Components) -> Result<SchemaObject, Error> {
+            Ok(components.schema_for::<String>())
+        }
+
+        /// HTTP authentication scheme, named after credentials type (`bearerAuth`, `basicAuth`).
+        fn security_scheme() -> Option<(String, SecurityScheme)> {
+            let scheme = C::SCHEME.to_lowercase();
+            Some((
+                format!("{scheme}Auth"),
+                SecurityScheme {
+                    description: None,
+                    data: okapi::openapi3::SecuritySchemeData::Http {
+                        scheme,
+                        bearer_format: None,
+                    },
+                    extensions: Default::default(),
+                },
+            ))
+        }
+    }
+
+    impl<C: Credentials> ToHeaderSchema for ProxyAuthorization<C> {
+        fn header_name() -> String {
+            <Self as Header>::name().to_string()
+        }
+
+        fn generate(components: &mut Components) -> Result<SchemaObject, Error> {
+            Ok(components.schema_for::<String>())
+        }
+    }
+}
@@ -1,10 +1,10 @@
 use okapi::{openapi3::MediaType, Map};
 
-use crate::Components;
+use crate::{Components, Error};
 
 /// Generate [`MediaType`] for type.
 pub trait ToMediaTypes {
-    fn generate(components: &mut Components) -> Result<Map<String, MediaType>, anyhow::Error>;
+    fn generate(components: &mut Components) -> Result<Map<String, MediaType>, Error>;
 }
 
 /// Generate [`ToMediaTypes`] implementation for newtype.
@@ -27,7 +27,7 @@ macro_rules! impl_to_media_types_for_wrapper {
                 components: &mut $crate::Components,
             ) -> Result<
                     $crate::okapi::Map<String, $crate::okapi::openapi3::MediaType>,
-                    $crate::anyhow::Error
+                    $crate::Error
                 >
             {
                 let schema = components.schema_for::<T>();
@@ -41,6 +41,50 @@ macro_rules! impl_to_media_types_for_wrapper {
     };
 }
 
+/// Replace media type keys of generated content with single `content_type`.
+///
+/// Used for `content_type` attribute of [`openapi`](crate::openapi) macro. If content is empty,
+/// media type without schema is created. Content with multiple media types can't be overridden.
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub fn override_content_type(
+    content: Map<String, MediaType>,
+    content_type: &str,
+) -> Result<Map<String, MediaType>, Error> {
+    if content.len() > 1 {
+        return Err(Error::InvalidDefinition(anyhow::anyhow!(
+            "Cannot override content type with '{}': multiple media types found ({})",
+            content_type,
+            content.keys().cloned().collect::<Vec<_>>().join(", ")
+        )));
+    }
+    let media_type = content.into_values().next().unwrap_or_default();
+    Ok(okapi::map! { content_type.to_owned() => media_type })
+}
+
+/// Add media types from `other` to `content`.
+///
+/// Used for multiple `content` definitions of request body and negotiated responses in
+/// [`openapi`](crate::openapi) macro.
+/// Returns error if same media type is provided multiple times.
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub fn merge_media_types(
+    content: &mut Map<String, MediaType>,
+    other: Map<String, MediaType>,
+) -> Result<(), Error> {
+    for (mime, media_type) in other {
+        if content.contains_key(&mime) {
+            return Err(Error::Merge(anyhow::anyhow!(
+                "Media type '{}' is defined multiple times",
+                mime
+            )));
+        }
+        let _ = content.insert(mime, media_type);
+    }
+    Ok(())
+}
+
 macro_rules! forward_impl_to_media_types {
     ($ty_for:ty, $ty_base:ty) => {
         impl $crate::ToMediaTypes for $ty_for {
@@ -48,7 +92,7 @@ macro_rules! forward_impl_to_media_types {
                 components: &mut $crate::Components,
             ) -> Result<
                 $crate::okapi::Map<String, $crate::okapi::openapi3::MediaType>,
-                $crate::anyhow::Error,
+                $crate::Error,
             > {
                 <$ty_base as $crate::ToMediaTypes>::generate(components)
             }
@@ -57,7 +101,7 @@ macro_rules! forward_impl_to_media_types {
 }
 
 mod impls {
-    use std::borrow::Cow;
+    use std::{borrow::Cow, sync::Arc};
 
     use bytes::{Bytes, BytesMut};
     use mime::{APPLICATION_OCTET_STREAM, TEXT_PLAIN};
@@ -70,23 +114,22 @@ mod impls {
     use super::*;
 
     impl ToMediaTypes for () {
-        fn generate(_components: &mut Components) -> Result<Map<String, MediaType>, anyhow::Error> {
+        fn generate(_components: &mut Components) -> Result<Map<String, MediaType>, Error> {
             Ok(map! {})
         }
     }
 
     impl ToMediaTypes for String {
-        fn generate(_components: &mut Components) -> Result<Map<String, MediaType>, anyhow::Error> {
+        fn generate(_components: &mut Components) -> Result<Map<String, MediaType>, Error> {
             Ok(map! {
                 TEXT_PLAIN.to_string() => MediaType::default()
             })
         }
     }
-    forward_impl_to_media_types!(&'static str, String);
-    forward_impl_to_media_types!(Cow<'static, str>, String);
+    forward_impl_to_media_types!(str, String);
 
     impl ToMediaTypes for Vec<u8> {
-        fn generate(_components: &mut Components) -> Result<Map<String, MediaType>, anyhow::Error> {
+        fn generate(_components: &mut Components) -> Result<Map<String, MediaType>, Error> {
             // In schemars Bytes defined as array of integers, but OpenAPI recommend
             // use string type with binary format
             // https://swagger.io/docs/specification/describing-request-body/file-upload/
@@ -104,8 +147,32 @@ mod impls {
             })
         }
     }
-    forward_impl_to_media_types!(&'static [u8], Vec<u8>);
-    forward_impl_to_media_types!(Cow<'static, [u8]>, Vec<u8>);
+    forward_impl_to_media_types!([u8], Vec<u8>);
     forward_impl_to_media_types!(Bytes, Vec<u8>);
     forward_impl_to_media_types!(BytesMut, Vec<u8>);
+
+    // Smart pointers and references
+    impl<T: ToMediaTypes + ?Sized> ToMediaTypes for Box<T> {
+        fn generate(components: &mut Components) -> Result<Map<String, MediaType>, Error> {
+            T::generate(components)
+        }
+    }
+
+    impl<T: ToMediaTypes + ?Sized> ToMediaTypes for Arc<T> {
+        fn generate(components: &mut Components) -> Result<Map<String, MediaType>, Error> {
+            T::generate(components)
+        }
+    }
+
+    impl<T: ToMediaTypes + ?Sized> ToMediaTypes for &'static T {
+        fn generate(components: &mut Components) -> Result<Map<String, MediaType>, Error> {
+            T::generate(components)
+        }
+    }
+
+    impl<T: ToMediaTypes + ToOwned + ?Sized> ToMediaTypes for Cow<'static, T> {
+        fn generate(components: &mut Components) -> Result<Map<String, MediaType>, Error> {
+            T::generate(components)
+        }
+    }
 }
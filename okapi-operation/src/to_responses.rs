@@ -1,10 +1,61 @@
 use okapi::openapi3::{RefOr, Responses};
 
-use crate::Components;
+use crate::{Components, Error};
 
 /// Generate [`Responses`] for type.
 pub trait ToResponses {
-    fn generate(components: &mut Components) -> Result<Responses, anyhow::Error>;
+    fn generate(components: &mut Components) -> Result<Responses, Error>;
+}
+
+/// Optional response, which is empty response with `STATUS` status code if value is `None`.
+///
+/// Same as `Option<T>` (which documents `None` as `404 Not Found`), but with configurable
+/// status code.
+///
+/// # Example
+///
+/// ```rust
+/// # use okapi_operation::*;
+/// // `204 No Content` if nothing is found
+/// #[openapi]
+/// async fn find_user() -> OrStatus<String, 204> {
+///     OrStatus(None)
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OrStatus<T, const STATUS: u16 = 404>(pub Option<T>);
+
+impl<T, const STATUS: u16> From<Option<T>> for OrStatus<T, STATUS> {
+    fn from(value: Option<T>) -> Self {
+        Self(value)
+    }
+}
+
+/// Generate [`Responses`] for output of never called function.
+///
+/// Used by [`openapi`](crate::openapi) macro for inferring responses of
+/// functions, which return opaque type (`impl Trait`).
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub fn generate_responses_for_output<T: ToResponses>(
+    _output: Option<T>,
+    components: &mut Components,
+) -> Result<Responses, Error> {
+    T::generate(components)
+}
+
+/// Same as [`generate_responses_for_output`], but for `async` functions.
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub fn generate_responses_for_future_output<F>(
+    _output: Option<F>,
+    components: &mut Components,
+) -> Result<Responses, Error>
+where
+    F: std::future::Future,
+    F::Output: ToResponses,
+{
+    F::Output::generate(components)
 }
 
 /// Generate [`ToResponses`] implementation for newtype.
@@ -24,7 +75,7 @@ pub trait ToResponses {
 macro_rules! impl_to_responses_for_wrapper {
     ($ty:path) => {
         impl<T: $crate::schemars::JsonSchema> $crate::ToResponses for $ty {
-            fn generate(components: &mut $crate::Components) -> Result<$crate::okapi::openapi3::Responses, $crate::anyhow::Error> {
+            fn generate(components: &mut $crate::Components) -> Result<$crate::okapi::openapi3::Responses, $crate::Error> {
                 let media_types = <$ty as $crate::ToMediaTypes>::generate(components)?;
                 Ok($crate::okapi::openapi3::Responses {
                     responses: $crate::okapi::map! {
@@ -44,7 +95,7 @@ macro_rules! forward_impl_to_responses {
         impl $crate::ToResponses for $ty_for {
             fn generate(
                 components: &mut $crate::Components,
-            ) -> Result<$crate::okapi::openapi3::Responses, $crate::anyhow::Error> {
+            ) -> Result<$crate::okapi::openapi3::Responses, $crate::Error> {
                 <$ty_base as $crate::ToResponses>::generate(components)
             }
         }
@@ -52,16 +103,17 @@ macro_rules! forward_impl_to_responses {
 }
 
 mod impls {
-    use std::borrow::Cow;
+    use std::{borrow::Cow, sync::Arc};
 
+    use anyhow::Context;
     use bytes::{Bytes, BytesMut};
     use okapi::openapi3::Response;
 
     use super::*;
-    use crate::ToMediaTypes;
+    use crate::{merge::merge_response_maps, MergeStrategy, ToMediaTypes};
 
     impl ToResponses for () {
-        fn generate(_components: &mut Components) -> Result<Responses, anyhow::Error> {
+        fn generate(_components: &mut Components) -> Result<Responses, Error> {
             Ok(Responses {
                 responses: okapi::map! {
                     "200".into() => RefOr::Object(Default::default())
@@ -71,40 +123,63 @@ mod impls {
         }
     }
 
+    /// Responses of `Ok` and `Err` types with same status are merged with
+    /// [`MergeStrategy::Merge`] (schemas are combined under `oneOf`), so neither of them is
+    /// lost.
     impl<T, E> ToResponses for Result<T, E>
     where
         T: ToResponses,
         E: ToResponses,
     {
-        fn generate(components: &mut Components) -> Result<Responses, anyhow::Error> {
-            let overlap_err_fn = |status| {
-                anyhow::anyhow!(
-                    "Type {} produces {} response in both Ok and Err variants",
-                    std::any::type_name::<Self>(),
-                    status
-                )
-            };
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
             let mut ok = T::generate(components)?;
             let err = E::generate(components)?;
+            merge_response_maps(&mut ok, err, MergeStrategy::Merge)
+                .with_context(|| {
+                    format!(
+                        "Failed to merge Ok and Err responses of {}",
+                        std::any::type_name::<Self>()
+                    )
+                })
+                .map_err(Error::Merge)?;
+            Ok(ok)
+        }
+    }
 
-            if ok.default.is_some() && err.default.is_some() {
-                return Err(overlap_err_fn("default"));
-            }
-            ok.default = ok.default.or(err.default);
-
-            for (status, response) in err.responses.into_iter() {
-                if ok.responses.contains_key(&status) {
-                    return Err(overlap_err_fn(&status));
-                }
-                let _ = ok.responses.insert(status, response);
-            }
+    impl<T, const STATUS: u16> ToResponses for OrStatus<T, STATUS>
+    where
+        T: ToResponses,
+    {
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
+            let mut responses = T::generate(components)?;
+            let description = http::StatusCode::from_u16(STATUS)
+                .ok()
+                .and_then(|x| x.canonical_reason())
+                .unwrap_or_default();
+            let _ = responses
+                .responses
+                .entry(STATUS.to_string())
+                .or_insert_with(|| {
+                    RefOr::Object(Response {
+                        description: description.into(),
+                        ..Default::default()
+                    })
+                });
+            Ok(responses)
+        }
+    }
 
-            Ok(ok)
+    impl<T> ToResponses for Option<T>
+    where
+        T: ToResponses,
+    {
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
+            OrStatus::<T>::generate(components)
         }
     }
 
     impl ToResponses for String {
-        fn generate(components: &mut Components) -> Result<Responses, anyhow::Error> {
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
             Ok(Responses {
                 responses: okapi::map! {
                     "200".into() => RefOr::Object(Response {
@@ -116,11 +191,10 @@ mod impls {
             })
         }
     }
-    forward_impl_to_responses!(&'static str, String);
-    forward_impl_to_responses!(Cow<'static, str>, String);
+    forward_impl_to_responses!(str, String);
 
     impl ToResponses for Vec<u8> {
-        fn generate(components: &mut Components) -> Result<Responses, anyhow::Error> {
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
             Ok(Responses {
                 responses: okapi::map! {
                     "200".into() => RefOr::Object(Response {
@@ -132,8 +206,32 @@ mod impls {
             })
         }
     }
-    forward_impl_to_responses!(&'static [u8], Vec<u8>);
-    forward_impl_to_responses!(Cow<'static, [u8]>, Vec<u8>);
+    forward_impl_to_responses!([u8], Vec<u8>);
     forward_impl_to_responses!(Bytes, Vec<u8>);
     forward_impl_to_responses!(BytesMut, Vec<u8>);
+
+    // Smart pointers and references
+    impl<T: ToResponses + ?Sized> ToResponses for Box<T> {
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
+            T::generate(components)
+        }
+    }
+
+    impl<T: ToResponses + ?Sized> ToResponses for Arc<T> {
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
+            T::generate(components)
+        }
+    }
+
+    impl<T: ToResponses + ?Sized> ToResponses for &'static T {
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
+            T::generate(components)
+        }
+    }
+
+    impl<T: ToResponses + ToOwned + ?Sized> ToResponses for Cow<'static, T> {
+        fn generate(components: &mut Components) -> Result<Responses, Error> {
+            T::generate(components)
+        }
+    }
 }
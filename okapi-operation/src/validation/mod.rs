@@ -0,0 +1,531 @@
+//! Validation of requests and responses against specification.
+//!
+//! [`RequestValidator`] checks path, query, header and cookie parameters and JSON request
+//! bodies of incoming requests against schemas from specification. [`ValidationLayer`] wraps
+//! it into [`tower::Layer`], which rejects invalid requests with `400 Bad Request` and
+//! detailed list of errors:
+//!
+//! ```no_run
+//! # use okapi_operation::{axum_integration::*, validation::ValidationLayer, *};
+//! #[openapi(parameters(query(name = "limit", required = true, schema = "u32")))]
+//! async fn list_users() {}
+//!
+//! let router = Router::<()>::new().route("/users", get(openapi_handler!(list_users)));
+//! let spec = router
+//!     .generate_openapi_builder()
+//!     .build()
+//!     .expect("Failed to build specification");
+//! let router = router.layer(ValidationLayer::new(&spec));
+//! ```
+//!
+//! Requests, which don't match any operation from specification, are passed as is.
+//! Only `$ref`, type, enumeration, combination (`allOf`, `anyOf`, `oneOf`, `not`), length,
+//! range, `pattern` and object/array keywords of schemas are checked (`format` is checked
+//! only for integers).
+//! Bodies with non-JSON media types are not validated.
+//!
+//! [`ResponseValidationLayer`] checks status, content type and body of outgoing responses
+//! and panics (or calls provided callback) on mismatch. It is intended for tests and
+//! development environments, where it catches drift between handlers and specification:
+//!
+//! ```no_run
+//! # use okapi_operation::{axum_integration::*, validation::ResponseValidationLayer, *};
+//! #[openapi]
+//! async fn get_user() -> String {
+//!     "user".into()
+//! }
+//!
+//! let router = Router::<()>::new().route("/user", get(openapi_handler!(get_user)));
+//! let spec = router
+//!     .generate_openapi_builder()
+//!     .build()
+//!     .expect("Failed to build specification");
+//! let router = router.layer(
+//!     ResponseValidationLayer::new(&spec)
+//!         .on_mismatch(|mismatch| eprintln!("{mismatch}")),
+//! );
+//! ```
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::Body,
+    extract::Request,
+    response::{IntoResponse, Response},
+    Json,
+};
+use http::{header, HeaderMap, Method, StatusCode};
+use http_body_util::LengthLimitError;
+use okapi::{
+    openapi3::{OpenApi, ParameterValue},
+    schemars::schema::{InstanceType, SchemaObject, SingleOrVec},
+};
+use serde::Serialize;
+use serde_json::Value;
+use tower::{Layer, Service};
+
+use self::operations::{Operations, ValidatedOperation};
+pub use self::response::{
+    ResponseMismatch, ResponseValidationLayer, ResponseValidationService, ResponseValidator,
+};
+use crate::walk::SCHEMA_REFERENCE_PREFIX;
+
+mod operations;
+mod response;
+mod routes;
+mod schema;
+
+/// Default limit of buffered request body (2 MiB, same as in `axum`).
+const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Part of request or response, which contains invalid value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Location {
+    Path,
+    Query,
+    Header,
+    Cookie,
+    Body,
+    /// Status of response.
+    Status,
+}
+
+/// Single validation error.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationError {
+    /// Part of request or response.
+    pub location: Location,
+    /// Name of parameter or header (not present for body and status).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// JSON pointer to invalid value (empty, if whole value is invalid).
+    pub pointer: String,
+    /// Description of error.
+    pub message: String,
+}
+
+/// Errors of request validation.
+///
+/// Converted to `400 Bad Request` response with JSON body `{"errors": [...]}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationErrors {
+    pub errors: Vec<ValidationError>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.location)?;
+        if let Some(name) = &self.name {
+            write!(f, " '{name}'")?;
+        }
+        if !self.pointer.is_empty() {
+            write!(f, " at {}", self.pointer)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid request: ")?;
+        write_errors(f, &self.errors)
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+fn write_errors(f: &mut fmt::Formatter<'_>, errors: &[ValidationError]) -> fmt::Result {
+    for (i, error) in errors.iter().enumerate() {
+        if i > 0 {
+            f.write_str("; ")?;
+        }
+        write!(f, "{error}")?;
+    }
+    Ok(())
+}
+
+impl IntoResponse for ValidationErrors {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(self)).into_response()
+    }
+}
+
+/// Validator of requests against specification.
+#[derive(Debug)]
+pub struct RequestValidator {
+    operations: Operations,
+}
+
+impl RequestValidator {
+    /// Create validator for specification.
+    pub fn new(spec: &OpenApi) -> Self {
+        Self::with_definitions_path(spec, SCHEMA_REFERENCE_PREFIX)
+    }
+
+    /// Create validator for specification, generated with custom
+    /// [definitions path](crate::ComponentsBuilder::definitions_path).
+    pub fn with_definitions_path(spec: &OpenApi, definitions_path: &str) -> Self {
+        Self {
+            operations: Operations::new(spec, definitions_path),
+        }
+    }
+
+    /// Whether request with specified method and path matches any documented operation.
+    pub fn has_operation(&self, method: &Method, path: &str) -> bool {
+        self.operations.find(method, path).is_some()
+    }
+
+    /// Validate request.
+    ///
+    /// Requests, which don't match any operation from specification, are considered valid.
+    pub fn validate<B: AsRef<[u8]>>(
+        &self,
+        request: &http::Request<B>,
+    ) -> Result<(), ValidationErrors> {
+        let Some((operation, path_parameters)) =
+            self.operations.find(request.method(), request.uri().path())
+        else {
+            return Ok(());
+        };
+        let mut errors = self.validate_parameters(
+            operation,
+            &path_parameters,
+            request.uri().query(),
+            request.headers(),
+        );
+        self.validate_body(
+            operation,
+            request.headers(),
+            request.body().as_ref(),
+            &mut errors,
+        );
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors { errors })
+        }
+    }
+
+    fn validate_parameters(
+        &self,
+        operation: &ValidatedOperation,
+        path_parameters: &[(String, String)],
+        query: Option<&str>,
+        headers: &HeaderMap,
+    ) -> Vec<ValidationError> {
+        let query: Vec<_> = form_urlencoded::parse(query.unwrap_or_default().as_bytes()).collect();
+        let cookies: Vec<_> = headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|x| x.to_str().ok())
+            .flat_map(|x| x.split(';'))
+            .filter_map(|x| x.trim().split_once('='))
+            .collect();
+
+        let mut errors = Vec::new();
+        for parameter in &operation.parameters {
+            let name = parameter.name.as_str();
+            let (location, values, explode): (_, Vec<&str>, _) = match parameter.location.as_str() {
+                "path" => (
+                    Location::Path,
+                    path_parameters
+                        .iter()
+                        .filter(|x| x.0 == name)
+                        .map(|x| x.1.as_str())
+                        .collect(),
+                    false,
+                ),
+                "query" => (
+                    Location::Query,
+                    query
+                        .iter()
+                        .filter(|x| x.0 == name)
+                        .map(|x| x.1.as_ref())
+                        .collect(),
+                    true,
+                ),
+                // Described by other parts of specification
+                "header"
+                    if name.eq_ignore_ascii_case("accept")
+                        || name.eq_ignore_ascii_case("content-type")
+                        || name.eq_ignore_ascii_case("authorization") =>
+                {
+                    continue
+                }
+                "header" => (
+                    Location::Header,
+                    headers
+                        .get_all(name)
+                        .iter()
+                        .filter_map(|x| x.to_str().ok())
+                        .collect(),
+                    false,
+                ),
+                "cookie" => (
+                    Location::Cookie,
+                    cookies
+                        .iter()
+                        .filter(|x| x.0 == name)
+                        .map(|x| x.1)
+                        .collect(),
+                    false,
+                ),
+                _ => continue,
+            };
+            let mut error = |pointer: String, message: String| {
+                errors.push(ValidationError {
+                    location,
+                    name: Some(name.into()),
+                    pointer,
+                    message,
+                })
+            };
+
+            if values.is_empty() {
+                if parameter.required || location == Location::Path {
+                    error(String::new(), "Missing required parameter".into());
+                }
+                continue;
+            }
+            let (schema, explode) = match &parameter.value {
+                ParameterValue::Schema {
+                    schema, explode: x, ..
+                } => (schema, x.unwrap_or(explode)),
+                ParameterValue::Content { content } => {
+                    match content.values().next().and_then(|x| x.schema.as_ref()) {
+                        Some(schema) => (schema, explode),
+                        None => continue,
+                    }
+                }
+            };
+            let Some(value) = self.parameter_value(schema, &values, explode) else {
+                continue;
+            };
+            let mut schema_errors = Vec::new();
+            self.operations
+                .schemas
+                .validate(schema, &value, "", &mut schema_errors);
+            for (pointer, message) in schema_errors {
+                error(pointer, message);
+            }
+        }
+        errors
+    }
+
+    /// Convert raw values of parameter to JSON value, according to schema.
+    ///
+    /// Objects are not supported, so `None` is returned for them.
+    fn parameter_value(
+        &self,
+        schema: &SchemaObject,
+        values: &[&str],
+        explode: bool,
+    ) -> Option<Value> {
+        let schema = self.operations.schemas.resolve(schema);
+        match instance_types(schema).first() {
+            Some(InstanceType::Object) => None,
+            Some(InstanceType::Array) => {
+                let items = match schema.array.as_ref().and_then(|x| x.items.as_ref()) {
+                    Some(SingleOrVec::Single(items)) => items.clone().into_object(),
+                    _ => SchemaObject::default(),
+                };
+                let items = self.operations.schemas.resolve(&items);
+                let values: Vec<_> = if explode {
+                    values.to_vec()
+                } else {
+                    values.iter().flat_map(|x| x.split(',')).collect()
+                };
+                Some(values.iter().map(|x| scalar_value(items, x)).collect())
+            }
+            _ => Some(scalar_value(schema, values[0])),
+        }
+    }
+
+    fn validate_body(
+        &self,
+        operation: &ValidatedOperation,
+        headers: &HeaderMap,
+        body: &[u8],
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let Some(request_body) = &operation.request_body else {
+            return;
+        };
+        let mut error = |pointer: String, message: String| {
+            errors.push(ValidationError {
+                location: Location::Body,
+                name: None,
+                pointer,
+                message,
+            })
+        };
+        if body.is_empty() {
+            if request_body.required {
+                error(String::new(), "Request body is required".into());
+            }
+            return;
+        }
+        if request_body.content.is_empty() {
+            return;
+        }
+        for (pointer, message) in
+            self.operations
+                .validate_content(&request_body.content, headers, body)
+        {
+            error(pointer, message);
+        }
+    }
+}
+
+/// Convert raw value to JSON value, according to type of schema.
+///
+/// If value can't be converted, string is returned, so validation reports type mismatch.
+fn scalar_value(schema: &SchemaObject, raw: &str) -> Value {
+    for ty in instance_types(schema) {
+        let value = match ty {
+            InstanceType::Integer => raw
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| raw.parse::<u64>().map(Value::from))
+                .ok(),
+            InstanceType::Number => raw.parse::<f64>().ok().map(Value::from),
+            InstanceType::Boolean => raw.parse::<bool>().ok().map(Value::from),
+            InstanceType::Null => (raw == "null").then_some(Value::Null),
+            _ => None,
+        };
+        if let Some(value) = value {
+            return value;
+        }
+    }
+    Value::String(raw.into())
+}
+
+fn instance_types(schema: &SchemaObject) -> Vec<InstanceType> {
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(x)) => vec![**x],
+        Some(SingleOrVec::Vec(x)) => x.clone(),
+        None => Vec::new(),
+    }
+}
+
+/// [`Layer`], which validates requests against specification.
+///
+/// Requests are rejected with [`ValidationErrors`] response. Bodies of requests to
+/// operations with request body are buffered (up to [`ValidationLayer::body_limit`] bytes,
+/// `413 Payload Too Large` is returned for larger bodies).
+#[derive(Debug, Clone)]
+pub struct ValidationLayer {
+    validator: Arc<RequestValidator>,
+    body_limit: usize,
+}
+
+impl ValidationLayer {
+    /// Create layer for specification.
+    pub fn new(spec: &OpenApi) -> Self {
+        Self::from_validator(RequestValidator::new(spec))
+    }
+
+    /// Create layer from existing validator.
+    pub fn from_validator(validator: RequestValidator) -> Self {
+        Self {
+            validator: Arc::new(validator),
+            body_limit: DEFAULT_BODY_LIMIT,
+        }
+    }
+
+    /// Set maximum size of buffered request body (2 MiB by default).
+    pub fn body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = limit;
+        self
+    }
+}
+
+impl<S> Layer<S> for ValidationLayer {
+    type Service = ValidationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ValidationService {
+            inner,
+            validator: self.validator.clone(),
+            body_limit: self.body_limit,
+        }
+    }
+}
+
+/// Service, created by [`ValidationLayer`].
+#[derive(Debug, Clone)]
+pub struct ValidationService<S> {
+    inner: S,
+    validator: Arc<RequestValidator>,
+    body_limit: usize,
+}
+
+impl<S> Service<Request> for ValidationService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // Use service, which was polled, and leave its clone in place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let validator = self.validator.clone();
+        let body_limit = self.body_limit;
+        Box::pin(async move {
+            let Some((operation, path_parameters)) = validator
+                .operations
+                .find(request.method(), request.uri().path())
+            else {
+                return inner.call(request).await;
+            };
+
+            let (parts, body) = request.into_parts();
+            let mut errors = validator.validate_parameters(
+                operation,
+                &path_parameters,
+                parts.uri.query(),
+                &parts.headers,
+            );
+            let body = if operation.request_body.is_some() {
+                let bytes = match axum::body::to_bytes(body, body_limit).await {
+                    Ok(x) => x,
+                    Err(err) => {
+                        if err.into_inner().is::<LengthLimitError>() {
+                            return Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+                        }
+                        let errors = vec![ValidationError {
+                            location: Location::Body,
+                            name: None,
+                            pointer: String::new(),
+                            message: "Failed to read request body".into(),
+                        }];
+                        return Ok(ValidationErrors { errors }.into_response());
+                    }
+                };
+                validator.validate_body(operation, &parts.headers, &bytes, &mut errors);
+                Body::from(bytes)
+            } else {
+                body
+            };
+            if !errors.is_empty() {
+                return Ok(ValidationErrors { errors }.into_response());
+            }
+            inner.call(Request::from_parts(parts, body)).await
+        })
+    }
+}
@@ -0,0 +1,156 @@
+//! Operations of specification, prepared for validation.
+
+use http::{header, HeaderMap, Method, StatusCode};
+use okapi::{
+    openapi3::{MediaType, OpenApi, Parameter, RefOr, RequestBody, Response, Responses},
+    Map,
+};
+use serde_json::Value;
+
+use super::{routes::PathTemplate, schema::SchemaValidator};
+use crate::{
+    resolve::flatten_refs,
+    walk::{all_path_item_operations, walk_spec},
+};
+
+/// Operation from specification, prepared for validation.
+#[derive(Debug)]
+pub(super) struct ValidatedOperation {
+    path: PathTemplate,
+    method: Method,
+    pub(super) parameters: Vec<Parameter>,
+    pub(super) request_body: Option<RequestBody>,
+    responses: Responses,
+}
+
+impl ValidatedOperation {
+    /// Find response for status (exact, then range like `2XX`, then default one).
+    pub(super) fn response(&self, status: StatusCode) -> Option<&Response> {
+        let status = status.as_str();
+        let range = &status[..1];
+        self.responses
+            .responses
+            .get(status)
+            .or_else(|| self.responses.responses.get(&format!("{range}XX")))
+            .or_else(|| self.responses.responses.get(&format!("{range}xx")))
+            .or(self.responses.default.as_ref())
+            .and_then(|x| match x {
+                RefOr::Object(x) => Some(x),
+                RefOr::Ref(_) => None,
+            })
+    }
+}
+
+/// Operations of specification with schemas.
+#[derive(Debug)]
+pub(super) struct Operations {
+    operations: Vec<ValidatedOperation>,
+    pub(super) schemas: SchemaValidator,
+}
+
+impl Operations {
+    pub(super) fn new(spec: &OpenApi, definitions_path: &str) -> Self {
+        let mut spec = spec.clone();
+        flatten_refs(&mut spec, definitions_path);
+
+        let mut operations = Vec::new();
+        for (path, item) in &spec.paths {
+            for (method, operation) in all_path_item_operations(item) {
+                // Operation parameters override path-level parameters with same name and location
+                let mut parameters: Map<(String, String), Parameter> = Map::new();
+                for parameter in item.parameters.iter().chain(&operation.parameters) {
+                    if let RefOr::Object(parameter) = parameter {
+                        let _ = parameters.insert(
+                            (parameter.name.clone(), parameter.location.clone()),
+                            parameter.clone(),
+                        );
+                    }
+                }
+                let request_body = match &operation.request_body {
+                    Some(RefOr::Object(body)) => Some(body.clone()),
+                    _ => None,
+                };
+                operations.push(ValidatedOperation {
+                    path: PathTemplate::new(path),
+                    method,
+                    parameters: parameters.into_values().collect(),
+                    request_body,
+                    responses: operation.responses.clone(),
+                });
+            }
+        }
+        // Stable sort keeps order of specification for equally specific paths
+        operations.sort_by_key(|x| std::cmp::Reverse(x.path.specificity()));
+
+        let mut schemas = SchemaValidator::new(
+            spec.components.take().unwrap_or_default().schemas,
+            definitions_path,
+        );
+        walk_spec(&mut schemas, &mut spec);
+        Self {
+            operations,
+            schemas,
+        }
+    }
+
+    /// Find operation, returning it with values of path parameters.
+    pub(super) fn find(
+        &self,
+        method: &Method,
+        path: &str,
+    ) -> Option<(&ValidatedOperation, Vec<(String, String)>)> {
+        self.operations
+            .iter()
+            .filter(|x| x.method == method)
+            .find_map(|x| Some((x, x.path.matches(path)?)))
+    }
+
+    /// Validate body with media type from `Content-Type` header against documented content.
+    ///
+    /// Returns errors as pairs of JSON pointer and message.
+    pub(super) fn validate_content(
+        &self,
+        content: &Map<String, MediaType>,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Vec<(String, String)> {
+        let error = |message: String| vec![(String::new(), message)];
+        let Some(content_type) = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|x| x.to_str().ok())
+            .map(|x| {
+                x.split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_ascii_lowercase()
+            })
+        else {
+            return error("Missing Content-Type header".into());
+        };
+        let media_type = content.iter().find_map(|(mime, media_type)| {
+            let mime = mime.split(';').next().unwrap_or_default().trim();
+            let matches = mime.eq_ignore_ascii_case(&content_type)
+                || mime == "*/*"
+                || mime
+                    .strip_suffix("/*")
+                    .is_some_and(|x| content_type.split('/').next() == Some(x));
+            matches.then_some(media_type)
+        });
+        let Some(media_type) = media_type else {
+            return error(format!("Unsupported content type '{content_type}'"));
+        };
+
+        let is_json = content_type == "application/json" || content_type.ends_with("+json");
+        let Some(schema) = media_type.schema.as_ref().filter(|_| is_json) else {
+            return Vec::new();
+        };
+        let value: Value = match serde_json::from_slice(body) {
+            Ok(x) => x,
+            Err(err) => return error(format!("Invalid JSON: {err}")),
+        };
+        let mut errors = Vec::new();
+        self.schemas.validate(schema, &value, "", &mut errors);
+        errors
+    }
+}
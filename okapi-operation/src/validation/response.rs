@@ -0,0 +1,284 @@
+//! Validation of responses against specification.
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{body::Body, extract::Request, response::Response};
+use http::{Method, StatusCode};
+use okapi::openapi3::{OpenApi, RefOr};
+use tower::{Layer, Service};
+
+use super::{operations::Operations, write_errors, Location, ValidationError};
+use crate::walk::SCHEMA_REFERENCE_PREFIX;
+
+/// Mismatch between response and specification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseMismatch {
+    /// Method of request.
+    pub method: Method,
+    /// Path of request.
+    pub path: String,
+    /// Status of response.
+    pub status: StatusCode,
+    pub errors: Vec<ValidationError>,
+}
+
+impl fmt::Display for ResponseMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Response {} to {} {} doesn't match specification: ",
+            self.status, self.method, self.path
+        )?;
+        write_errors(f, &self.errors)
+    }
+}
+
+impl std::error::Error for ResponseMismatch {}
+
+/// Validator of responses against specification.
+#[derive(Debug)]
+pub struct ResponseValidator {
+    operations: Operations,
+}
+
+impl ResponseValidator {
+    /// Create validator for specification.
+    pub fn new(spec: &OpenApi) -> Self {
+        Self::with_definitions_path(spec, SCHEMA_REFERENCE_PREFIX)
+    }
+
+    /// Create validator for specification, generated with custom
+    /// [definitions path](crate::ComponentsBuilder::definitions_path).
+    pub fn with_definitions_path(spec: &OpenApi, definitions_path: &str) -> Self {
+        Self {
+            operations: Operations::new(spec, definitions_path),
+        }
+    }
+
+    /// Whether request with specified method and path matches any documented operation.
+    pub fn has_operation(&self, method: &Method, path: &str) -> bool {
+        self.operations.find(method, path).is_some()
+    }
+
+    /// Validate response to request with specified method and path.
+    ///
+    /// Responses to requests, which don't match any operation from specification, are
+    /// considered valid.
+    pub fn validate<B: AsRef<[u8]>>(
+        &self,
+        method: &Method,
+        path: &str,
+        response: &http::Response<B>,
+    ) -> Result<(), ResponseMismatch> {
+        let Some((operation, _)) = self.operations.find(method, path) else {
+            return Ok(());
+        };
+
+        let mut errors = Vec::new();
+        let mut error = |location, name: Option<&str>, pointer: String, message: String| {
+            errors.push(ValidationError {
+                location,
+                name: name.map(Into::into),
+                pointer,
+                message,
+            })
+        };
+        let status = response.status();
+        match operation.response(status) {
+            None => error(
+                Location::Status,
+                None,
+                String::new(),
+                format!("Status {} is not documented", status.as_u16()),
+            ),
+            Some(documented) => {
+                for (name, header) in &documented.headers {
+                    if let RefOr::Object(header) = header {
+                        if header.required && !response.headers().contains_key(name.as_str()) {
+                            error(
+                                Location::Header,
+                                Some(name),
+                                String::new(),
+                                "Missing required header".into(),
+                            );
+                        }
+                    }
+                }
+
+                let body = response.body().as_ref();
+                if documented.content.is_empty() {
+                    if !body.is_empty() {
+                        error(
+                            Location::Body,
+                            None,
+                            String::new(),
+                            "Response body is not documented".into(),
+                        );
+                    }
+                } else if body.is_empty() {
+                    // Responses to HEAD requests never have body
+                    if method != Method::HEAD {
+                        error(
+                            Location::Body,
+                            None,
+                            String::new(),
+                            "Missing response body".into(),
+                        );
+                    }
+                } else {
+                    let content_errors = self.operations.validate_content(
+                        &documented.content,
+                        response.headers(),
+                        body,
+                    );
+                    for (pointer, message) in content_errors {
+                        error(Location::Body, None, pointer, message);
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ResponseMismatch {
+                method: method.clone(),
+                path: path.into(),
+                status,
+                errors,
+            })
+        }
+    }
+}
+
+type MismatchHandler = Arc<dyn Fn(ResponseMismatch) + Send + Sync>;
+
+/// [`Layer`], which validates responses against specification.
+///
+/// Bodies of responses to documented operations are buffered. By default layer panics on
+/// mismatch, failing test, which sent request.
+#[derive(Clone)]
+pub struct ResponseValidationLayer {
+    validator: Arc<ResponseValidator>,
+    on_mismatch: MismatchHandler,
+}
+
+impl fmt::Debug for ResponseValidationLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseValidationLayer")
+            .field("validator", &self.validator)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResponseValidationLayer {
+    /// Create layer for specification.
+    pub fn new(spec: &OpenApi) -> Self {
+        Self::from_validator(ResponseValidator::new(spec))
+    }
+
+    /// Create layer from existing validator.
+    pub fn from_validator(validator: ResponseValidator) -> Self {
+        Self {
+            validator: Arc::new(validator),
+            on_mismatch: Arc::new(|mismatch| panic!("{mismatch}")),
+        }
+    }
+
+    /// Set handler of mismatches (i.e. for logging) instead of panicking.
+    ///
+    /// Response is passed to client unchanged.
+    pub fn on_mismatch<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ResponseMismatch) + Send + Sync + 'static,
+    {
+        self.on_mismatch = Arc::new(f);
+        self
+    }
+}
+
+impl<S> Layer<S> for ResponseValidationLayer {
+    type Service = ResponseValidationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ResponseValidationService {
+            inner,
+            validator: self.validator.clone(),
+            on_mismatch: self.on_mismatch.clone(),
+        }
+    }
+}
+
+/// Service, created by [`ResponseValidationLayer`].
+#[derive(Clone)]
+pub struct ResponseValidationService<S> {
+    inner: S,
+    validator: Arc<ResponseValidator>,
+    on_mismatch: MismatchHandler,
+}
+
+impl<S: fmt::Debug> fmt::Debug for ResponseValidationService<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseValidationService")
+            .field("inner", &self.inner)
+            .field("validator", &self.validator)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> Service<Request> for ResponseValidationService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // Use service, which was polled, and leave its clone in place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let validator = self.validator.clone();
+        let on_mismatch = self.on_mismatch.clone();
+        Box::pin(async move {
+            let method = request.method().clone();
+            let path = request.uri().path().to_owned();
+            let response = inner.call(request).await?;
+            if validator.operations.find(&method, &path).is_none() {
+                return Ok(response);
+            }
+
+            let (parts, body) = response.into_parts();
+            let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+                on_mismatch(ResponseMismatch {
+                    method,
+                    path,
+                    status: parts.status,
+                    errors: vec![ValidationError {
+                        location: Location::Body,
+                        name: None,
+                        pointer: String::new(),
+                        message: "Failed to read response body".into(),
+                    }],
+                });
+                return Ok(Response::from_parts(parts, Body::empty()));
+            };
+            let response = Response::from_parts(parts, bytes);
+            if let Err(mismatch) = validator.validate(&method, &path, &response) {
+                on_mismatch(mismatch);
+            }
+            Ok(response.map(Body::from))
+        })
+    }
+}
@@ -0,0 +1,103 @@
+//! Matching of request paths against paths of specification.
+
+use percent_encoding::percent_decode_str;
+
+/// Segment of path template.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    /// Parameter with optional literal prefix and suffix (like `{id}.json`).
+    Parameter {
+        prefix: String,
+        name: String,
+        suffix: String,
+    },
+}
+
+/// Path from specification (like `/users/{id}`).
+#[derive(Debug, Clone)]
+pub(super) struct PathTemplate {
+    segments: Vec<Segment>,
+}
+
+impl PathTemplate {
+    pub(super) fn new(path: &str) -> Self {
+        let segments = split(path)
+            .map(|segment| {
+                let parameter = segment.find('{').and_then(|start| {
+                    let end = start + segment[start..].find('}')?;
+                    Some(Segment::Parameter {
+                        prefix: segment[..start].into(),
+                        name: segment[start + 1..end].into(),
+                        suffix: segment[end + 1..].into(),
+                    })
+                });
+                parameter.unwrap_or_else(|| Segment::Literal(segment.into()))
+            })
+            .collect();
+        Self { segments }
+    }
+
+    /// Number of literal segments (more specific templates are matched first).
+    pub(super) fn specificity(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|x| matches!(x, Segment::Literal(_)))
+            .count()
+    }
+
+    /// Match path, returning decoded values of path parameters.
+    pub(super) fn matches(&self, path: &str) -> Option<Vec<(String, String)>> {
+        let mut parameters = Vec::new();
+        let mut segments = split(path);
+        for template in &self.segments {
+            let segment = segments.next()?;
+            match template {
+                Segment::Literal(literal) => {
+                    if literal != segment {
+                        return None;
+                    }
+                }
+                Segment::Parameter {
+                    prefix,
+                    name,
+                    suffix,
+                } => {
+                    let value = segment
+                        .strip_prefix(prefix.as_str())?
+                        .strip_suffix(suffix.as_str())?;
+                    let value = percent_decode_str(value).decode_utf8_lossy();
+                    parameters.push((name.clone(), value.into_owned()));
+                }
+            }
+        }
+        segments.next().is_none().then_some(parameters)
+    }
+}
+
+fn split(path: &str) -> impl Iterator<Item = &str> {
+    path.trim_matches('/').split('/').filter(|x| !x.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_paths() {
+        let template = PathTemplate::new("/users/{id}/files/{name}.json");
+        assert_eq!(
+            template.matches("/users/1/files/a%20b.json"),
+            Some(vec![
+                ("id".into(), "1".into()),
+                ("name".into(), "a b".into())
+            ])
+        );
+        assert_eq!(template.matches("/users/1/files/a.xml"), None);
+        assert_eq!(template.matches("/users/1/files"), None);
+        assert_eq!(template.specificity(), 2);
+
+        assert_eq!(PathTemplate::new("/").matches("/"), Some(vec![]));
+        assert_eq!(PathTemplate::new("/users/").matches("/users"), Some(vec![]));
+    }
+}
@@ -0,0 +1,538 @@
+//! Validation of JSON values against schemas.
+
+use std::collections::HashMap;
+
+use okapi::{
+    schemars::{
+        schema::{InstanceType, Schema, SchemaObject, SingleOrVec},
+        visit::{visit_schema_object, Visitor},
+    },
+    Map,
+};
+use regex::Regex;
+use serde_json::Value;
+
+use crate::walk::{escape_pointer, SpecVisitor};
+
+/// Maximum depth of nested schemas (protects from cyclic references without nesting of data).
+const MAX_DEPTH: usize = 128;
+
+/// Validator of values against schemas of single specification.
+#[derive(Debug, Default)]
+pub(super) struct SchemaValidator {
+    /// Schemas from components (used for resolving references).
+    schemas: Map<String, SchemaObject>,
+    /// Prefix of references to schemas.
+    definitions_path: String,
+    /// Compiled `pattern` keywords.
+    patterns: HashMap<String, Regex>,
+}
+
+impl SchemaValidator {
+    pub(super) fn new(schemas: Map<String, SchemaObject>, definitions_path: &str) -> Self {
+        let mut this = Self {
+            schemas,
+            definitions_path: definitions_path.into(),
+            patterns: HashMap::new(),
+        };
+        let mut schemas = std::mem::take(&mut this.schemas);
+        for schema in schemas.values_mut() {
+            this.visit_schema_object(schema);
+        }
+        this.schemas = schemas;
+        this
+    }
+
+    /// Resolve reference to schema from components.
+    pub(super) fn resolve<'a>(&'a self, schema: &'a SchemaObject) -> &'a SchemaObject {
+        let mut schema = schema;
+        for _ in 0..MAX_DEPTH {
+            let Some(target) = schema
+                .reference
+                .as_deref()
+                .and_then(|x| x.strip_prefix(self.definitions_path.as_str()))
+                .and_then(|x| self.schemas.get(x))
+            else {
+                break;
+            };
+            schema = target;
+        }
+        schema
+    }
+
+    /// Validate value, collecting errors as pairs of JSON pointer and message.
+    pub(super) fn validate(
+        &self,
+        schema: &SchemaObject,
+        value: &Value,
+        pointer: &str,
+        errors: &mut Vec<(String, String)>,
+    ) {
+        self.validate_inner(schema, value, pointer, errors, 0)
+    }
+
+    fn is_valid(&self, schema: &Schema, value: &Value, depth: usize) -> bool {
+        let mut errors = Vec::new();
+        self.validate_schema(schema, value, "", &mut errors, depth);
+        errors.is_empty()
+    }
+
+    fn validate_schema(
+        &self,
+        schema: &Schema,
+        value: &Value,
+        pointer: &str,
+        errors: &mut Vec<(String, String)>,
+        depth: usize,
+    ) {
+        match schema {
+            Schema::Bool(true) => {}
+            Schema::Bool(false) => errors.push((pointer.into(), "No value is allowed".into())),
+            Schema::Object(schema) => self.validate_inner(schema, value, pointer, errors, depth),
+        }
+    }
+
+    fn validate_inner(
+        &self,
+        schema: &SchemaObject,
+        value: &Value,
+        pointer: &str,
+        errors: &mut Vec<(String, String)>,
+        depth: usize,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let depth = depth + 1;
+        let mut error = |message: String| errors.push((pointer.into(), message));
+
+        if let Some(reference) = &schema.reference {
+            match reference
+                .strip_prefix(self.definitions_path.as_str())
+                .and_then(|x| self.schemas.get(x))
+            {
+                Some(target) => self.validate_inner(target, value, pointer, errors, depth),
+                None => error(format!("Unresolvable schema reference '{reference}'")),
+            }
+            return;
+        }
+
+        if value.is_null() && is_nullable(schema) {
+            return;
+        }
+
+        if let Some(types) = &schema.instance_type {
+            let types: &[InstanceType] = match types {
+                SingleOrVec::Single(x) => std::slice::from_ref(x),
+                SingleOrVec::Vec(x) => x,
+            };
+            if !types.iter().any(|x| has_type(value, *x)) {
+                let expected: Vec<_> = types.iter().map(|x| type_name(*x)).collect();
+                error(format!(
+                    "Expected {}, found {}",
+                    expected.join(" or "),
+                    value_type_name(value)
+                ));
+                return;
+            }
+        }
+
+        if let Some(values) = &schema.enum_values {
+            if !values.contains(value) {
+                error("Value is not one of allowed values".into());
+            }
+        }
+        if let Some(expected) = &schema.const_value {
+            if expected != value {
+                error("Value is not equal to expected constant".into());
+            }
+        }
+
+        if let Some(subschemas) = &schema.subschemas {
+            for subschema in subschemas.all_of.iter().flatten() {
+                self.validate_schema(subschema, value, pointer, errors, depth);
+            }
+            let mut error = |message: String| errors.push((pointer.into(), message));
+            if let Some(any_of) = &subschemas.any_of {
+                if !any_of.iter().any(|x| self.is_valid(x, value, depth)) {
+                    error("Value doesn't match any of schemas".into());
+                }
+            }
+            if let Some(one_of) = &subschemas.one_of {
+                let matched = one_of
+                    .iter()
+                    .filter(|x| self.is_valid(x, value, depth))
+                    .count();
+                if matched != 1 {
+                    error(format!(
+                        "Value should match exactly one of schemas, but matches {matched}"
+                    ));
+                }
+            }
+            if let Some(not) = &subschemas.not {
+                if self.is_valid(not, value, depth) {
+                    error("Value matches schema, which is not allowed".into());
+                }
+            }
+        }
+
+        match value {
+            Value::String(value) => self.validate_string(schema, value, pointer, errors),
+            Value::Number(value) => validate_number(schema, value, pointer, errors),
+            Value::Array(values) => self.validate_array(schema, values, pointer, errors, depth),
+            Value::Object(values) => self.validate_object(schema, values, pointer, errors, depth),
+            Value::Null | Value::Bool(_) => {}
+        }
+    }
+
+    fn validate_string(
+        &self,
+        schema: &SchemaObject,
+        value: &str,
+        pointer: &str,
+        errors: &mut Vec<(String, String)>,
+    ) {
+        let Some(validation) = &schema.string else {
+            return;
+        };
+        let mut error = |message: String| errors.push((pointer.into(), message));
+        let length = value.chars().count();
+        if let Some(min) = validation.min_length {
+            if length < min as usize {
+                error(format!("String is shorter than {min} characters"));
+            }
+        }
+        if let Some(max) = validation.max_length {
+            if length > max as usize {
+                error(format!("String is longer than {max} characters"));
+            }
+        }
+        if let Some(pattern) = &validation.pattern {
+            if let Some(regex) = self.patterns.get(pattern) {
+                if !regex.is_match(value) {
+                    error(format!("String doesn't match pattern '{pattern}'"));
+                }
+            }
+        }
+    }
+
+    fn validate_array(
+        &self,
+        schema: &SchemaObject,
+        values: &[Value],
+        pointer: &str,
+        errors: &mut Vec<(String, String)>,
+        depth: usize,
+    ) {
+        let Some(validation) = &schema.array else {
+            return;
+        };
+        if let Some(min) = validation.min_items {
+            if values.len() < min as usize {
+                errors.push((pointer.into(), format!("Array has less than {min} items")));
+            }
+        }
+        if let Some(max) = validation.max_items {
+            if values.len() > max as usize {
+                errors.push((pointer.into(), format!("Array has more than {max} items")));
+            }
+        }
+        if validation.unique_items == Some(true)
+            && values
+                .iter()
+                .enumerate()
+                .any(|(i, x)| values[..i].contains(x))
+        {
+            errors.push((pointer.into(), "Array items are not unique".into()));
+        }
+        for (i, value) in values.iter().enumerate() {
+            let item = match &validation.items {
+                Some(SingleOrVec::Single(item)) => Some(item.as_ref()),
+                Some(SingleOrVec::Vec(items)) => {
+                    items.get(i).or(validation.additional_items.as_deref())
+                }
+                None => None,
+            };
+            if let Some(item) = item {
+                let pointer = format!("{pointer}/{i}");
+                self.validate_schema(item, value, &pointer, errors, depth);
+            }
+        }
+    }
+
+    fn validate_object(
+        &self,
+        schema: &SchemaObject,
+        values: &serde_json::Map<String, Value>,
+        pointer: &str,
+        errors: &mut Vec<(String, String)>,
+        depth: usize,
+    ) {
+        let Some(validation) = &schema.object else {
+            return;
+        };
+        if let Some(min) = validation.min_properties {
+            if values.len() < min as usize {
+                errors.push((
+                    pointer.into(),
+                    format!("Object has less than {min} properties"),
+                ));
+            }
+        }
+        if let Some(max) = validation.max_properties {
+            if values.len() > max as usize {
+                errors.push((
+                    pointer.into(),
+                    format!("Object has more than {max} properties"),
+                ));
+            }
+        }
+        for name in &validation.required {
+            if !values.contains_key(name) {
+                errors.push((
+                    pointer.into(),
+                    format!("Missing required property '{name}'"),
+                ));
+            }
+        }
+        for (name, value) in values {
+            let property_pointer = format!("{pointer}/{}", escape_pointer(name));
+            match validation.properties.get(name) {
+                Some(property) => {
+                    self.validate_schema(property, value, &property_pointer, errors, depth)
+                }
+                None => match validation.additional_properties.as_deref() {
+                    Some(Schema::Bool(false)) => {
+                        errors.push((pointer.into(), format!("Unknown property '{name}'")))
+                    }
+                    Some(additional) => {
+                        self.validate_schema(additional, value, &property_pointer, errors, depth)
+                    }
+                    None => {}
+                },
+            }
+        }
+    }
+}
+
+impl Visitor for SchemaValidator {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(pattern) = schema.string.as_ref().and_then(|x| x.pattern.as_ref()) {
+            if !self.patterns.contains_key(pattern) {
+                // Invalid patterns are ignored
+                if let Ok(regex) = Regex::new(pattern) {
+                    let _ = self.patterns.insert(pattern.clone(), regex);
+                }
+            }
+        }
+        visit_schema_object(self, schema)
+    }
+}
+
+impl SpecVisitor for SchemaValidator {}
+
+fn validate_number(
+    schema: &SchemaObject,
+    value: &serde_json::Number,
+    pointer: &str,
+    errors: &mut Vec<(String, String)>,
+) {
+    let mut error = |message: String| errors.push((pointer.into(), message));
+    // Range of integer formats (`schemars` doesn't set bounds for integers)
+    let range = match schema.format.as_deref() {
+        Some("int8") => Some((i8::MIN as f64, i8::MAX as f64)),
+        Some("int16") => Some((i16::MIN as f64, i16::MAX as f64)),
+        Some("int32") => Some((i32::MIN as f64, i32::MAX as f64)),
+        Some("int64") => Some((i64::MIN as f64, i64::MAX as f64)),
+        Some("uint8") => Some((0.0, u8::MAX as f64)),
+        Some("uint16") => Some((0.0, u16::MAX as f64)),
+        Some("uint32") => Some((0.0, u32::MAX as f64)),
+        Some("uint64") => Some((0.0, u64::MAX as f64)),
+        _ => None,
+    };
+    let Some(value) = value.as_f64() else {
+        return;
+    };
+    if let Some((min, max)) = range {
+        if value < min || value > max {
+            error(format!(
+                "Value is out of range of format '{}'",
+                schema.format.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+    let Some(validation) = &schema.number else {
+        return;
+    };
+    if let Some(min) = validation.minimum {
+        if value < min {
+            error(format!("Value is less than {min}"));
+        }
+    }
+    if let Some(min) = validation.exclusive_minimum {
+        if value <= min {
+            error(format!("Value is less than or equal to {min}"));
+        }
+    }
+    if let Some(max) = validation.maximum {
+        if value > max {
+            error(format!("Value is greater than {max}"));
+        }
+    }
+    if let Some(max) = validation.exclusive_maximum {
+        if value >= max {
+            error(format!("Value is greater than or equal to {max}"));
+        }
+    }
+    if let Some(multiple_of) = validation.multiple_of {
+        if multiple_of > 0.0 && (value / multiple_of).fract() != 0.0 {
+            error(format!("Value is not multiple of {multiple_of}"));
+        }
+    }
+}
+
+/// Whether schema allows `null` (with `nullable` of OpenAPI 3.0).
+fn is_nullable(schema: &SchemaObject) -> bool {
+    schema.extensions.get("nullable") == Some(&Value::Bool(true))
+}
+
+fn has_type(value: &Value, ty: InstanceType) -> bool {
+    match ty {
+        InstanceType::Null => value.is_null(),
+        InstanceType::Boolean => value.is_boolean(),
+        InstanceType::Object => value.is_object(),
+        InstanceType::Array => value.is_array(),
+        InstanceType::Number => value.is_number(),
+        InstanceType::String => value.is_string(),
+        InstanceType::Integer => match value {
+            Value::Number(x) => {
+                x.is_i64() || x.is_u64() || x.as_f64().is_some_and(|x| x.fract() == 0.0)
+            }
+            _ => false,
+        },
+    }
+}
+
+fn type_name(ty: InstanceType) -> &'static str {
+    match ty {
+        InstanceType::Null => "null",
+        InstanceType::Boolean => "boolean",
+        InstanceType::Object => "object",
+        InstanceType::Array => "array",
+        InstanceType::Number => "number",
+        InstanceType::String => "string",
+        InstanceType::Integer => "integer",
+    }
+}
+
+fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn errors(schema: Value, value: Value) -> Vec<(String, String)> {
+        let mut schemas = Map::new();
+        let _ = schemas.insert(
+            "Node".to_string(),
+            serde_json::from_value(json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": {
+                    "id": {"type": "integer", "minimum": 1},
+                    "children": {"type": "array", "items": {"$ref": "#/components/schemas/Node"}}
+                }
+            }))
+            .expect("Valid schema"),
+        );
+        let mut schema: SchemaObject = serde_json::from_value(schema).expect("Valid schema");
+        let mut validator = SchemaValidator::new(schemas, "#/components/schemas/");
+        validator.visit_schema_object(&mut schema);
+        let mut errors = Vec::new();
+        validator.validate(&schema, &value, "", &mut errors);
+        errors
+    }
+
+    fn error(pointer: &str, message: &str) -> Vec<(String, String)> {
+        vec![(pointer.into(), message.into())]
+    }
+
+    #[test]
+    fn validate_values() {
+        let string = json!({"type": "string", "minLength": 2, "pattern": "^[a-z]+$"});
+        assert!(errors(string.clone(), json!("abc")).is_empty());
+        assert_eq!(
+            errors(string.clone(), json!("a")),
+            error("", "String is shorter than 2 characters")
+        );
+        assert_eq!(
+            errors(string, json!("ABC")),
+            error("", "String doesn't match pattern '^[a-z]+$'")
+        );
+        assert_eq!(
+            errors(json!({"type": "integer", "format": "uint8"}), json!(256)),
+            error("", "Value is out of range of format 'uint8'")
+        );
+        assert_eq!(
+            errors(json!({"type": "integer"}), json!("1")),
+            error("", "Expected integer, found string")
+        );
+        assert!(errors(json!({"type": "integer", "nullable": true}), json!(null)).is_empty());
+        assert!(errors(json!({"type": ["integer", "null"]}), json!(null)).is_empty());
+        assert_eq!(
+            errors(json!({"enum": ["a", "b"]}), json!("c")),
+            error("", "Value is not one of allowed values")
+        );
+        assert_eq!(
+            errors(
+                json!({"type": "array", "items": {"type": "integer", "maximum": 10}}),
+                json!([1, 20])
+            ),
+            error("/1", "Value is greater than 10")
+        );
+        assert_eq!(
+            errors(
+                json!({"oneOf": [{"type": "integer"}, {"type": "number"}]}),
+                json!(1)
+            ),
+            error(
+                "",
+                "Value should match exactly one of schemas, but matches 2"
+            )
+        );
+        assert_eq!(
+            errors(
+                json!({"type": "object", "additionalProperties": false}),
+                json!({"a/b": 1})
+            ),
+            error("", "Unknown property 'a/b'")
+        );
+    }
+
+    #[test]
+    fn validate_references() {
+        let node = json!({"$ref": "#/components/schemas/Node"});
+        assert!(errors(node.clone(), json!({"id": 1, "children": [{"id": 2}]})).is_empty());
+        assert_eq!(
+            errors(node, json!({"id": 1, "children": [{"id": 0}, {}]})),
+            vec![
+                ("/children/0/id".into(), "Value is less than 1".into()),
+                (
+                    "/children/1".into(),
+                    "Missing required property 'id'".into()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,604 @@
+//! Helpers for traversing specification.
+
+use std::{borrow::Cow, collections::HashSet};
+
+use http::Method;
+use okapi::{
+    openapi3::{
+        Callback, Components, Example, Header, Link, MediaType, OpenApi, Operation, Parameter,
+        ParameterValue, PathItem, RefOr, RequestBody, Response, Responses,
+    },
+    schemars::{
+        schema::SchemaObject,
+        visit::{visit_schema_object, Visitor},
+    },
+    Map,
+};
+
+use crate::Error;
+
+/// Extension of path item, containing operations of methods, which can't be documented in
+/// OpenAPI 3.0 (like `additionalOperations` of OpenAPI 3.2), by method name.
+pub(crate) const ADDITIONAL_OPERATIONS_EXTENSION: &str = "x-additionalOperations";
+
+/// Methods, which operations are fields of path item in OpenAPI 3.0.
+pub(crate) const STANDARD_METHODS: [Method; 8] = [
+    Method::GET,
+    Method::PUT,
+    Method::POST,
+    Method::DELETE,
+    Method::OPTIONS,
+    Method::HEAD,
+    Method::PATCH,
+    Method::TRACE,
+];
+
+/// Prefix of reference to schema component (default definitions path, used only by optional
+/// features).
+#[allow(dead_code)]
+pub(crate) const SCHEMA_REFERENCE_PREFIX: &str = "#/components/schemas/";
+
+/// Escape name (like path or property name) for using as segment of JSON pointer.
+pub(crate) fn escape_pointer(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+/// Field of path item, containing operation of standard method.
+fn operation_field<'a>(item: &'a PathItem, method: &Method) -> Option<&'a Option<Operation>> {
+    Some(match method.as_str() {
+        "GET" => &item.get,
+        "PUT" => &item.put,
+        "POST" => &item.post,
+        "DELETE" => &item.delete,
+        "OPTIONS" => &item.options,
+        "HEAD" => &item.head,
+        "PATCH" => &item.patch,
+        "TRACE" => &item.trace,
+        _ => return None,
+    })
+}
+
+/// Mutable field of path item, containing operation of standard method.
+fn operation_slot<'a>(
+    item: &'a mut PathItem,
+    method: &Method,
+) -> Option<&'a mut Option<Operation>> {
+    Some(match method.as_str() {
+        "GET" => &mut item.get,
+        "PUT" => &mut item.put,
+        "POST" => &mut item.post,
+        "DELETE" => &mut item.delete,
+        "OPTIONS" => &mut item.options,
+        "HEAD" => &mut item.head,
+        "PATCH" => &mut item.patch,
+        "TRACE" => &mut item.trace,
+        _ => return None,
+    })
+}
+
+/// Whether method can be documented in path item of OpenAPI 3.0 (i.e. not stored in
+/// `x-additionalOperations` extension).
+pub(crate) fn is_standard_method(method: &Method) -> bool {
+    STANDARD_METHODS.contains(method)
+}
+
+/// Operations of standard methods of path item with their methods.
+pub(crate) fn path_item_operations(item: &PathItem) -> impl Iterator<Item = (Method, &Operation)> {
+    STANDARD_METHODS
+        .into_iter()
+        .filter_map(|method| Some((method.clone(), operation_field(item, &method)?.as_ref()?)))
+}
+
+/// Operations of path item with their methods, including operations of custom methods
+/// (stored in `x-additionalOperations` extension).
+pub(crate) fn all_path_item_operations(
+    item: &PathItem,
+) -> impl Iterator<Item = (Method, Cow<'_, Operation>)> {
+    let standard =
+        path_item_operations(item).map(|(method, operation)| (method, Cow::Borrowed(operation)));
+    let custom = match item.extensions.get(ADDITIONAL_OPERATIONS_EXTENSION) {
+        Some(serde_json::Value::Object(operations)) => Some(operations),
+        _ => None,
+    };
+    let custom = custom.into_iter().flatten().filter_map(|(method, value)| {
+        let method = Method::from_bytes(method.as_bytes()).ok()?;
+        let operation = serde_json::from_value(value.clone()).ok()?;
+        Some((method, Cow::Owned(operation)))
+    });
+    standard.chain(custom)
+}
+
+/// Call `f` for every operation of path item (including operations of custom methods),
+/// removing operations, for which `f` returns `false`.
+///
+/// Operations of custom methods are deserialized from `x-additionalOperations` extension for
+/// calling `f` and serialized back. If any of them can't be deserialized,
+/// [`Error::InvalidDefinition`] is returned and path item is left unchanged.
+pub(crate) fn retain_path_item_operations<F>(item: &mut PathItem, mut f: F) -> Result<(), Error>
+where
+    F: FnMut(&Method, &mut Operation) -> bool,
+{
+    let Some(custom) = custom_path_item_operations(item)? else {
+        retain_standard_operations(item, f);
+        return Ok(());
+    };
+    retain_standard_operations(item, &mut f);
+    let mut operations = serde_json::Map::new();
+    for (name, method, mut operation) in custom {
+        if f(&method, &mut operation) {
+            let value =
+                serde_json::to_value(operation).map_err(|err| Error::Serialize(err.into()))?;
+            let _ = operations.insert(name, value);
+        }
+    }
+    if operations.is_empty() {
+        let _ = item
+            .extensions
+            .shift_remove(ADDITIONAL_OPERATIONS_EXTENSION);
+    } else {
+        let _ = item.extensions.insert(
+            ADDITIONAL_OPERATIONS_EXTENSION.into(),
+            serde_json::Value::Object(operations),
+        );
+    }
+    Ok(())
+}
+
+/// Operations of custom methods with names of methods.
+type CustomOperations = Vec<(String, Method, Operation)>;
+
+/// Operations of custom methods (with names of methods), deserialized from
+/// `x-additionalOperations` extension, or `None` if path item has no such extension.
+fn custom_path_item_operations(item: &PathItem) -> Result<Option<CustomOperations>, Error> {
+    let Some(serde_json::Value::Object(operations)) =
+        item.extensions.get(ADDITIONAL_OPERATIONS_EXTENSION)
+    else {
+        return Ok(None);
+    };
+    operations
+        .iter()
+        .map(|(name, value)| {
+            let invalid = |err: anyhow::Error| {
+                Error::InvalidDefinition(err.context(format!(
+                    "Invalid operation of method `{name}` in `{ADDITIONAL_OPERATIONS_EXTENSION}` extension"
+                )))
+            };
+            let method = Method::from_bytes(name.as_bytes()).map_err(|err| invalid(err.into()))?;
+            let operation = serde_json::from_value::<Operation>(value.clone())
+                .map_err(|err| invalid(err.into()))?;
+            Ok((name.clone(), method, operation))
+        })
+        .collect::<Result<Vec<_>, Error>>()
+        .map(Some)
+}
+
+/// Check, that operations of custom methods in `x-additionalOperations` extension can be
+/// deserialized.
+pub(crate) fn check_path_item_operations(item: &PathItem) -> Result<(), Error> {
+    custom_path_item_operations(item).map(drop)
+}
+
+/// Call `f` for every operation of standard method, removing operations, for which `f`
+/// returns `false`.
+fn retain_standard_operations<F>(item: &mut PathItem, mut f: F)
+where
+    F: FnMut(&Method, &mut Operation) -> bool,
+{
+    for method in STANDARD_METHODS {
+        if let Some(operation) = operation_slot(item, &method) {
+            if operation.as_mut().is_some_and(|x| !f(&method, x)) {
+                *operation = None;
+            }
+        }
+    }
+}
+
+/// Call `f` for every operation of path item (including operations of custom methods).
+pub(crate) fn for_each_path_item_operation_mut<F>(
+    item: &mut PathItem,
+    mut f: F,
+) -> Result<(), Error>
+where
+    F: FnMut(&Method, &mut Operation),
+{
+    retain_path_item_operations(item, |method, operation| {
+        f(method, operation);
+        true
+    })
+}
+
+/// Whether path item has any operation (including operations of custom methods).
+pub(crate) fn has_path_item_operations(item: &PathItem) -> bool {
+    all_path_item_operations(item).next().is_some()
+}
+
+/// Remove all operations (including operations of custom methods) from path item.
+pub(crate) fn take_path_item_operations(
+    item: &mut PathItem,
+) -> Result<Vec<(Method, Operation)>, Error> {
+    let mut operations = Vec::new();
+    retain_path_item_operations(item, |method, operation| {
+        operations.push((method.clone(), std::mem::take(operation)));
+        false
+    })?;
+    Ok(operations)
+}
+
+/// Remove operation of method from path item.
+pub(crate) fn take_path_item_operation(
+    item: &mut PathItem,
+    method: &Method,
+) -> Result<Option<Operation>, Error> {
+    if let Some(operation) = operation_slot(item, method) {
+        return Ok(operation.take());
+    }
+    let mut operation = None;
+    retain_path_item_operations(item, |x, value| {
+        if x != method {
+            return true;
+        }
+        operation = Some(std::mem::take(value));
+        false
+    })?;
+    Ok(operation)
+}
+
+/// Put operation of method into path item, replacing existing one (operations of custom
+/// methods are put into `x-additionalOperations` extension).
+pub(crate) fn insert_path_item_operation(
+    item: &mut PathItem,
+    method: &Method,
+    operation: Operation,
+) -> Result<(), serde_json::Error> {
+    if let Some(slot) = operation_slot(item, method) {
+        *slot = Some(operation);
+        return Ok(());
+    }
+    let operation = serde_json::to_value(operation)?;
+    let operations = item
+        .extensions
+        .entry(ADDITIONAL_OPERATIONS_EXTENSION.into())
+        .or_insert_with(|| serde_json::Value::Object(Default::default()));
+    if let serde_json::Value::Object(operations) = operations {
+        let _ = operations.insert(method.as_str().into(), operation);
+    }
+    Ok(())
+}
+
+/// Get content of [`RefOr`], resolving reference to component (at most one level).
+pub(crate) fn resolve_ref<'a, T: Referenceable>(
+    components: &'a Components,
+    value: &'a RefOr<T>,
+) -> Option<&'a T> {
+    match value {
+        RefOr::Object(x) => Some(x),
+        RefOr::Ref(reference) => {
+            let name = reference.reference.strip_prefix(T::REFERENCE_PREFIX)?;
+            match T::components(components).get(name)? {
+                RefOr::Object(x) => Some(x),
+                RefOr::Ref(_) => None,
+            }
+        }
+    }
+}
+
+/// Component, which can be referenced with [`RefOr`].
+pub(crate) trait Referenceable: Clone + PartialEq + Sized {
+    /// Prefix of reference to component of this type.
+    const REFERENCE_PREFIX: &'static str;
+
+    /// Components of this type.
+    fn components(components: &Components) -> &Map<String, RefOr<Self>>;
+
+    /// Visit content of component.
+    fn walk<V: SpecVisitor + ?Sized>(&mut self, v: &mut V);
+}
+
+/// Visitor of specification.
+///
+/// Schemas are visited with [`Visitor`] methods.
+pub(crate) trait SpecVisitor: Visitor {
+    /// Visit [`RefOr`] (before visiting its content).
+    fn visit_ref_or<T: Referenceable>(&mut self, value: &mut RefOr<T>) {
+        if let RefOr::Ref(reference) = value {
+            self.visit_reference(&mut reference.reference);
+        }
+    }
+
+    /// Visit reference, which is not part of [`RefOr`] or schema.
+    fn visit_reference(&mut self, reference: &mut String) {
+        let _ = reference;
+    }
+
+    /// Visit human-readable text (summary or description), which is not part of schema.
+    fn visit_text(&mut self, text: &mut String) {
+        let _ = text;
+    }
+}
+
+fn visit_optional_text<V: SpecVisitor + ?Sized>(v: &mut V, text: &mut Option<String>) {
+    if let Some(text) = text.as_mut() {
+        v.visit_text(text);
+    }
+}
+
+struct ReferenceVisitor<'a> {
+    f: &'a mut dyn FnMut(&mut String),
+}
+
+impl Visitor for ReferenceVisitor<'_> {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(reference) = schema.reference.as_mut() {
+            (self.f)(reference);
+        }
+        visit_schema_object(self, schema)
+    }
+}
+
+impl SpecVisitor for ReferenceVisitor<'_> {
+    fn visit_reference(&mut self, reference: &mut String) {
+        (self.f)(reference)
+    }
+}
+
+/// Call `f` for every reference (`$ref`) in specification, including references in schemas.
+pub(crate) fn for_each_reference<F>(spec: &mut OpenApi, mut f: F)
+where
+    F: FnMut(&mut String),
+{
+    walk_spec(&mut ReferenceVisitor { f: &mut f }, spec);
+}
+
+/// Name of component, referenced by reference (last segment of JSON pointer in fragment), so
+/// references with custom definitions path are resolved too.
+pub(crate) fn reference_name(reference: &str) -> Option<String> {
+    let (_, pointer) = reference.split_once('#')?;
+    let (_, name) = pointer.rsplit_once('/')?;
+    Some(name.replace("~1", "/").replace("~0", "~"))
+}
+
+/// Names of schema components, reachable by references from paths and other components
+/// (schemas, referenced only by unreachable schemas, are not included).
+pub(crate) fn reachable_schemas(spec: &mut OpenApi) -> HashSet<String> {
+    let schemas = spec
+        .components
+        .as_mut()
+        .map(|x| std::mem::take(&mut x.schemas))
+        .unwrap_or_default();
+    let mut pending = Vec::new();
+    for_each_reference(spec, |reference| pending.extend(reference_name(reference)));
+    let mut reachable = HashSet::new();
+    while let Some(name) = pending.pop() {
+        let Some(schema) = schemas.get(&name) else {
+            continue;
+        };
+        if reachable.insert(name) {
+            let mut f = |reference: &mut String| pending.extend(reference_name(reference));
+            ReferenceVisitor { f: &mut f }.visit_schema_object(&mut schema.clone());
+        }
+    }
+    if let Some(components) = spec.components.as_mut() {
+        components.schemas = schemas;
+    }
+    reachable
+}
+
+/// Visit paths and components of specification.
+pub(crate) fn walk_spec<V: SpecVisitor + ?Sized>(v: &mut V, spec: &mut OpenApi) {
+    for path_item in spec.paths.values_mut() {
+        walk_path_item(v, path_item);
+    }
+    if let Some(components) = spec.components.as_mut() {
+        walk_components(v, components);
+    }
+}
+
+/// Visit all components.
+pub(crate) fn walk_components<V: SpecVisitor + ?Sized>(v: &mut V, components: &mut Components) {
+    for schema in components.schemas.values_mut() {
+        v.visit_schema_object(schema);
+    }
+    walk_ref_or_map(v, &mut components.responses);
+    walk_ref_or_map(v, &mut components.parameters);
+    walk_ref_or_map(v, &mut components.examples);
+    walk_ref_or_map(v, &mut components.request_bodies);
+    walk_ref_or_map(v, &mut components.headers);
+    walk_ref_or_map(v, &mut components.links);
+    walk_ref_or_map(v, &mut components.callbacks);
+    for security_scheme in components.security_schemes.values_mut() {
+        if let RefOr::Ref(reference) = security_scheme {
+            v.visit_reference(&mut reference.reference);
+        }
+    }
+}
+
+/// Visit content of non-schema components, without visiting components themselves.
+pub(crate) fn walk_components_content<V: SpecVisitor + ?Sized>(
+    v: &mut V,
+    components: &mut Components,
+) {
+    fn walk_map_content<V, T>(v: &mut V, values: &mut Map<String, RefOr<T>>)
+    where
+        V: SpecVisitor + ?Sized,
+        T: Referenceable,
+    {
+        for value in values.values_mut() {
+            if let RefOr::Object(x) = value {
+                x.walk(v);
+            }
+        }
+    }
+
+    walk_map_content(v, &mut components.responses);
+    walk_map_content(v, &mut components.parameters);
+    walk_map_content(v, &mut components.request_bodies);
+    walk_map_content(v, &mut components.headers);
+    walk_map_content(v, &mut components.callbacks);
+}
+
+/// Visit [`RefOr`] and its content.
+pub(crate) fn walk_ref_or<V, T>(v: &mut V, value: &mut RefOr<T>)
+where
+    V: SpecVisitor + ?Sized,
+    T: Referenceable,
+{
+    v.visit_ref_or(value);
+    if let RefOr::Object(x) = value {
+        x.walk(v);
+    }
+}
+
+fn walk_ref_or_map<V, T>(v: &mut V, values: &mut Map<String, RefOr<T>>)
+where
+    V: SpecVisitor + ?Sized,
+    T: Referenceable,
+{
+    for value in values.values_mut() {
+        walk_ref_or(v, value);
+    }
+}
+
+fn walk_path_item<V: SpecVisitor + ?Sized>(v: &mut V, path_item: &mut PathItem) {
+    if let Some(reference) = path_item.reference.as_mut() {
+        v.visit_reference(reference);
+    }
+    visit_optional_text(v, &mut path_item.summary);
+    visit_optional_text(v, &mut path_item.description);
+    for parameter in &mut path_item.parameters {
+        walk_ref_or(v, parameter);
+    }
+    // Operations of custom methods are checked by builder, invalid ones are not visited
+    let _ =
+        for_each_path_item_operation_mut(path_item, |_, operation| walk_operation(v, operation));
+}
+
+fn walk_operation<V: SpecVisitor + ?Sized>(v: &mut V, operation: &mut Operation) {
+    visit_optional_text(v, &mut operation.summary);
+    visit_optional_text(v, &mut operation.description);
+    for parameter in &mut operation.parameters {
+        walk_ref_or(v, parameter);
+    }
+    if let Some(request_body) = operation.request_body.as_mut() {
+        walk_ref_or(v, request_body);
+    }
+    walk_responses(v, &mut operation.responses);
+    walk_ref_or_map(v, &mut operation.callbacks);
+}
+
+fn walk_responses<V: SpecVisitor + ?Sized>(v: &mut V, responses: &mut Responses) {
+    if let Some(response) = responses.default.as_mut() {
+        walk_ref_or(v, response);
+    }
+    walk_ref_or_map(v, &mut responses.responses);
+}
+
+fn walk_parameter_value<V: SpecVisitor + ?Sized>(v: &mut V, value: &mut ParameterValue) {
+    match value {
+        ParameterValue::Schema { schema, .. } => v.visit_schema_object(schema),
+        ParameterValue::Content { content } => walk_content(v, content),
+    }
+}
+
+fn walk_content<V: SpecVisitor + ?Sized>(v: &mut V, content: &mut Map<String, MediaType>) {
+    for media_type in content.values_mut() {
+        if let Some(schema) = media_type.schema.as_mut() {
+            v.visit_schema_object(schema);
+        }
+        for encoding in media_type.encoding.values_mut() {
+            walk_ref_or_map(v, &mut encoding.headers);
+        }
+    }
+}
+
+impl Referenceable for Response {
+    const REFERENCE_PREFIX: &'static str = "#/components/responses/";
+
+    fn components(components: &Components) -> &Map<String, RefOr<Self>> {
+        &components.responses
+    }
+
+    fn walk<V: SpecVisitor + ?Sized>(&mut self, v: &mut V) {
+        v.visit_text(&mut self.description);
+        walk_ref_or_map(v, &mut self.headers);
+        walk_content(v, &mut self.content);
+        walk_ref_or_map(v, &mut self.links);
+    }
+}
+
+impl Referenceable for Parameter {
+    const REFERENCE_PREFIX: &'static str = "#/components/parameters/";
+
+    fn components(components: &Components) -> &Map<String, RefOr<Self>> {
+        &components.parameters
+    }
+
+    fn walk<V: SpecVisitor + ?Sized>(&mut self, v: &mut V) {
+        visit_optional_text(v, &mut self.description);
+        walk_parameter_value(v, &mut self.value);
+    }
+}
+
+impl Referenceable for Header {
+    const REFERENCE_PREFIX: &'static str = "#/components/headers/";
+
+    fn components(components: &Components) -> &Map<String, RefOr<Self>> {
+        &components.headers
+    }
+
+    fn walk<V: SpecVisitor + ?Sized>(&mut self, v: &mut V) {
+        visit_optional_text(v, &mut self.description);
+        walk_parameter_value(v, &mut self.value);
+    }
+}
+
+impl Referenceable for RequestBody {
+    const REFERENCE_PREFIX: &'static str = "#/components/requestBodies/";
+
+    fn components(components: &Components) -> &Map<String, RefOr<Self>> {
+        &components.request_bodies
+    }
+
+    fn walk<V: SpecVisitor + ?Sized>(&mut self, v: &mut V) {
+        visit_optional_text(v, &mut self.description);
+        walk_content(v, &mut self.content);
+    }
+}
+
+impl Referenceable for Callback {
+    const REFERENCE_PREFIX: &'static str = "#/components/callbacks/";
+
+    fn components(components: &Components) -> &Map<String, RefOr<Self>> {
+        &components.callbacks
+    }
+
+    fn walk<V: SpecVisitor + ?Sized>(&mut self, v: &mut V) {
+        for path_item in self.callbacks.values_mut() {
+            walk_path_item(v, path_item);
+        }
+    }
+}
+
+impl Referenceable for Example {
+    const REFERENCE_PREFIX: &'static str = "#/components/examples/";
+
+    fn components(components: &Components) -> &Map<String, RefOr<Self>> {
+        &components.examples
+    }
+
+    fn walk<V: SpecVisitor + ?Sized>(&mut self, v: &mut V) {
+        visit_optional_text(v, &mut self.summary);
+        visit_optional_text(v, &mut self.description);
+    }
+}
+
+impl Referenceable for Link {
+    const REFERENCE_PREFIX: &'static str = "#/components/links/";
+
+    fn components(components: &Components) -> &Map<String, RefOr<Self>> {
+        &components.links
+    }
+
+    fn walk<V: SpecVisitor + ?Sized>(&mut self, v: &mut V) {
+        visit_optional_text(v, &mut self.description);
+    }
+}
@@ -40,6 +40,137 @@ mod openapi {
         assert_eq!(body_schema, expected_schema);
     }
 
+    #[test]
+    fn optional_body_detection() {
+        #[openapi]
+        async fn required(_arg: Json<String>) {}
+
+        #[openapi]
+        async fn optional(_arg: Option<Json<String>>) {}
+
+        #[openapi]
+        async fn explicit_required(#[body(required = true)] _arg: Json<String>) {}
+
+        #[openapi]
+        async fn explicit_optional(#[body(description = "Optional")] _arg: Option<Json<String>>) {}
+
+        let schema = Router::<()>::new()
+            .route("/required", get(oh!(required)))
+            .route("/optional", get(oh!(optional)))
+            .route("/explicit_required", get(oh!(explicit_required)))
+            .route("/explicit_optional", get(oh!(explicit_optional)))
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+
+        for (path, expected_required) in [
+            ("/required", true),
+            ("/optional", false),
+            ("/explicit_required", true),
+            ("/explicit_optional", false),
+        ] {
+            let body = schema.paths[path]
+                .clone()
+                .get
+                .expect("GET should be present")
+                .request_body
+                .expect("Request body should be present");
+            let RefOr::Object(body) = body else {
+                panic!("Request body should be RefOr::Object");
+            };
+            assert_eq!(body.required, expected_required, "{path}");
+            assert!(body.content.contains_key("application/json"), "{path}");
+        }
+    }
+
+    #[test]
+    fn opaque_return_type_inference() {
+        use okapi_operation::axum_integration::ApiResponse;
+
+        #[openapi]
+        async fn handle() -> impl ApiResponse {
+            Json("")
+        }
+
+        let schema = Router::<()>::new()
+            .route("/", get(oh!(handle)))
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+
+        let responses = schema.paths["/"]
+            .clone()
+            .get
+            .expect("GET / should be present")
+            .responses;
+        let RefOr::Object(response) = &responses.responses["200"] else {
+            panic!("GET / 200 response should be RefOr::Object");
+        };
+
+        assert!(response.content.contains_key("application/json"));
+    }
+
+    #[test]
+    fn response_type() {
+        use axum::response::IntoResponse;
+
+        #[openapi(response_type = "Json<String>")]
+        async fn handle() -> impl IntoResponse {
+            Json("")
+        }
+
+        let schema = Router::<()>::new()
+            .route("/", get(oh!(handle)))
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+
+        let responses = schema.paths["/"]
+            .clone()
+            .get
+            .expect("GET / should be present")
+            .responses;
+        let RefOr::Object(response) = &responses.responses["200"] else {
+            panic!("GET / 200 response should be RefOr::Object");
+        };
+
+        assert!(response.content.contains_key("application/json"));
+    }
+
+    #[test]
+    fn route_operation_overrides() {
+        use okapi_operation::axum_integration::get_with;
+
+        #[openapi(summary = "List users", tags = "users")]
+        async fn list_users() {}
+
+        let schema = Router::<()>::new()
+            .route("/users", get(oh!(list_users)))
+            .route(
+                "/v2/users",
+                get_with(oh!(list_users), |op| {
+                    op.operation_id = Some("list_users_v2".into());
+                    op.tags.push("v2".into());
+                }),
+            )
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+
+        let operation = |path: &str| {
+            schema.paths[path]
+                .clone()
+                .get
+                .expect("GET should be present")
+        };
+        assert_eq!(operation("/users").operation_id, None);
+        assert_eq!(operation("/users").tags, ["users"]);
+        let v2 = operation("/v2/users");
+        assert_eq!(v2.operation_id.as_deref(), Some("list_users_v2"));
+        assert_eq!(v2.summary.as_deref(), Some("List users"));
+        assert_eq!(v2.tags, ["users", "v2"]);
+    }
+
     #[test]
     fn string_body_detection() {
         #[openapi]
@@ -66,6 +197,415 @@ mod openapi {
             "String body (text/plain) shouldn't have schema"
         );
     }
+
+    #[test]
+    #[allow(dead_code)]
+    fn configurable_body_detection() {
+        use okapi_operation::{impl_to_media_types_for_wrapper, OpenApiBuilder};
+
+        struct ApiJson<T>(T);
+        impl_to_media_types_for_wrapper!(ApiJson<T>, "application/json");
+
+        #[openapi(body_detection(extra = "ApiJson, OtherJson"))]
+        async fn extra(_arg: ApiJson<String>) {}
+
+        #[openapi(body_detection(off))]
+        async fn off(_arg: Json<String>) {}
+
+        let schema = OpenApiBuilder::new("title", "version")
+            .operation("/extra", http::Method::POST, extra__openapi)
+            .operation("/off", http::Method::POST, off__openapi)
+            .build()
+            .expect("Schema generation shouldn't fail");
+        let request_body = |path: &str| {
+            schema.paths[path]
+                .post
+                .as_ref()
+                .expect("POST should be present")
+                .request_body
+                .clone()
+        };
+        assert!(request_body("/extra").is_some());
+        assert!(request_body("/off").is_none());
+    }
+
+    #[test]
+    #[allow(dead_code)]
+    fn unknown_extractors() {
+        use axum::{
+            extract::{Extension, State},
+            http::HeaderMap,
+        };
+        use okapi_operation::{BuildWarning, OpenApiBuilder};
+
+        struct Custom;
+
+        #[openapi(warn_unknown_extractors)]
+        async fn handle(
+            _state: State<()>,
+            _extension: Extension<u32>,
+            _headers: HeaderMap,
+            _custom: Custom,
+            _body: Json<String>,
+        ) {
+        }
+
+        let (schema, warnings) = OpenApiBuilder::new("title", "version")
+            .operation("/", http::Method::POST, handle__openapi)
+            .build_with_report()
+            .expect("Schema generation shouldn't fail");
+        assert!(schema.paths["/"]
+            .post
+            .as_ref()
+            .unwrap()
+            .request_body
+            .is_some());
+        assert_eq!(
+            warnings,
+            [BuildWarning::UnknownExtractor {
+                path: "/".into(),
+                method: http::Method::POST,
+                type_name: "Custom".into(),
+            }]
+        );
+    }
+
+    #[test]
+    #[allow(dead_code, unused_variables)]
+    fn parameters_from_arguments() {
+        use axum::extract::{Path, Query};
+        use okapi::openapi3::ParameterValue;
+        use okapi_operation::OpenApiBuilder;
+
+        #[openapi(parameters(
+            path(name = "user_id", from_arg = "id"),
+            query(from_arg = "page", required = true)
+        ))]
+        async fn handle(Path(id): Path<u64>, page: Query<u32>) {}
+
+        let schema = OpenApiBuilder::new("title", "version")
+            .operation("/users/{user_id}", http::Method::GET, handle__openapi)
+            .build()
+            .expect("Schema generation shouldn't fail");
+        let operation = schema.paths["/users/{user_id}"].get.clone().unwrap();
+        let mut gen = SchemaGenerator::default();
+        let parameters: Vec<_> = operation
+            .parameters
+            .into_iter()
+            .map(|x| {
+                let RefOr::Object(parameter) = x else {
+                    panic!("Parameter should be RefOr::Object");
+                };
+                let ParameterValue::Schema { schema, .. } = parameter.value else {
+                    panic!("Parameter should have schema");
+                };
+                (parameter.name, parameter.location, schema)
+            })
+            .collect();
+        assert_eq!(
+            parameters,
+            [
+                (
+                    "user_id".to_string(),
+                    "path".to_string(),
+                    gen.subschema_for::<u64>().into_object()
+                ),
+                (
+                    "page".to_string(),
+                    "query".to_string(),
+                    gen.subschema_for::<u32>().into_object()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn path_templates() {
+        #[openapi]
+        async fn handle() {}
+
+        let nested = Router::<()>::new()
+            .route("/", get(oh!(handle)))
+            .route("/:id/files/*path", get(oh!(handle)));
+        let schema = Router::<()>::new()
+            .nest("/api", nested)
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+
+        assert!(schema.paths.contains_key("/api"));
+        let parameters = schema.paths["/api/{id}/files/{path}"]
+            .clone()
+            .get
+            .expect("GET /api/{id}/files/{path} should be present")
+            .parameters;
+        assert_eq!(parameters.len(), 1);
+        let RefOr::Object(parameter) = &parameters[0] else {
+            panic!("Wildcard parameter should be RefOr::Object");
+        };
+        assert_eq!(parameter.name, "path");
+        assert_eq!(parameter.location, "path");
+        assert_eq!(parameter.extensions["x-wildcard"], true);
+    }
+
+    #[test]
+    fn wildcard_routes() {
+        use okapi_operation::{BuilderOptions, WildcardRoutes};
+
+        #[openapi]
+        async fn handle() {}
+
+        #[openapi(path_override = "/static/{path}")]
+        async fn handle_static() {}
+
+        let mut builder = Router::<()>::new()
+            .route("/", get(oh!(handle)))
+            .route("/files/*path", get(oh!(handle)))
+            .route("/static/*rest", get(oh!(handle_static)))
+            .generate_openapi_builder();
+        let schema = builder
+            .set_options(BuilderOptions::new().wildcard_routes(WildcardRoutes::Skip))
+            .build()
+            .expect("Schema generation shouldn't fail");
+
+        assert_eq!(
+            schema.paths.keys().collect::<Vec<_>>(),
+            ["/", "/static/{path}"]
+        );
+        assert_eq!(
+            builder.skipped_routes(),
+            [("/files/{path}".to_owned(), http::Method::GET)]
+        );
+        let operation = schema.paths["/static/{path}"]
+            .clone()
+            .get
+            .expect("GET /static/{path} should be present");
+        assert!(operation.parameters.is_empty());
+    }
+
+    #[test]
+    fn build_report() {
+        use okapi::openapi3::Tag;
+        use okapi_operation::{BuildWarning, BuilderOptions};
+
+        #[openapi]
+        async fn handle() {}
+
+        async fn undocumented() {}
+
+        let mut builder = Router::<()>::new()
+            .route("/users/:id", get(oh!(handle)))
+            .route("/health", get(undocumented))
+            .generate_openapi_builder();
+        let (_, warnings) = builder
+            .set_options(
+                BuilderOptions::new()
+                    .infer_operation_id(true)
+                    .infer_path_parameters(true),
+            )
+            .tag(Tag {
+                name: "users".into(),
+                ..Default::default()
+            })
+            .tag(Tag {
+                name: "users".into(),
+                ..Default::default()
+            })
+            .build_with_report()
+            .expect("Schema generation shouldn't fail");
+
+        assert_eq!(
+            warnings,
+            [
+                BuildWarning::UndocumentedRoute {
+                    path: "/health".into(),
+                    method: http::Method::GET
+                },
+                BuildWarning::InferredPathParameter {
+                    path: "/users/{id}".into(),
+                    method: http::Method::GET,
+                    name: "id".into()
+                },
+                BuildWarning::InferredOperationId {
+                    path: "/users/{id}".into(),
+                    method: http::Method::GET,
+                    operation_id: "handle".into()
+                },
+                BuildWarning::DuplicateTag {
+                    name: "users".into()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fallback_documented() {
+        use axum::body::Body;
+        use http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        #[openapi(
+            summary = "Not found",
+            responses(
+                ignore_return_type = true,
+                response(status = "404", description = "Not found", content = "String")
+            )
+        )]
+        async fn not_found() -> (StatusCode, &'static str) {
+            (StatusCode::NOT_FOUND, "Not found")
+        }
+
+        let app = Router::<()>::new().fallback_documented(oh!(not_found));
+        let spec = app
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        assert_eq!(spec.extensions["x-fallback"]["summary"], "Not found");
+
+        let response = app
+            .axum_router()
+            .oneshot(Request::get("/missing").body(Body::empty()).unwrap())
+            .await
+            .expect("Request shouldn't fail");
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let spec = app
+            .fallback(|| async {})
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        assert!(!spec.extensions.contains_key("x-fallback"));
+    }
+
+    #[test]
+    fn nested_template() {
+        use okapi::openapi3::{
+            SecurityRequirement, SecurityScheme, SecuritySchemeData, Server, Tag,
+        };
+
+        #[openapi]
+        async fn handle() {}
+
+        #[openapi(security(security_scheme(name = "bearer", scopes = "")))]
+        async fn secured() {}
+
+        let tag = |name: &str, description: Option<&str>| Tag {
+            name: name.into(),
+            description: description.map(Into::into),
+            ..Default::default()
+        };
+        let scheme = |description: &str| SecurityScheme {
+            description: Some(description.into()),
+            data: SecuritySchemeData::Http {
+                scheme: "bearer".into(),
+                bearer_format: None,
+            },
+            extensions: Default::default(),
+        };
+
+        let mut nested = Router::<()>::new()
+            .route("/", get(oh!(handle)))
+            .route("/secured", get(oh!(secured)));
+        nested
+            .openapi_builder_template_mut()
+            .title("Nested")
+            .tag(tag("users", Some("Nested")))
+            .tag(tag("admin", None))
+            .security_scheme("api_key", scheme("Nested"))
+            .security_scheme("bearer", scheme("Nested"))
+            .apply_global_security("api_key", [])
+            .server(Server {
+                url: "https://users.example.com".into(),
+                ..Default::default()
+            });
+        let mut app = Router::<()>::new().route("/health", get(oh!(handle)));
+        app.openapi_builder_template_mut()
+            .title("Parent")
+            .tag(tag("users", None))
+            .security_scheme("bearer", scheme("Parent"));
+        let spec = app
+            .nest("/users", nested)
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+
+        assert_eq!(spec.info.title, "Parent");
+        assert_eq!(
+            spec.tags,
+            [tag("users", Some("Nested")), tag("admin", None)]
+        );
+        assert!(spec.security.is_empty());
+        let schemes = &spec.components.expect("Components").security_schemes;
+        assert_eq!(
+            schemes.get("api_key"),
+            Some(&RefOr::Object(scheme("Nested")))
+        );
+        assert_eq!(
+            schemes.get("bearer"),
+            Some(&RefOr::Object(scheme("Parent")))
+        );
+
+        let mut api_key = SecurityRequirement::new();
+        api_key.insert("api_key".into(), vec![]);
+        let operation = spec.paths["/users"].get.as_ref().expect("Operation");
+        assert_eq!(operation.security, Some(vec![api_key]));
+        assert_eq!(operation.servers.as_ref().map(Vec::len), Some(1));
+        let operation = spec.paths["/users/secured"]
+            .get
+            .as_ref()
+            .expect("Operation");
+        assert_ne!(operation.security, None);
+        assert_eq!(
+            operation.security.as_ref().unwrap()[0]
+                .keys()
+                .collect::<Vec<_>>(),
+            ["bearer"]
+        );
+        let operation = spec.paths["/health"].get.as_ref().expect("Operation");
+        assert_eq!((&operation.security, &operation.servers), (&None, &None));
+    }
+
+    #[test]
+    fn framework_responses() {
+        use okapi_operation::BuilderOptions;
+
+        #[openapi]
+        async fn handle() {}
+
+        let mut app = Router::<()>::new()
+            .route("/users", get(oh!(handle)).post(oh!(handle)))
+            .route("/any", get(oh!(handle)).fallback(|| async {}));
+        app.openapi_builder_template_mut()
+            .set_options(BuilderOptions::new().framework_responses(true));
+        let spec = app
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+
+        let responses = &spec.paths["/users"]
+            .post
+            .as_ref()
+            .expect("Operation")
+            .responses
+            .responses;
+        let Some(RefOr::Object(response)) = responses.get("405") else {
+            panic!("405 response should be present");
+        };
+        let RefOr::Object(allow) = &response.headers["Allow"] else {
+            panic!("Allow header should be object");
+        };
+        assert_eq!(
+            allow.description.as_deref(),
+            Some("Methods, allowed for path (GET, HEAD, POST)")
+        );
+        let responses = &spec.paths["/any"]
+            .get
+            .as_ref()
+            .expect("Operation")
+            .responses
+            .responses;
+        assert!(!responses.contains_key("405"));
+    }
 }
 
 #[cfg(feature = "axum")]
@@ -112,9 +652,26 @@ mod openapi_handler {
         let _ = Router::<()>::new().route("/", get(openapi_handler!(S::handle)));
     }
 
+    #[test]
+    fn openapi_handler_trait_method() {
+        trait Api {
+            #[openapi]
+            async fn handle() -> String {
+                String::new()
+            }
+        }
+
+        struct S {}
+
+        impl Api for S {}
+
+        let _ = Router::<()>::new().route("/", get(openapi_handler!(<S as Api>::handle)));
+    }
+
     #[test]
     fn openapi_handler_typed() {
         #[openapi]
+        #[allow(clippy::extra_unused_type_parameters)]
         async fn handle<T>() {}
 
         let _ = Router::<()>::new().route("/", get(openapi_handler!(handle::<()>)));
@@ -131,3 +688,1364 @@ mod openapi_handler {
         let _ = Router::<()>::new().route("/", get(openapi_service!(service)));
     }
 }
+
+#[cfg(all(
+    feature = "axum",
+    feature = "xml",
+    feature = "msgpack",
+    feature = "cbor",
+    feature = "csv"
+))]
+mod media {
+    use axum::{body::Body, http::Request};
+    use okapi_operation::{
+        axum_integration::{post, Router},
+        media::{Cbor, Csv, MsgPack, Xml},
+        oh, openapi,
+    };
+    use serde::{Deserialize, Serialize};
+    use tower::ServiceExt;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct User {
+        name: String,
+    }
+
+    #[openapi]
+    async fn msgpack(#[body] MsgPack(user): MsgPack<User>) -> MsgPack<User> {
+        MsgPack(user)
+    }
+
+    #[openapi]
+    async fn cbor(#[body] Cbor(user): Cbor<User>) -> Cbor<User> {
+        Cbor(user)
+    }
+
+    #[openapi]
+    async fn xml(#[body] Xml(user): Xml<User>) -> Xml<User> {
+        Xml(user)
+    }
+
+    #[openapi]
+    async fn csv(#[body] Csv(users): Csv<User>) -> Csv<User> {
+        Csv(users)
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/msgpack", post(oh!(msgpack)))
+            .route("/cbor", post(oh!(cbor)))
+            .route("/xml", post(oh!(xml)))
+            .route("/csv", post(oh!(csv)))
+    }
+
+    async fn roundtrip(path: &str, body: Vec<u8>) -> (String, Vec<u8>) {
+        let response = app()
+            .axum_router()
+            .oneshot(
+                Request::post(path)
+                    .body(Body::from(body))
+                    .expect("Request should be valid"),
+            )
+            .await
+            .expect("Request shouldn't fail");
+        assert!(response.status().is_success());
+        let content_type = response.headers()["content-type"]
+            .to_str()
+            .expect("Content type should be valid string")
+            .to_owned();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Body should be readable");
+        (content_type, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn wrappers_roundtrip() {
+        let user = User {
+            name: "test".into(),
+        };
+
+        let (content_type, body) = roundtrip(
+            "/msgpack",
+            rmp_serde::to_vec_named(&user).expect("Serialization shouldn't fail"),
+        )
+        .await;
+        assert_eq!(content_type, "application/msgpack");
+        assert_eq!(rmp_serde::from_slice::<User>(&body).unwrap(), user);
+
+        let mut cbor_body = Vec::new();
+        ciborium::into_writer(&user, &mut cbor_body).expect("Serialization shouldn't fail");
+        let (content_type, body) = roundtrip("/cbor", cbor_body).await;
+        assert_eq!(content_type, "application/cbor");
+        assert_eq!(ciborium::from_reader::<User, _>(&body[..]).unwrap(), user);
+
+        let (content_type, body) = roundtrip(
+            "/xml",
+            quick_xml::se::to_string(&user)
+                .expect("Serialization shouldn't fail")
+                .into_bytes(),
+        )
+        .await;
+        assert_eq!(content_type, "application/xml");
+        assert_eq!(
+            quick_xml::de::from_reader::<_, User>(&body[..]).unwrap(),
+            user
+        );
+    }
+
+    #[test]
+    fn wrappers_media_types() {
+        let spec = app()
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        for (path, mime) in [
+            ("/msgpack", "application/msgpack"),
+            ("/cbor", "application/cbor"),
+            ("/xml", "application/xml"),
+            ("/csv", "text/csv"),
+        ] {
+            let operation = spec.paths[path]
+                .post
+                .clone()
+                .expect("POST should be present");
+            let Some(okapi_operation::okapi::openapi3::RefOr::Object(body)) =
+                operation.request_body
+            else {
+                panic!("Request body should be RefOr::Object");
+            };
+            assert!(body.content.contains_key(mime));
+            let okapi_operation::okapi::openapi3::RefOr::Object(response) =
+                &operation.responses.responses["200"]
+            else {
+                panic!("200 response should be RefOr::Object");
+            };
+            assert!(response.content.contains_key(mime));
+        }
+
+        let Some(okapi_operation::okapi::openapi3::RefOr::Object(body)) =
+            spec.paths["/csv"].post.clone().and_then(|x| x.request_body)
+        else {
+            panic!("Request body should be RefOr::Object");
+        };
+        assert_eq!(
+            serde_json::to_value(&body.content["text/csv"].schema).unwrap(),
+            serde_json::json!({
+                "type": "array",
+                "items": { "$ref": "#/components/schemas/User" }
+            })
+        );
+    }
+}
+
+#[cfg(feature = "axum")]
+mod custom_methods {
+    use axum::{body::Body, http::Request};
+    use http::{Method, StatusCode};
+    use okapi_operation::{
+        axum_integration::{get, on_custom, Router, ServiceExt as _},
+        oh, openapi, Components,
+    };
+    use tower::{service_fn, ServiceExt};
+
+    #[openapi(operation_id = "search")]
+    async fn search() -> &'static str {
+        "search"
+    }
+
+    #[openapi(operation_id = "list")]
+    async fn list() -> &'static str {
+        "list"
+    }
+
+    async fn propfind() -> &'static str {
+        "propfind"
+    }
+
+    fn proppatch_operation(
+        _: &mut Components,
+    ) -> Result<okapi_operation::okapi::openapi3::Operation, okapi_operation::Error> {
+        Ok(okapi_operation::okapi::openapi3::Operation {
+            operation_id: Some("proppatch".into()),
+            ..Default::default()
+        })
+    }
+
+    fn app() -> Router {
+        let query = Method::from_bytes(b"QUERY").unwrap();
+        let propfind_method = Method::from_bytes(b"PROPFIND").unwrap();
+        Router::new().route(
+            "/users",
+            on_custom(query, oh!(search))
+                .on_custom(propfind_method, propfind)
+                .on_custom_service(
+                    Method::from_bytes(b"PROPPATCH").unwrap(),
+                    service_fn(|_: Request<Body>| async {
+                        Ok::<_, std::convert::Infallible>("proppatch")
+                    })
+                    .with_openapi(proppatch_operation),
+                )
+                .merge(get(oh!(list))),
+        )
+    }
+
+    async fn call(method: &[u8]) -> (StatusCode, String) {
+        call_router(app().axum_router(), method).await
+    }
+
+    async fn call_router(router: axum::Router, method: &[u8]) -> (StatusCode, String) {
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::from_bytes(method).unwrap())
+                    .uri("/users")
+                    .body(Body::empty())
+                    .expect("Request should be valid"),
+            )
+            .await
+            .expect("Request shouldn't fail");
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Body should be readable");
+        (status, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    #[tokio::test]
+    async fn routing() {
+        assert_eq!(call(b"QUERY").await, (StatusCode::OK, "search".into()));
+        assert_eq!(call(b"PROPFIND").await, (StatusCode::OK, "propfind".into()));
+        assert_eq!(
+            call(b"PROPPATCH").await,
+            (StatusCode::OK, "proppatch".into())
+        );
+        assert_eq!(call(b"GET").await, (StatusCode::OK, "list".into()));
+        assert_eq!(call(b"MKCOL").await.0, StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn routes_of_same_path() {
+        let query = Method::from_bytes(b"QUERY").unwrap();
+        let propfind_method = Method::from_bytes(b"PROPFIND").unwrap();
+        let routes = Router::new()
+            .route("/users", on_custom(query, oh!(search)))
+            .route("/users", on_custom(propfind_method.clone(), propfind))
+            .merge(Router::new().route("/users", get(oh!(list))));
+        let app = Router::new()
+            .merge(routes)
+            .merge(Router::new().route(
+                "/users",
+                on_custom(Method::from_bytes(b"MKCOL").unwrap(), || async { "mkcol" }),
+            ))
+            .with_state(());
+        for (method, body) in [
+            (&b"QUERY"[..], "search"),
+            (b"PROPFIND", "propfind"),
+            (b"MKCOL", "mkcol"),
+            (b"GET", "list"),
+        ] {
+            assert_eq!(
+                call_router(app.axum_router(), method).await,
+                (StatusCode::OK, body.into())
+            );
+        }
+        assert_eq!(
+            call_router(app.axum_router(), b"PROPPATCH").await.0,
+            StatusCode::METHOD_NOT_ALLOWED
+        );
+
+        let spec = app
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        assert_eq!(
+            spec.paths["/users"].extensions["x-additionalOperations"]["QUERY"]["operationId"],
+            "search"
+        );
+    }
+
+    #[tokio::test]
+    async fn merged_method_routers() {
+        #[derive(Clone)]
+        struct AppState(&'static str);
+
+        let method_router = on_custom(
+            Method::from_bytes(b"QUERY").unwrap(),
+            |axum::extract::State(state): axum::extract::State<AppState>| async move { state.0 },
+        )
+        .merge(on_custom(
+            Method::from_bytes(b"PROPFIND").unwrap(),
+            propfind,
+        ))
+        .fallback(|| async { "fallback" })
+        .with_state(AppState("query"));
+        let app = axum::Router::new().route("/users", method_router.into_axum());
+        for (method, body) in [
+            (&b"QUERY"[..], "query"),
+            (b"PROPFIND", "propfind"),
+            (b"MKCOL", "fallback"),
+        ] {
+            assert_eq!(
+                call_router(app.clone(), method).await,
+                (StatusCode::OK, body.into())
+            );
+        }
+    }
+
+    #[test]
+    fn documentation() {
+        let (spec, warnings) = app()
+            .generate_openapi_builder()
+            .build_with_report()
+            .expect("Schema generation shouldn't fail");
+        let path_item = &spec.paths["/users"];
+        assert!(path_item.get.is_some());
+        assert_eq!(
+            path_item.extensions["x-additionalOperations"]["QUERY"]["operationId"],
+            "search"
+        );
+        assert_eq!(
+            path_item.extensions["x-additionalOperations"]["PROPPATCH"]["operationId"],
+            "proppatch"
+        );
+        assert_eq!(warnings.len(), 1, "PROPFIND should be undocumented");
+    }
+}
+
+#[cfg(feature = "axum")]
+mod route_auto {
+    use axum::{body::Body, http::Request};
+    use http::{Method, StatusCode};
+    use okapi_operation::{
+        axum_integration::{get, Router},
+        oh, openapi, BuildWarning,
+    };
+    use tower::ServiceExt;
+
+    #[openapi(path = "/users/{id}", method = "get")]
+    async fn get_user() -> &'static str {
+        "user"
+    }
+
+    #[openapi(path = "/users/{id}", method = "query")]
+    async fn search_user() -> &'static str {
+        "search"
+    }
+
+    #[openapi(path = "/users", method = "post")]
+    async fn create_user() {}
+
+    #[tokio::test]
+    async fn routing() {
+        let app = Router::<()>::new()
+            .route_auto(oh!(get_user))
+            .route_auto(oh!(search_user));
+        for (method, body) in [("GET", "user"), ("QUERY", "search")] {
+            let response = app
+                .axum_router()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::from_bytes(method.as_bytes()).unwrap())
+                        .uri("/users/1")
+                        .body(Body::empty())
+                        .expect("Request should be valid"),
+                )
+                .await
+                .expect("Request shouldn't fail");
+            assert_eq!(response.status(), StatusCode::OK);
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Body should be readable");
+            assert_eq!(bytes, body);
+        }
+    }
+
+    #[test]
+    fn documentation() {
+        let (spec, warnings) = Router::<()>::new()
+            .nest("/api", Router::new().route_auto(oh!(get_user)))
+            .route("/people", get(oh!(create_user)))
+            .generate_openapi_builder()
+            .build_with_report()
+            .expect("Schema generation shouldn't fail");
+        assert!(spec.paths["/api/users/{id}"].get.is_some());
+        assert!(spec.paths["/people"].get.is_some());
+        assert_eq!(
+            warnings,
+            vec![BuildWarning::DeclaredRouteMismatch {
+                path: "/people".into(),
+                method: Method::GET,
+                declared_path: "/users".into(),
+                declared_method: Method::POST,
+            }]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn without_declared_route() {
+        #[openapi]
+        async fn handle() {}
+
+        let _ = Router::<()>::new().route_auto(oh!(handle));
+    }
+}
+
+#[cfg(feature = "axum")]
+mod finish_openapi {
+    use axum::{body::Body, http::Request, Router as AxumRouter};
+    use okapi_operation::axum_integration::{FinishOpenapiOptions, FinishOptions, Router, Ui};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn get(app: AxumRouter, path: &str) -> Vec<u8> {
+        let response = app
+            .oneshot(
+                Request::get(path)
+                    .body(Body::empty())
+                    .expect("Request should be valid"),
+            )
+            .await
+            .expect("Request shouldn't fail");
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Body should be readable")
+            .to_vec()
+    }
+
+    async fn spec(app: AxumRouter) -> Value {
+        let body = get(app, "/openapi").await;
+        serde_json::from_slice(&body).expect("Specification should be JSON")
+    }
+
+    #[tokio::test]
+    async fn operation_options() {
+        let options = FinishOpenapiOptions::new()
+            .tag("meta")
+            .summary("Specification")
+            .security("api_key", []);
+        let app = Router::new()
+            .finish_openapi_with_options(None, "title", "1.0.0", options)
+            .expect("Specification should be built");
+        let spec = spec(app).await;
+        let operation = &spec["paths"]["/openapi"]["get"];
+        assert_eq!(operation["tags"], json!(["meta"]));
+        assert_eq!(operation["summary"], "Specification");
+        assert_eq!(operation["security"], json!([{ "api_key": [] }]));
+    }
+
+    #[tokio::test]
+    async fn hidden() {
+        let app = Router::new()
+            .finish_openapi_with_options(
+                None,
+                "title",
+                "1.0.0",
+                FinishOpenapiOptions::new().hidden(true),
+            )
+            .expect("Specification should be built");
+        let spec = spec(app).await;
+        assert_eq!(spec["info"]["title"], "title");
+        assert_eq!(spec["paths"], json!({}));
+    }
+
+    #[tokio::test]
+    async fn finish_with_options() {
+        let mut router = Router::new();
+        router.openapi_builder_template_mut().title("Template");
+        let app = router
+            .finish_openapi_with(
+                FinishOptions::new()
+                    .path("/spec")
+                    .version("2.0.0")
+                    .hide_self(true)
+                    .ui(Ui::SwaggerUi),
+            )
+            .expect("Specification should be built");
+
+        let spec: Value = serde_json::from_slice(&get(app.clone(), "/spec").await)
+            .expect("Specification should be JSON");
+        assert_eq!(
+            spec["info"],
+            json!({ "title": "Template", "version": "2.0.0" })
+        );
+        assert_eq!(spec["paths"], json!({}));
+
+        let ui = String::from_utf8(get(app, "/docs").await).expect("UI should be HTML");
+        assert!(ui.contains("swagger-ui"));
+        assert!(ui.contains("url: \"/spec\""));
+    }
+
+    #[tokio::test]
+    async fn into_service_with_spec_at() {
+        let mut router = Router::new();
+        router
+            .openapi_builder_template_mut()
+            .title("Template")
+            .version("1.0.0");
+        let app = router
+            .into_service_with_spec_at("/openapi")
+            .expect("Specification should be built");
+        let spec = spec(app).await;
+        assert_eq!(
+            spec["info"],
+            json!({ "title": "Template", "version": "1.0.0" })
+        );
+        assert!(spec["paths"]["/openapi"]["get"].is_object());
+    }
+
+    #[cfg(feature = "into-axum-router")]
+    #[tokio::test]
+    async fn into_axum_router() {
+        let app: AxumRouter = Router::new()
+            .route("/", axum::routing::get(|| async { "root" }))
+            .into();
+        assert_eq!(get(app, "/").await, b"root");
+    }
+
+    #[okapi_operation::openapi(tags = "internal")]
+    async fn internal() {}
+
+    #[tokio::test]
+    async fn filtered() {
+        use okapi_operation::{
+            axum_integration::{get as get_route, SpecFilter},
+            oh, Audience,
+        };
+
+        let filter = SpecFilter::new(|parts| {
+            if parts.headers.contains_key("x-staff") {
+                Audience::new().tag("internal")
+            } else {
+                Audience::new()
+            }
+        })
+        .restricted_tag("internal");
+        let app = Router::new()
+            .route("/internal", get_route(oh!(internal)))
+            .finish_openapi_with(FinishOptions::new().filter(filter))
+            .expect("Specification should be built");
+
+        let paths = |spec: Value| {
+            spec["paths"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(paths(spec(app.clone()).await), ["/openapi"]);
+
+        let response = app
+            .oneshot(
+                Request::get("/openapi")
+                    .header("x-staff", "1")
+                    .body(Body::empty())
+                    .expect("Request should be valid"),
+            )
+            .await
+            .expect("Request shouldn't fail");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Body should be readable");
+        let spec: Value = serde_json::from_slice(&body).expect("Specification should be JSON");
+        assert_eq!(paths(spec), ["/internal", "/openapi"]);
+    }
+}
+
+#[cfg(feature = "axum")]
+mod cors {
+    use okapi::openapi3::{ParameterValue, RefOr};
+    use okapi_operation::{
+        axum_integration::{get, DocumentedCors, Router},
+        oh, openapi,
+    };
+    use tower::layer::util::Identity;
+
+    #[openapi]
+    async fn handle() {}
+
+    #[test]
+    fn preflight_documented() {
+        let spec = Router::<()>::new()
+            .route("/users", get(oh!(handle)).post(|| async {}))
+            .route("/health", get(|| async {}))
+            .cors_layer(DocumentedCors::new(Identity::new()).allow_headers(["content-type"]))
+            .route("/late", get(oh!(handle)))
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+
+        let operation = spec.paths["/users"].options.as_ref().expect("Preflight");
+        let RefOr::Object(response) = &operation.responses.responses["200"] else {
+            panic!("Response should be inline");
+        };
+        let header = |name: &str| match &response.headers[name] {
+            RefOr::Object(header) => match &header.value {
+                ParameterValue::Schema { example, .. } => example.clone(),
+                ParameterValue::Content { .. } => None,
+            },
+            RefOr::Ref(_) => None,
+        };
+        assert_eq!(
+            header("Access-Control-Allow-Methods"),
+            Some("GET, OPTIONS, POST".into())
+        );
+        assert_eq!(
+            header("Access-Control-Allow-Headers"),
+            Some("content-type".into())
+        );
+        assert!(spec.paths["/health"].options.is_some());
+        assert!(spec.paths["/late"].options.is_none());
+    }
+}
+
+#[cfg(feature = "axum")]
+mod versioning {
+    use axum::{body::Body, http::Request, Router as AxumRouter};
+    use okapi_operation::{
+        axum_integration::{get, Router},
+        oh, openapi,
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    #[openapi]
+    async fn list_users() -> &'static str {
+        "v1"
+    }
+
+    #[openapi]
+    async fn list_users_v2() -> &'static str {
+        "v2"
+    }
+
+    fn versioned() -> okapi_operation::axum_integration::VersionedRouter {
+        Router::versioned()
+            .route_v(1, "/users", get(oh!(list_users)))
+            .route_v(2, "/users", get(oh!(list_users_v2)))
+    }
+
+    async fn get_body(app: AxumRouter, path: &str) -> Vec<u8> {
+        let response = app
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .expect("Request shouldn't fail");
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Body should be readable")
+            .to_vec()
+    }
+
+    #[test]
+    fn combined() {
+        let spec = versioned()
+            .into_router()
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        let tags = |path: &str| spec.paths[path].get.as_ref().unwrap().tags.clone();
+        assert_eq!(tags("/v1/users"), ["v1"]);
+        assert_eq!(tags("/v2/users"), ["v2"]);
+        let names: Vec<_> = spec.tags.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, ["v1", "v2"]);
+    }
+
+    #[tokio::test]
+    async fn per_version() {
+        let app = versioned()
+            .finish_openapi_per_version("Demo")
+            .expect("Specifications should be built");
+        assert_eq!(get_body(app.clone(), "/v1/users").await, b"v1");
+        assert_eq!(get_body(app.clone(), "/v2/users").await, b"v2");
+
+        let spec: Value = serde_json::from_slice(&get_body(app, "/v2/openapi").await)
+            .expect("Specification should be JSON");
+        assert_eq!(spec["info"]["version"], "2");
+        let paths: Vec<_> = spec["paths"].as_object().unwrap().keys().collect();
+        assert_eq!(paths, ["/v2/openapi", "/v2/users"]);
+    }
+}
+
+#[cfg(feature = "axum")]
+mod experimental {
+    use okapi_operation::{
+        axum_integration::{get, get_with, Router},
+        oh, openapi,
+    };
+
+    #[openapi]
+    async fn handle() {}
+
+    fn router(enabled: bool) -> Router {
+        Router::new()
+            .route("/stable", get(oh!(handle)))
+            .route_if(enabled, "/flagged", get(oh!(handle)))
+            .route(
+                "/experimental",
+                get_with(oh!(handle), |op| {
+                    let _ = op.extensions.insert("x-experimental".into(), true.into());
+                }),
+            )
+    }
+
+    #[test]
+    fn route_if() {
+        let spec = router(false)
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        assert!(!spec.paths.contains_key("/flagged"));
+        let spec = router(true)
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        assert!(spec.paths.contains_key("/flagged"));
+    }
+
+    #[test]
+    fn excluded_by_extension() {
+        let spec = router(true)
+            .generate_openapi_builder()
+            .exclude_operations_with_extension("x-experimental")
+            .build()
+            .expect("Schema generation shouldn't fail");
+        let paths: Vec<_> = spec.paths.keys().collect();
+        assert_eq!(paths, ["/flagged", "/stable"]);
+    }
+}
+
+#[cfg(feature = "axum")]
+mod operation_ids {
+    use axum::{
+        body::Body,
+        extract::Request,
+        http::HeaderValue,
+        middleware::{self, Next},
+        response::Response,
+    };
+    use okapi_operation::{
+        axum_integration::{get, post, OperationIds, Router},
+        oh, openapi, BuilderOptions,
+    };
+    use tower::ServiceExt;
+
+    #[openapi(operation_id = "get_user")]
+    async fn get_user() {}
+
+    #[openapi]
+    async fn create_user() {}
+
+    async fn label(request: Request, next: Next) -> Response {
+        let operation_id = OperationIds::for_request(&request).map(ToOwned::to_owned);
+        let mut response = next.run(request).await;
+        if let Some(operation_id) = operation_id {
+            let _ = response.headers_mut().insert(
+                "x-operation-id",
+                HeaderValue::from_str(&operation_id).unwrap(),
+            );
+        }
+        response
+    }
+
+    async fn operation_id(request: Request<Body>) -> Option<String> {
+        let mut router = Router::new()
+            .nest(
+                "/api",
+                Router::new()
+                    .route("/users/:id", get(oh!(get_user)))
+                    .route("/users", post(oh!(create_user))),
+            )
+            .layer(middleware::from_fn(label));
+        router.openapi_builder_template_mut().set_options(
+            BuilderOptions::new()
+                .infer_operation_id(true)
+                .operation_id_strategy(okapi_operation::OperationIdStrategy::MethodPath),
+        );
+        let app = router
+            .finish_openapi("/openapi", "title", "version")
+            .expect("Schema generation shouldn't fail");
+        let response = app.oneshot(request).await.expect("Request shouldn't fail");
+        response
+            .headers()
+            .get("x-operation-id")
+            .map(|x| x.to_str().unwrap().to_owned())
+    }
+
+    #[test]
+    fn inventory() {
+        let mut router = Router::<()>::new().nest(
+            "/api",
+            Router::new()
+                .route("/users/:id", get(oh!(get_user)))
+                .route("/users", post(oh!(create_user))),
+        );
+        let inventory = router.routes_operations().inventory().unwrap();
+        assert_eq!(inventory[0].operation_id, None);
+        assert_eq!(inventory[1].operation_id.as_deref(), Some("get_user"));
+
+        router.openapi_builder_template_mut().set_options(
+            BuilderOptions::new()
+                .infer_operation_id(true)
+                .operation_id_strategy(okapi_operation::OperationIdStrategy::MethodPath),
+        );
+        let inventory = serde_json::to_value(router.operation_inventory().unwrap()).unwrap();
+        assert_eq!(
+            inventory,
+            serde_json::json!([
+                {
+                    "operationId": "post_api_users",
+                    "method": "POST",
+                    "path": "/api/users",
+                    "route": "/api/users"
+                },
+                {
+                    "operationId": "get_user",
+                    "method": "GET",
+                    "path": "/api/users/{id}",
+                    "route": "/api/users/:id"
+                }
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn matched_path() {
+        let request = |method: &str, path: &str| {
+            Request::builder()
+                .method(method)
+                .uri(path)
+                .body(Body::empty())
+                .unwrap()
+        };
+        assert_eq!(
+            operation_id(request("GET", "/api/users/1"))
+                .await
+                .as_deref(),
+            Some("get_user")
+        );
+        assert_eq!(
+            operation_id(request("HEAD", "/api/users/1"))
+                .await
+                .as_deref(),
+            Some("get_user")
+        );
+        assert_eq!(
+            operation_id(request("POST", "/api/users")).await.as_deref(),
+            Some("post_api_users")
+        );
+        assert_eq!(operation_id(request("GET", "/missing")).await, None);
+    }
+}
+
+#[cfg(feature = "tracing")]
+mod tracing {
+    use axum::{body::Body, extract::Request, Extension};
+    use okapi_operation::{
+        axum_integration::{get, OperationInfo, OperationTraceLayer, Router},
+        oh, openapi,
+    };
+    use tower::ServiceExt;
+
+    #[openapi(operation_id = "get_user", tags = "users,admin")]
+    async fn get_user(Extension(info): Extension<OperationInfo>) -> String {
+        format!("{} {}", info.operation_id.unwrap(), info.tags.join(","))
+    }
+
+    #[tokio::test]
+    async fn operation_info_extension() {
+        let app = Router::new()
+            .route("/users/:id", get(oh!(get_user)))
+            .layer(OperationTraceLayer)
+            .finish_openapi("/openapi", "title", "version")
+            .expect("Schema generation shouldn't fail");
+        let response = app
+            .oneshot(Request::get("/users/1").body(Body::empty()).unwrap())
+            .await
+            .expect("Request shouldn't fail");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Body should be readable");
+        assert_eq!(body, "get_user users,admin");
+    }
+}
+
+#[cfg(feature = "mock")]
+mod mock {
+    use axum::{body::Body, extract::Request, http::StatusCode, Json};
+    use okapi_operation::{
+        axum_integration::{get, post, Router},
+        mock::mock_router,
+        oh, openapi, JsonSchema,
+    };
+    use serde::Serialize;
+    use tower::ServiceExt;
+
+    #[derive(Serialize, JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct User {
+        id: u64,
+        name: String,
+        active: bool,
+    }
+
+    #[openapi]
+    async fn get_user() -> Json<User> {
+        unimplemented!()
+    }
+
+    #[openapi(responses(
+        ignore_return_type = true,
+        response(status = "201", description = "Created", content = "Json<User>")
+    ))]
+    async fn create_user() {
+        unimplemented!()
+    }
+
+    #[tokio::test]
+    async fn serve_synthesized_responses() {
+        let spec = Router::<()>::new()
+            .route("/users/:id", get(oh!(get_user)))
+            .route("/users", post(oh!(create_user)))
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        let app: axum::Router = mock_router(&spec);
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/users/1").body(Body::empty()).unwrap())
+            .await
+            .expect("Request shouldn't fail");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Body should be readable");
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            serde_json::json!({ "id": 0, "name": "string", "active": false })
+        );
+
+        let response = app
+            .oneshot(Request::post("/users").body(Body::empty()).unwrap())
+            .await
+            .expect("Request shouldn't fail");
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+}
+
+#[cfg(all(feature = "axum-msgpack", feature = "axum-streams"))]
+mod ecosystem {
+    use axum_msgpack::MsgPack;
+    use axum_streams::StreamBodyAs;
+    use okapi_operation::{
+        axum_integration::{get, post, Router},
+        oh, openapi,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct User {
+        name: String,
+    }
+
+    #[openapi]
+    async fn msgpack(user: MsgPack<User>) -> MsgPack<User> {
+        user
+    }
+
+    #[openapi]
+    async fn stream() -> StreamBodyAs<'static> {
+        unimplemented!()
+    }
+
+    #[test]
+    fn media_types() {
+        let spec = Router::<()>::new()
+            .route("/msgpack", post(oh!(msgpack)))
+            .route("/stream", get(oh!(stream)))
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        let operation = spec.paths["/msgpack"].post.as_ref().unwrap();
+        let okapi_operation::okapi::openapi3::RefOr::Object(body) = operation
+            .request_body
+            .as_ref()
+            .expect("Body should be detected")
+        else {
+            panic!("Body should be RefOr::Object");
+        };
+        assert!(body.content.contains_key("application/msgpack"));
+        let responses = &spec.paths["/stream"].get.as_ref().unwrap().responses;
+        assert!(responses.responses.contains_key("200"));
+    }
+}
+
+#[cfg(all(feature = "axum", feature = "headers"))]
+mod typed_headers {
+    use axum_extra::{
+        headers::{self, authorization::Bearer, Authorization, Header, HeaderName, UserAgent},
+        TypedHeader,
+    };
+    use okapi_operation::{
+        axum_integration::{get, Router},
+        oh,
+        okapi::openapi3::RefOr,
+        openapi,
+    };
+
+    /// Header without `ToHeaderSchema` implementation.
+    struct XCustom;
+
+    static X_CUSTOM: HeaderName = HeaderName::from_static("x-custom");
+
+    impl Header for XCustom {
+        fn name() -> &'static HeaderName {
+            &X_CUSTOM
+        }
+
+        fn decode<'i, I>(_: &mut I) -> Result<Self, headers::Error>
+        where
+            I: Iterator<Item = &'i http::HeaderValue>,
+        {
+            Ok(Self)
+        }
+
+        fn encode<E: Extend<http::HeaderValue>>(&self, _: &mut E) {}
+    }
+
+    #[openapi]
+    async fn handle(
+        TypedHeader(_auth): TypedHeader<Authorization<Bearer>>,
+        _user_agent: Option<TypedHeader<UserAgent>>,
+        TypedHeader(_custom): TypedHeader<XCustom>,
+    ) {
+    }
+
+    #[test]
+    fn detected() {
+        let spec = Router::<()>::new()
+            .route("/", get(oh!(handle)))
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        let operation = spec.paths["/"].get.as_ref().unwrap();
+        let parameters: Vec<_> = operation
+            .parameters
+            .iter()
+            .map(|x| {
+                let RefOr::Object(parameter) = x else {
+                    panic!("Parameter should be RefOr::Object");
+                };
+                (parameter.name.as_str(), parameter.required)
+            })
+            .collect();
+        assert_eq!(parameters, [("user-agent", false)]);
+        assert_eq!(
+            operation.security,
+            Some(vec![
+                okapi_operation::okapi::map! { "bearerAuth".into() => vec![] }
+            ])
+        );
+        let components = spec.components.as_ref().unwrap();
+        assert!(components.security_schemes.contains_key("bearerAuth"));
+    }
+}
+
+#[cfg(feature = "validation")]
+mod validation {
+    use axum::{body::Body, http::Request, Json};
+    use http::{header::CONTENT_TYPE, StatusCode};
+    use okapi_operation::{
+        axum_integration::{get, post, Router},
+        oh, openapi,
+        validation::ValidationLayer,
+    };
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    #[derive(Deserialize, okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    #[allow(dead_code)]
+    struct NewUser {
+        #[schemars(length(min = 1))]
+        name: String,
+        age: u8,
+    }
+
+    #[openapi(parameters(
+        path(name = "id", schema = "u64"),
+        query(name = "verbose", schema = "bool"),
+        header(name = "x-request-id", required = true, schema = "String"),
+    ))]
+    async fn get_user() -> &'static str {
+        "user"
+    }
+
+    #[openapi]
+    async fn create_user(#[body(required = true)] Json(_): Json<NewUser>) {}
+
+    fn app() -> axum::Router {
+        let router = Router::new()
+            .route("/users/:id", get(oh!(get_user)))
+            .route("/users", post(oh!(create_user)));
+        let spec = router
+            .generate_openapi_builder()
+            .build()
+            .expect("Schema generation shouldn't fail");
+        router.layer(ValidationLayer::new(&spec)).axum_router()
+    }
+
+    async fn send(request: Request<Body>) -> (StatusCode, Value) {
+        let response = app()
+            .oneshot(request)
+            .await
+            .expect("Request shouldn't fail");
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Body should be readable");
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn parameters() {
+        let request = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .header("x-request-id", "1")
+                .body(Body::empty())
+                .expect("Request should be valid")
+        };
+        assert_eq!(
+            send(request("/users/1?verbose=true")).await.0,
+            StatusCode::OK
+        );
+        assert_eq!(
+            send(request("/users/abc?verbose=1")).await,
+            (
+                StatusCode::BAD_REQUEST,
+                json!({"errors": [
+                    {"location": "path", "name": "id", "pointer": "", "message": "Expected integer, found string"},
+                    {"location": "query", "name": "verbose", "pointer": "", "message": "Expected boolean, found string"},
+                ]})
+            )
+        );
+
+        let request = Request::builder()
+            .uri("/users/1")
+            .body(Body::empty())
+            .expect("Request should be valid");
+        assert_eq!(
+            send(request).await,
+            (
+                StatusCode::BAD_REQUEST,
+                json!({"errors": [
+                    {"location": "header", "name": "x-request-id", "pointer": "", "message": "Missing required parameter"},
+                ]})
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn body() {
+        let request = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/users")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .expect("Request should be valid")
+        };
+        assert_eq!(
+            send(request(r#"{"name": "user", "age": 20}"#)).await.0,
+            StatusCode::OK
+        );
+        assert_eq!(
+            send(request(r#"{"name": "", "age": 300}"#)).await,
+            (
+                StatusCode::BAD_REQUEST,
+                json!({"errors": [
+                    {"location": "body", "pointer": "/age", "message": "Value is out of range of format 'uint8'"},
+                    {"location": "body", "pointer": "/name", "message": "String is shorter than 1 characters"},
+                ]})
+            )
+        );
+        assert_eq!(
+            send(request("")).await.1["errors"][0]["message"],
+            "Request body is required"
+        );
+    }
+
+    mod responses {
+        use std::sync::{Arc, Mutex};
+
+        use axum::{
+            body::Body,
+            extract::Path,
+            http::Request,
+            response::{IntoResponse, Response},
+            Json,
+        };
+        use http::StatusCode;
+        use okapi_operation::{
+            axum_integration::{get, Router},
+            oh, openapi,
+            validation::{Location, ResponseMismatch, ResponseValidationLayer},
+        };
+        use serde_json::json;
+        use tower::ServiceExt;
+
+        #[derive(okapi_operation::JsonSchema)]
+        #[schemars(crate = "okapi_operation::schemars")]
+        #[allow(dead_code)]
+        struct User {
+            id: u64,
+        }
+
+        #[openapi(responses(
+            ignore_return_type = true,
+            response(status = "200", description = "", content = "Json<User>")
+        ))]
+        async fn get_user(Path(id): Path<u64>) -> Response {
+            match id {
+                1 => Json(json!({"id": 1})).into_response(),
+                2 => Json(json!({"id": "2"})).into_response(),
+                _ => StatusCode::NOT_FOUND.into_response(),
+            }
+        }
+
+        async fn send(uri: &str, on_mismatch: Option<Arc<Mutex<Vec<ResponseMismatch>>>>) {
+            let router = Router::new().route("/users/:id", get(oh!(get_user)));
+            let spec = router
+                .generate_openapi_builder()
+                .build()
+                .expect("Schema generation shouldn't fail");
+            let mut layer = ResponseValidationLayer::new(&spec);
+            if let Some(mismatches) = on_mismatch {
+                layer = layer.on_mismatch(move |x| mismatches.lock().unwrap().push(x));
+            }
+            let _ = router
+                .layer(layer)
+                .axum_router()
+                .oneshot(
+                    Request::builder()
+                        .uri(uri)
+                        .body(Body::empty())
+                        .expect("Request should be valid"),
+                )
+                .await
+                .expect("Request shouldn't fail");
+        }
+
+        #[tokio::test]
+        async fn mismatches() {
+            let mismatches = Arc::new(Mutex::new(Vec::new()));
+            for uri in ["/users/1", "/users/2", "/users/3"] {
+                send(uri, Some(mismatches.clone())).await;
+            }
+            let mismatches = mismatches.lock().unwrap();
+            assert_eq!(mismatches.len(), 2);
+            assert_eq!(mismatches[0].path, "/users/2");
+            assert_eq!(mismatches[0].errors[0].location, Location::Body);
+            assert_eq!(mismatches[0].errors[0].pointer, "/id");
+            assert_eq!(mismatches[1].errors[0].location, Location::Status);
+            assert_eq!(
+                mismatches[1].to_string(),
+                "Response 404 Not Found to GET /users/3 doesn't match specification: \
+                 Status: Status 404 is not documented"
+            );
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "doesn't match specification")]
+        async fn panic_on_mismatch() {
+            send("/users/2", None).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_routes_are_passed() {
+        let request = Request::builder()
+            .uri("/unknown")
+            .body(Body::empty())
+            .expect("Request should be valid");
+        assert_eq!(send(request).await.0, StatusCode::NOT_FOUND);
+    }
+}
+
+#[cfg(feature = "testing")]
+mod testing {
+    use http::{header::CONTENT_TYPE, Method, Request, Response};
+    use okapi_operation::{
+        axum_integration::{get, post, Router},
+        oh, openapi,
+        testing::{
+            assert_operation_documented, assert_request_matches_spec, assert_response_matches_spec,
+        },
+    };
+
+    #[openapi(parameters(query(name = "verbose", required = true, schema = "bool")))]
+    async fn get_user() -> String {
+        "user".into()
+    }
+
+    #[openapi]
+    async fn create_user() {}
+
+    fn router() -> Router {
+        Router::new()
+            .route("/users/:id", get(oh!(get_user)))
+            .route("/users", post(oh!(create_user)))
+    }
+
+    fn text_response(body: &'static str) -> Response<&'static str> {
+        Response::builder()
+            .header(CONTENT_TYPE, "text/plain")
+            .body(body)
+            .expect("Response should be valid")
+    }
+
+    #[test]
+    fn assertions() {
+        let router = router();
+        assert_operation_documented(&router, "/users/{id}", Method::GET);
+        assert_operation_documented(&router, "/users/:id", Method::GET);
+        assert_operation_documented(router.generate_openapi_builder(), "/users", Method::POST);
+
+        let request = Request::get("/users/1?verbose=true")
+            .body("")
+            .expect("Request should be valid");
+        assert_request_matches_spec(&router, &request);
+        assert_response_matches_spec(&router, Method::GET, "/users/1", &text_response("user"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "DELETE /users/{id} is not documented, documented operations: \
+                    [POST /users, GET /users/{id}]"
+    )]
+    fn undocumented_operation() {
+        assert_operation_documented(router(), "/users/{id}", Method::DELETE);
+    }
+
+    #[test]
+    #[should_panic(expected = "Query 'verbose': Missing required parameter")]
+    fn invalid_request() {
+        let request = Request::get("/users/1")
+            .body("")
+            .expect("Request should be valid");
+        assert_request_matches_spec(router(), &request);
+    }
+
+    #[test]
+    #[should_panic(expected = "Body: Missing response body")]
+    fn invalid_response() {
+        assert_response_matches_spec(router(), Method::GET, "/users/1", &text_response(""));
+    }
+}
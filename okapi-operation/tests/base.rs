@@ -1,5 +1,9 @@
 use okapi_operation::openapi;
 
+struct Json<T>(T);
+okapi_operation::impl_to_media_types_for_wrapper!(Json<T>, "application/json");
+okapi_operation::impl_to_responses_for_wrapper!(Json<T>);
+
 #[test]
 #[allow(unused)]
 fn crate_name_override() {
@@ -8,3 +12,2006 @@ fn crate_name_override() {
     #[openapi(crate = "renamed_crate")]
     async fn handle() {}
 }
+
+#[test]
+#[allow(dead_code)]
+fn sync_handler_with_return_type() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder};
+
+    #[openapi(responses(return_type = "String"))]
+    fn handle() -> impl std::fmt::Display {
+        ""
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let responses = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present")
+        .responses;
+    let RefOr::Object(response) = &responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert!(response.content.contains_key("text/plain"));
+}
+
+#[test]
+#[allow(dead_code)]
+fn responses_merge_strategy() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder};
+
+    #[openapi(responses(response(status = "200", description = "", content = "Vec<u8>")))]
+    async fn merged() -> String {
+        unimplemented!()
+    }
+
+    #[openapi(responses(
+        merge_strategy = "error",
+        response(status = "200", description = "", content = "Vec<u8>")
+    ))]
+    async fn failed() -> String {
+        unimplemented!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::GET, merged__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let responses = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present")
+        .responses;
+    let RefOr::Object(response) = &responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert!(response.content.contains_key("text/plain"));
+    assert!(response.content.contains_key("application/octet-stream"));
+
+    assert!(OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::GET, failed__openapi)
+        .build()
+        .is_err());
+}
+
+#[test]
+#[allow(dead_code)]
+fn optional_responses() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder, OrStatus};
+
+    #[openapi]
+    async fn find() -> Option<String> {
+        unimplemented!()
+    }
+
+    #[openapi]
+    async fn find_or_empty() -> OrStatus<String, 204> {
+        unimplemented!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/find", http::Method::GET, find__openapi)
+        .operation("/find_or_empty", http::Method::GET, find_or_empty__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let status_codes = |path: &str| {
+        spec.paths[path]
+            .get
+            .as_ref()
+            .expect("GET should be present")
+            .responses
+            .responses
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(status_codes("/find"), ["200", "404"]);
+    assert_eq!(status_codes("/find_or_empty"), ["200", "204"]);
+    let RefOr::Object(response) = &spec.paths["/find"]
+        .get
+        .as_ref()
+        .unwrap()
+        .responses
+        .responses["404"]
+    else {
+        panic!("404 response should be RefOr::Object");
+    };
+    assert_eq!(response.description, "Not Found");
+    assert!(response.content.is_empty());
+}
+
+#[test]
+#[allow(dead_code)]
+fn schema_json_literals() {
+    use okapi_operation::{
+        okapi::openapi3::{ParameterValue, RefOr},
+        OpenApiBuilder,
+    };
+
+    #[openapi(
+        parameters(
+            query(name = "id", schema_json = r#"{"type": "string", "format": "uuid"}"#),
+            header(name = "X-Trace", schema_json = r#"{"type": "string"}"#)
+        ),
+        responses(
+            ignore_return_type = true,
+            response(
+                status = "200",
+                description = "",
+                schema_json = r#"{"type": "object", "additionalProperties": true}"#
+            )
+        )
+    )]
+    async fn handle() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present");
+    let schemas: Vec<_> = operation
+        .parameters
+        .iter()
+        .map(|x| {
+            let RefOr::Object(parameter) = x else {
+                panic!("Parameter should be RefOr::Object");
+            };
+            let ParameterValue::Schema { schema, .. } = &parameter.value else {
+                panic!("Parameter should have schema");
+            };
+            serde_json::to_value(schema).unwrap()
+        })
+        .collect();
+    assert_eq!(
+        schemas,
+        [
+            serde_json::json!({"type": "string"}),
+            serde_json::json!({"type": "string", "format": "uuid"}),
+        ]
+    );
+    let RefOr::Object(response) = &operation.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert_eq!(
+        serde_json::to_value(&response.content["application/json"].schema).unwrap(),
+        serde_json::json!({"type": "object", "additionalProperties": true})
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn enum_values_parameters() {
+    use okapi_operation::{
+        okapi::openapi3::{ParameterValue, RefOr},
+        OpenApiBuilder,
+    };
+
+    #[openapi(parameters(
+        query(name = "order", enum_values = "asc, desc"),
+        header(name = "X-Mode", enum_values = "fast,slow")
+    ))]
+    async fn handle() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present");
+    let schemas: Vec<_> = operation
+        .parameters
+        .iter()
+        .map(|x| {
+            let RefOr::Object(parameter) = x else {
+                panic!("Parameter should be RefOr::Object");
+            };
+            let ParameterValue::Schema { schema, .. } = &parameter.value else {
+                panic!("Parameter should have schema");
+            };
+            serde_json::to_value(schema).unwrap()
+        })
+        .collect();
+    assert_eq!(
+        schemas,
+        [
+            serde_json::json!({"type": "string", "enum": ["fast", "slow"]}),
+            serde_json::json!({"type": "string", "enum": ["asc", "desc"]}),
+        ]
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn parameter_constraints() {
+    use okapi_operation::{
+        okapi::openapi3::{ParameterValue, RefOr},
+        OpenApiBuilder,
+    };
+
+    #[openapi(parameters(
+        query(
+            name = "limit",
+            schema = "u32",
+            default = "10",
+            minimum = 1,
+            maximum = 100
+        ),
+        path(name = "slug", schema = "std::string::String", pattern = "^[a-z]+$"),
+        query(name = "order", enum_values = "asc,desc", default = "asc")
+    ))]
+    async fn handle() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/{slug}", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/{slug}"]
+        .get
+        .clone()
+        .expect("GET /{slug} should be present");
+    let schemas: Vec<_> = operation
+        .parameters
+        .iter()
+        .map(|x| {
+            let RefOr::Object(parameter) = x else {
+                panic!("Parameter should be RefOr::Object");
+            };
+            let ParameterValue::Schema { schema, .. } = &parameter.value else {
+                panic!("Parameter should have schema");
+            };
+            (
+                parameter.name.as_str(),
+                serde_json::to_value(schema).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        schemas,
+        [
+            (
+                "slug",
+                serde_json::json!({"type": "string", "pattern": "^[a-z]+$"})
+            ),
+            (
+                "limit",
+                serde_json::json!({
+                    "type": "integer",
+                    "format": "uint32",
+                    "minimum": 1.0,
+                    "maximum": 100.0,
+                    "default": 10
+                })
+            ),
+            (
+                "order",
+                serde_json::json!({"type": "string", "enum": ["asc", "desc"], "default": "asc"})
+            ),
+        ]
+    );
+}
+
+#[test]
+fn wrapped_responses() {
+    use std::{borrow::Cow, sync::Arc};
+
+    use okapi_operation::{
+        okapi::openapi3::{MediaType, Responses},
+        ComponentsBuilder, ToMediaTypes, ToResponses,
+    };
+
+    fn responses<T: ToResponses + ?Sized>() -> Responses {
+        T::generate(&mut ComponentsBuilder::default().build())
+            .expect("Responses should be generated")
+    }
+
+    let expected = responses::<String>();
+    assert_eq!(responses::<Box<String>>(), expected);
+    assert_eq!(responses::<Box<str>>(), expected);
+    assert_eq!(responses::<Arc<str>>(), expected);
+    assert_eq!(responses::<&'static str>(), expected);
+    assert_eq!(responses::<Cow<'static, str>>(), expected);
+    assert_eq!(responses::<Arc<Vec<u8>>>(), responses::<Vec<u8>>());
+
+    fn media_types<T: ToMediaTypes + ?Sized>() -> okapi_operation::okapi::Map<String, MediaType> {
+        T::generate(&mut ComponentsBuilder::default().build())
+            .expect("Media types should be generated")
+    }
+
+    assert_eq!(media_types::<Box<[u8]>>(), media_types::<Vec<u8>>());
+}
+
+#[test]
+#[allow(dead_code)]
+fn api_component() {
+    use okapi_operation::{
+        okapi::openapi3::{RefOr, SchemaObject},
+        ApiComponent, Components, OpenApiBuilder,
+    };
+
+    struct Created;
+
+    impl ApiComponent for Created {
+        const MEDIA_TYPE: &'static str = "text/plain";
+        const STATUS: u16 = 201;
+        const DESCRIPTION: &'static str = "Created";
+
+        fn schema(components: &mut Components) -> Option<SchemaObject> {
+            Some(components.schema_for::<String>())
+        }
+    }
+
+    okapi_operation::impl_api_component!(Created);
+
+    #[openapi]
+    async fn create() -> Created {
+        unimplemented!()
+    }
+
+    #[openapi]
+    async fn create_boxed() -> Box<Created> {
+        unimplemented!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::POST, create__openapi)
+        .operation("/boxed", http::Method::POST, create_boxed__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .post
+        .as_ref()
+        .expect("POST / should be present");
+    let RefOr::Object(response) = &operation.responses.responses["201"] else {
+        panic!("201 response should be RefOr::Object");
+    };
+    assert_eq!(response.description, "Created");
+    assert!(response.content["text/plain"].schema.is_some());
+    assert_eq!(
+        spec.paths["/boxed"].post.as_ref().map(|x| &x.responses),
+        Some(&operation.responses)
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn typed_headers() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder};
+
+    // Implemented in separate module, so trait is not in scope of macro
+    mod headers {
+        use okapi_operation::{okapi::openapi3::SchemaObject, Components, Error, ToHeaderSchema};
+
+        pub struct RateLimitRemaining;
+
+        impl ToHeaderSchema for RateLimitRemaining {
+            fn header_name() -> String {
+                "X-RateLimit-Remaining".into()
+            }
+
+            fn generate(components: &mut Components) -> Result<SchemaObject, Error> {
+                Ok(components.schema_for::<u32>())
+            }
+        }
+    }
+    use headers::RateLimitRemaining;
+
+    #[openapi(
+        parameters(header(typed = "RateLimitRemaining")),
+        responses(response(
+            status = "200",
+            description = "",
+            content = "String",
+            headers(header(typed = "RateLimitRemaining"))
+        ))
+    )]
+    async fn handle() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present");
+    let RefOr::Object(parameter) = &operation.parameters[0] else {
+        panic!("Parameter should be RefOr::Object");
+    };
+    assert_eq!(parameter.name, "X-RateLimit-Remaining");
+    let RefOr::Object(response) = &operation.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert!(response.headers.contains_key("X-RateLimit-Remaining"));
+}
+
+#[test]
+#[allow(dead_code)]
+fn reusable_components() {
+    use okapi_operation::{
+        okapi::openapi3::{RefOr, Response},
+        OpenApiBuilder,
+    };
+
+    #[openapi(responses(reference(
+        status = "401",
+        reference = "#/components/responses/AuthError"
+    )))]
+    async fn handle() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .response(
+            "AuthError",
+            Response {
+                description: "Authorization error".into(),
+                ..Default::default()
+            },
+        )
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let components = spec.components.expect("Components should be present");
+    assert!(matches!(
+        components.responses.get("AuthError"),
+        Some(RefOr::Object(x)) if x.description == "Authorization error"
+    ));
+}
+
+#[test]
+#[allow(dead_code)]
+fn negotiated_response() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder};
+
+    struct Csv<T>(T);
+    okapi_operation::impl_to_media_types_for_wrapper!(Csv<T>, "text/csv");
+
+    #[openapi(responses(
+        ignore_return_type = true,
+        negotiated(description = "Report", content("Json<String>", "Csv<String>"))
+    ))]
+    async fn handle() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present");
+    let RefOr::Object(response) = &operation.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert_eq!(response.description, "Report");
+    assert_eq!(
+        response.content.keys().collect::<Vec<_>>(),
+        ["application/json", "text/csv"]
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn schema_name_strategy() {
+    use okapi_operation::{
+        okapi::openapi3::{OpenApi, RefOr},
+        ComponentsBuilder, OpenApiBuilder, SchemaNameStrategy,
+    };
+
+    mod users {
+        #[derive(okapi_operation::JsonSchema)]
+        #[schemars(crate = "okapi_operation::schemars")]
+        pub struct Request {
+            pub name: String,
+        }
+    }
+
+    mod orders {
+        #[derive(okapi_operation::JsonSchema)]
+        #[schemars(crate = "okapi_operation::schemars")]
+        pub struct Request {
+            pub amount: u64,
+        }
+    }
+
+    #[openapi]
+    async fn create_user(#[body] _body: Json<users::Request>) {}
+
+    #[openapi]
+    async fn create_order(#[body] _body: Json<orders::Request>) {}
+
+    fn build(strategy: SchemaNameStrategy) -> Result<OpenApi, okapi_operation::Error> {
+        OpenApiBuilder::new("title", "version")
+            .set_components(ComponentsBuilder::default().name_strategy(strategy).build())
+            .operation("/users", http::Method::POST, create_user__openapi)
+            .operation("/orders", http::Method::POST, create_order__openapi)
+            .build()
+    }
+
+    fn body_reference(spec: &OpenApi, path: &str) -> String {
+        let Some(RefOr::Object(body)) = spec.paths[path]
+            .post
+            .as_ref()
+            .and_then(|x| x.request_body.as_ref())
+        else {
+            panic!("Request body should be RefOr::Object");
+        };
+        body.content["application/json"]
+            .schema
+            .as_ref()
+            .and_then(|x| x.reference.clone())
+            .expect("Schema should be reference")
+    }
+
+    let spec = build(SchemaNameStrategy::Numbered).expect("Schema generation shouldn't fail");
+    assert_eq!(
+        body_reference(&spec, "/orders"),
+        "#/components/schemas/Request"
+    );
+    assert_eq!(
+        body_reference(&spec, "/users"),
+        "#/components/schemas/Request2"
+    );
+
+    assert!(matches!(
+        build(SchemaNameStrategy::Error),
+        Err(okapi_operation::Error::SchemaConflict { name }) if name == "Request"
+    ));
+
+    let spec =
+        build(SchemaNameStrategy::ModulePathSuffix).expect("Schema generation shouldn't fail");
+    let module_path = module_path!().replace("::", "_");
+    let users_name = format!("Request_{module_path}_users");
+    assert_eq!(
+        body_reference(&spec, "/users"),
+        format!("#/components/schemas/{users_name}")
+    );
+    let schemas = spec
+        .components
+        .expect("Components should be present")
+        .schemas;
+    assert!(schemas.contains_key(&users_name));
+    assert!(schemas.contains_key(&format!("Request_{module_path}_orders")));
+
+    let spec = build(SchemaNameStrategy::FullPath).expect("Schema generation shouldn't fail");
+    assert_eq!(
+        body_reference(&spec, "/users"),
+        format!(
+            "#/components/schemas/{}.users.Request",
+            module_path!().replace("::", ".")
+        )
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn rename_schemas() {
+    use okapi_operation::{okapi::openapi3::RefOr, ComponentsBuilder, OpenApiBuilder};
+
+    #[derive(okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Item {
+        name: String,
+    }
+
+    #[derive(okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Request {
+        items: Vec<Item>,
+    }
+
+    #[openapi]
+    async fn handle(#[body] _body: Json<Request>) {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .set_components(
+            ComponentsBuilder::default()
+                .rename_schemas(|name, _| format!("Api{name}"))
+                .build(),
+        )
+        .operation("/", http::Method::POST, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let Some(RefOr::Object(body)) = spec.paths["/"]
+        .post
+        .as_ref()
+        .and_then(|x| x.request_body.as_ref())
+    else {
+        panic!("Request body should be RefOr::Object");
+    };
+    assert_eq!(
+        body.content["application/json"]
+            .schema
+            .as_ref()
+            .and_then(|x| x.reference.as_deref()),
+        Some("#/components/schemas/ApiRequest")
+    );
+    let schemas = spec
+        .components
+        .expect("Components should be present")
+        .schemas;
+    assert_eq!(
+        schemas.keys().collect::<Vec<_>>(),
+        vec!["ApiRequest", "ApiItem"]
+    );
+    let items = &schemas["ApiRequest"]
+        .object
+        .as_ref()
+        .expect("Request should be object")
+        .properties["items"];
+    assert_eq!(
+        items
+            .clone()
+            .into_object()
+            .array
+            .and_then(|x| x.items)
+            .map(|x| match x {
+                okapi_operation::schemars::schema::SingleOrVec::Single(x) => x.into_object(),
+                _ => panic!("Items should be single schema"),
+            })
+            .and_then(|x| x.reference),
+        Some("#/components/schemas/ApiItem".into())
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn schema_visitors() {
+    use okapi_operation::{
+        okapi::{
+            openapi3::{ParameterValue, RefOr},
+            schemars::{
+                schema::SchemaObject,
+                visit::{visit_schema_object, Visitor},
+            },
+        },
+        ComponentsBuilder, OpenApiBuilder,
+    };
+
+    #[derive(Debug, Clone)]
+    struct StripFormat;
+
+    impl Visitor for StripFormat {
+        fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+            schema.format = None;
+            visit_schema_object(self, schema)
+        }
+    }
+
+    #[derive(okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Counter {
+        value: u32,
+    }
+
+    #[openapi(parameters(query(name = "limit", schema = "u32")))]
+    async fn handle() -> Json<Counter> {
+        unreachable!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .set_components(
+            ComponentsBuilder::default()
+                .with_schema_visitor(StripFormat)
+                .build(),
+        )
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present");
+    let RefOr::Object(parameter) = &operation.parameters[0] else {
+        panic!("Parameter should be RefOr::Object");
+    };
+    let ParameterValue::Schema { schema, .. } = &parameter.value else {
+        panic!("Parameter should have schema");
+    };
+    assert_eq!(schema.format, None);
+    let counter = spec
+        .components
+        .expect("Components should be present")
+        .schemas["Counter"]
+        .clone();
+    assert_eq!(
+        counter.object.expect("Counter should be object").properties["value"]
+            .clone()
+            .into_object()
+            .format,
+        None
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn schema_settings() {
+    use okapi_operation::{schemars::gen::SchemaSettings, ComponentsBuilder, OpenApiBuilder};
+
+    #[derive(okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Profile {
+        nickname: Option<String>,
+    }
+
+    #[openapi]
+    async fn handle() -> Json<Profile> {
+        unreachable!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .set_components(
+            ComponentsBuilder::default()
+                .schema_settings(SchemaSettings::openapi3().with(|settings| {
+                    settings.option_nullable = false;
+                    settings.option_add_null_type = true;
+                }))
+                .build(),
+        )
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let profile = spec
+        .components
+        .expect("Components should be present")
+        .schemas["Profile"]
+        .clone();
+    assert_eq!(
+        serde_json::to_value(
+            &profile.object.expect("Profile should be object").properties["nickname"]
+        )
+        .unwrap(),
+        serde_json::json!({"type": ["string", "null"]})
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn definitions_path() {
+    use okapi_operation::{
+        okapi::openapi3::{ParameterValue, RefOr},
+        ComponentsBuilder, OpenApiBuilder,
+    };
+
+    #[derive(okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Counter {
+        value: u32,
+    }
+
+    #[openapi(parameters(query(
+        name = "filter",
+        schema_json = r##"{"$ref": "#/components/schemas/Counter"}"##
+    )))]
+    async fn handle() -> Json<Counter> {
+        unreachable!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .set_components(
+            ComponentsBuilder::default()
+                .definitions_path("schemas.yaml#/")
+                .build(),
+        )
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present");
+    let RefOr::Object(parameter) = &operation.parameters[0] else {
+        panic!("Parameter should be RefOr::Object");
+    };
+    let ParameterValue::Schema { schema, .. } = &parameter.value else {
+        panic!("Parameter should have schema");
+    };
+    assert_eq!(schema.reference.as_deref(), Some("schemas.yaml#/Counter"));
+    let RefOr::Object(response) = &operation.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert_eq!(
+        response.content["application/json"]
+            .schema
+            .as_ref()
+            .and_then(|x| x.reference.as_deref()),
+        Some("schemas.yaml#/Counter")
+    );
+    assert!(spec
+        .components
+        .expect("Components should be present")
+        .schemas
+        .contains_key("Counter"));
+}
+
+#[test]
+fn merge_spec() {
+    use okapi_operation::{
+        okapi::{
+            map,
+            openapi3::{Components, OpenApi, Operation, PathItem, SchemaObject, Tag},
+        },
+        MergeStrategy, OpenApiBuilder,
+    };
+
+    let fragment = |summary: &str| OpenApi {
+        paths: map! {
+            "/users".into() => PathItem {
+                get: Some(Operation {
+                    summary: Some(summary.into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        },
+        components: Some(Components {
+            schemas: map! {
+                "User".into() => SchemaObject::default()
+            },
+            ..Default::default()
+        }),
+        tags: vec![Tag {
+            name: "users".into(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .operation("/orders", http::Method::GET, |_| Ok(Operation::default()))
+        .merge_spec(fragment("List users"), MergeStrategy::Merge)
+        .expect("Merge shouldn't fail")
+        .merge_spec(fragment("List users"), MergeStrategy::Error)
+        .expect("Merging identical specification shouldn't fail");
+    let summary = |builder: &mut OpenApiBuilder| {
+        builder
+            .build()
+            .expect("Schema generation shouldn't fail")
+            .paths["/users"]
+            .get
+            .as_ref()
+            .and_then(|x| x.summary.clone())
+    };
+    builder
+        .merge_spec(fragment("Get users"), MergeStrategy::Merge)
+        .expect("Merge shouldn't fail");
+    assert_eq!(summary(&mut builder).as_deref(), Some("List users"));
+
+    // Failed merge leaves builder unchanged
+    let mut conflicting = fragment("Get users");
+    let _ = conflicting
+        .paths
+        .insert("/teams".into(), PathItem::default());
+    conflicting.paths.sort_keys();
+    assert!(builder
+        .merge_spec(conflicting, MergeStrategy::Error)
+        .is_err());
+    assert!(!builder.spec_mut().paths.contains_key("/teams"));
+
+    builder
+        .merge_spec(fragment("Get users"), MergeStrategy::Replace)
+        .expect("Replace shouldn't fail");
+
+    let spec = builder.build().expect("Schema generation shouldn't fail");
+    assert_eq!(spec.paths.keys().collect::<Vec<_>>(), ["/orders", "/users"]);
+    assert_eq!(
+        spec.paths["/users"]
+            .get
+            .as_ref()
+            .and_then(|x| x.summary.as_deref()),
+        Some("Get users")
+    );
+    assert!(spec
+        .components
+        .expect("Components should be present")
+        .schemas
+        .contains_key("User"));
+    assert_eq!(spec.tags.len(), 1);
+}
+
+#[test]
+fn include_spec_fragment() {
+    use okapi_operation::OpenApiBuilder;
+
+    let path = std::env::temp_dir().join(format!(
+        "okapi-operation-fragment-{}.json",
+        std::process::id()
+    ));
+    std::fs::write(
+        &path,
+        r#"{
+            "paths": {
+                "/legacy": {
+                    "get": {
+                        "responses": {
+                            "200": {"description": "Legacy response"}
+                        }
+                    }
+                }
+            }
+        }"#,
+    )
+    .expect("Failed to write fragment");
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .include_json(&path)
+        .expect("Failed to include fragment")
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let _ = std::fs::remove_file(&path);
+    assert!(spec.paths["/legacy"].get.is_some());
+
+    assert!(OpenApiBuilder::new("title", "version")
+        .include_json(path.with_extension("missing"))
+        .is_err());
+}
+
+#[test]
+#[allow(dead_code)]
+fn flatten_and_externalize_refs() {
+    use okapi_operation::{
+        resolve::{externalize_refs, flatten_refs},
+        OpenApiBuilder,
+    };
+
+    #[derive(okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Item {
+        name: String,
+    }
+
+    #[derive(okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Node {
+        item: Item,
+        children: Vec<Node>,
+    }
+
+    #[openapi]
+    async fn handle(#[body] _body: Json<Item>) -> Json<Node> {
+        unreachable!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::POST, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+
+    let mut flattened = spec.clone();
+    flatten_refs(&mut flattened, "#/components/schemas/");
+    let flattened_json = serde_json::to_string(&flattened.paths).expect("Serialization failed");
+    assert!(!flattened_json.contains("#/components/schemas/Item"));
+    // Recursive schema can't be flattened completely
+    assert!(flattened_json.contains("#/components/schemas/Node"));
+
+    externalize_refs(&mut flattened, "#/components/schemas/");
+    assert_eq!(flattened, spec);
+}
+
+#[test]
+#[allow(dead_code)]
+fn infer_operation_id() {
+    use okapi_operation::{BuilderOptions, OpenApiBuilder, OperationIdStrategy};
+
+    #[openapi]
+    async fn list_users() {}
+
+    #[openapi(operation_id = "explicit")]
+    async fn explicit_id() {}
+
+    let operation_id = |options: BuilderOptions, path: &str| {
+        OpenApiBuilder::new("title", "version")
+            .set_options(options)
+            .operation("/users", http::Method::GET, list_users__openapi)
+            .operation("/explicit", http::Method::GET, explicit_id__openapi)
+            .build()
+            .expect("Schema generation shouldn't fail")
+            .paths[path]
+            .get
+            .clone()
+            .expect("GET should be present")
+            .operation_id
+    };
+
+    assert_eq!(operation_id(BuilderOptions::new(), "/users"), None);
+    let options = BuilderOptions::new().infer_operation_id(true);
+    assert_eq!(
+        operation_id(options.clone(), "/users").as_deref(),
+        Some("list_users")
+    );
+    assert_eq!(
+        operation_id(options.clone(), "/explicit").as_deref(),
+        Some("explicit")
+    );
+    assert_eq!(
+        operation_id(
+            options
+                .clone()
+                .operation_id_strategy(OperationIdStrategy::MethodPath),
+            "/users"
+        )
+        .as_deref(),
+        Some("get_users")
+    );
+    assert_eq!(
+        operation_id(
+            options.operation_id_strategy(OperationIdStrategy::custom(|path, method, name| {
+                Some(format!("{method}{path}:{}", name.unwrap_or_default()))
+            })),
+            "/users"
+        )
+        .as_deref(),
+        Some("GET/users:list_users")
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn duplicate_inferred_operation_id() {
+    use okapi_operation::{BuilderOptions, DuplicateOperationId, OpenApiBuilder};
+
+    #[openapi]
+    async fn list_users() {}
+
+    let build = |options: BuilderOptions| {
+        OpenApiBuilder::new("title", "version")
+            .set_options(options.infer_operation_id(true))
+            .operation("/users", http::Method::GET, list_users__openapi)
+            .operation("/v2/users", http::Method::GET, list_users__openapi)
+            .build()
+    };
+
+    assert!(matches!(
+        build(BuilderOptions::new()),
+        Err(okapi_operation::Error::DuplicateOperationId { operation_id, path, .. })
+            if operation_id == "list_users" && path == "/v2/users"
+    ));
+    let spec = build(BuilderOptions::new().duplicate_operation_id(DuplicateOperationId::Suffix))
+        .expect("Schema generation shouldn't fail");
+    let operation_id = |path: &str| {
+        spec.paths[path]
+            .get
+            .as_ref()
+            .and_then(|x| x.operation_id.clone())
+    };
+    assert_eq!(operation_id("/users").as_deref(), Some("list_users"));
+    assert_eq!(
+        operation_id("/v2/users").as_deref(),
+        Some("list_users_get_v2_users")
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn explicit_operation_id_reserved() {
+    use okapi_operation::{BuilderOptions, DuplicateOperationId, OpenApiBuilder};
+
+    #[openapi]
+    async fn list_users() {}
+
+    #[openapi(operation_id = "list_users")]
+    async fn list_users_v2() {}
+
+    let build = |options: BuilderOptions| {
+        OpenApiBuilder::new("title", "version")
+            .set_options(options.infer_operation_id(true))
+            .operation("/a/users", http::Method::GET, list_users__openapi)
+            .operation("/b/users", http::Method::GET, list_users_v2__openapi)
+            .build()
+    };
+
+    assert!(matches!(
+        build(BuilderOptions::new()),
+        Err(okapi_operation::Error::DuplicateOperationId { operation_id, path, .. })
+            if operation_id == "list_users" && path == "/a/users"
+    ));
+    let spec = build(BuilderOptions::new().duplicate_operation_id(DuplicateOperationId::Suffix))
+        .expect("Schema generation shouldn't fail");
+    let operation_id = |path: &str| {
+        spec.paths[path]
+            .get
+            .as_ref()
+            .and_then(|x| x.operation_id.clone())
+    };
+    assert_eq!(
+        operation_id("/a/users").as_deref(),
+        Some("list_users_get_a_users")
+    );
+    assert_eq!(operation_id("/b/users").as_deref(), Some("list_users"));
+}
+
+#[test]
+#[allow(dead_code)]
+fn content_type_override() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder};
+
+    #[openapi(responses(
+        ignore_return_type = true,
+        response(
+            status = "200",
+            description = "",
+            content = "String",
+            content_type = "text/csv"
+        )
+    ))]
+    async fn handle(#[body(content_type = "application/vnd.api+json")] _body: String) {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::POST, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .post
+        .clone()
+        .expect("POST / should be present");
+    let Some(RefOr::Object(request_body)) = &operation.request_body else {
+        panic!("Request body should be RefOr::Object");
+    };
+    assert_eq!(
+        request_body.content.keys().collect::<Vec<_>>(),
+        ["application/vnd.api+json"]
+    );
+    let RefOr::Object(response) = &operation.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert_eq!(response.content.keys().collect::<Vec<_>>(), ["text/csv"]);
+}
+
+#[test]
+#[allow(dead_code)]
+fn multiple_body_contents() {
+    use okapi_operation::{okapi::openapi3::RefOr, schemars::JsonSchema, OpenApiBuilder};
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Request {
+        data: String,
+    }
+
+    #[openapi(responses(ignore_return_type = true))]
+    async fn handle(
+        #[body(
+            content(type = "Json<Request>"),
+            content(type = "Json<Request>", mime = "application/x-www-form-urlencoded"),
+            content = "String"
+        )]
+        _body: Json<Request>,
+    ) {
+    }
+
+    #[openapi(responses(ignore_return_type = true))]
+    async fn duplicated(
+        #[body(content = "Json<Request>", content(type = "Json<String>"))] _body: Json<Request>,
+    ) {
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::POST, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let Some(RefOr::Object(request_body)) = &spec.paths["/"]
+        .post
+        .as_ref()
+        .expect("POST / should be present")
+        .request_body
+    else {
+        panic!("Request body should be RefOr::Object");
+    };
+    assert_eq!(
+        request_body.content.keys().collect::<Vec<_>>(),
+        [
+            "application/json",
+            "application/x-www-form-urlencoded",
+            "text/plain"
+        ]
+    );
+    assert_eq!(
+        request_body.content["application/json"],
+        request_body.content["application/x-www-form-urlencoded"]
+    );
+
+    assert!(OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::POST, duplicated__openapi)
+        .build()
+        .is_err());
+}
+
+#[test]
+#[allow(dead_code)]
+fn xml_object() {
+    use okapi_operation::{okapi::openapi3::RefOr, schemars::JsonSchema, OpenApiBuilder};
+    use serde_json::json;
+
+    struct Xml<T>(T);
+    okapi_operation::impl_to_media_types_for_wrapper!(Xml<T>, "application/xml");
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct User {
+        name: String,
+    }
+
+    #[openapi(responses(
+        ignore_return_type = true,
+        response(
+            status = "200",
+            description = "",
+            content = "Xml<Vec<User>>",
+            xml(name = "users", wrapped)
+        )
+    ))]
+    async fn handle(
+        #[body(
+            content(type = "Xml<User>"),
+            content(type = "Xml<User>", mime = "application/json"),
+            xml(name = "user", prefix = "u")
+        )]
+        _body: Xml<User>,
+    ) {
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::POST, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .post
+        .clone()
+        .expect("POST / should be present");
+    let Some(RefOr::Object(request_body)) = &operation.request_body else {
+        panic!("Request body should be RefOr::Object");
+    };
+    assert_eq!(
+        serde_json::to_value(&request_body.content["application/xml"].schema).unwrap(),
+        json!({
+            "allOf": [{ "$ref": "#/components/schemas/User" }],
+            "xml": { "name": "user", "prefix": "u" }
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(&request_body.content["application/json"].schema).unwrap(),
+        json!({ "$ref": "#/components/schemas/User" })
+    );
+    let RefOr::Object(response) = &operation.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert_eq!(
+        serde_json::to_value(&response.content["application/xml"].schema).unwrap(),
+        json!({
+            "type": "array",
+            "items": { "$ref": "#/components/schemas/User" },
+            "xml": { "name": "users", "wrapped": true }
+        })
+    );
+}
+
+#[cfg(feature = "problem-details")]
+#[test]
+#[allow(dead_code)]
+fn default_error_responses() {
+    use okapi_operation::{okapi::openapi3::RefOr, BuilderOptions, OpenApiBuilder, ProblemDetails};
+
+    #[openapi]
+    async fn undocumented() {}
+
+    #[openapi(responses(response(
+        status = "default",
+        description = "Custom",
+        content = "String"
+    )))]
+    async fn documented() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .set_options(BuilderOptions::new().default_error_responses::<ProblemDetails>())
+        .operation("/undocumented", http::Method::GET, undocumented__openapi)
+        .operation("/documented", http::Method::GET, documented__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+
+    let Some(RefOr::Object(response)) = spec.paths["/undocumented"]
+        .get
+        .clone()
+        .expect("GET /undocumented should be present")
+        .responses
+        .default
+    else {
+        panic!("Default response should be RefOr::Object");
+    };
+    assert_eq!(
+        serde_json::to_value(&response.content["application/problem+json"].schema).unwrap(),
+        serde_json::json!({ "$ref": "#/components/schemas/ProblemDetails" })
+    );
+    assert!(spec
+        .components
+        .expect("Components should be present")
+        .schemas
+        .contains_key("ProblemDetails"));
+
+    let Some(RefOr::Object(response)) = spec.paths["/documented"]
+        .get
+        .clone()
+        .expect("GET /documented should be present")
+        .responses
+        .default
+    else {
+        panic!("Default response should be RefOr::Object");
+    };
+    assert_eq!(response.description, "Custom");
+}
+
+#[test]
+#[allow(dead_code)]
+fn pagination() {
+    use okapi_operation::{
+        okapi::openapi3::{ParameterValue, RefOr},
+        schemars::JsonSchema,
+        OpenApiBuilder, Paginated,
+    };
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct User {
+        name: String,
+    }
+
+    #[openapi(parameters(pagination(page_size = "size", max_page_size = 100)))]
+    async fn handle() -> Paginated<User> {
+        unimplemented!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present");
+
+    let parameters: Vec<_> = operation
+        .parameters
+        .iter()
+        .map(|x| match x {
+            RefOr::Object(x) => x,
+            RefOr::Ref(_) => panic!("Parameter should be RefOr::Object"),
+        })
+        .collect();
+    assert_eq!(
+        parameters
+            .iter()
+            .map(|x| (x.name.as_str(), x.location.as_str()))
+            .collect::<Vec<_>>(),
+        [("page", "query"), ("size", "query")]
+    );
+    let ParameterValue::Schema { schema, .. } = &parameters[1].value else {
+        panic!("Parameter should have schema");
+    };
+    let number = schema
+        .number
+        .as_ref()
+        .expect("Number validation should be set");
+    assert_eq!((number.minimum, number.maximum), (Some(1.0), Some(100.0)));
+
+    let RefOr::Object(response) = &operation.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert!(response.headers.contains_key("Link"));
+    assert_eq!(
+        serde_json::to_value(&response.content["application/json"].schema).unwrap(),
+        serde_json::json!({ "$ref": "#/components/schemas/Paginated_for_User" })
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn idempotent() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder};
+
+    #[openapi(idempotent)]
+    async fn handle() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::POST, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .post
+        .clone()
+        .expect("POST / should be present");
+    assert!(matches!(
+        &operation.parameters[..],
+        [RefOr::Object(x)] if x.name == "Idempotency-Key" && x.location == "header" && x.required
+    ));
+    assert_eq!(
+        operation.responses.responses.keys().collect::<Vec<_>>(),
+        ["200", "409", "422"]
+    );
+    assert_eq!(
+        operation.extensions["x-idempotent"],
+        serde_json::Value::Bool(true)
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn conditional_etag() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder};
+
+    #[openapi(conditional(etag))]
+    async fn handle() -> String {
+        unimplemented!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::PUT, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let operation = spec.paths["/"]
+        .put
+        .clone()
+        .expect("PUT / should be present");
+    let parameters: Vec<_> = operation
+        .parameters
+        .iter()
+        .map(|x| match x {
+            RefOr::Object(x) => (x.name.as_str(), x.location.as_str()),
+            RefOr::Ref(_) => panic!("Parameter should be RefOr::Object"),
+        })
+        .collect();
+    assert_eq!(
+        parameters,
+        [("If-Match", "header"), ("If-None-Match", "header")]
+    );
+    assert_eq!(
+        operation.responses.responses.keys().collect::<Vec<_>>(),
+        ["200", "304", "412"]
+    );
+    let RefOr::Object(response) = &operation.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert!(response.headers.contains_key("ETag"));
+}
+
+#[test]
+#[allow(dead_code)]
+fn route_context() {
+    use okapi_operation::{
+        okapi::openapi3::{Operation, RefOr},
+        BuilderOptions, Components, Error, OpenApiBuilder,
+    };
+
+    fn generator(components: &mut Components) -> Result<Operation, Error> {
+        let (path, method) = components
+            .current_route()
+            .expect("Route should be available");
+        Ok(Operation {
+            summary: Some(format!("{method} {path}")),
+            ..Default::default()
+        })
+    }
+
+    #[openapi(parameters(path(name = "id", schema = "u64")))]
+    async fn handle() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .set_options(BuilderOptions::new().infer_path_parameters(true))
+        .operation(
+            "/users/{id}/posts/{post_id}",
+            http::Method::GET,
+            handle__openapi,
+        )
+        .operation("/users/{id}", http::Method::PUT, generator)
+        .build()
+        .expect("Schema generation shouldn't fail");
+
+    let parameters = |operation: &Operation| {
+        operation
+            .parameters
+            .iter()
+            .map(|x| match x {
+                RefOr::Object(x) => (x.name.clone(), x.required),
+                RefOr::Ref(_) => panic!("Parameter should be RefOr::Object"),
+            })
+            .collect::<Vec<_>>()
+    };
+    let operation = spec.paths["/users/{id}/posts/{post_id}"]
+        .get
+        .as_ref()
+        .expect("GET should be present");
+    assert_eq!(
+        parameters(operation),
+        [("id".to_owned(), true), ("post_id".to_owned(), true)]
+    );
+    let operation = spec.paths["/users/{id}"]
+        .put
+        .as_ref()
+        .expect("PUT should be present");
+    assert_eq!(operation.summary.as_deref(), Some("PUT /users/{id}"));
+    assert_eq!(parameters(operation), [("id".to_owned(), true)]);
+}
+
+#[tokio::test]
+#[allow(dead_code)]
+async fn async_operations() {
+    use std::{future::Future, pin::Pin};
+
+    use okapi_operation::{
+        okapi::openapi3::Operation, schemars::JsonSchema, Components, Error, OpenApiBuilder,
+    };
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Loaded {
+        data: String,
+    }
+
+    fn generator(
+        components: &mut Components,
+    ) -> Pin<Box<dyn Future<Output = Result<Operation, Error>> + '_>> {
+        Box::pin(async move {
+            let description = async { "Loaded asynchronously".to_owned() }.await;
+            let schema = components.schema_for::<Loaded>();
+            Ok(Operation {
+                description: Some(description),
+                extensions: okapi_operation::okapi::map! {
+                    "x-schema".into() => serde_json::to_value(schema).map_err(anyhow::Error::from)?,
+                },
+                ..Default::default()
+            })
+        })
+    }
+
+    #[openapi]
+    async fn handle() {}
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    builder
+        .operation("/sync", http::Method::GET, handle__openapi)
+        .async_operation("/async", http::Method::GET, generator);
+
+    assert!(builder.build().is_err());
+
+    let spec = builder
+        .build_async()
+        .await
+        .expect("Schema generation shouldn't fail");
+    assert!(spec.paths["/sync"].get.is_some());
+    let operation = spec.paths["/async"]
+        .get
+        .as_ref()
+        .expect("GET /async should be present");
+    assert_eq!(
+        operation.description.as_deref(),
+        Some("Loaded asynchronously")
+    );
+    assert!(spec
+        .components
+        .expect("Components should be present")
+        .schemas
+        .contains_key("Loaded"));
+}
+
+#[test]
+#[allow(dead_code)]
+fn schema_for_cache() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use okapi_operation::{
+        okapi::schemars::{schema::SchemaObject, visit::Visitor},
+        ComponentsBuilder,
+    };
+
+    #[derive(Debug, Clone, Default)]
+    struct CountVisits(Arc<AtomicUsize>);
+
+    impl Visitor for CountVisits {
+        fn visit_schema_object(&mut self, _schema: &mut SchemaObject) {
+            let _ = self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[derive(okapi_operation::JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Counter {
+        value: u32,
+    }
+
+    let visitor = CountVisits::default();
+    let visits = visitor.0.clone();
+    let mut components = ComponentsBuilder::default()
+        .with_schema_visitor(visitor)
+        .build();
+
+    let first = components.schema_for::<Vec<Counter>>();
+    let after_first = visits.load(Ordering::Relaxed);
+    assert!(after_first > 0);
+    for _ in 0..10 {
+        assert_eq!(components.schema_for::<Vec<Counter>>(), first);
+    }
+    assert_eq!(visits.load(Ordering::Relaxed), after_first);
+}
+
+#[test]
+fn error_kinds() {
+    use okapi_operation::{
+        okapi::openapi3::Operation, BuilderOptions, Components, CustomMethods, Error,
+        OpenApiBuilder,
+    };
+
+    fn failed(_: &mut Components) -> Result<Operation, Error> {
+        Err(anyhow::anyhow!("Generator failed").into())
+    }
+
+    fn empty(_: &mut Components) -> Result<Operation, Error> {
+        Ok(Operation::default())
+    }
+
+    let mut builder = OpenApiBuilder::new("title", "version");
+    let _ = builder.operation("/", http::Method::GET, failed);
+    let err = builder.build().expect_err("Generator should fail");
+    assert!(matches!(
+        &err,
+        Error::Generator { path, method, source }
+            if path == "/" && method == http::Method::GET && matches!(**source, Error::Custom(_))
+    ));
+    assert_eq!(
+        std::error::Error::source(&err).map(ToString::to_string),
+        Some("Generator failed".into())
+    );
+
+    assert!(matches!(
+        OpenApiBuilder::new("title", "version")
+            .operation("/", http::Method::GET, empty)
+            .try_operation("/", http::Method::GET, empty),
+        Err(Error::DuplicateOperation { .. })
+    ));
+    assert!(matches!(
+        OpenApiBuilder::new("title", "version")
+            .set_options(BuilderOptions::new().custom_methods(CustomMethods::Error))
+            .operation("/", http::Method::CONNECT, empty)
+            .build(),
+        Err(Error::UnsupportedMethod { .. })
+    ));
+}
+
+#[test]
+#[allow(dead_code)]
+fn schema_overrides() {
+    use std::net::Ipv4Addr;
+
+    use okapi_operation::{okapi::openapi3::SchemaObject, schemars::JsonSchema, OpenApiBuilder};
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Money {
+        amount: String,
+    }
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Host {
+        /// Address of host.
+        address: Ipv4Addr,
+        balance: Money,
+    }
+
+    #[openapi]
+    async fn handle(#[body] _body: Json<Host>) {}
+
+    #[openapi]
+    async fn ping(#[body] _body: Json<Ipv4Addr>) {}
+
+    let schema = |value| serde_json::from_value::<SchemaObject>(value).unwrap();
+    let spec = OpenApiBuilder::new("title", "version")
+        .override_schema_for::<Ipv4Addr>(schema(serde_json::json!({
+            "type": "string",
+            "format": "ipv4",
+            "example": "127.0.0.1"
+        })))
+        .override_schema_for::<Money>(schema(serde_json::json!({
+            "type": "string",
+            "pattern": "^[0-9]+$"
+        })))
+        .operation("/", http::Method::POST, handle__openapi)
+        .operation("/ping", http::Method::POST, ping__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+
+    let body = serde_json::to_value(&spec.paths["/ping"].post).unwrap();
+    assert_eq!(
+        body["requestBody"]["content"]["application/json"]["schema"]["example"],
+        "127.0.0.1"
+    );
+    let schemas = serde_json::to_value(spec.components.unwrap().schemas).unwrap();
+    let properties = &schemas["Host"]["properties"];
+    // Inlined schemas of fields are not matched by shape
+    assert_eq!(properties["address"]["format"], "ipv4");
+    assert!(properties["address"].get("example").is_none());
+    assert_eq!(properties["balance"]["$ref"], "#/components/schemas/Money");
+    assert_eq!(schemas["Money"]["pattern"], "^[0-9]+$");
+}
+
+#[test]
+#[allow(dead_code)]
+fn discriminators() {
+    use okapi_operation::{schemars::JsonSchema, BuilderOptions, OpenApiBuilder};
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars", tag = "kind")]
+    enum Payment {
+        Cash,
+        CreditCard { number: String },
+    }
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars", tag = "type", content = "value")]
+    enum Event {
+        Created(u64),
+        Deleted(u64),
+    }
+
+    #[openapi(discriminator(schema = "Payment"))]
+    async fn pay(#[body] _body: Json<Payment>) {}
+
+    #[openapi]
+    async fn events() -> Json<Event> {
+        todo!()
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/pay", http::Method::POST, pay__openapi)
+        .operation("/events", http::Method::GET, events__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let schemas = serde_json::to_value(spec.components.unwrap().schemas).unwrap();
+    assert_eq!(
+        schemas["Payment"]["discriminator"],
+        serde_json::json!({
+            "propertyName": "kind",
+            "mapping": {
+                "Cash": "#/components/schemas/PaymentCash",
+                "CreditCard": "#/components/schemas/PaymentCreditCard",
+            }
+        })
+    );
+    assert_eq!(
+        schemas["PaymentCreditCard"]["required"],
+        serde_json::json!(["kind", "number"])
+    );
+    assert!(schemas["Event"].get("discriminator").is_none());
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .set_options(BuilderOptions::default().infer_discriminators(true))
+        .operation("/events", http::Method::GET, events__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let schemas = serde_json::to_value(spec.components.unwrap().schemas).unwrap();
+    assert_eq!(schemas["Event"]["discriminator"]["propertyName"], "type");
+}
+
+#[test]
+#[allow(dead_code)]
+fn recursive_schemas() {
+    use okapi_operation::{
+        schemars::JsonSchema, BuildWarning, ComponentsBuilder, Error, OpenApiBuilder,
+        RecursionPolicy,
+    };
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Tree {
+        children: Vec<Tree>,
+    }
+
+    #[openapi]
+    async fn handle(#[body] _body: Json<Tree>) {}
+
+    let build = |components| {
+        OpenApiBuilder::new("title", "version")
+            .set_components(components)
+            .operation("/", http::Method::POST, handle__openapi)
+            .build_with_report()
+    };
+
+    let (spec, warnings) = build(ComponentsBuilder::default().inline_subschemas(true).build())
+        .expect("Schema generation shouldn't fail");
+    assert!(spec.components.unwrap().schemas.contains_key("Tree"));
+    assert_eq!(
+        warnings,
+        [BuildWarning::RecursiveSchema {
+            name: "Tree".into()
+        }]
+    );
+
+    let (spec, warnings) = build(
+        ComponentsBuilder::default()
+            .recursion_policy(RecursionPolicy::InlineDepth(1))
+            .build(),
+    )
+    .expect("Schema generation shouldn't fail");
+    assert!(warnings.is_empty());
+    let tree = serde_json::to_value(&spec.components.unwrap().schemas["Tree"]).unwrap();
+    assert_eq!(
+        tree["properties"]["children"]["items"]["properties"]["children"]["items"]["$ref"],
+        "#/components/schemas/Tree"
+    );
+
+    let result = build(
+        ComponentsBuilder::default()
+            .recursion_policy(RecursionPolicy::Error)
+            .build(),
+    );
+    assert!(matches!(result, Err(Error::RecursiveSchema { name }) if name == "Tree"));
+}
+
+#[test]
+#[allow(dead_code)]
+fn custom_methods() {
+    use okapi_operation::{schemars::JsonSchema, OpenApiBuilder};
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "okapi_operation::schemars")]
+    struct Filter {
+        name: String,
+    }
+
+    #[openapi(operation_id = "search")]
+    async fn search(#[body] _body: Json<Filter>) {}
+
+    let query = http::Method::from_bytes(b"QUERY").unwrap();
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/users", query, search__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+
+    let operation = &spec.paths["/users"].extensions["x-additionalOperations"]["QUERY"];
+    assert_eq!(operation["operationId"], "search");
+    assert_eq!(
+        operation["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Filter"
+    );
+    assert!(spec.components.unwrap().schemas.contains_key("Filter"));
+}
+
+#[test]
+#[allow(dead_code)]
+fn status_ranges_and_default_response() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder};
+
+    #[openapi(responses(
+        ignore_return_type = true,
+        response(status = "2xx", description = "Success", content = "Json<String>"),
+        default_response(description = "Unexpected error", content = "Json<String>"),
+    ))]
+    async fn handle() {}
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation("/", http::Method::GET, handle__openapi)
+        .build()
+        .expect("Schema generation shouldn't fail");
+    let responses = spec.paths["/"]
+        .get
+        .clone()
+        .expect("GET / should be present")
+        .responses;
+    assert!(responses.responses.contains_key("2XX"));
+    let Some(RefOr::Object(default)) = responses.default else {
+        panic!("Default response should be RefOr::Object");
+    };
+    assert_eq!(default.description, "Unexpected error");
+}
+
+#[test]
+#[allow(dead_code)]
+fn trait_methods() {
+    use okapi_operation::{okapi::openapi3::RefOr, OpenApiBuilder};
+
+    trait UserApi {
+        #[openapi(summary = "Get user", operation_id = "getUser")]
+        async fn get_user(#[body] body: String) -> String;
+
+        #[openapi(tags = "users")]
+        async fn delete_user() -> Vec<u8> {
+            Vec::new()
+        }
+    }
+
+    struct Postgres;
+
+    impl UserApi for Postgres {
+        async fn get_user(body: String) -> String {
+            body
+        }
+    }
+
+    let spec = OpenApiBuilder::new("title", "version")
+        .operation(
+            "/users",
+            http::Method::GET,
+            <Postgres as UserApi>::get_user__openapi,
+        )
+        .operation(
+            "/users",
+            http::Method::DELETE,
+            <Postgres as UserApi>::delete_user__openapi,
+        )
+        .build()
+        .expect("Schema generation shouldn't fail");
+
+    let get = spec.paths["/users"]
+        .get
+        .clone()
+        .expect("GET should be present");
+    assert_eq!(get.summary.as_deref(), Some("Get user"));
+    assert_eq!(get.operation_id.as_deref(), Some("getUser"));
+    let Some(RefOr::Object(body)) = get.request_body else {
+        panic!("Request body should be present");
+    };
+    assert!(body.content.contains_key("text/plain"));
+    let RefOr::Object(response) = &get.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert!(response.content.contains_key("text/plain"));
+
+    let delete = spec.paths["/users"]
+        .delete
+        .clone()
+        .expect("DELETE should be present");
+    assert_eq!(delete.tags, vec!["users".to_string()]);
+}
+
+#[cfg(feature = "auto-collect")]
+#[test]
+#[allow(dead_code)]
+fn auto_collect() {
+    use okapi_operation::{collected_operations, OpenApiBuilder};
+
+    #[openapi(collect(path = "/users/{id}", method = "get"))]
+    async fn get_user() -> String {
+        unimplemented!()
+    }
+
+    #[openapi(
+        operation_id = "deleteUser",
+        collect(path = "/users/{id}", method = "delete")
+    )]
+    async fn delete_user() {}
+
+    #[openapi(path = "/users", method = "post", collect)]
+    async fn create_user() {}
+
+    #[openapi]
+    async fn not_collected() {}
+
+    assert_eq!(collected_operations().count(), 3);
+
+    let spec = OpenApiBuilder::from_inventory("title", "version")
+        .expect("Operations are unique")
+        .build()
+        .expect("Schema generation shouldn't fail");
+    assert_eq!(spec.paths.len(), 2);
+    assert!(spec.paths["/users"].post.is_some());
+    let path_item = &spec.paths["/users/{id}"];
+    assert!(path_item.get.is_some());
+    assert_eq!(
+        path_item
+            .delete
+            .as_ref()
+            .and_then(|x| x.operation_id.as_deref()),
+        Some("deleteUser")
+    );
+
+    let mut builder =
+        OpenApiBuilder::from_inventory("title", "version").expect("Operations are unique");
+    assert!(builder.try_inventory_operations().is_err());
+}
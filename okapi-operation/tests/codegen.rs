@@ -0,0 +1,165 @@
+#![cfg(feature = "codegen")]
+
+use http::Method;
+use okapi_operation::{
+    codegen::Codegen,
+    okapi::openapi3::{OpenApi, RefOr},
+    OpenApiBuilder,
+};
+
+const SPEC: &str = include_str!("codegen/spec.json");
+const GENERATED_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/codegen/generated.rs");
+
+struct Json<T>(T);
+okapi_operation::impl_to_media_types_for_wrapper!(Json<T>, "application/json");
+
+#[allow(dead_code)]
+mod generated {
+    use super::Json;
+
+    include!("codegen/generated.rs");
+}
+
+use generated::*;
+
+struct Server;
+
+impl UsersApi for Server {
+    async fn list_users() -> Json<Vec<User>> {
+        unimplemented!()
+    }
+
+    async fn create_user(_body: Json<CreateUserRequest>) -> Json<User> {
+        unimplemented!()
+    }
+
+    async fn delete_users_id() {}
+
+    async fn get_user() -> Json<User> {
+        unimplemented!()
+    }
+
+    async fn upload_avatar(_body: Vec<u8>) {}
+}
+
+fn spec() -> OpenApi {
+    serde_json::from_str(SPEC).expect("Valid specification")
+}
+
+#[test]
+fn generated_code_is_up_to_date() {
+    let code = Codegen::new("UsersApi")
+        .json_wrapper("Json")
+        .generate(&spec())
+        .expect("Code generation shouldn't fail");
+    if std::env::var_os("UPDATE_CODEGEN").is_some() {
+        std::fs::write(GENERATED_PATH, &code).expect("Failed to write generated code");
+    }
+    let current = std::fs::read_to_string(GENERATED_PATH).expect("Failed to read generated code");
+    assert!(
+        code == current,
+        "Generated code is outdated, run tests with UPDATE_CODEGEN=1"
+    );
+}
+
+#[test]
+fn generated_code_reproduces_specification() {
+    let original = spec();
+    let built = OpenApiBuilder::new("Users", "1.0.0")
+        .operation(
+            "/users",
+            Method::GET,
+            <Server as UsersApi>::list_users__openapi,
+        )
+        .operation(
+            "/users",
+            Method::POST,
+            <Server as UsersApi>::create_user__openapi,
+        )
+        .operation(
+            "/users/{id}",
+            Method::GET,
+            <Server as UsersApi>::get_user__openapi,
+        )
+        .operation(
+            "/users/{id}",
+            Method::DELETE,
+            <Server as UsersApi>::delete_users_id__openapi,
+        )
+        .operation(
+            "/users/{id}/avatar",
+            Method::PUT,
+            <Server as UsersApi>::upload_avatar__openapi,
+        )
+        .build()
+        .expect("Schema generation shouldn't fail");
+
+    assert_eq!(
+        built.paths.keys().collect::<Vec<_>>(),
+        original.paths.keys().collect::<Vec<_>>()
+    );
+
+    let get_user = built.paths["/users/{id}"]
+        .get
+        .clone()
+        .expect("GET /users/{id}");
+    assert_eq!(get_user.operation_id.as_deref(), Some("getUser"));
+    assert_eq!(get_user.summary.as_deref(), Some("Get user"));
+    assert_eq!(get_user.tags, ["users"]);
+    let parameters: Vec<_> = get_user
+        .parameters
+        .iter()
+        .map(|x| match x {
+            RefOr::Object(x) => (x.name.as_str(), x.location.as_str(), x.required),
+            RefOr::Ref(_) => panic!("Parameters should be inlined"),
+        })
+        .collect();
+    assert_eq!(
+        parameters,
+        [("x-request-id", "header", false), ("id", "path", true)]
+    );
+    let RefOr::Object(not_found) = &get_user.responses.responses["404"] else {
+        panic!("404 response should be RefOr::Object");
+    };
+    assert!(not_found.content.contains_key("text/plain"));
+
+    let create_user = built.paths["/users"].post.clone().expect("POST /users");
+    let Some(RefOr::Object(body)) = &create_user.request_body else {
+        panic!("Request body should be present");
+    };
+    assert!(body.required);
+    assert!(body.content.contains_key("application/json"));
+    assert!(create_user.responses.default.is_some());
+    assert_eq!(
+        create_user.security.expect("Security should be present")[0]["OAuth2"],
+        ["users:write"]
+    );
+
+    let list_users = built.paths["/users"].get.clone().expect("GET /users");
+    let RefOr::Object(users) = &list_users.responses.responses["200"] else {
+        panic!("200 response should be RefOr::Object");
+    };
+    assert!(users.headers.contains_key("x-total-count"));
+
+    let upload_avatar = built.paths["/users/{id}/avatar"]
+        .put
+        .clone()
+        .expect("PUT /users/{id}/avatar");
+    let Some(RefOr::Object(body)) = &upload_avatar.request_body else {
+        panic!("Request body should be present");
+    };
+    assert!(body.content.contains_key("image/png"));
+
+    assert!(built.paths["/users/{id}"]
+        .delete
+        .as_ref()
+        .is_some_and(|x| x.deprecated));
+
+    let schemas = &built.components.expect("Components").schemas;
+    for name in ["User", "UserStatus", "Error", "CreateUserRequest"] {
+        assert!(
+            schemas.contains_key(name),
+            "{name} schema should be present"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+// Generated by okapi-operation codegen, do not edit.
+
+/// Registered user.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, okapi_operation::JsonSchema)]
+#[schemars(crate = "okapi_operation::schemars")]
+pub struct User {
+    pub id: i64,
+    pub name: String,
+    pub status: UserStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize, okapi_operation::JsonSchema)]
+#[schemars(crate = "okapi_operation::schemars")]
+pub enum UserStatus {
+    #[serde(rename = "active")]
+    Active,
+    #[serde(rename = "blocked")]
+    Blocked,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, okapi_operation::JsonSchema)]
+#[schemars(crate = "okapi_operation::schemars")]
+pub struct Error {
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize, okapi_operation::JsonSchema)]
+#[schemars(crate = "okapi_operation::schemars")]
+pub enum ListUsersStatus {
+    #[serde(rename = "active")]
+    Active,
+    #[serde(rename = "blocked")]
+    Blocked,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, okapi_operation::JsonSchema)]
+#[schemars(crate = "okapi_operation::schemars")]
+pub struct CreateUserRequest {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+#[allow(async_fn_in_trait)]
+pub trait UsersApi {
+    /// List users
+    #[okapi_operation::openapi(
+        operation_id = "listUsers",
+        summary = "List users",
+        tags = "users",
+        path = "/users",
+        method = "GET",
+        parameters(
+            query(name = "limit", description = "Maximum number of users", schema = "u32"),
+            query(name = "status", schema = "ListUsersStatus"),
+        ),
+        responses(
+            ignore_return_type = true,
+            response(
+                status = "200",
+                description = "Users",
+                content = "Json<Vec<User>>",
+                headers(
+                    header(name = "x-total-count", description = "Total number of users", required = true, schema = "i64"),
+                ),
+            ),
+        ),
+    )]
+    async fn list_users() -> Json<Vec<User>>;
+
+    /// Create user
+    #[okapi_operation::openapi(
+        operation_id = "createUser",
+        summary = "Create user",
+        tags = "users",
+        path = "/users",
+        method = "POST",
+        responses(
+            ignore_return_type = true,
+            response(
+                status = "201",
+                description = "Created user",
+                content = "Json<User>",
+            ),
+            default_response(
+                description = "Unexpected error",
+                content = "Json<Error>",
+            ),
+        ),
+        security(
+            security_scheme(name = "OAuth2", scopes = "users:write"),
+        ),
+    )]
+    async fn create_user(#[body(description = "New user", required = true)] body: Json<CreateUserRequest>) -> Json<User>;
+
+    #[okapi_operation::openapi(
+        deprecated = true,
+        path = "/users/{id}",
+        method = "DELETE",
+        parameters(
+            path(name = "id", schema = "i64"),
+        ),
+        responses(
+            ignore_return_type = true,
+            response(
+                status = "204",
+                description = "User deleted",
+                content = "okapi_operation::Empty",
+            ),
+        ),
+    )]
+    async fn delete_users_id();
+
+    /// Get user
+    #[okapi_operation::openapi(
+        operation_id = "getUser",
+        summary = "Get user",
+        description = "Get user by ID.",
+        tags = "users",
+        path = "/users/{id}",
+        method = "GET",
+        parameters(
+            path(name = "id", schema = "i64"),
+            header(name = "x-request-id", schema = "String"),
+        ),
+        responses(
+            ignore_return_type = true,
+            response(
+                status = "200",
+                description = "User",
+                content = "Json<User>",
+            ),
+            response(
+                status = "404",
+                description = "User not found",
+                content = "String",
+            ),
+        ),
+    )]
+    async fn get_user() -> Json<User>;
+
+    #[okapi_operation::openapi(
+        operation_id = "uploadAvatar",
+        path = "/users/{id}/avatar",
+        method = "PUT",
+        parameters(
+            path(name = "id", schema = "i64"),
+        ),
+        responses(
+            ignore_return_type = true,
+            response(
+                status = "204",
+                description = "Avatar uploaded",
+                content = "okapi_operation::Empty",
+            ),
+        ),
+    )]
+    async fn upload_avatar(#[body(required = true, content_type = "image/png")] body: Vec<u8>);
+}
@@ -0,0 +1,6 @@
+use okapi_operation::openapi;
+
+#[openapi(responses(default_response(status = "200", description = "Ok", content = "String")))]
+async fn handle() {}
+
+fn main() {}
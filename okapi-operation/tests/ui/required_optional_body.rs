@@ -0,0 +1,6 @@
+use okapi_operation::openapi;
+
+#[openapi]
+async fn handle(#[body(required = true)] _body: Option<String>) {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use okapi_operation::openapi;
+
+#[openapi(responses(response(status)))]
+async fn handle() {}
+
+fn main() {}
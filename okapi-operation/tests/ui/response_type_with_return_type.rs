@@ -0,0 +1,8 @@
+use okapi_operation::openapi;
+
+#[openapi(response_type = "String", responses(return_type = "String"))]
+async fn handle() -> String {
+    String::new()
+}
+
+fn main() {}